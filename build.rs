@@ -0,0 +1,27 @@
+// File: build.rs
+fn main() {
+    println!("cargo:rerun-if-changed=proto/sss.proto");
+    #[cfg(feature = "grpc")]
+    {
+        std::env::set_var(
+            "PROTOC",
+            protoc_bin_vendored::protoc_bin_path().expect("vendored protoc"),
+        );
+        tonic_build::compile_protos("proto/sss.proto").expect("compile proto/sss.proto");
+    }
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("set by cargo");
+        cbindgen::Builder::new()
+            .with_crate(&crate_dir)
+            .with_config(
+                cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+                    .expect("read cbindgen.toml"),
+            )
+            .generate()
+            .expect("generate the C header")
+            .write_to_file(format!("{}/include/sss.h", crate_dir));
+    }
+}
@@ -0,0 +1,108 @@
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#ifndef SSS_H
+#define SSS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result codes; the values match the exit statuses of the `sss` tool.
+typedef enum SssStatus {
+  SSS_STATUS_OK = 0,
+  // A required pointer was null.
+  SSS_STATUS_NULL_POINTER = 1,
+  SSS_STATUS_INVALID_INPUT = 3,
+  SSS_STATUS_INVALID_PARAMETERS = 4,
+  SSS_STATUS_INCONSISTENT_SHARES = 5,
+  // A signature, digest or decryption check failed.
+  SSS_STATUS_CRYPTO_FAILURE = 6,
+  SSS_STATUS_IO = 7,
+} SssStatus;
+
+// An opaque collection of shares.
+typedef struct SssShares SssShares;
+
+// A byte buffer owned by the library.
+typedef struct SssBuffer {
+  uint8_t *data;
+  uintptr_t len;
+} SssBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns a static, NUL-terminated description of a status.
+const char *sss_status_message(enum SssStatus status);
+
+// Splits `secret_len` bytes at `secret` into `total` shares, any
+// `threshold` of which recover it, and stores a new collection in `*out`.
+//
+// # Safety
+//
+// `secret` must point to `secret_len` readable bytes and `out` must be
+// valid for writes.
+enum SssStatus sss_split(const uint8_t *secret,
+                         uintptr_t secret_len,
+                         uintptr_t threshold,
+                         uintptr_t total,
+                         struct SssShares **out);
+
+// Creates an empty collection, to fill with [`sss_shares_push`].
+struct SssShares *sss_shares_new(void);
+
+// The number of shares in a collection, or 0 for null.
+//
+// # Safety
+//
+// `shares` must be null or a collection returned by this library.
+uintptr_t sss_shares_len(const struct SssShares *shares);
+
+// Writes the binary encoding of share `index` into `*out`.
+//
+// # Safety
+//
+// `shares` must be a collection returned by this library and `out` must be
+// valid for writes.
+enum SssStatus sss_shares_get(const struct SssShares *shares,
+                              uintptr_t index,
+                              struct SssBuffer *out);
+
+// Parses a binary share encoding and appends it to the collection.
+//
+// # Safety
+//
+// `shares` must be a collection returned by this library and `share` must
+// point to `share_len` readable bytes.
+enum SssStatus sss_shares_push(struct SssShares *shares, const uint8_t *share, uintptr_t share_len);
+
+// Recovers the secret from a collection into `*out`.
+//
+// # Safety
+//
+// `shares` must be a collection returned by this library and `out` must be
+// valid for writes.
+enum SssStatus sss_combine(const struct SssShares *shares, struct SssBuffer *out);
+
+// Frees a collection; null is ignored.
+//
+// # Safety
+//
+// `shares` must be null or a collection returned by this library that has
+// not been freed.
+void sss_shares_free(struct SssShares *shares);
+
+// Frees a buffer, wiping its contents first; a null buffer is ignored.
+//
+// # Safety
+//
+// `buffer` must have been returned by this library and not freed before.
+void sss_buffer_free(struct SssBuffer buffer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SSS_H */
@@ -0,0 +1,157 @@
+// File: src/advice.rs
+//! Advisory checks on a secret before it is dealt.
+//!
+//! Splitting cannot add strength to a weak secret: three shares of
+//! `password` still protect `password`. [`advise`] looks for the common
+//! mistakes (a secret too short to resist guessing, one whose bytes repeat
+//! so much that it carries little entropy, or a value copied from
+//! documentation or test vectors) and returns [`Warning`]s for the caller to
+//! show. It never refuses a secret; whether to deal anyway is the caller's
+//! decision.
+//!
+//! The entropy figure is a byte-frequency estimate, so it can only flag
+//! obviously weak secrets: a passphrase that passes may still be guessable.
+
+use std::fmt;
+
+/// Secrets shorter than this many bytes draw a [`Warning::Short`].
+pub const MIN_LENGTH: usize = 16;
+/// Estimates below this many bits draw a [`Warning::LowEntropy`].
+pub const MIN_ENTROPY_BITS: f64 = 64.0;
+
+/// Values that turn up in examples and defaults, compared case-insensitively.
+const KNOWN_VALUES: &[&str] = &[
+    "123456",
+    "admin",
+    "changeme",
+    "correct horse battery staple",
+    "hello world",
+    "letmein",
+    "password",
+    "qwerty",
+    "secret",
+    "test",
+];
+
+/// A reason to reconsider a secret before dealing it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Warning {
+    /// Fewer than [`MIN_LENGTH`] bytes.
+    Short { len: usize },
+    /// The estimated entropy is below [`MIN_ENTROPY_BITS`].
+    LowEntropy { bits: f64 },
+    /// The secret is a well-known or trivially patterned value.
+    KnownValue(&'static str),
+}
+
+impl Warning {
+    /// A stable identifier for the warning, e.g. for JSON reports.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::Short { .. } => "short",
+            Warning::LowEntropy { .. } => "low_entropy",
+            Warning::KnownValue(_) => "known_value",
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::Short { len } => write!(
+                f,
+                "secret is only {} bytes; at least {} are recommended",
+                len, MIN_LENGTH
+            ),
+            Warning::LowEntropy { bits } => write!(
+                f,
+                "secret carries an estimated {:.0} bits of entropy; at least {:.0} are recommended",
+                bits, MIN_ENTROPY_BITS
+            ),
+            Warning::KnownValue(what) => write!(f, "secret is {}", what),
+        }
+    }
+}
+
+/// Checks `secret` and returns every warning that applies, or none.
+pub fn advise(secret: &[u8]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    if secret.len() < MIN_LENGTH {
+        warnings.push(Warning::Short { len: secret.len() });
+    }
+    let bits = entropy_bits(secret);
+    if bits < MIN_ENTROPY_BITS {
+        warnings.push(Warning::LowEntropy { bits });
+    }
+    if let Some(what) = known_value(secret) {
+        warnings.push(Warning::KnownValue(what));
+    }
+    warnings
+}
+
+/// The secret's length times the Shannon entropy of its byte frequencies.
+pub fn entropy_bits(secret: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &byte in secret {
+        counts[byte as usize] += 1;
+    }
+    let len = secret.len() as f64;
+    let per_byte: f64 = counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+    per_byte * len
+}
+
+fn known_value(secret: &[u8]) -> Option<&'static str> {
+    if secret.len() < 2 {
+        return None;
+    }
+    if secret.iter().all(|&byte| byte == secret[0]) {
+        return Some("a single repeated byte");
+    }
+    if secret
+        .windows(2)
+        .all(|pair| pair[1] == pair[0].wrapping_add(1))
+    {
+        return Some("a counting sequence");
+    }
+    let text = std::str::from_utf8(secret).ok()?.trim().to_lowercase();
+    KNOWN_VALUES
+        .contains(&text.as_str())
+        .then_some("a well-known example value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advice() {
+        let random: Vec<u8> = (0..32u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+        assert_eq!(advise(&random), []);
+
+        let codes =
+            |secret: &[u8]| -> Vec<&str> { advise(secret).iter().map(Warning::code).collect() };
+        assert_eq!(
+            codes(b"Password\n"),
+            ["short", "low_entropy", "known_value"]
+        );
+        assert_eq!(codes(&[0; 32]), ["low_entropy", "known_value"]);
+        assert_eq!(codes(&(0..32).collect::<Vec<u8>>()), ["known_value"]);
+        assert_eq!(codes(b"abababababababababababab"), ["low_entropy"]);
+        assert!(advise(b"")
+            .iter()
+            .any(|w| matches!(w, Warning::Short { len: 0 })));
+        assert_eq!(
+            Warning::Short { len: 4 }.to_string(),
+            "secret is only 4 bytes; at least 16 are recommended"
+        );
+    }
+}
@@ -0,0 +1,458 @@
+// File: src/age_plugin.rs
+//! An age plugin for threshold decryption.
+//!
+//! A [`ThresholdRecipient`] (`age1threshold1...`) names `n` X25519 member
+//! keys and a threshold `t`. Encrypting to it splits age's file key into
+//! byte-wise shares and seals one to each member in a [`ShareBundle`], which
+//! becomes the body of a `threshold` stanza. Decryption needs the plugin
+//! identities (`AGE-PLUGIN-THRESHOLD-1...`) of at least `t` members, passed
+//! to `age -d` together; members' plugin identities are their ordinary age
+//! X25519 keys, re-encoded.
+//!
+//! [`run_recipient_v1`] and [`run_identity_v1`] speak age's plugin protocol
+//! over the plugin's stdin and stdout for the `age-plugin-threshold` binary.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::bech32::{self, Variant};
+use crate::ecies::ShareBundle;
+use crate::error::{Error, Result};
+use crate::serialized::{reconstruct_auto, split_bytes};
+
+const RECIPIENT_HRP: &str = "age1threshold";
+const IDENTITY_HRP: &str = "age-plugin-threshold-";
+const AGE_RECIPIENT_HRP: &str = "age";
+const AGE_SECRET_HRP: &str = "age-secret-key-";
+const STANZA_TYPE: &str = "threshold";
+const FILE_KEY_LEN: usize = 16;
+const BODY_COLUMNS: usize = 64;
+
+/// A protocol message: `-> tag args...` followed by a base64 body.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stanza {
+    pub tag: String,
+    pub args: Vec<String>,
+    pub body: Vec<u8>,
+}
+
+impl Stanza {
+    pub fn new(tag: &str, args: &[&str], body: &[u8]) -> Self {
+        Stanza {
+            tag: tag.into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            body: body.to_vec(),
+        }
+    }
+
+    /// Reads the next stanza, or `None` at end of input.
+    pub fn read(input: &mut impl BufRead) -> Result<Option<Self>> {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let mut words = header
+            .trim_end_matches('\n')
+            .strip_prefix("-> ")
+            .ok_or(Error::InvalidEncoding("malformed age stanza header"))?
+            .split(' ');
+        let tag = words.next().unwrap_or_default().to_string();
+        let args = words.map(String::from).collect();
+
+        let mut encoded = String::new();
+        loop {
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Err(Error::InvalidEncoding("truncated age stanza body"));
+            }
+            let line = line.trim_end_matches('\n');
+            encoded.push_str(line);
+            if line.len() < BODY_COLUMNS {
+                break;
+            }
+        }
+        let body = STANDARD_NO_PAD
+            .decode(encoded)
+            .map_err(|_| Error::InvalidEncoding("invalid base64 in age stanza"))?;
+        Ok(Some(Stanza { tag, args, body }))
+    }
+
+    pub fn write(&self, output: &mut impl Write) -> Result<()> {
+        let mut header = format!("-> {}", self.tag);
+        for arg in &self.args {
+            header.push(' ');
+            header.push_str(arg);
+        }
+        writeln!(output, "{header}")?;
+        let encoded = STANDARD_NO_PAD.encode(&self.body);
+        for line in encoded.as_bytes().chunks(BODY_COLUMNS) {
+            output.write_all(line)?;
+            output.write_all(b"\n")?;
+        }
+        if encoded.len().is_multiple_of(BODY_COLUMNS) {
+            output.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// `n` member keys, any `threshold` of which decrypt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThresholdRecipient {
+    pub threshold: usize,
+    pub members: Vec<PublicKey>,
+}
+
+impl ThresholdRecipient {
+    pub fn new(threshold: usize, members: Vec<PublicKey>) -> Result<Self> {
+        if threshold == 0 || threshold > members.len() || members.len() > 255 {
+            return Err(Error::InvalidParameters(
+                "threshold recipients need 1 <= threshold <= members <= 255",
+            ));
+        }
+        Ok(ThresholdRecipient { threshold, members })
+    }
+
+    /// Builds a recipient from members' native age recipients (`age1...`).
+    pub fn from_age_recipients<S: AsRef<str>>(threshold: usize, members: &[S]) -> Result<Self> {
+        let members = members
+            .iter()
+            .map(|member| {
+                let key =
+                    bech32::decode_variant(AGE_RECIPIENT_HRP, member.as_ref(), Variant::Bech32)?;
+                <[u8; 32]>::try_from(key)
+                    .map(PublicKey::from)
+                    .map_err(|_| Error::InvalidEncoding("age recipients hold 32-byte keys"))
+            })
+            .collect::<Result<_>>()?;
+        Self::new(threshold, members)
+    }
+
+    /// Splits a file key among the members into a `threshold` stanza.
+    pub fn wrap_file_key(&self, file_key: &[u8]) -> Result<Stanza> {
+        let shares = split_bytes(file_key, self.threshold, self.members.len())?;
+        let bundle = ShareBundle::seal(&shares, &self.members)?;
+        Ok(Stanza::new(
+            STANZA_TYPE,
+            &[&self.threshold.to_string()],
+            &bundle.to_bytes(),
+        ))
+    }
+}
+
+impl fmt::Display for ThresholdRecipient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut data = vec![self.threshold as u8];
+        for member in &self.members {
+            data.extend_from_slice(member.as_bytes());
+        }
+        f.write_str(&bech32::encode_variant(
+            RECIPIENT_HRP,
+            &data,
+            Variant::Bech32,
+        ))
+    }
+}
+
+impl FromStr for ThresholdRecipient {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let data = bech32::decode_variant(RECIPIENT_HRP, text, Variant::Bech32)?;
+        let (&threshold, keys) = data.split_first().ok_or(Error::MalformedShare)?;
+        if !keys.len().is_multiple_of(32) {
+            return Err(Error::MalformedShare);
+        }
+        let members = keys
+            .chunks(32)
+            .map(|key| PublicKey::from(<[u8; 32]>::try_from(key).expect("chunks are 32 bytes")))
+            .collect();
+        Self::new(threshold as usize, members)
+    }
+}
+
+/// Encodes a member's X25519 secret as a plugin identity.
+pub fn encode_identity(secret: &StaticSecret) -> String {
+    bech32::encode_variant(IDENTITY_HRP, secret.as_bytes(), Variant::Bech32).to_uppercase()
+}
+
+pub fn decode_identity(text: &str) -> Result<StaticSecret> {
+    let secret = bech32::decode_variant(IDENTITY_HRP, text, Variant::Bech32)?;
+    <[u8; 32]>::try_from(secret)
+        .map(StaticSecret::from)
+        .map_err(|_| Error::InvalidEncoding("threshold identities hold 32-byte keys"))
+}
+
+/// Reads the `AGE-SECRET-KEY-1...` line of an age identity file.
+pub fn identity_from_age_secret_key(text: &str) -> Result<StaticSecret> {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| line.to_ascii_lowercase().starts_with(AGE_SECRET_HRP))
+        .ok_or(Error::InvalidEncoding("no AGE-SECRET-KEY line found"))?;
+    let secret = bech32::decode_variant(AGE_SECRET_HRP, line, Variant::Bech32)?;
+    <[u8; 32]>::try_from(secret)
+        .map(StaticSecret::from)
+        .map_err(|_| Error::InvalidEncoding("age identities hold 32-byte keys"))
+}
+
+/// Recovers the file key from a `threshold` stanza with members' identities.
+pub fn unwrap_file_key(stanza: &Stanza, identities: &[StaticSecret]) -> Result<Vec<u8>> {
+    if stanza.tag != STANZA_TYPE {
+        return Err(Error::InvalidParameters("not a threshold stanza"));
+    }
+    let bundle = ShareBundle::from_bytes(&stanza.body)?;
+    let mut shares = BTreeMap::new();
+    for identity in identities {
+        if let Ok(share) = bundle.open(identity) {
+            shares.insert(share.x.clone(), share);
+        }
+    }
+    let shares: Vec<_> = shares.into_values().collect();
+    if shares
+        .first()
+        .is_none_or(|share| shares.len() < share.threshold)
+    {
+        return Err(Error::InsufficientShares);
+    }
+    let file_key = reconstruct_auto(&shares)?;
+    if file_key.len() != FILE_KEY_LEN {
+        return Err(Error::InconsistentShares(
+            "recovered file key is not 16 bytes",
+        ));
+    }
+    Ok(file_key)
+}
+
+/// Runs the `recipient-v1` state machine: wraps each file key to every
+/// threshold recipient age passes in.
+pub fn run_recipient_v1(input: &mut impl BufRead, output: &mut impl Write) -> Result<()> {
+    let mut recipients = Vec::new();
+    let mut identities = 0;
+    let mut file_keys = Vec::new();
+    while let Some(stanza) = Stanza::read(input)? {
+        match stanza.tag.as_str() {
+            "add-recipient" => recipients.push(
+                stanza
+                    .args
+                    .first()
+                    .ok_or(Error::InvalidEncoding("add-recipient without a recipient"))
+                    .and_then(|text| text.parse::<ThresholdRecipient>()),
+            ),
+            "add-identity" => identities += 1,
+            "wrap-file-key" => file_keys.push(stanza.body),
+            "done" => break,
+            _ => {}
+        }
+    }
+
+    let mut failed = false;
+    for (index, recipient) in recipients.iter().enumerate() {
+        if let Err(err) = recipient {
+            let index = index.to_string();
+            command(
+                input,
+                output,
+                &Stanza::new("error", &["recipient", &index], err.to_string().as_bytes()),
+            )?;
+            failed = true;
+        }
+    }
+    for index in 0..identities {
+        let index = index.to_string();
+        let message = b"threshold identities cannot encrypt; use the threshold recipient";
+        command(
+            input,
+            output,
+            &Stanza::new("error", &["identity", &index], message),
+        )?;
+        failed = true;
+    }
+    if !failed {
+        for (file, file_key) in file_keys.iter().enumerate() {
+            for recipient in recipients.iter().flatten() {
+                let stanza = recipient.wrap_file_key(file_key)?;
+                let mut args = vec![file.to_string(), stanza.tag];
+                args.extend(stanza.args);
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                command(
+                    input,
+                    output,
+                    &Stanza::new("recipient-stanza", &args, &stanza.body),
+                )?;
+            }
+        }
+    }
+    Stanza::new("done", &[], &[]).write(output)?;
+    output.flush()?;
+    Ok(())
+}
+
+/// Runs the `identity-v1` state machine: pools every threshold identity age
+/// passes in and unwraps each file whose stanza they reach the threshold of.
+pub fn run_identity_v1(input: &mut impl BufRead, output: &mut impl Write) -> Result<()> {
+    let mut identities = Vec::new();
+    let mut files: BTreeMap<usize, Vec<(usize, Stanza)>> = BTreeMap::new();
+    let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+    while let Some(stanza) = Stanza::read(input)? {
+        match stanza.tag.as_str() {
+            "add-identity" => identities.push(
+                stanza
+                    .args
+                    .first()
+                    .ok_or(Error::InvalidEncoding("add-identity without an identity"))
+                    .and_then(|text| decode_identity(text)),
+            ),
+            "recipient-stanza" => {
+                let [file, kind, args @ ..] = stanza.args.as_slice() else {
+                    continue;
+                };
+                let Ok(file) = file.parse::<usize>() else {
+                    continue;
+                };
+                let count = counts.entry(file).or_default();
+                if kind == STANZA_TYPE {
+                    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                    files
+                        .entry(file)
+                        .or_default()
+                        .push((*count, Stanza::new(kind, &args, &stanza.body)));
+                }
+                *count += 1;
+            }
+            "done" => break,
+            _ => {}
+        }
+    }
+
+    let mut secrets = Vec::new();
+    for (index, identity) in identities.into_iter().enumerate() {
+        match identity {
+            Ok(secret) => secrets.push(secret),
+            Err(err) => {
+                let index = index.to_string();
+                command(
+                    input,
+                    output,
+                    &Stanza::new("error", &["identity", &index], err.to_string().as_bytes()),
+                )?;
+            }
+        }
+    }
+    for (file, stanzas) in files {
+        let file = file.to_string();
+        for (index, stanza) in stanzas {
+            match unwrap_file_key(&stanza, &secrets) {
+                Ok(file_key) => {
+                    command(input, output, &Stanza::new("file-key", &[&file], &file_key))?;
+                    break;
+                }
+                Err(Error::InsufficientShares) => {
+                    let message = format!(
+                        "file {file}: these identities do not reach the threshold of {}",
+                        stanza.args.first().map_or("?", String::as_str)
+                    );
+                    command(input, output, &Stanza::new("msg", &[], message.as_bytes()))?;
+                }
+                Err(err) => {
+                    let index = index.to_string();
+                    command(
+                        input,
+                        output,
+                        &Stanza::new(
+                            "error",
+                            &["stanza", &file, &index],
+                            err.to_string().as_bytes(),
+                        ),
+                    )?;
+                }
+            }
+        }
+    }
+    Stanza::new("done", &[], &[]).write(output)?;
+    output.flush()?;
+    Ok(())
+}
+
+/// Sends a phase-two command and waits for age's `ok` or `fail`.
+fn command(input: &mut impl BufRead, output: &mut impl Write, stanza: &Stanza) -> Result<()> {
+    stanza.write(output)?;
+    output.flush()?;
+    Stanza::read(input)?.ok_or(Error::InvalidEncoding("age closed the plugin connection"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    fn stanzas(mut bytes: &[u8]) -> Vec<Stanza> {
+        std::iter::from_fn(|| Stanza::read(&mut bytes).unwrap()).collect()
+    }
+
+    fn transcript(stanzas: &[Stanza]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for stanza in stanzas {
+            stanza.write(&mut out).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_plugin_sessions_need_the_threshold() {
+        let members: Vec<StaticSecret> = (0..3)
+            .map(|_| StaticSecret::random_from_rng(OsRng))
+            .collect();
+        let recipient =
+            ThresholdRecipient::new(2, members.iter().map(PublicKey::from).collect()).unwrap();
+        let recipient_text = recipient.to_string();
+        assert!(recipient_text.starts_with("age1threshold1"));
+        assert_eq!(
+            recipient_text.parse::<ThresholdRecipient>().unwrap(),
+            recipient
+        );
+
+        let file_key = [42u8; 16];
+        let ok = Stanza::new("ok", &[], &[]);
+        let input = transcript(&[
+            Stanza::new("add-recipient", &[&recipient_text], &[]),
+            Stanza::new("wrap-file-key", &[], &file_key),
+            Stanza::new("done", &[], &[]),
+            ok.clone(),
+        ]);
+        let mut output = Vec::new();
+        run_recipient_v1(&mut input.as_slice(), &mut output).unwrap();
+        let replies = stanzas(&output);
+        assert_eq!(replies[0].tag, "recipient-stanza");
+        assert_eq!(replies[0].args, ["0", "threshold", "2"]);
+        assert_eq!(replies[1].tag, "done");
+
+        let identity =
+            |secret: &StaticSecret| Stanza::new("add-identity", &[&encode_identity(secret)], &[]);
+        let stanza = Stanza::new(
+            "recipient-stanza",
+            &["0", "threshold", "2"],
+            &replies[0].body,
+        );
+        let decrypt = |identities: Vec<Stanza>| {
+            let mut session = identities;
+            session.extend([stanza.clone(), Stanza::new("done", &[], &[]), ok.clone()]);
+            let mut output = Vec::new();
+            run_identity_v1(&mut transcript(&session).as_slice(), &mut output).unwrap();
+            stanzas(&output)
+        };
+
+        let replies = decrypt(vec![identity(&members[2]), identity(&members[0])]);
+        assert_eq!(replies[0], Stanza::new("file-key", &["0"], &file_key));
+        let replies = decrypt(vec![identity(&members[1])]);
+        assert_eq!(replies[0].tag, "msg");
+        assert_eq!(replies[1].tag, "done");
+    }
+}
@@ -0,0 +1,135 @@
+// File: src/arkworks.rs
+//! Sharing over arkworks fields and curves (`arkworks` feature).
+//!
+//! zk and SNARK projects hold their witnesses and keys as `ark-ff` field
+//! elements over whatever curve their circuits use. This module maps any
+//! [`PrimeField`] onto the crate's prime-field sharing: [`split`] deals a
+//! field element with [`SecretSharer`] over the field's modulus, so the
+//! shares are ordinary [`SerializedShare`]s that [`reconstruct`] (or
+//! [`crate::serialized::reconstruct_auto`]) combines. For any `ark-ec`
+//! [`PrimeGroup`], [`split_verifiable`] adds Feldman commitments `a_k * G`
+//! to the same polynomial in the curve group, which [`verify_share`] checks
+//! shares against, the elliptic-curve counterpart of [`crate::vss`].
+
+use ark_ec::PrimeGroup;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+use crate::error::{Error, Result};
+use crate::serialized::{reconstruct_auto, Scheme, SerializedShare};
+use crate::sss::SecretSharer;
+
+/// The modulus of `F` as the crate's field prime.
+pub fn modulus<F: PrimeField>() -> BigUint {
+    F::MODULUS.into()
+}
+
+/// Splits a field element into `total` shares over its own field.
+pub fn split<F: PrimeField>(
+    secret: F,
+    threshold: usize,
+    total: usize,
+) -> Result<Vec<SerializedShare>> {
+    check_threshold(threshold, total)?;
+    let sharer = SecretSharer::with_prime(modulus::<F>(), threshold, total);
+    Ok(sharer
+        .split_secret(&secret.into())
+        .iter()
+        .map(|share| sharer.serialize_share(share))
+        .collect())
+}
+
+/// Like [`split`], also returning the Feldman commitments `a_k * G` to the
+/// polynomial's coefficients in the group `G` whose scalars are shared.
+pub fn split_verifiable<G: PrimeGroup>(
+    secret: G::ScalarField,
+    threshold: usize,
+    total: usize,
+) -> Result<(Vec<SerializedShare>, Vec<G>)> {
+    check_threshold(threshold, total)?;
+    let sharer = SecretSharer::with_prime(modulus::<G::ScalarField>(), threshold, total);
+    let polynomial = sharer.polynomial(&secret.into());
+    let shares = (1..=total as u32)
+        .map(|x| {
+            let share = polynomial
+                .share_at(&x.into())
+                .expect("indices 1..=n are nonzero");
+            sharer.serialize_share(&share)
+        })
+        .collect();
+    let commitments = polynomial
+        .coefficients()
+        .iter()
+        .map(|a| G::generator() * G::ScalarField::from(a.clone()))
+        .collect();
+    Ok((shares, commitments))
+}
+
+/// Checks `y * G == sum of x^k * C_k` for a share dealt by [`split_verifiable`].
+pub fn verify_share<G: PrimeGroup>(share: &SerializedShare, commitments: &[G]) -> bool {
+    if !in_field::<G::ScalarField>(share) {
+        return false;
+    }
+    let x = G::ScalarField::from(share.x.clone());
+    let y = G::ScalarField::from(BigUint::from_bytes_be(&share.value));
+    let expected = commitments
+        .iter()
+        .rev()
+        .fold(G::zero(), |acc, commitment| acc * x + commitment);
+    G::generator() * y == expected
+}
+
+/// Recombines shares of a field element made by [`split`] or [`split_verifiable`].
+pub fn reconstruct<F: PrimeField>(shares: &[SerializedShare]) -> Result<F> {
+    if !shares.iter().all(in_field::<F>) {
+        return Err(Error::InconsistentShares(
+            "shares are not over the requested field",
+        ));
+    }
+    Ok(F::from_be_bytes_mod_order(&reconstruct_auto(shares)?))
+}
+
+fn check_threshold(threshold: usize, total: usize) -> Result<()> {
+    if threshold == 0 || threshold > total {
+        return Err(Error::InvalidParameters(
+            "threshold must be between 1 and the total",
+        ));
+    }
+    Ok(())
+}
+
+fn in_field<F: PrimeField>(share: &SerializedShare) -> bool {
+    matches!(&share.scheme, Scheme::Shamir { prime } if *prime == modulus::<F>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective};
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn test_bn254_sharing() {
+        let mut rng = rand::thread_rng();
+        let witness = Fr::rand(&mut rng);
+        let shares = split(witness, 3, 5).unwrap();
+        assert_eq!(reconstruct::<Fr>(&shares[2..]).unwrap(), witness);
+        assert!(reconstruct::<ark_bn254::Fq>(&shares).is_err());
+
+        let key = Fr::rand(&mut rng);
+        let (shares, commitments) = split_verifiable::<G1Projective>(key, 2, 4).unwrap();
+        assert!(shares.iter().all(|share| verify_share(share, &commitments)));
+        assert_eq!(commitments[0], G1Projective::generator() * key);
+        assert_eq!(reconstruct::<Fr>(&shares[..2]).unwrap(), key);
+
+        let mut forged = shares[1].clone();
+        let value = Fr::from(BigUint::from_bytes_be(&forged.value)) + Fr::from(1u8);
+        forged.value = BigUint::from(value).to_bytes_be();
+        assert!(!verify_share(&forged, &commitments));
+
+        // A zero threshold would hand every holder the secret itself
+        assert!(split(witness, 0, 3).is_err());
+        assert!(split(witness, 4, 3).is_err());
+        assert!(split_verifiable::<G1Projective>(key, 0, 3).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+// File: src/audit.rs
+//! Hash-chained audit transcripts of share operations.
+//!
+//! An [`AuditLog`] is opt-in: callers record an [`Event`] for each dealing,
+//! share verification and reconstruction attempt they want on file. Events
+//! name shares only by their fingerprints, so the log holds no share values
+//! or secrets. Each [`Entry`] hashes the previous entry's hash, so removing,
+//! reordering or editing an entry breaks the chain, and the exported JSON is
+//! signed over the head hash with the operator's Ed25519 key for compliance
+//! review of key ceremonies.
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::encoding::{hex, unhex};
+use crate::error::{Error, Result};
+use crate::expiry;
+use crate::manifest::AuditSignature;
+use crate::serialized::{reconstruct_auto, SerializedShare};
+use crate::signing::DealerSignature;
+
+const LOG_VERSION: u8 = 1;
+const ENTRY_PREFIX: &[u8] = b"SSA1";
+const SIGNATURE_PREFIX: &[u8] = b"SSA1 signed log";
+
+/// An operation worth auditing, naming shares by fingerprint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    DealingCreated {
+        scheme: String,
+        threshold: usize,
+        total: usize,
+        shares: Vec<String>,
+    },
+    ShareVerified {
+        share: String,
+        valid: bool,
+    },
+    ReconstructionAttempted {
+        shares: Vec<String>,
+        succeeded: bool,
+    },
+}
+
+impl Event {
+    /// A dealing of `shares`, all from the same split.
+    pub fn dealing(shares: &[SerializedShare]) -> Result<Self> {
+        let first = shares.first().ok_or(Error::InsufficientShares)?;
+        Ok(Event::DealingCreated {
+            scheme: first.scheme.to_string(),
+            threshold: first.threshold,
+            total: first.total,
+            shares: shares.iter().map(SerializedShare::fingerprint).collect(),
+        })
+    }
+
+    pub fn verification(share: &SerializedShare, valid: bool) -> Self {
+        Event::ShareVerified {
+            share: share.fingerprint(),
+            valid,
+        }
+    }
+
+    pub fn reconstruction(shares: &[SerializedShare], succeeded: bool) -> Self {
+        Event::ReconstructionAttempted {
+            shares: shares.iter().map(SerializedShare::fingerprint).collect(),
+            succeeded,
+        }
+    }
+}
+
+/// One link of the chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub seq: u64,
+    /// Unix seconds.
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub event: Event,
+    /// Hash of the previous entry, all zeros for the first.
+    pub prev: String,
+    pub hash: String,
+}
+
+impl Entry {
+    fn digest(seq: u64, timestamp: u64, event: &Event, prev: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(ENTRY_PREFIX);
+        hasher.update(seq.to_be_bytes());
+        hasher.update(timestamp.to_be_bytes());
+        hasher.update(prev.as_bytes());
+        hasher.update(serde_json::to_vec(event).expect("events serialize"));
+        hex(&hasher.finalize())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonLog {
+    version: u8,
+    entries: Vec<Entry>,
+    signature: AuditSignature,
+}
+
+/// An append-only, hash-chained record of operations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditLog {
+    entries: Vec<Entry>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The hash of the last entry, or all zeros for an empty log.
+    pub fn head(&self) -> String {
+        self.entries
+            .last()
+            .map_or_else(|| hex(&[0u8; 32]), |entry| entry.hash.clone())
+    }
+
+    /// Appends `event`, timestamped now.
+    pub fn record(&mut self, event: Event) -> &Entry {
+        let (seq, timestamp, prev) = (self.entries.len() as u64, expiry::now(), self.head());
+        let hash = Entry::digest(seq, timestamp, &event, &prev);
+        self.entries.push(Entry {
+            seq,
+            timestamp,
+            event,
+            prev,
+            hash,
+        });
+        self.entries.last().expect("just pushed")
+    }
+
+    /// [`reconstruct_auto`], recording the attempt and whether it succeeded.
+    pub fn reconstruct(&mut self, shares: &[SerializedShare]) -> Result<Vec<u8>> {
+        let result = reconstruct_auto(shares);
+        self.record(Event::reconstruction(shares, result.is_ok()));
+        result
+    }
+
+    /// Checks that every entry links to the previous one and hashes to its
+    /// recorded hash.
+    pub fn verify(&self) -> Result<()> {
+        let mut prev = hex(&[0u8; 32]);
+        for (seq, entry) in self.entries.iter().enumerate() {
+            if entry.seq != seq as u64
+                || entry.prev != prev
+                || entry.hash != Entry::digest(entry.seq, entry.timestamp, &entry.event, &prev)
+            {
+                return Err(Error::DigestMismatch);
+            }
+            prev = entry.hash.clone();
+        }
+        Ok(())
+    }
+
+    /// The log as JSON, signed over its head hash by `key`.
+    pub fn to_signed_json(&self, key: &SigningKey) -> String {
+        let signature = DealerSignature::sign(key, &self.signed_message());
+        let document = JsonLog {
+            version: LOG_VERSION,
+            entries: self.entries.clone(),
+            signature: AuditSignature {
+                dealer: hex(&signature.dealer),
+                signature: hex(&signature.signature),
+            },
+        };
+        serde_json::to_string_pretty(&document).expect("audit logs serialize")
+    }
+
+    /// Parses a signed log, checking the chain and that `signer` signed it.
+    pub fn from_signed_json(text: &str, signer: &VerifyingKey) -> Result<Self> {
+        let document: JsonLog = serde_json::from_str(text)
+            .map_err(|_| Error::InvalidEncoding("invalid audit log JSON"))?;
+        if document.version != LOG_VERSION {
+            return Err(Error::UnsupportedVersion(document.version));
+        }
+        let log = AuditLog {
+            entries: document.entries,
+        };
+        log.verify()?;
+        DealerSignature {
+            dealer: unhex(&document.signature.dealer)?
+                .try_into()
+                .map_err(|_| Error::InvalidSignature)?,
+            signature: unhex(&document.signature.signature)?
+                .try_into()
+                .map_err(|_| Error::InvalidSignature)?,
+        }
+        .verify(signer, &log.signed_message())?;
+        Ok(log)
+    }
+
+    fn signed_message(&self) -> Vec<u8> {
+        let mut message = SIGNATURE_PREFIX.to_vec();
+        message.extend_from_slice(&(self.entries.len() as u64).to_be_bytes());
+        message.extend_from_slice(self.head().as_bytes());
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::split_bytes;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_audit_chain() {
+        let shares = split_bytes(b"ceremony secret", 2, 3).unwrap();
+        let mut log = AuditLog::new();
+        log.record(Event::dealing(&shares).unwrap());
+        log.record(Event::verification(&shares[0], true));
+        assert_eq!(log.reconstruct(&shares[1..]).unwrap(), b"ceremony secret");
+        assert!(log.reconstruct(&shares[..1]).is_err());
+        assert_eq!(log.entries().len(), 4);
+        assert!(!format!("{:?}", log).contains("ceremony secret"));
+
+        let operator = SigningKey::generate(&mut OsRng);
+        let json = log.to_signed_json(&operator);
+        assert!(json.contains("reconstruction_attempted"));
+        let parsed = AuditLog::from_signed_json(&json, &operator.verifying_key()).unwrap();
+        assert_eq!(parsed, log);
+        let stranger = SigningKey::generate(&mut OsRng);
+        assert!(AuditLog::from_signed_json(&json, &stranger.verifying_key()).is_err());
+
+        let mut tampered = log.clone();
+        tampered.entries.remove(1);
+        assert!(tampered.verify().is_err());
+        let json = json.replacen("\"succeeded\": false", "\"succeeded\": true", 1);
+        assert!(AuditLog::from_signed_json(&json, &operator.verifying_key()).is_err());
+    }
+}
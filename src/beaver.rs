@@ -0,0 +1,143 @@
+// File: src/beaver.rs
+//! Multiplication of Shamir-shared values with Beaver triples.
+//!
+//! A trusted dealer hands every party shares of random `a` and `b` and of
+//! their product `c = ab` ([`Beaver::deal_triple`]). To multiply shared `x`
+//! and `y`, each party publishes its shares of `d = x - a` and `e = y - b`
+//! ([`Beaver::mask`]); these reveal nothing, as `a` and `b` are uniform. Once
+//! `d` and `e` are opened ([`Beaver::open`]), each party computes its share
+//! of `xy = c + d·b + e·a + d·e` locally ([`Beaver::multiply`]). Every triple
+//! must be used for a single multiplication.
+
+use num_bigint::{BigUint, RandBigInt};
+use rand::thread_rng;
+
+use crate::error::{Error, Result};
+use crate::sss::{SecretSharer, Share};
+
+/// One party's shares of a Beaver triple.
+#[derive(Clone, Debug)]
+pub struct TripleShare {
+    pub x: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+    pub c: BigUint,
+}
+
+/// Beaver multiplication over the integers modulo `prime`.
+pub struct Beaver {
+    prime: BigUint,
+    threshold: usize,
+    total: usize,
+}
+
+impl Beaver {
+    pub fn new(prime: BigUint, threshold: usize, total: usize) -> Self {
+        Beaver {
+            prime,
+            threshold,
+            total,
+        }
+    }
+
+    /// Deals a fresh triple, one [`TripleShare`] per party in index order.
+    pub fn deal_triple(&self) -> Vec<TripleShare> {
+        let mut rng = thread_rng();
+        let a = rng.gen_biguint_below(&self.prime);
+        let b = rng.gen_biguint_below(&self.prime);
+        let c = &a * &b % &self.prime;
+        let sharer = self.sharer();
+        let (a, b, c) = (
+            sharer.split_secret(&a),
+            sharer.split_secret(&b),
+            sharer.split_secret(&c),
+        );
+        a.into_iter()
+            .zip(b)
+            .zip(c)
+            .map(|((a, b), c)| TripleShare {
+                x: a.x,
+                a: a.y,
+                b: b.y,
+                c: c.y,
+            })
+            .collect()
+    }
+
+    /// A party's shares of `d = x - a` and `e = y - b`, to be published.
+    pub fn mask(&self, triple: &TripleShare, x: &Share, y: &Share) -> Result<(Share, Share)> {
+        if x.x != triple.x || y.x != triple.x {
+            return Err(Error::InconsistentShares("shares have different indices"));
+        }
+        Ok((
+            Share {
+                x: triple.x.clone(),
+                y: self.sub(&x.y, &triple.a),
+            },
+            Share {
+                x: triple.x.clone(),
+                y: self.sub(&y.y, &triple.b),
+            },
+        ))
+    }
+
+    /// Reconstructs a published value such as `d` or `e` from the shares of
+    /// at least a threshold of parties.
+    pub fn open(&self, shares: &[Share]) -> Result<BigUint> {
+        if shares.len() < self.threshold {
+            return Err(Error::InsufficientShares);
+        }
+        self.sharer()
+            .reconstruct_secret(shares)
+            .ok_or(Error::InconsistentShares("duplicate share indices"))
+    }
+
+    /// A party's share of `xy`, given the opened `d` and `e`.
+    pub fn multiply(&self, triple: &TripleShare, d: &BigUint, e: &BigUint) -> Share {
+        let p = &self.prime;
+        let y = (&triple.c + d * &triple.b + e * &triple.a + d * e) % p;
+        Share {
+            x: triple.x.clone(),
+            y,
+        }
+    }
+
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let p = &self.prime;
+        (a % p + p - b % p) % p
+    }
+
+    fn sharer(&self) -> SecretSharer {
+        SecretSharer::with_prime(self.prime.clone(), self.threshold, self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beaver_multiplication() {
+        let prime = BigUint::from(2u32).pow(127) - 1u32;
+        let beaver = Beaver::new(prime.clone(), 2, 3);
+        let sharer = SecretSharer::with_prime(prime, 2, 3);
+        let xs = sharer.split_secret(&BigUint::from(6u32));
+        let ys = sharer.split_secret(&BigUint::from(7u32));
+        let triples = beaver.deal_triple();
+
+        let masked: Vec<_> = (0..3)
+            .map(|i| beaver.mask(&triples[i], &xs[i], &ys[i]).unwrap())
+            .collect();
+        let ds: Vec<_> = masked.iter().map(|(d, _)| d.clone()).collect();
+        let es: Vec<_> = masked.iter().map(|(_, e)| e.clone()).collect();
+        let (d, e) = (beaver.open(&ds[1..]).unwrap(), beaver.open(&es).unwrap());
+
+        let products: Vec<_> = triples
+            .iter()
+            .map(|triple| beaver.multiply(triple, &d, &e))
+            .collect();
+        let pair = [products[2].clone(), products[0].clone()];
+        assert_eq!(sharer.reconstruct_secret(&pair), Some(BigUint::from(42u32)));
+        assert!(beaver.mask(&triples[0], &xs[1], &ys[0]).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+// File: src/bech32.rs
+//! Bech32m text encoding with transcription-error location.
+//!
+//! The bech32m checksum is a BCH code over GF(32), so a failed checksum
+//! leaves a residue that depends only on where and how the string was
+//! mistyped. [`decode`] searches that residue for one- or two-character
+//! errors and reports their positions instead of a bare failure.
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const BECH32M_CONST: u32 = 0x2bc830a3;
+const CHECKSUM_LEN: usize = 6;
+
+/// The checksum constant: BIP-173 bech32 or BIP-350 bech32m.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => BECH32M_CONST,
+        }
+    }
+}
+
+fn polymod_from(mut chk: u32, values: impl IntoIterator<Item = u8>) -> u32 {
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ value as u32;
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|b| b & 31));
+    out
+}
+
+/// Encodes `data` as `hrp1...` with a six-character bech32m checksum.
+pub fn encode(hrp: &str, data: &[u8]) -> String {
+    encode_variant(hrp, data, Variant::Bech32m)
+}
+
+pub fn encode_variant(hrp: &str, data: &[u8], variant: Variant) -> String {
+    let mut values = to_base32(data);
+    let chk = polymod_from(
+        1,
+        hrp_expand(hrp)
+            .into_iter()
+            .chain(values.clone())
+            .chain([0; 6]),
+    ) ^ variant.constant();
+    values.extend((0..CHECKSUM_LEN).map(|i| ((chk >> (5 * (5 - i))) & 31) as u8));
+
+    let mut out = format!("{}1", hrp);
+    out.extend(values.iter().map(|&v| CHARSET[v as usize] as char));
+    out
+}
+
+/// Decodes a string produced by [`encode`].
+///
+/// A checksum failure is reported as [`Error::TranscriptionErrors`] with the
+/// character positions (0-based, counting the prefix) most likely mistyped.
+pub fn decode(hrp: &str, text: &str) -> Result<Vec<u8>> {
+    decode_variant(hrp, text, Variant::Bech32m)
+}
+
+pub fn decode_variant(hrp: &str, text: &str, variant: Variant) -> Result<Vec<u8>> {
+    let text = text.trim().to_ascii_lowercase();
+    let data = text
+        .strip_prefix(hrp)
+        .and_then(|rest| rest.strip_prefix('1'))
+        .ok_or(Error::InvalidEncoding("unexpected bech32 prefix"))?;
+    let offset = hrp.len() + 1;
+    if data.len() < CHECKSUM_LEN {
+        return Err(Error::InvalidEncoding("bech32 string too short"));
+    }
+
+    let invalid: Vec<usize> = data
+        .bytes()
+        .enumerate()
+        .filter(|(_, c)| !CHARSET.contains(c))
+        .map(|(i, _)| offset + i)
+        .collect();
+    if !invalid.is_empty() {
+        return Err(Error::TranscriptionErrors(invalid));
+    }
+    let values: Vec<u8> = data
+        .bytes()
+        .map(|c| CHARSET.iter().position(|&x| x == c).unwrap() as u8)
+        .collect();
+
+    let residue =
+        polymod_from(1, hrp_expand(hrp).into_iter().chain(values.clone())) ^ variant.constant();
+    if residue != 0 {
+        let positions = locate_errors(residue, values.len());
+        return Err(Error::TranscriptionErrors(
+            positions.into_iter().map(|i| offset + i).collect(),
+        ));
+    }
+    from_base32(&values[..values.len() - CHECKSUM_LEN])
+}
+
+/// Finds the positions of one or two substitutions explaining `residue`.
+///
+/// The checksum is linear, so an error of value `e` at position `i` always
+/// contributes the same residue whatever the rest of the string holds.
+fn locate_errors(residue: u32, len: usize) -> Vec<usize> {
+    let contribution = |i: usize, e: u8| {
+        polymod_from(
+            0,
+            std::iter::once(e).chain(std::iter::repeat_n(0, len - 1 - i)),
+        )
+    };
+    let singles: Vec<(u32, usize)> = (0..len)
+        .flat_map(|i| (1..32).map(move |e| (i, e)))
+        .map(|(i, e)| (contribution(i, e), i))
+        .collect();
+
+    let mut found: Vec<usize> = singles
+        .iter()
+        .filter(|&&(r, _)| r == residue)
+        .map(|&(_, i)| i)
+        .collect();
+    if found.is_empty() {
+        let mut by_residue: HashMap<u32, Vec<usize>> = HashMap::new();
+        for &(r, i) in &singles {
+            by_residue.entry(r).or_default().push(i);
+        }
+        for &(r, i) in &singles {
+            if let Some(others) = by_residue.get(&(r ^ residue)) {
+                found.extend(others.iter().filter(|&&j| j > i).flat_map(|&j| [i, j]));
+            }
+        }
+    }
+    found.sort_unstable();
+    found.dedup();
+    found
+}
+
+fn to_base32(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity((data.len() * 8).div_ceil(5));
+    let (mut acc, mut bits) = (0u32, 0);
+    for &byte in data {
+        acc = (acc << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(((acc >> bits) & 31) as u8);
+        }
+    }
+    if bits > 0 {
+        out.push(((acc << (5 - bits)) & 31) as u8);
+    }
+    out
+}
+
+fn from_base32(values: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(values.len() * 5 / 8);
+    let (mut acc, mut bits) = (0u32, 0);
+    for &value in values {
+        acc = (acc << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    if bits >= 5 || (acc & ((1 << bits) - 1)) != 0 {
+        return Err(Error::InvalidEncoding("non-zero bech32 padding"));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locates_typos() {
+        // BIP-350 test vector
+        assert_eq!(encode("a", &[]), "a1lqfn3a");
+
+        let data: Vec<u8> = (0u8..40).collect();
+        let text = encode("sss", &data);
+        assert_eq!(decode("sss", &text).unwrap(), data);
+        assert_eq!(decode("sss", &text.to_uppercase()).unwrap(), data);
+
+        let typo = |text: &str, at: usize| {
+            let mut bytes = text.as_bytes().to_vec();
+            bytes[at] = if bytes[at] == b'q' { b'p' } else { b'q' };
+            String::from_utf8(bytes).unwrap()
+        };
+
+        let one = typo(&text, 20);
+        match decode("sss", &one) {
+            Err(Error::TranscriptionErrors(positions)) => assert!(positions.contains(&20)),
+            other => panic!("expected a located error, got {:?}", other),
+        }
+
+        let two = typo(&typo(&text, 9), 50);
+        match decode("sss", &two) {
+            Err(Error::TranscriptionErrors(positions)) => {
+                assert!(positions.contains(&9) && positions.contains(&50))
+            }
+            other => panic!("expected located errors, got {:?}", other),
+        }
+
+        let mut bad = text.clone();
+        bad.replace_range(12..13, "b");
+        assert!(matches!(
+            decode("sss", &bad),
+            Err(Error::TranscriptionErrors(p)) if p == vec![12]
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+// File: src/bin/age-plugin-threshold.rs
+//! The `age-plugin-threshold` binary: age's plugin protocol for threshold
+//! recipients, plus helpers to create recipients and identities.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use cryptographic_algorithms::age_plugin::{
+    encode_identity, identity_from_age_secret_key, run_identity_v1, run_recipient_v1,
+    ThresholdRecipient,
+};
+use cryptographic_algorithms::bech32::{self, Variant};
+use cryptographic_algorithms::Result;
+use x25519_dalek::PublicKey;
+
+#[derive(Parser)]
+#[command(
+    name = "age-plugin-threshold",
+    version,
+    about = "Threshold recipients for age"
+)]
+struct Cli {
+    /// Run a plugin state machine; set by age itself
+    #[arg(long, value_name = "STATE_MACHINE")]
+    age_plugin: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print a threshold recipient for members' age recipients (age1...)
+    Recipient {
+        #[arg(short, long)]
+        threshold: usize,
+        #[arg(required = true)]
+        members: Vec<String>,
+    },
+    /// Convert a member's age identity file into a threshold identity
+    Identity {
+        /// The AGE-SECRET-KEY-1 file, or - for stdin
+        #[arg(default_value = "-")]
+        file: PathBuf,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("age-plugin-threshold: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let (stdin, stdout) = (io::stdin(), io::stdout());
+    match (cli.age_plugin.as_deref(), cli.command) {
+        (Some("recipient-v1"), _) => run_recipient_v1(&mut stdin.lock(), &mut stdout.lock()),
+        (Some("identity-v1"), _) => run_identity_v1(&mut stdin.lock(), &mut stdout.lock()),
+        (Some(other), _) => {
+            eprintln!("age-plugin-threshold: unsupported state machine {other:?}");
+            Ok(())
+        }
+        (None, Some(Command::Recipient { threshold, members })) => {
+            println!(
+                "{}",
+                ThresholdRecipient::from_age_recipients(threshold, &members)?
+            );
+            Ok(())
+        }
+        (None, Some(Command::Identity { file })) => {
+            let text = if file.as_os_str() == "-" {
+                let mut text = String::new();
+                stdin.lock().read_to_string(&mut text)?;
+                text
+            } else {
+                fs::read_to_string(&file)?
+            };
+            let secret = identity_from_age_secret_key(&text)?;
+            let public = PublicKey::from(&secret);
+            println!(
+                "# member: {}",
+                bech32::encode_variant("age", public.as_bytes(), Variant::Bech32)
+            );
+            println!("{}", encode_identity(&secret));
+            Ok(())
+        }
+        (None, None) => {
+            eprintln!("age-plugin-threshold: run by age, or see --help");
+            Ok(())
+        }
+    }
+}
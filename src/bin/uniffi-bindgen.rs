@@ -0,0 +1,6 @@
+// File: src/bin/uniffi-bindgen.rs
+//! Generates the Swift and Kotlin bindings for the `mobile` feature.
+
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}
@@ -0,0 +1,546 @@
+// File: src/ceremony.rs
+//! A dealer–participant protocol for distributing Feldman VSS shares.
+//!
+//! A ceremony runs over one connection per participant:
+//!
+//! 1. the participant sends [`Message::Hello`] with its index and X25519 key;
+//! 2. the dealer replies with [`Message::Parameters`], then broadcasts the
+//!    dealing's [`Message::Commitments`] to everyone;
+//! 3. the dealer sends each participant its share sealed to its key in
+//!    [`Message::ShareDelivery`];
+//! 4. the participant checks the share against the commitments and answers
+//!    with [`Message::Ack`] or [`Message::Complaint`].
+//!
+//! Messages are encoded like the other binary artifacts (magic, version, then
+//! fields) and framed on the wire with a u32 length. [`Connection`] is a
+//! reference transport over TCP; [`deal`] and [`participate`] run the two
+//! sides of a ceremony over it, and [`deal_over`] and [`participate_over`]
+//! over any [`Transport`], such as the encrypted
+//! [`crate::noise::NoiseConnection`]. [`Participant`] is the participant's
+//! side without I/O, for browsers joining over a WebSocket.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use ed25519_dalek::VerifyingKey;
+use num_bigint::BigUint;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::commitment::CommitmentDocument;
+use crate::ecies::EncryptedShare;
+use crate::encoding::{
+    byte_width, padded, put_biguint, put_bytes, put_bytes_u32, take_biguint, take_bytes,
+    take_bytes_u32, take_u32,
+};
+use crate::error::{Error, Result};
+use crate::params::GroupParameters;
+use crate::serialized::Scheme;
+use crate::vss::{FeldmanVSS, Share};
+
+const MAGIC: &[u8; 3] = b"SHN";
+const MESSAGE_VERSION: u8 = 2;
+/// Frames larger than this are rejected before any allocation.
+const MAX_FRAME: usize = 1 << 20;
+
+const TAG_HELLO: u8 = 1;
+const TAG_PARAMETERS: u8 = 2;
+const TAG_SHARE_DELIVERY: u8 = 3;
+const TAG_COMMITMENTS: u8 = 4;
+const TAG_ACK: u8 = 5;
+const TAG_COMPLAINT: u8 = 6;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    /// A participant announces its share index and encryption key.
+    Hello { index: u32, key: [u8; 32] },
+    /// The dealer announces the group and sharing parameters of the session.
+    Parameters {
+        session: Vec<u8>,
+        group: GroupParameters,
+        threshold: u32,
+        total: u32,
+    },
+    /// A participant's share, sealed to the key from its `Hello`.
+    ShareDelivery(EncryptedShare),
+    /// The commitments of the dealing, broadcast to every participant.
+    Commitments(CommitmentDocument),
+    /// The participant's share matched the commitments.
+    Ack { index: u32 },
+    /// The participant rejects its share.
+    Complaint { index: u32, reason: String },
+}
+
+impl Message {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(MESSAGE_VERSION);
+        match self {
+            Message::Hello { index, key } => {
+                out.push(TAG_HELLO);
+                out.extend_from_slice(&index.to_be_bytes());
+                out.extend_from_slice(key);
+            }
+            Message::Parameters {
+                session,
+                group,
+                threshold,
+                total,
+            } => {
+                out.push(TAG_PARAMETERS);
+                put_bytes(&mut out, session);
+                // q and g at the width of p, like every encoding that carries p
+                let width = byte_width(&group.p);
+                put_biguint(&mut out, &group.p);
+                put_bytes(&mut out, &padded(&group.q, width));
+                put_bytes(&mut out, &padded(&group.g, width));
+                out.extend_from_slice(&threshold.to_be_bytes());
+                out.extend_from_slice(&total.to_be_bytes());
+            }
+            Message::ShareDelivery(share) => {
+                out.push(TAG_SHARE_DELIVERY);
+                out.extend_from_slice(&share.recipient);
+                out.extend_from_slice(&share.ephemeral);
+                put_bytes_u32(&mut out, &share.ciphertext);
+            }
+            Message::Commitments(document) => {
+                out.push(TAG_COMMITMENTS);
+                out.extend_from_slice(&document.to_bytes());
+            }
+            Message::Ack { index } => {
+                out.push(TAG_ACK);
+                out.extend_from_slice(&index.to_be_bytes());
+            }
+            Message::Complaint { index, reason } => {
+                out.push(TAG_COMPLAINT);
+                out.extend_from_slice(&index.to_be_bytes());
+                put_bytes(&mut out, reason.as_bytes());
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if version != MESSAGE_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let (&tag, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        let message = match tag {
+            TAG_HELLO => Message::Hello {
+                index: take_u32(&mut input)?,
+                key: take_key(&mut input)?,
+            },
+            TAG_PARAMETERS => Message::Parameters {
+                session: take_bytes(&mut input)?.to_vec(),
+                group: GroupParameters {
+                    p: take_biguint(&mut input)?,
+                    q: take_biguint(&mut input)?,
+                    g: take_biguint(&mut input)?,
+                },
+                threshold: take_u32(&mut input)?,
+                total: take_u32(&mut input)?,
+            },
+            TAG_SHARE_DELIVERY => Message::ShareDelivery(EncryptedShare {
+                recipient: take_key(&mut input)?,
+                ephemeral: take_key(&mut input)?,
+                ciphertext: take_bytes_u32(&mut input)?.to_vec(),
+            }),
+            TAG_COMMITMENTS => {
+                let document = CommitmentDocument::from_bytes(input)?;
+                input = &[];
+                Message::Commitments(document)
+            }
+            TAG_ACK => Message::Ack {
+                index: take_u32(&mut input)?,
+            },
+            TAG_COMPLAINT => Message::Complaint {
+                index: take_u32(&mut input)?,
+                reason: String::from_utf8(take_bytes(&mut input)?.to_vec())
+                    .map_err(|_| Error::MalformedShare)?,
+            },
+            _ => return Err(Error::InvalidEncoding("unknown ceremony message")),
+        };
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(message)
+    }
+}
+
+fn take_key(input: &mut &[u8]) -> Result<[u8; 32]> {
+    if input.len() < 32 {
+        return Err(Error::MalformedShare);
+    }
+    let (key, rest) = input.split_at(32);
+    *input = rest;
+    Ok(key.try_into().expect("split at 32 bytes"))
+}
+
+/// A channel carrying ceremony [`Message`]s.
+pub trait Transport {
+    fn send(&mut self, message: &Message) -> Result<()>;
+
+    fn receive(&mut self) -> Result<Message>;
+
+    /// The X25519 key the peer authenticated with, for channels that
+    /// authenticate; a participant's `Hello` key must then match it.
+    fn peer_key(&self) -> Option<[u8; 32]> {
+        None
+    }
+}
+
+/// A length-framed stream of [`Message`]s over TCP.
+pub struct Connection {
+    stream: TcpStream,
+}
+
+impl Connection {
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<Self> {
+        Ok(Connection {
+            stream: TcpStream::connect(addr)?,
+        })
+    }
+
+    pub fn new(stream: TcpStream) -> Self {
+        Connection { stream }
+    }
+}
+
+impl Transport for Connection {
+    fn send(&mut self, message: &Message) -> Result<()> {
+        let bytes = message.to_bytes();
+        self.stream.write_all(&(bytes.len() as u32).to_be_bytes())?;
+        self.stream.write_all(&bytes)?;
+        Ok(self.stream.flush()?)
+    }
+
+    fn receive(&mut self) -> Result<Message> {
+        let mut len = [0u8; 4];
+        self.stream.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_FRAME {
+            return Err(Error::InvalidEncoding("ceremony message too large"));
+        }
+        let mut bytes = vec![0u8; len];
+        self.stream.read_exact(&mut bytes)?;
+        Message::from_bytes(&bytes)
+    }
+}
+
+/// Each participant's answer to a dealing, in index order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reply {
+    Ack,
+    Complaint(String),
+}
+
+/// Runs the dealer's side: accepts one connection per participant, deals
+/// `secret` to them and returns their replies.
+pub fn deal(
+    listener: &TcpListener,
+    group: &GroupParameters,
+    threshold: usize,
+    total: usize,
+    secret: &BigUint,
+    session: &[u8],
+) -> Result<Vec<Reply>> {
+    let accept = || Ok(Connection::new(listener.accept()?.0));
+    deal_over(accept, group, threshold, total, secret, session)
+}
+
+/// Runs the dealer's side over connections obtained from `accept`, one per
+/// participant.
+pub fn deal_over<T: Transport>(
+    mut accept: impl FnMut() -> Result<T>,
+    group: &GroupParameters,
+    threshold: usize,
+    total: usize,
+    secret: &BigUint,
+    session: &[u8],
+) -> Result<Vec<Reply>> {
+    let mut vss = group.vss(threshold, total)?;
+    let mut participants: Vec<Option<(T, PublicKey)>> = (0..total).map(|_| None).collect();
+    while participants.iter().any(Option::is_none) {
+        let mut connection = accept()?;
+        let Message::Hello { index, key } = connection.receive()? else {
+            return Err(Error::InvalidEncoding("expected a hello message"));
+        };
+        if connection.peer_key().is_some_and(|peer| peer != key) {
+            return Err(Error::InvalidParameters(
+                "hello key differs from the authenticated channel key",
+            ));
+        }
+        let slot = (index as usize)
+            .checked_sub(1)
+            .and_then(|i| participants.get_mut(i))
+            .ok_or(Error::InvalidParameters("participant index out of range"))?;
+        if slot.is_some() {
+            return Err(Error::InvalidParameters("participant index already taken"));
+        }
+        *slot = Some((connection, PublicKey::from(key)));
+    }
+
+    let (shares, commitments) = vss
+        .split_secret(secret)
+        .map_err(|_| Error::InvalidParameters("secret must be less than q"))?;
+    let parameters = Message::Parameters {
+        session: session.to_vec(),
+        group: group.clone(),
+        threshold: threshold as u32,
+        total: total as u32,
+    };
+    let broadcast = Message::Commitments(vss.commitment_document(&commitments));
+    let mut participants: Vec<_> = participants.into_iter().flatten().collect();
+    for ((connection, key), share) in participants.iter_mut().zip(&shares) {
+        let sealed = EncryptedShare::seal(&vss.serialize_share(share), key)?;
+        connection.send(&parameters)?;
+        connection.send(&broadcast)?;
+        connection.send(&Message::ShareDelivery(sealed))?;
+    }
+
+    participants
+        .iter_mut()
+        .map(|(connection, _)| match connection.receive()? {
+            Message::Ack { .. } => Ok(Reply::Ack),
+            Message::Complaint { reason, .. } => Ok(Reply::Complaint(reason)),
+            _ => Err(Error::InvalidEncoding("expected an ack or complaint")),
+        })
+        .collect()
+}
+
+/// Runs a participant's side: announces `index` and `key` to the dealer,
+/// checks the delivered share against the commitments (and their signature,
+/// when `dealer` is given) and acknowledges or complains.
+pub fn participate(
+    addr: impl ToSocketAddrs,
+    index: u32,
+    key: &StaticSecret,
+    dealer: Option<&VerifyingKey>,
+) -> Result<(Share, CommitmentDocument)> {
+    participate_over(Connection::connect(addr)?, index, key, dealer)
+}
+
+/// Runs a participant's side over an established connection to the dealer.
+pub fn participate_over(
+    mut connection: impl Transport,
+    index: u32,
+    key: &StaticSecret,
+    dealer: Option<&VerifyingKey>,
+) -> Result<(Share, CommitmentDocument)> {
+    let mut participant = Participant::new(index, key.clone(), dealer.copied());
+    connection.send(&participant.hello())?;
+    loop {
+        if let Some(reply) = participant.receive(connection.receive()?)? {
+            connection.send(&reply)?;
+            return participant.finish();
+        }
+    }
+}
+
+/// A participant's side of a ceremony without I/O, for callers that move
+/// the messages themselves, such as a browser over a WebSocket.
+pub struct Participant {
+    index: u32,
+    key: StaticSecret,
+    dealer: Option<VerifyingKey>,
+    parameters: Option<(GroupParameters, u32, u32)>,
+    commitments: Option<CommitmentDocument>,
+    outcome: Option<Result<(Share, CommitmentDocument)>>,
+}
+
+impl Participant {
+    /// With `dealer`, the commitments must carry that dealer's signature.
+    pub fn new(index: u32, key: StaticSecret, dealer: Option<VerifyingKey>) -> Self {
+        Participant {
+            index,
+            key,
+            dealer,
+            parameters: None,
+            commitments: None,
+            outcome: None,
+        }
+    }
+
+    /// The first message to send.
+    pub fn hello(&self) -> Message {
+        Message::Hello {
+            index: self.index,
+            key: PublicKey::from(&self.key).to_bytes(),
+        }
+    }
+
+    /// Takes the dealer's next message. Once the share has arrived, returns
+    /// the ack or complaint to send back, and [`Participant::finish`] gives
+    /// the outcome.
+    pub fn receive(&mut self, message: Message) -> Result<Option<Message>> {
+        match (message, &self.parameters, &self.commitments) {
+            (
+                Message::Parameters {
+                    group,
+                    threshold,
+                    total,
+                    ..
+                },
+                None,
+                _,
+            ) => {
+                self.parameters = Some((group, threshold, total));
+                Ok(None)
+            }
+            (Message::Commitments(document), Some(_), None) => {
+                self.commitments = Some(document);
+                Ok(None)
+            }
+            (Message::ShareDelivery(sealed), Some((group, threshold, total)), Some(document))
+                if self.outcome.is_none() =>
+            {
+                let checked =
+                    check_parameters(group, *threshold, *total, document).and_then(|()| {
+                        check_delivery(
+                            self.index,
+                            document,
+                            &sealed,
+                            &self.key,
+                            self.dealer.as_ref(),
+                        )
+                    });
+                let reply = match &checked {
+                    Ok(_) => Message::Ack { index: self.index },
+                    Err(err) => Message::Complaint {
+                        index: self.index,
+                        reason: err.to_string(),
+                    },
+                };
+                self.outcome = Some(checked.map(|share| (share, document.clone())));
+                Ok(Some(reply))
+            }
+            (_, None, _) => Err(Error::InvalidEncoding("expected the session parameters")),
+            (_, Some(_), None) => Err(Error::InvalidEncoding("expected the commitments")),
+            _ => Err(Error::InvalidEncoding("expected a share delivery")),
+        }
+    }
+
+    /// The checked share and the commitments, or why the share was rejected.
+    pub fn finish(self) -> Result<(Share, CommitmentDocument)> {
+        self.outcome.unwrap_or(Err(Error::InvalidParameters(
+            "the share has not arrived yet",
+        )))
+    }
+}
+
+/// Checks the announced group and that the commitments are for it.
+fn check_parameters(
+    group: &GroupParameters,
+    threshold: u32,
+    total: u32,
+    document: &CommitmentDocument,
+) -> Result<()> {
+    group.validate()?;
+    if (&document.p, &document.q, &document.g) != (&group.p, &group.q, &group.g)
+        || (document.threshold, document.total) != (threshold as usize, total as usize)
+    {
+        return Err(Error::InconsistentShares(
+            "commitments do not match the session parameters",
+        ));
+    }
+    Ok(())
+}
+
+/// Opens the delivered share and checks it against the commitments.
+fn check_delivery(
+    index: u32,
+    document: &CommitmentDocument,
+    sealed: &EncryptedShare,
+    key: &StaticSecret,
+    dealer: Option<&VerifyingKey>,
+) -> Result<Share> {
+    if let Some(dealer) = dealer {
+        document.verify_signature(dealer)?;
+    }
+    let serialized = sealed.open(key)?;
+    if !matches!(&serialized.scheme, Scheme::Feldman { p, .. } if *p == document.p) {
+        return Err(Error::InconsistentShares("share is not from this group"));
+    }
+    if serialized.x != BigUint::from(index) {
+        return Err(Error::InconsistentShares("share has another index"));
+    }
+    let share = Share {
+        id: serialized.x,
+        value: BigUint::from_bytes_be(&serialized.value),
+    };
+    if !document.verify_share(&share) {
+        return Err(Error::InconsistentShares(
+            "share does not match the commitments",
+        ));
+    }
+    Ok(share)
+}
+
+/// Builds a [`FeldmanVSS`] able to reconstruct from shares received in a ceremony.
+pub fn reconstructor(document: &CommitmentDocument) -> FeldmanVSS {
+    FeldmanVSS::new(
+        document.p.clone(),
+        document.q.clone(),
+        document.g.clone(),
+        document.threshold,
+        document.total,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_ceremony_over_tcp() {
+        let message = Message::Complaint {
+            index: 2,
+            reason: "bad share".into(),
+        };
+        assert_eq!(Message::from_bytes(&message.to_bytes()).unwrap(), message);
+        let delivery = Message::ShareDelivery(EncryptedShare {
+            recipient: [1; 32],
+            ephemeral: [2; 32],
+            ciphertext: vec![3; 70_000],
+        });
+        assert_eq!(Message::from_bytes(&delivery.to_bytes()).unwrap(), delivery);
+
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let parameters = Message::Parameters {
+            session: b"session".to_vec(),
+            group: group.clone(),
+            threshold: 2,
+            total: 3,
+        };
+        let bytes = parameters.to_bytes();
+        // p, then q and g padded to its 32 bytes
+        assert_eq!(bytes.len(), 5 + (2 + 7) + 3 * (2 + 32) + 8);
+        assert_eq!(Message::from_bytes(&bytes).unwrap(), parameters);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let participants: Vec<_> = (1..=3u32)
+            .map(|index| {
+                std::thread::spawn(move || {
+                    let key = StaticSecret::random_from_rng(OsRng);
+                    participate(addr, index, &key, None).unwrap()
+                })
+            })
+            .collect();
+
+        let secret = BigUint::from(1234567u32);
+        let replies = deal(&listener, &group, 2, 3, &secret, b"session").unwrap();
+        assert_eq!(replies, vec![Reply::Ack; 3]);
+
+        let received: Vec<_> = participants
+            .into_iter()
+            .map(|p| p.join().unwrap())
+            .collect();
+        let vss = reconstructor(&received[0].1);
+        let shares = [received[2].0.clone(), received[0].0.clone()];
+        assert_eq!(vss.reconstruct_secret(&shares).unwrap(), secret);
+    }
+}
@@ -0,0 +1,148 @@
+// File: src/checkpoint.rs
+//! Integrity-protected checkpoints of ceremonies in progress.
+//!
+//! A DKG runs over several rounds, and a coordinator or participant that
+//! crashes between them would otherwise have to restart from round one.
+//! [`crate::coordinator::Coordinator::checkpoint`] and
+//! [`crate::dkg::Party::checkpoint`] export the full intermediate state
+//! (messages received, complaints, the current round) as JSON tagged with
+//! HMAC-SHA256 under a key the caller keeps, and `resume` picks the
+//! ceremony up again, refusing a checkpoint that was altered or sealed
+//! under another key with [`Error::CheckpointTampered`].
+//!
+//! Refresh and reshare need no checkpoints: their only state is the
+//! [`crate::refresh::RefreshMessage`]s and [`crate::reshare::ReshareMessage`]s
+//! already received, which are self-contained and can simply be kept.
+//!
+//! The tag protects integrity only. A party's checkpoint contains its
+//! polynomial, so store it as carefully as a share.
+
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::encoding::{hex, unhex};
+use crate::error::{Error, Result};
+
+const CHECKPOINT_VERSION: u8 = 1;
+
+/// The JSON envelope around a ceremony's state.
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    version: u8,
+    kind: String,
+    state: Value,
+    mac: String,
+}
+
+/// Serializes `state` as a checkpoint of the given `kind`, tagged under `key`.
+pub(crate) fn seal<T: Serialize>(kind: &str, state: &T, key: &[u8]) -> String {
+    let state = serde_json::to_value(state).expect("ceremony state serializes");
+    let envelope = Envelope {
+        version: CHECKPOINT_VERSION,
+        kind: kind.into(),
+        mac: hex(&tag(key, kind, &state).finalize().into_bytes()),
+        state,
+    };
+    serde_json::to_string_pretty(&envelope).expect("checkpoints serialize")
+}
+
+/// Checks a checkpoint's tag under `key` and returns the state it holds.
+pub(crate) fn open<T: DeserializeOwned>(text: &str, kind: &str, key: &[u8]) -> Result<T> {
+    let envelope: Envelope = serde_json::from_str(text)
+        .map_err(|_| Error::InvalidEncoding("invalid checkpoint JSON"))?;
+    if envelope.version != CHECKPOINT_VERSION {
+        return Err(Error::UnsupportedVersion(envelope.version));
+    }
+    if envelope.kind != kind {
+        return Err(Error::InvalidParameters("checkpoint is of another kind"));
+    }
+    tag(key, kind, &envelope.state)
+        .verify_slice(&unhex(&envelope.mac)?)
+        .map_err(|_| Error::CheckpointTampered)?;
+    serde_json::from_value(envelope.state)
+        .map_err(|_| Error::InvalidEncoding("invalid checkpoint state"))
+}
+
+/// HMAC-SHA256 over the kind and the compact JSON of the state, which
+/// reads back with the same key order it was written with.
+fn tag(key: &[u8], kind: &str, state: &Value) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(kind.as_bytes());
+    mac.update(&[0]);
+    mac.update(&serde_json::to_vec(state).expect("JSON values serialize"));
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::coordinator::{Coordinator, Phase};
+    use crate::dkg::{self, Party};
+    use crate::params::GroupParameters;
+
+    #[test]
+    fn test_resume_interrupted_dkg() {
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let key = b"checkpoint key";
+        let now = Instant::now();
+        let timeout = Duration::from_secs(60);
+        let mut coordinator = Coordinator::new(&group, 2, 3, timeout, now).unwrap();
+        let mut parties: Vec<Party> = (1..=3)
+            .map(|index| Party::new(&group, index, 2, 3).unwrap())
+            .collect();
+        let broadcasts: Vec<_> = parties.iter().map(Party::commitments).collect();
+        for broadcast in &broadcasts {
+            coordinator.submit_commitments(broadcast, now).unwrap();
+        }
+
+        // The coordinator crashes after the first round
+        let saved = coordinator.checkpoint(key);
+        let mut coordinator = Coordinator::resume(&saved, key, now).unwrap();
+        assert_eq!(coordinator.phase(), &Phase::Verdicts);
+        assert_eq!(coordinator.qualified(), [1, 2, 3]);
+
+        let dealt: Vec<_> = parties.iter().flat_map(Party::shares).collect();
+        for party in &mut parties {
+            for broadcast in &broadcasts {
+                party.receive_commitments(broadcast);
+            }
+            let index = party.index();
+            for share in dealt.iter().filter(|share| share.to == index) {
+                party.receive_share(share).unwrap();
+            }
+        }
+        // Party 2 crashes holding its shares, before sending its verdict
+        parties[1] = Party::resume(&parties[1].checkpoint(key), key).unwrap();
+        for party in &parties {
+            coordinator
+                .submit_verdict(party.index(), &party.missing(1..=3), now)
+                .unwrap();
+        }
+        let transcript = coordinator.transcript().unwrap().clone();
+        let resumed = Coordinator::resume(&coordinator.checkpoint(key), key, now).unwrap();
+        assert_eq!(resumed.transcript(), Some(&transcript));
+
+        let shares: Vec<_> = parties[1..]
+            .iter()
+            .map(|party| party.finish(&transcript).unwrap())
+            .collect();
+        let secret = dkg::combine(&group, &shares);
+        assert_eq!(group.g.modpow(&secret, &group.p), transcript.public_key);
+
+        assert!(matches!(
+            Coordinator::resume(&saved, b"other key", now),
+            Err(Error::CheckpointTampered)
+        ));
+        let tampered = saved.replacen("\"Verdicts\"", "\"Justifications\"", 1);
+        assert!(matches!(
+            Coordinator::resume(&tampered, key, now),
+            Err(Error::CheckpointTampered)
+        ));
+        assert!(Party::resume(&saved, key).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+// File: src/chunked.rs
+//! Chunked share streams with per-chunk integrity tags.
+//!
+//! [`crate::gf256::split_stream`] writes bare payloads, so a corrupted or
+//! mismatched share is only noticed, if at all, after the whole secret has
+//! been written out. [`split_chunked`] instead frames every share as a
+//! header followed by length-prefixed chunks, each carrying an HMAC-SHA256
+//! tag of the plaintext chunk, its position and whether it is the last one.
+//! The HMAC key is random per dealing and shared in the headers like the
+//! secret, so only a quorum can check the tags and they reveal nothing about
+//! the plaintext.
+//!
+//! [`StreamCombiner`] reads the share streams in lockstep, recovering the
+//! key first and then one chunk at a time, and stops at the first chunk
+//! whose shares disagree or whose tag does not match, before writing it.
+//! Memory use is one chunk per share however large the secret, and a stream
+//! cut short is refused because the final chunk is tagged as such.
+//!
+//! Layout of each share stream: `SSC`, version, reduction polynomial (u16),
+//! x-coordinate, threshold, the share of the 32-byte key, then records of a
+//! u32 length, the payload and the 32-byte tag, ending with an empty record.
+
+use std::io::{Read, Write};
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::error::{Error, Result};
+use crate::gf256::{self, fill, Gf256};
+use crate::progress::Progress;
+
+/// Plaintext bytes per chunk.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+const MAGIC: &[u8; 3] = b"SSC";
+const CHUNKED_VERSION: u8 = 1;
+const KEY_LEN: usize = 32;
+const TAG_LEN: usize = 32;
+const HEADER_LEN: usize = MAGIC.len() + 5 + KEY_LEN;
+
+/// Splits `secret` into one chunked share stream per x-coordinate, writing
+/// to `writers` in the same order. Reports the bytes read to `progress` and
+/// stops if it is cancelled. Returns the number of bytes split.
+pub fn split_chunked<R: Read, W: Write>(
+    field: Gf256,
+    secret: &mut R,
+    threshold: usize,
+    xs: &[u8],
+    writers: &mut [W],
+    rng: &mut impl RngCore,
+    progress: &mut Progress,
+) -> Result<u64> {
+    if writers.len() != xs.len() {
+        return Err(Error::InvalidParameters("need one writer per x-coordinate"));
+    }
+    let mut key = [0u8; KEY_LEN];
+    rng.fill_bytes(&mut key);
+    let key_shares = gf256::split(field, &key, threshold, xs, rng)?;
+    for ((writer, &x), key_share) in writers.iter_mut().zip(xs).zip(key_shares) {
+        let mut header = MAGIC.to_vec();
+        header.push(CHUNKED_VERSION);
+        header.extend_from_slice(&field.poly().to_be_bytes());
+        header.extend_from_slice(&[x, threshold as u8]);
+        header.extend_from_slice(&key_share);
+        writer.write_all(&header)?;
+    }
+
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut done = 0u64;
+    for index in 0.. {
+        progress.check()?;
+        let read = fill(secret, &mut buffer)?;
+        let chunk = &buffer[..read];
+        let tag = chunk_tag(&key, index, read == 0, chunk);
+        let payloads = if read == 0 {
+            vec![Vec::new(); xs.len()]
+        } else {
+            gf256::split(field, chunk, threshold, xs, rng)?
+        };
+        for (writer, payload) in writers.iter_mut().zip(payloads) {
+            writer.write_all(&(read as u32).to_be_bytes())?;
+            writer.write_all(&payload)?;
+            writer.write_all(&tag)?;
+        }
+        if read == 0 {
+            break;
+        }
+        done += read as u64;
+        progress.step(done, None)?;
+    }
+    Ok(done)
+}
+
+/// Recovers a secret from chunked share streams, one chunk at a time.
+pub struct StreamCombiner<R> {
+    field: Gf256,
+    shares: Vec<(u8, R)>,
+    key: Vec<u8>,
+    index: u64,
+    finished: bool,
+}
+
+impl<R: Read> StreamCombiner<R> {
+    /// Reads the stream headers and recovers the tag key. At least the
+    /// dealing's threshold of streams are needed, and all of them are used.
+    pub fn new(readers: Vec<R>) -> Result<Self> {
+        let mut field = None;
+        let mut shares = Vec::with_capacity(readers.len());
+        let mut key_shares = Vec::with_capacity(readers.len());
+        for mut reader in readers {
+            let mut header = [0u8; HEADER_LEN];
+            if fill(&mut reader, &mut header)? != HEADER_LEN {
+                return Err(Error::MalformedShare);
+            }
+            let rest = header
+                .strip_prefix(MAGIC.as_slice())
+                .ok_or(Error::MalformedShare)?;
+            if rest[0] != CHUNKED_VERSION {
+                return Err(Error::UnsupportedVersion(rest[0]));
+            }
+            let poly = u16::from_be_bytes([rest[1], rest[2]]);
+            let (x, threshold) = (rest[3], rest[4] as usize);
+            if *field.get_or_insert((poly, threshold)) != (poly, threshold) {
+                return Err(Error::InconsistentShares(
+                    "share streams come from different dealings",
+                ));
+            }
+            key_shares.push((x, rest[5..].to_vec()));
+            shares.push((x, reader));
+        }
+        let (poly, threshold) = field.ok_or(Error::InsufficientShares)?;
+        if shares.len() < threshold {
+            return Err(Error::InsufficientShares);
+        }
+        let points: Vec<(u8, &[u8])> = key_shares
+            .iter()
+            .map(|(x, share)| (*x, share.as_slice()))
+            .collect();
+        let field = Gf256::new(poly);
+        let key = gf256::interpolate(field, &points, 0)?;
+        Ok(StreamCombiner {
+            field,
+            shares,
+            key,
+            index: 0,
+            finished: false,
+        })
+    }
+
+    /// The next verified chunk of the secret, or `None` after the last one.
+    pub fn next_chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.finished {
+            return Ok(None);
+        }
+        let mut len = None;
+        let mut records = Vec::with_capacity(self.shares.len());
+        for (x, reader) in &mut self.shares {
+            let mut prefix = [0u8; 4];
+            if fill(reader, &mut prefix)? != prefix.len() {
+                return Err(Error::InconsistentShares(
+                    "share stream ended before its final chunk",
+                ));
+            }
+            let size = u32::from_be_bytes(prefix) as usize;
+            if size > CHUNK_SIZE || *len.get_or_insert(size) != size {
+                return Err(Error::InconsistentShares("share chunk lengths differ"));
+            }
+            let mut record = vec![0u8; size + TAG_LEN];
+            if fill(reader, &mut record)? != record.len() {
+                return Err(Error::InconsistentShares(
+                    "share stream ended before its final chunk",
+                ));
+            }
+            records.push((*x, record));
+        }
+        let size = len.unwrap_or(0);
+        let tag = &records[0].1[size..];
+        if records.iter().any(|(_, record)| &record[size..] != tag) {
+            return Err(Error::InconsistentShares(
+                "shares disagree on a chunk's integrity tag",
+            ));
+        }
+
+        let chunk = if size == 0 {
+            Vec::new()
+        } else {
+            let points: Vec<(u8, &[u8])> = records
+                .iter()
+                .map(|(x, record)| (*x, &record[..size]))
+                .collect();
+            gf256::interpolate(self.field, &points, 0)?
+        };
+        chunk_mac(&self.key, self.index, size == 0, &chunk)
+            .verify_slice(tag)
+            .map_err(|_| Error::InconsistentShares("chunk does not match its integrity tag"))?;
+        self.index += 1;
+        if size == 0 {
+            self.finished = true;
+            return Ok(None);
+        }
+        Ok(Some(chunk))
+    }
+
+    /// Writes every remaining chunk to `out` as it is verified. Reports the
+    /// bytes written to `progress` and stops if it is cancelled. Returns the
+    /// number of bytes written.
+    pub fn combine<W: Write>(&mut self, out: &mut W, progress: &mut Progress) -> Result<u64> {
+        let mut done = 0u64;
+        loop {
+            progress.check()?;
+            match self.next_chunk()? {
+                Some(chunk) => {
+                    out.write_all(&chunk)?;
+                    done += chunk.len() as u64;
+                    progress.step(done, None)?;
+                }
+                None => return Ok(done),
+            }
+        }
+    }
+}
+
+fn chunk_mac(key: &[u8], index: u64, last: bool, chunk: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&index.to_be_bytes());
+    mac.update(&[last as u8]);
+    mac.update(chunk);
+    mac
+}
+
+fn chunk_tag(key: &[u8], index: u64, last: bool, chunk: &[u8]) -> [u8; TAG_LEN] {
+    chunk_mac(key, index, last, chunk)
+        .finalize()
+        .into_bytes()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_chunked_streams() {
+        let field = Gf256::RIJNDAEL;
+        let secret: Vec<u8> = (0..2 * CHUNK_SIZE + 99).map(|i| (i % 253) as u8).collect();
+        let mut streams = vec![Vec::new(); 4];
+        let len = split_chunked(
+            field,
+            &mut &secret[..],
+            3,
+            &[1, 2, 3, 4],
+            &mut streams,
+            &mut thread_rng(),
+            &mut Progress::new(),
+        )
+        .unwrap();
+        assert_eq!(len, secret.len() as u64);
+
+        let mut combiner =
+            StreamCombiner::new(vec![&streams[3][..], &streams[0][..], &streams[2][..]]).unwrap();
+        let mut recovered = Vec::new();
+        combiner
+            .combine(&mut recovered, &mut Progress::new())
+            .unwrap();
+        assert_eq!(recovered, secret);
+
+        // A flipped byte in the second chunk of one share: the first chunk is
+        // still emitted, the second is refused
+        let mut corrupt = streams[1].clone();
+        corrupt[HEADER_LEN + 4 + CHUNK_SIZE + TAG_LEN + 4 + 10] ^= 1;
+        let mut combiner =
+            StreamCombiner::new(vec![&corrupt[..], &streams[0][..], &streams[2][..]]).unwrap();
+        assert_eq!(
+            combiner.next_chunk().unwrap().unwrap(),
+            secret[..CHUNK_SIZE]
+        );
+        assert!(matches!(
+            combiner.next_chunk(),
+            Err(Error::InconsistentShares(_))
+        ));
+
+        // Dropping the final record is detected as truncation
+        let cut: Vec<&[u8]> = streams[..3]
+            .iter()
+            .map(|stream| &stream[..stream.len() - 4 - TAG_LEN])
+            .collect();
+        let mut combiner = StreamCombiner::new(cut).unwrap();
+        assert!(combiner
+            .combine(&mut Vec::new(), &mut Progress::new())
+            .is_err());
+
+        assert!(matches!(
+            StreamCombiner::new(vec![&streams[0][..], &streams[1][..]]),
+            Err(Error::InsufficientShares)
+        ));
+    }
+}
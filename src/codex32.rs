@@ -0,0 +1,462 @@
+// File: src/codex32.rs
+//! codex32 (BIP-93) strings for splitting BIP-32 master seeds.
+//!
+//! Shares are bech32-alphabet strings such as `ms12namea320...`: a threshold
+//! digit, a four-character identifier, a share index and the seed data,
+//! followed by a BCH checksum over GF(32) that can be verified by hand.
+//! Sharing works character-wise, so the checksum survives interpolation.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use rand::{thread_rng, RngCore};
+
+use crate::error::{Error, Result};
+
+const HRP: &str = "ms";
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const SHORT_CHECKSUM: Params = Params {
+    generator: b"em3gqeeelmcss",
+    target: b"secretshare32",
+};
+const LONG_CHECKSUM: Params = Params {
+    generator: b"02e6fe4xh4x9kyh",
+    target: b"secretshare32ex",
+};
+/// Seed lengths that fit the short (48 to 93 characters) and long (125 to
+/// 127 characters) string lengths BIP-93 allows.
+const SHORT_SEED_BYTES: RangeInclusive<usize> = 16..=44;
+const LONG_SEED_BYTES: RangeInclusive<usize> = 63..=64;
+/// Share indices other than the secret `s`, in the order they are assigned.
+const SHARE_INDICES: &[u8] = b"acdefghjklmnpqrtuvwxyz023456789";
+
+#[rustfmt::skip]
+const LOG: [u8; 32] = [
+     0,  0,  1, 14,  2, 28, 15, 22,  3,  5, 29, 26, 16,  7, 23, 11,
+     4, 25,  6, 10, 30, 13, 27, 21, 17, 18,  8, 19, 24,  9, 12, 20,
+];
+#[rustfmt::skip]
+const EXP: [u8; 31] = [
+     1,  2,  4,  8, 16,  9, 18, 13, 26, 29, 19, 15, 30, 21,  3,  6,
+    12, 24, 25, 27, 31, 23,  7, 14, 28, 17, 11, 22,  5, 10, 20,
+];
+
+/// A validated codex32 string.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Codex32String(String);
+
+impl Codex32String {
+    /// Encodes a seed (or share data) with the given header fields.
+    ///
+    /// `threshold` is 0 for an unshared seed or 2..=9; `share_index` must be
+    /// `'s'` for the seed itself.
+    pub fn from_seed(threshold: u8, id: &str, share_index: char, data: &[u8]) -> Result<Self> {
+        if threshold == 1 || threshold > 9 {
+            return Err(Error::InvalidParameters(
+                "threshold must be 0 or 2 through 9",
+            ));
+        }
+        if id.len() != 4 || !id.bytes().all(|c| to_fe(c).is_some()) {
+            return Err(Error::InvalidParameters(
+                "identifier must be four bech32 characters",
+            ));
+        }
+        if to_fe(share_index as u8).is_none() || (threshold == 0 && share_index != 's') {
+            return Err(Error::InvalidParameters("invalid share index"));
+        }
+        let params = if SHORT_SEED_BYTES.contains(&data.len()) {
+            SHORT_CHECKSUM
+        } else if LONG_SEED_BYTES.contains(&data.len()) {
+            LONG_CHECKSUM
+        } else {
+            return Err(Error::InvalidParameters(
+                "seed must be 16 to 44 or 63 to 64 bytes",
+            ));
+        };
+
+        let mut body = format!("{}{}{}", threshold, id.to_lowercase(), share_index);
+        body.extend(
+            bytes_to_fes(data)
+                .into_iter()
+                .map(|fe| CHARSET[fe as usize] as char),
+        );
+
+        let mut engine = Engine::new(params);
+        engine.input_str(&body);
+        for &c in params.target {
+            engine.input(to_fe(c).unwrap());
+        }
+        body.extend(
+            engine
+                .residue
+                .iter()
+                .map(|&fe| CHARSET[fe as usize] as char),
+        );
+
+        Ok(Codex32String(format!("{}1{}", HRP, body)))
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.body().as_bytes()[0] - b'0'
+    }
+
+    pub fn id(&self) -> &str {
+        &self.body()[1..5]
+    }
+
+    pub fn share_index(&self) -> char {
+        self.body().as_bytes()[5] as char
+    }
+
+    /// Returns the seed bytes, dropping the incomplete trailing group of bits.
+    pub fn data(&self) -> Vec<u8> {
+        let body = self.body();
+        let payload = &body[6..body.len() - self.checksum_len()];
+        fes_to_bytes(payload.bytes().map(|c| to_fe(c).unwrap()))
+    }
+
+    /// Interpolates the shares to produce the share with index `target`.
+    ///
+    /// Interpolating at `'s'` recovers the secret seed share.
+    pub fn interpolate_at(shares: &[Codex32String], target: char) -> Result<Codex32String> {
+        let first = shares.first().ok_or(Error::InsufficientShares)?;
+        let threshold = first.threshold() as usize;
+        if shares.len() < threshold.max(1) {
+            return Err(Error::InsufficientShares);
+        }
+        let target = to_fe(target.to_ascii_lowercase() as u8)
+            .ok_or(Error::InvalidParameters("invalid share index"))?;
+
+        let mut indices = Vec::with_capacity(shares.len());
+        for share in shares {
+            if share.0.len() != first.0.len() {
+                return Err(Error::InconsistentShares("share lengths differ"));
+            }
+            if share.threshold() != first.threshold() || share.id() != first.id() {
+                return Err(Error::InconsistentShares("share headers differ"));
+            }
+            let index = to_fe(share.share_index() as u8).unwrap();
+            if indices.contains(&index) {
+                return Err(Error::InconsistentShares("duplicate share index"));
+            }
+            indices.push(index);
+        }
+        if let Some(i) = indices.iter().position(|&x| x == target) {
+            return Ok(shares[i].clone());
+        }
+
+        let len = first.body().len();
+        let mut result = vec![0u8; len];
+        for (i, share) in shares.iter().enumerate() {
+            // Lagrange basis at `target`: prod (target - x_j) / (x_i - x_j)
+            let (num, den) = indices
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| i != j)
+                .fold((1u8, 1u8), |(num, den), (_, &xj)| {
+                    (mul(num, target ^ xj), mul(den, indices[i] ^ xj))
+                });
+            let coeff = div(num, den);
+            for (r, c) in result.iter_mut().zip(share.body().bytes()) {
+                *r ^= mul(coeff, to_fe(c).unwrap());
+            }
+        }
+
+        let body: String = result
+            .into_iter()
+            .map(|fe| CHARSET[fe as usize] as char)
+            .collect();
+        Ok(Codex32String(format!("{}1{}", HRP, body)))
+    }
+
+    fn body(&self) -> &str {
+        &self.0[HRP.len() + 1..]
+    }
+
+    fn checksum_len(&self) -> usize {
+        if self.0.len() > 93 {
+            LONG_CHECKSUM.target.len()
+        } else {
+            SHORT_CHECKSUM.target.len()
+        }
+    }
+}
+
+impl FromStr for Codex32String {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.to_ascii_lowercase();
+        if lower != s && s.to_ascii_uppercase() != s {
+            return Err(Error::InvalidEncoding("mixed-case codex32 string"));
+        }
+        let body = lower
+            .strip_prefix("ms1")
+            .ok_or(Error::InvalidEncoding("missing ms1 prefix"))?;
+        if body.bytes().any(|c| to_fe(c).is_none()) {
+            return Err(Error::InvalidEncoding("invalid bech32 character"));
+        }
+
+        let params = match lower.len() {
+            48..=93 => SHORT_CHECKSUM,
+            125..=127 => LONG_CHECKSUM,
+            _ => return Err(Error::InvalidEncoding("invalid codex32 string length")),
+        };
+        let mut engine = Engine::new(params);
+        engine.input_str(body);
+        if !engine.is_valid() {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        let payload_len = body.len() - 6 - params.target.len();
+        if (payload_len * 5) % 8 > 4 {
+            return Err(Error::InvalidEncoding("incomplete group in payload"));
+        }
+        let threshold = body.as_bytes()[0];
+        if !matches!(threshold, b'0' | b'2'..=b'9') {
+            return Err(Error::InvalidEncoding("invalid threshold"));
+        }
+        if threshold == b'0' && body.as_bytes()[5] != b's' {
+            return Err(Error::InvalidEncoding(
+                "unshared seed must use share index s",
+            ));
+        }
+        Ok(Codex32String(lower))
+    }
+}
+
+impl fmt::Display for Codex32String {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Splits a BIP-32 master seed into `count` codex32 shares.
+///
+/// Following BIP-93, the first `threshold - 1` shares are random and the
+/// rest are derived from them and the secret share `s` by interpolation.
+pub fn split_seed(
+    seed: &[u8],
+    threshold: u8,
+    id: &str,
+    count: usize,
+) -> Result<Vec<Codex32String>> {
+    if !(2..=9).contains(&threshold) || count < threshold as usize || count > SHARE_INDICES.len() {
+        return Err(Error::InvalidParameters(
+            "threshold must be 2 through 9 and at most the share count",
+        ));
+    }
+    let mut rng = thread_rng();
+    let mut base = vec![Codex32String::from_seed(threshold, id, 's', seed)?];
+    for &index in &SHARE_INDICES[..threshold as usize - 1] {
+        let mut data = vec![0u8; seed.len()];
+        rng.fill_bytes(&mut data);
+        base.push(Codex32String::from_seed(
+            threshold,
+            id,
+            index as char,
+            &data,
+        )?);
+    }
+
+    SHARE_INDICES[..count]
+        .iter()
+        .map(|&index| Codex32String::interpolate_at(&base, index as char))
+        .collect()
+}
+
+/// Recovers the master seed from at least `threshold` shares.
+pub fn recover_seed(shares: &[Codex32String]) -> Result<Vec<u8>> {
+    Ok(Codex32String::interpolate_at(shares, 's')?.data())
+}
+
+#[derive(Clone, Copy)]
+struct Params {
+    generator: &'static [u8],
+    target: &'static [u8],
+}
+
+/// Computes the residue of a GF(32) string modulo the checksum generator.
+struct Engine {
+    params: Params,
+    residue: Vec<u8>,
+}
+
+impl Engine {
+    fn new(params: Params) -> Self {
+        let mut residue = vec![0u8; params.target.len()];
+        *residue.last_mut().unwrap() = 1;
+        let mut engine = Engine { params, residue };
+        for c in HRP.bytes() {
+            engine.input(c >> 5);
+        }
+        engine.input(0);
+        for c in HRP.bytes() {
+            engine.input(c & 0x1f);
+        }
+        engine
+    }
+
+    fn input_str(&mut self, s: &str) {
+        for c in s.bytes() {
+            self.input(to_fe(c).unwrap());
+        }
+    }
+
+    fn input(&mut self, fe: u8) {
+        let top = self.residue[0];
+        self.residue.rotate_left(1);
+        *self.residue.last_mut().unwrap() = fe;
+        for (r, &g) in self.residue.iter_mut().zip(self.params.generator) {
+            *r ^= mul(to_fe(g).unwrap(), top);
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.residue
+            .iter()
+            .zip(self.params.target)
+            .all(|(&r, &t)| Some(r) == to_fe(t))
+    }
+}
+
+fn to_fe(c: u8) -> Option<u8> {
+    CHARSET
+        .iter()
+        .position(|&x| x == c.to_ascii_lowercase())
+        .map(|i| i as u8)
+}
+
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    EXP[(LOG[a as usize] as usize + LOG[b as usize] as usize) % 31]
+}
+
+fn div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    EXP[(31 + LOG[a as usize] as usize - LOG[b as usize] as usize) % 31]
+}
+
+fn bytes_to_fes(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity((data.len() * 8).div_ceil(5));
+    let (mut acc, mut bits) = (0u16, 0usize);
+    for &byte in data {
+        acc = (acc << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(((acc >> bits) & 0x1f) as u8);
+        }
+    }
+    if bits > 0 {
+        out.push(((acc << (5 - bits)) & 0x1f) as u8);
+    }
+    out
+}
+
+fn fes_to_bytes(fes: impl Iterator<Item = u8>) -> Vec<u8> {
+    let mut out = Vec::new();
+    let (mut acc, mut bits) = (0u16, 0usize);
+    for fe in fes {
+        acc = ((acc << 5) | fe as u16) & 0x1fff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_codex32_bip93_vectors() {
+        let seed: Codex32String = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            (seed.threshold(), seed.id(), seed.share_index()),
+            (0, "test", 's')
+        );
+        assert_eq!(hex(&seed.data()), "318c6318c6318c6318c6318c6318c631");
+        assert!("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxxxxmazxdp4sx5q"
+            .parse::<Codex32String>()
+            .is_err());
+
+        let shares: Vec<Codex32String> = [
+            "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM",
+            "MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        assert_eq!(
+            Codex32String::interpolate_at(&shares, 'd')
+                .unwrap()
+                .to_string(),
+            "ms12namedll4f8jlh4e5vdvuldlfxu2jhdnlsm97xvenrxeg"
+        );
+        assert_eq!(
+            hex(&recover_seed(&shares).unwrap()),
+            "d1808e096b35b209ca12132b264662a5"
+        );
+
+        let seed_bytes: Vec<u8> = (0..32u8).map(|i| 0xff - (i % 16) * 0x11).collect();
+        assert_eq!(
+            Codex32String::from_seed(0, "leet", 's', &seed_bytes)
+                .unwrap()
+                .to_string(),
+            "ms10leetsllhdmn9m42vcsamx24zrxgs3qrl7ahwvhw4fnzrhve25gvezzyqqtum9pgv99ycma"
+        );
+
+        let long: Codex32String = "MS100C8VSM32ZXFGUHPCHTLUPZRY9X8GF2TVDW0S3JN54KHCE6MUA7LQPZYGSFJD6AN074RXVCEMLH8WU3TK925ACDEFGHJKLMNPQRSTUVWXY06FHPV80UNDVARHRAK"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            hex(&long.data()),
+            "dc5423251cb87175ff8110c8531d0952d8d73e1194e95b5f19d6f9df7c01111104c9baecdfea8cccc677fb9ddc8aec5553b86e528bcadfdcc201c17c638c47e9"
+        );
+    }
+
+    #[test]
+    fn test_codex32_split_and_recover() {
+        for len in [16usize, 32, 64] {
+            let seed: Vec<u8> = (0..len as u8).collect();
+            let shares = split_seed(&seed, 3, "cash", 5).unwrap();
+            let reparsed: Vec<Codex32String> = shares
+                .iter()
+                .map(|s| s.to_string().parse().unwrap())
+                .collect();
+            assert_eq!(recover_seed(&reparsed[2..5]).unwrap(), seed);
+            assert!(recover_seed(&reparsed[..2]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_codex32_seed_lengths() {
+        for len in [16usize, 44, 63, 64] {
+            let seed = vec![0xa5; len];
+            let encoded = Codex32String::from_seed(0, "leet", 's', &seed).unwrap();
+            let parsed: Codex32String = encoded.to_string().parse().unwrap();
+            assert_eq!(parsed.data(), seed);
+        }
+        for len in [15usize, 45, 51, 62, 65] {
+            assert!(matches!(
+                Codex32String::from_seed(0, "leet", 's', &vec![0xa5; len]),
+                Err(Error::InvalidParameters(_))
+            ));
+        }
+    }
+}
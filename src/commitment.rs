@@ -0,0 +1,568 @@
+// File: src/commitment.rs
+//! Publishable Feldman commitment documents.
+//!
+//! A [`CommitmentDocument`] holds the group parameters and coefficient
+//! commitments of one dealing. Dealers sign and publish it separately from
+//! the shares; shareholders later parse it and check their share against it.
+//! Documents are published either ASCII-armored or as JSON with hex integers,
+//! commitments in JSON zero-padded to the width of `p`.
+//! [`CommitmentDocument::to_fixed_bytes`] is the canonical form for comparing
+//! and hashing documents across implementations: `q`, `g` and every
+//! commitment take exactly the byte width of `p`, whatever their value. Commitments here are
+//! elements of Z_p*, so there is no point compression to apply.
+
+use std::fmt;
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::encoding::{
+    armor, dearmor, from_fixed, hex, put_biguint, put_bytes, take_biguint, take_bytes, take_u32,
+    to_fixed, unhex,
+};
+use crate::error::{Error, Result};
+use crate::signing::DealerSignature;
+use crate::vss::{Commitment, FeldmanVSS, PublicKey, Share};
+
+const ARMOR_LABEL: &str = "FELDMAN COMMITMENTS";
+const MAGIC: &[u8; 3] = b"SHC";
+const FIXED_MAGIC: &[u8; 3] = b"SHF";
+const DOCUMENT_VERSION: u8 = 1;
+
+/// The JSON shape of a [`CommitmentDocument`]; integers are big-endian hex.
+#[derive(Serialize, Deserialize)]
+struct JsonDocument {
+    version: u8,
+    p: String,
+    q: String,
+    g: String,
+    threshold: usize,
+    total: usize,
+    commitments: Vec<String>,
+    signature: Option<JsonSignature>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSignature {
+    dealer: String,
+    signature: String,
+}
+
+/// The outcome of checking one share in [`CommitmentDocument::verify_all_detailed`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Valid,
+    /// The value does not match the commitments: the share is corrupted or
+    /// belongs to another dealing.
+    WrongValue,
+    /// The id is zero or not below q, so it names no holder.
+    MalformedId,
+    /// The value is not below the group order q.
+    OutOfRange,
+    /// The commitments evaluate outside the order-q subgroup at this id, so
+    /// the dealer published bad commitments.
+    SubgroupViolation,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Verdict::Valid => "valid",
+            Verdict::WrongValue => "value does not match the commitments",
+            Verdict::MalformedId => "id must be nonzero and below q",
+            Verdict::OutOfRange => "value must be below q",
+            Verdict::SubgroupViolation => "commitments leave the order-q subgroup",
+        })
+    }
+}
+
+/// One share's entry in a batch verification report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShareReport {
+    pub id: BigUint,
+    pub verdict: Verdict,
+}
+
+impl ShareReport {
+    pub fn is_valid(&self) -> bool {
+        self.verdict == Verdict::Valid
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentDocument {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+    pub threshold: usize,
+    pub total: usize,
+    pub commitments: Vec<BigUint>,
+    pub signature: Option<DealerSignature>,
+}
+
+impl CommitmentDocument {
+    pub fn commitment(&self) -> Commitment {
+        Commitment(self.commitments.clone())
+    }
+
+    /// The group public key `C_0` of the dealing.
+    pub fn public_key(&self) -> Option<PublicKey> {
+        self.commitment().public_key()
+    }
+
+    /// The public share `g^f(id)` of the holder at `id`.
+    pub fn public_share_for(&self, id: &BigUint) -> PublicKey {
+        self.commitment().public_share_for(id, &self.p, &self.q)
+    }
+
+    /// Signs the document as the dealer, replacing any earlier signature.
+    pub fn sign(&mut self, key: &SigningKey) {
+        self.signature = Some(DealerSignature::sign(key, &self.body()));
+    }
+
+    /// Checks that the document carries a valid signature by `dealer`.
+    pub fn verify_signature(&self, dealer: &VerifyingKey) -> Result<()> {
+        self.signature
+            .as_ref()
+            .ok_or(Error::InvalidSignature)?
+            .verify(dealer, &self.body())
+    }
+
+    /// Checks a share against the published commitments.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(id = %share.id)))]
+    pub fn verify_share(&self, share: &Share) -> bool {
+        FeldmanVSS::new(
+            self.p.clone(),
+            self.q.clone(),
+            self.g.clone(),
+            self.threshold,
+            self.total,
+        )
+        .verify_share(share, &self.commitment())
+    }
+
+    /// Checks every share against the commitments, reporting for each why it
+    /// failed rather than a bare verdict, in the order given.
+    pub fn verify_all_detailed(&self, shares: &[Share]) -> Vec<ShareReport> {
+        shares
+            .iter()
+            .map(|share| ShareReport {
+                id: share.id.clone(),
+                verdict: self.verdict(share),
+            })
+            .collect()
+    }
+
+    fn verdict(&self, share: &Share) -> Verdict {
+        if share.id.is_zero() || share.id >= self.q {
+            return Verdict::MalformedId;
+        }
+        if share.value >= self.q {
+            return Verdict::OutOfRange;
+        }
+        let expected = self.public_share_for(&share.id).0;
+        if !expected.modpow(&self.q, &self.p).is_one() {
+            return Verdict::SubgroupViolation;
+        }
+        if expected != self.g.modpow(&share.value, &self.p) {
+            return Verdict::WrongValue;
+        }
+        Verdict::Valid
+    }
+
+    /// The commitments to the sum of two dealings over the same group and
+    /// sharing parameters, against which the summed shares verify. The
+    /// result is unsigned.
+    pub fn add(&self, other: &CommitmentDocument) -> Result<CommitmentDocument> {
+        if (&self.p, &self.q, &self.g) != (&other.p, &other.q, &other.g) {
+            return Err(Error::InconsistentShares(
+                "commitments use different groups",
+            ));
+        }
+        if (self.threshold, self.total) != (other.threshold, other.total) {
+            return Err(Error::InconsistentShares(
+                "commitments disagree on threshold or total",
+            ));
+        }
+        Ok(CommitmentDocument {
+            commitments: self.commitment().add(&other.commitment(), &self.p).0,
+            signature: None,
+            ..self.clone()
+        })
+    }
+
+    /// The commitments after multiplying the secret by `c`; unsigned.
+    pub fn scale(&self, c: &BigUint) -> CommitmentDocument {
+        CommitmentDocument {
+            commitments: self.commitment().scale(c, &self.p).0,
+            signature: None,
+            ..self.clone()
+        }
+    }
+
+    /// The commitments after adding `c` to the secret; unsigned.
+    pub fn add_constant(&self, c: &BigUint) -> CommitmentDocument {
+        CommitmentDocument {
+            commitments: self.commitment().add_constant(c, &self.g, &self.p).0,
+            signature: None,
+            ..self.clone()
+        }
+    }
+
+    /// The signed portion of the encoding: everything except the signature.
+    /// Its integers stay minimal, as published dealer signatures cover these
+    /// bytes; [`to_fixed_bytes`](Self::to_fixed_bytes) is the canonical form.
+    fn body(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(DOCUMENT_VERSION);
+        put_biguint(&mut out, &self.p);
+        put_biguint(&mut out, &self.q);
+        put_biguint(&mut out, &self.g);
+        out.extend_from_slice(&(self.threshold as u32).to_be_bytes());
+        out.extend_from_slice(&(self.total as u32).to_be_bytes());
+        out.extend_from_slice(&(self.commitments.len() as u16).to_be_bytes());
+        for commitment in &self.commitments {
+            put_biguint(&mut out, commitment);
+        }
+        out
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.body();
+        match &self.signature {
+            Some(signature) => {
+                out.push(1);
+                signature.put(&mut out);
+            }
+            None => out.push(0),
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if version != DOCUMENT_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let p = take_biguint(&mut input)?;
+        let q = take_biguint(&mut input)?;
+        let g = take_biguint(&mut input)?;
+        let threshold = take_u32(&mut input)? as usize;
+        let total = take_u32(&mut input)? as usize;
+        if threshold == 0 || threshold > total {
+            return Err(Error::InvalidParameters(
+                "threshold must be between 1 and the total",
+            ));
+        }
+        if input.len() < 2 {
+            return Err(Error::MalformedShare);
+        }
+        let count = u16::from_be_bytes([input[0], input[1]]);
+        input = &input[2..];
+        let commitments = (0..count)
+            .map(|_| take_biguint(&mut input))
+            .collect::<Result<Vec<_>>>()?;
+        let signature = match input.split_first() {
+            Some((0, rest)) => {
+                input = rest;
+                None
+            }
+            Some((1, rest)) => {
+                input = rest;
+                Some(DealerSignature::take(&mut input)?)
+            }
+            _ => return Err(Error::MalformedShare),
+        };
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        let document = CommitmentDocument {
+            p,
+            q,
+            g,
+            threshold,
+            total,
+            commitments,
+            signature,
+        };
+        document.check_ranges()?;
+        Ok(document)
+    }
+
+    /// The canonical, unsigned encoding of the document: the group and
+    /// sharing parameters followed by [`Commitment::to_fixed_bytes`]. Two
+    /// documents describe the same dealing exactly when these bytes match.
+    /// Fails for a document whose elements are not below `p`.
+    pub fn to_fixed_bytes(&self) -> Result<Vec<u8>> {
+        self.check_ranges()?;
+        let mut out = FIXED_MAGIC.to_vec();
+        out.push(DOCUMENT_VERSION);
+        put_biguint(&mut out, &self.p);
+        put_bytes(&mut out, &to_fixed(&self.q, &self.p)?);
+        put_bytes(&mut out, &to_fixed(&self.g, &self.p)?);
+        out.extend_from_slice(&(self.threshold as u32).to_be_bytes());
+        out.extend_from_slice(&(self.total as u32).to_be_bytes());
+        out.extend_from_slice(&self.commitment().to_fixed_bytes(&self.p)?);
+        Ok(out)
+    }
+
+    /// Decodes [`CommitmentDocument::to_fixed_bytes`]; the result is unsigned.
+    pub fn from_fixed_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(FIXED_MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if version != DOCUMENT_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let p = take_biguint(&mut input)?;
+        let q = from_fixed(take_bytes(&mut input)?, &p)?;
+        let g = from_fixed(take_bytes(&mut input)?, &p)?;
+        let threshold = take_u32(&mut input)? as usize;
+        let total = take_u32(&mut input)? as usize;
+        if threshold == 0 || threshold > total {
+            return Err(Error::InvalidParameters(
+                "threshold must be between 1 and the total",
+            ));
+        }
+        let commitments = Commitment::from_fixed_bytes(input, &p)?.0;
+        let document = CommitmentDocument {
+            p,
+            q,
+            g,
+            threshold,
+            total,
+            commitments,
+            signature: None,
+        };
+        document.check_ranges()?;
+        Ok(document)
+    }
+
+    /// SHA-256 of [`CommitmentDocument::to_fixed_bytes`], identifying the
+    /// dealing independently of the signature and the publication format.
+    pub fn digest(&self) -> Result<[u8; 32]> {
+        Ok(Sha256::digest(self.to_fixed_bytes()?).into())
+    }
+
+    /// Encodes the document as an ASCII-armored text block for publication.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+
+    /// Fails, like [`to_fixed_bytes`](Self::to_fixed_bytes), for elements
+    /// not below `p`.
+    pub fn to_json(&self) -> Result<String> {
+        self.check_ranges()?;
+        let document = JsonDocument {
+            version: DOCUMENT_VERSION,
+            p: hex(&to_fixed(&self.p, &self.p)?),
+            q: hex(&to_fixed(&self.q, &self.q)?),
+            g: hex(&to_fixed(&self.g, &self.p)?),
+            threshold: self.threshold,
+            total: self.total,
+            commitments: self
+                .commitments
+                .iter()
+                .map(|c| Ok(hex(&to_fixed(c, &self.p)?)))
+                .collect::<Result<_>>()?,
+            signature: self.signature.as_ref().map(|s| JsonSignature {
+                dealer: hex(&s.dealer),
+                signature: hex(&s.signature),
+            }),
+        };
+        Ok(serde_json::to_string_pretty(&document).expect("commitment documents serialize"))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        let document: JsonDocument = serde_json::from_str(text)
+            .map_err(|_| Error::InvalidEncoding("invalid commitment JSON"))?;
+        if document.version != DOCUMENT_VERSION {
+            return Err(Error::UnsupportedVersion(document.version));
+        }
+        if document.threshold == 0 || document.threshold > document.total {
+            return Err(Error::InvalidParameters(
+                "threshold must be between 1 and the total",
+            ));
+        }
+        let int = |text: &str| {
+            BigUint::parse_bytes(text.as_bytes(), 16)
+                .ok_or(Error::InvalidEncoding("invalid hex integer"))
+        };
+        let signature = match document.signature {
+            Some(s) => Some(DealerSignature {
+                dealer: unhex(&s.dealer)?
+                    .try_into()
+                    .map_err(|_| Error::InvalidEncoding("dealer key must be 32 bytes"))?,
+                signature: unhex(&s.signature)?
+                    .try_into()
+                    .map_err(|_| Error::InvalidEncoding("signature must be 64 bytes"))?,
+            }),
+            None => None,
+        };
+        let document = CommitmentDocument {
+            p: int(&document.p)?,
+            q: int(&document.q)?,
+            g: int(&document.g)?,
+            threshold: document.threshold,
+            total: document.total,
+            commitments: document
+                .commitments
+                .iter()
+                .map(|c| int(c))
+                .collect::<Result<_>>()?,
+            signature,
+        };
+        document.check_ranges()?;
+        Ok(document)
+    }
+
+    /// Checks that the group is not degenerate and that `q`, `g` and every
+    /// commitment lie below `p`, so the document encodes at the width of `p`.
+    fn check_ranges(&self) -> Result<()> {
+        let two = BigUint::from(2u32);
+        if self.p < two || self.q < two || self.q >= self.p || self.g < two || self.g >= self.p {
+            return Err(Error::InvalidParameters("degenerate group parameters"));
+        }
+        if self.commitments.iter().any(|c| c >= &self.p) {
+            return Err(Error::InvalidEncoding("value out of range"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_published_commitments() {
+        let mut vss = FeldmanVSS::new(23u32.into(), 11u32.into(), 2u32.into(), 2, 3);
+        let (shares, commitments) = vss.split_secret(&BigUint::from(5u32)).unwrap();
+
+        let dealer = SigningKey::generate(&mut OsRng);
+        let mut document = vss.commitment_document(&commitments);
+        document.sign(&dealer);
+
+        let published = CommitmentDocument::from_armored(&document.to_armored()).unwrap();
+        assert_eq!(
+            CommitmentDocument::from_json(&document.to_json().unwrap()).unwrap(),
+            published
+        );
+        published.verify_signature(&dealer.verifying_key()).unwrap();
+        assert!(shares.iter().all(|share| published.verify_share(share)));
+
+        // Altered commitments invalidate the dealer signature
+        let mut forged = published.clone();
+        forged.commitments[0] = BigUint::from(4u32);
+        assert!(forged.verify_signature(&dealer.verifying_key()).is_err());
+        let other = SigningKey::generate(&mut OsRng);
+        assert!(published.verify_signature(&other.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn test_fixed_width_encoding() {
+        let group = crate::params::GroupParameters::generate(256, 96).unwrap();
+        let mut vss = group.vss(3, 5).unwrap();
+        let (_, commitments) = vss.split_secret(&BigUint::from(99u32)).unwrap();
+        let document = vss.commitment_document(&commitments);
+        let bytes = commitments.to_fixed_bytes(&group.p).unwrap();
+        assert_eq!(bytes.len(), 2 + 3 * 32);
+        assert_eq!(
+            Commitment::from_fixed_bytes(&bytes, &group.p).unwrap().0,
+            commitments.0
+        );
+        assert!(Commitment::from_fixed_bytes(&bytes[..bytes.len() - 1], &group.p).is_err());
+
+        // Small commitments are padded, so the encoding has the same shape.
+        let mut small = document.clone();
+        small.commitments[1] = BigUint::from(1u32);
+        assert_eq!(
+            small.to_fixed_bytes().unwrap().len(),
+            document.to_fixed_bytes().unwrap().len()
+        );
+        assert!(small
+            .to_json()
+            .unwrap()
+            .contains(&format!("\"{}01\"", "00".repeat(31))));
+
+        let mut signed = document.clone();
+        signed.sign(&SigningKey::generate(&mut OsRng));
+        assert_eq!(signed.digest().unwrap(), document.digest().unwrap());
+        assert_ne!(small.digest().unwrap(), document.digest().unwrap());
+        let fixed = document.to_fixed_bytes().unwrap();
+        assert_eq!(fixed.len(), 4 + 3 * (2 + 32) + 8 + 2 + 3 * 32);
+        let decoded = CommitmentDocument::from_fixed_bytes(&fixed).unwrap();
+        assert_eq!(decoded, document);
+        assert_eq!(
+            CommitmentDocument::from_json(&small.to_json().unwrap()).unwrap(),
+            small
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_documents_are_refused() {
+        let mut vss = FeldmanVSS::new(23u32.into(), 11u32.into(), 2u32.into(), 2, 3);
+        let (_, commitments) = vss.split_secret(&BigUint::from(5u32)).unwrap();
+        let document = vss.commitment_document(&commitments);
+        let json = document.to_json().unwrap();
+
+        let wide_g = json.replacen("\"g\": \"02\"", "\"g\": \"ffffffffff\"", 1);
+        assert_ne!(wide_g, json);
+        assert!(CommitmentDocument::from_json(&wide_g).is_err());
+
+        let mut wide = document.clone();
+        wide.g = BigUint::from(0xff_ffff_ffffu64);
+        assert!(wide.to_json().is_err());
+        assert!(wide.to_fixed_bytes().is_err());
+        assert!(wide.digest().is_err());
+        assert!(CommitmentDocument::from_bytes(&wide.to_bytes()).is_err());
+
+        let mut outside = document.clone();
+        outside.commitments[0] = BigUint::from(23u32);
+        assert!(outside.to_json().is_err());
+        assert!(CommitmentDocument::from_bytes(&outside.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_detailed_verification() {
+        let mut vss = FeldmanVSS::new(23u32.into(), 11u32.into(), 2u32.into(), 2, 4);
+        let (mut shares, commitments) = vss.split_secret(&BigUint::from(7u32)).unwrap();
+        let document = vss.commitment_document(&commitments);
+        shares[1].value = (&shares[1].value + 1u32) % 11u32;
+        shares[2].id = BigUint::zero();
+        shares[3].value += 11u32;
+        let verdicts: Vec<_> = document
+            .verify_all_detailed(&shares)
+            .iter()
+            .map(|report| report.verdict)
+            .collect();
+        assert_eq!(
+            verdicts,
+            [
+                Verdict::Valid,
+                Verdict::WrongValue,
+                Verdict::MalformedId,
+                Verdict::OutOfRange
+            ]
+        );
+
+        // 22 has order 2 mod 23, outside the order-11 subgroup.
+        let mut tampered = document.clone();
+        tampered.commitments[1] = 22u32.into();
+        let report = tampered.verify_all_detailed(&shares[..1]);
+        assert_eq!(report[0].verdict, Verdict::SubgroupViolation);
+        assert!(!report[0].is_valid());
+    }
+}
@@ -0,0 +1,77 @@
+// File: src/config.rs
+//! Defaults for `sss` flags from a TOML configuration file.
+//!
+//! Recurring ceremonies tend to use the same threshold, share count and
+//! output directory every time. `sss` reads them from
+//! `~/.config/sss/config.toml` (or `$XDG_CONFIG_HOME/sss/config.toml`), or
+//! from the file named with `--config`, and fills in any flag left off the
+//! command line; flags given explicitly always win.
+//!
+//! ```toml
+//! threshold = 3
+//! shares = 5
+//! out_dir = "shares"
+//! preset = "modp-2048"   # group used by `gen-params` without --bits
+//! encoding = "bech32"    # `armor` (the default) or `bech32`
+//! ```
+
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use cryptographic_algorithms::{Error, Result};
+use serde::Deserialize;
+
+/// How `split` and `gen-secret` write shares.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareEncoding {
+    /// ASCII armor blocks
+    #[default]
+    Armor,
+    /// Single-line bech32m strings that locate typos
+    Bech32,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub threshold: Option<usize>,
+    pub shares: Option<usize>,
+    pub out_dir: Option<PathBuf>,
+    pub preset: Option<String>,
+    pub encoding: Option<ShareEncoding>,
+}
+
+impl Config {
+    /// Reads `path`, or the default location when `None`. A missing default
+    /// file means no defaults; a missing explicit one is an error.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, explicit) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text),
+            Err(err) if !explicit && err.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|_| Error::InvalidEncoding("invalid configuration file"))
+    }
+}
+
+fn default_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("sss").join("config.toml"))
+}
@@ -0,0 +1,572 @@
+// File: src/coordinator.rs
+//! Round bookkeeping for a [`crate::dkg`] ceremony.
+//!
+//! The [`Coordinator`] is a state machine with no I/O of its own: the
+//! integrator feeds it each party's messages as they arrive, from whatever
+//! transport or async runtime it uses, and calls [`Coordinator::tick`] to
+//! enforce round deadlines. The rounds are:
+//!
+//! 1. [`Phase::Commitments`]: every party broadcasts its [`Commitments`];
+//! 2. [`Phase::Verdicts`]: parties exchange shares directly, then each
+//!    reports the dealers it complains about;
+//! 3. [`Phase::Justifications`]: each accused dealer publishes the disputed
+//!    share, which is checked against its commitments.
+//!
+//! Parties that miss a deadline, or fail to justify a share, are
+//! disqualified. The ceremony then ends with a [`Transcript`] of the
+//! qualified dealers and the joint public key, or fails if fewer than
+//! `threshold` dealers remain.
+//!
+//! A coordinator that may crash mid-ceremony can save a
+//! [`Coordinator::checkpoint`] after each message and
+//! [`Coordinator::resume`] from it; see [`crate::checkpoint`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::checkpoint;
+use crate::dkg::{self, Commitments, DealtShare};
+use crate::encoding::{hex, unhex};
+use crate::error::{Error, Result};
+use crate::params::GroupParameters;
+
+const TRANSCRIPT_VERSION: u8 = 1;
+const CHECKPOINT_KIND: &str = "dkg-coordinator";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Phase {
+    Commitments,
+    Verdicts,
+    Justifications,
+    Finished,
+    /// Fewer than `threshold` dealers remained qualified.
+    Failed,
+}
+
+/// A party's claim that a dealer's share to it was bad or missing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Complaint {
+    pub from: u32,
+    pub against: u32,
+}
+
+/// The public record of a finished ceremony.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transcript {
+    pub group: GroupParameters,
+    pub threshold: usize,
+    pub total: usize,
+    pub qualified: Vec<u32>,
+    pub disqualified: Vec<u32>,
+    /// The commitments of every qualified dealer.
+    pub commitments: BTreeMap<u32, Vec<BigUint>>,
+    pub complaints: Vec<Complaint>,
+    /// Shares published by accused dealers in answer to complaints.
+    pub justifications: Vec<DealtShare>,
+    pub public_key: BigUint,
+}
+
+/// The JSON shape of a [`Transcript`]; integers are big-endian hex.
+#[derive(Serialize, Deserialize)]
+struct JsonTranscript {
+    version: u8,
+    p: String,
+    q: String,
+    g: String,
+    threshold: usize,
+    total: usize,
+    qualified: Vec<u32>,
+    disqualified: Vec<u32>,
+    commitments: BTreeMap<u32, Vec<String>>,
+    complaints: Vec<Complaint>,
+    justifications: Vec<JsonShare>,
+    public_key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonShare {
+    from: u32,
+    to: u32,
+    value: String,
+}
+
+impl Transcript {
+    pub fn to_json(&self) -> String {
+        let int = |value: &BigUint| hex(&value.to_bytes_be());
+        let document = JsonTranscript {
+            version: TRANSCRIPT_VERSION,
+            p: int(&self.group.p),
+            q: int(&self.group.q),
+            g: int(&self.group.g),
+            threshold: self.threshold,
+            total: self.total,
+            qualified: self.qualified.clone(),
+            disqualified: self.disqualified.clone(),
+            commitments: self
+                .commitments
+                .iter()
+                .map(|(party, commitments)| (*party, commitments.iter().map(int).collect()))
+                .collect(),
+            complaints: self.complaints.clone(),
+            justifications: self
+                .justifications
+                .iter()
+                .map(|share| JsonShare {
+                    from: share.from,
+                    to: share.to,
+                    value: int(&share.value),
+                })
+                .collect(),
+            public_key: int(&self.public_key),
+        };
+        serde_json::to_string_pretty(&document).expect("transcripts serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        let document: JsonTranscript = serde_json::from_str(text)
+            .map_err(|_| Error::InvalidEncoding("invalid transcript JSON"))?;
+        if document.version != TRANSCRIPT_VERSION {
+            return Err(Error::UnsupportedVersion(document.version));
+        }
+        let int = |text: &str| unhex(text).map(|bytes| BigUint::from_bytes_be(&bytes));
+        Ok(Transcript {
+            group: GroupParameters {
+                p: int(&document.p)?,
+                q: int(&document.q)?,
+                g: int(&document.g)?,
+            },
+            threshold: document.threshold,
+            total: document.total,
+            qualified: document.qualified,
+            disqualified: document.disqualified,
+            commitments: document
+                .commitments
+                .into_iter()
+                .map(|(party, commitments)| {
+                    let commitments = commitments.iter().map(|c| int(c)).collect::<Result<_>>()?;
+                    Ok((party, commitments))
+                })
+                .collect::<Result<_>>()?,
+            complaints: document.complaints,
+            justifications: document
+                .justifications
+                .into_iter()
+                .map(|share| {
+                    Ok(DealtShare {
+                        from: share.from,
+                        to: share.to,
+                        value: int(&share.value)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            public_key: int(&document.public_key)?,
+        })
+    }
+}
+
+/// The JSON shape of a [`Coordinator`] checkpoint; integers are big-endian hex.
+#[derive(Serialize, Deserialize)]
+struct CoordinatorState {
+    p: String,
+    q: String,
+    g: String,
+    threshold: usize,
+    total: usize,
+    round_timeout_ms: u64,
+    phase: Phase,
+    commitments: BTreeMap<u32, Vec<String>>,
+    verdicts: BTreeMap<u32, Vec<u32>>,
+    justifications: Vec<JsonShare>,
+    disqualified: Vec<u32>,
+}
+
+/// Tracks who has sent what in each round of a DKG ceremony.
+pub struct Coordinator {
+    group: GroupParameters,
+    threshold: usize,
+    total: usize,
+    round_timeout: Duration,
+    phase: Phase,
+    deadline: Instant,
+    commitments: BTreeMap<u32, Vec<BigUint>>,
+    verdicts: BTreeMap<u32, Vec<u32>>,
+    justifications: Vec<DealtShare>,
+    disqualified: BTreeSet<u32>,
+    transcript: Option<Transcript>,
+}
+
+impl Coordinator {
+    /// Starts a ceremony for parties `1..=total`, giving each round
+    /// `round_timeout` from `now`.
+    pub fn new(
+        group: &GroupParameters,
+        threshold: usize,
+        total: usize,
+        round_timeout: Duration,
+        now: Instant,
+    ) -> Result<Self> {
+        if threshold == 0 || threshold > total || total > u32::MAX as usize {
+            return Err(Error::InvalidParameters(
+                "threshold must be between 1 and the total",
+            ));
+        }
+        Ok(Coordinator {
+            group: group.clone(),
+            threshold,
+            total,
+            round_timeout,
+            phase: Phase::Commitments,
+            deadline: now + round_timeout,
+            commitments: BTreeMap::new(),
+            verdicts: BTreeMap::new(),
+            justifications: Vec::new(),
+            disqualified: BTreeSet::new(),
+            transcript: None,
+        })
+    }
+
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    /// When the current round closes.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// The parties still qualified to deal.
+    pub fn qualified(&self) -> Vec<u32> {
+        (1..=self.total as u32)
+            .filter(|party| !self.disqualified.contains(party))
+            .collect()
+    }
+
+    /// The parties the current round is still waiting for.
+    pub fn pending(&self) -> Vec<u32> {
+        match self.phase {
+            Phase::Commitments => self
+                .qualified()
+                .into_iter()
+                .filter(|party| !self.commitments.contains_key(party))
+                .collect(),
+            Phase::Verdicts => self
+                .qualified()
+                .into_iter()
+                .filter(|party| !self.verdicts.contains_key(party))
+                .collect(),
+            Phase::Justifications => self
+                .open_complaints()
+                .into_iter()
+                .map(|complaint| complaint.against)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            Phase::Finished | Phase::Failed => Vec::new(),
+        }
+    }
+
+    /// The transcript, once the ceremony has finished.
+    pub fn transcript(&self) -> Option<&Transcript> {
+        self.transcript.as_ref()
+    }
+
+    /// Everything recorded so far, tagged under `key`, for [`resume`](Self::resume).
+    pub fn checkpoint(&self, key: &[u8]) -> String {
+        let int = |value: &BigUint| hex(&value.to_bytes_be());
+        let state = CoordinatorState {
+            p: int(&self.group.p),
+            q: int(&self.group.q),
+            g: int(&self.group.g),
+            threshold: self.threshold,
+            total: self.total,
+            round_timeout_ms: self.round_timeout.as_millis() as u64,
+            phase: self.phase.clone(),
+            commitments: self
+                .commitments
+                .iter()
+                .map(|(party, commitments)| (*party, commitments.iter().map(int).collect()))
+                .collect(),
+            verdicts: self.verdicts.clone(),
+            justifications: self
+                .justifications
+                .iter()
+                .map(|share| JsonShare {
+                    from: share.from,
+                    to: share.to,
+                    value: int(&share.value),
+                })
+                .collect(),
+            disqualified: self.disqualified.iter().copied().collect(),
+        };
+        checkpoint::seal(CHECKPOINT_KIND, &state, key)
+    }
+
+    /// Picks a ceremony up from a [`checkpoint`](Self::checkpoint) sealed
+    /// under `key`. Deadlines are not carried across a restart: the current
+    /// round gets a fresh `round_timeout` from `now`.
+    pub fn resume(text: &str, key: &[u8], now: Instant) -> Result<Self> {
+        let state: CoordinatorState = checkpoint::open(text, CHECKPOINT_KIND, key)?;
+        let int = |text: &str| unhex(text).map(|bytes| BigUint::from_bytes_be(&bytes));
+        let group = GroupParameters {
+            p: int(&state.p)?,
+            q: int(&state.q)?,
+            g: int(&state.g)?,
+        };
+        let mut coordinator = Coordinator::new(
+            &group,
+            state.threshold,
+            state.total,
+            Duration::from_millis(state.round_timeout_ms),
+            now,
+        )?;
+        coordinator.phase = state.phase;
+        coordinator.commitments = state
+            .commitments
+            .into_iter()
+            .map(|(party, commitments)| {
+                let commitments = commitments.iter().map(|c| int(c)).collect::<Result<_>>()?;
+                Ok((party, commitments))
+            })
+            .collect::<Result<_>>()?;
+        coordinator.verdicts = state.verdicts;
+        coordinator.justifications = state
+            .justifications
+            .into_iter()
+            .map(|share| {
+                Ok(DealtShare {
+                    from: share.from,
+                    to: share.to,
+                    value: int(&share.value)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        coordinator.disqualified = state.disqualified.into_iter().collect();
+        if coordinator.phase == Phase::Finished {
+            coordinator.transcript = Some(coordinator.build_transcript());
+        }
+        Ok(coordinator)
+    }
+
+    /// Records a party's first-round broadcast.
+    pub fn submit_commitments(&mut self, commitments: &Commitments, now: Instant) -> Result<()> {
+        self.tick(now);
+        self.expect(Phase::Commitments, commitments.from)?;
+        if self.commitments.contains_key(&commitments.from) {
+            return Err(Error::InvalidParameters("commitments already submitted"));
+        }
+        let valid = commitments.commitments.len() == self.threshold
+            && commitments
+                .commitments
+                .iter()
+                .all(|c| *c > BigUint::from(1u32) && *c < self.group.p);
+        if !valid {
+            self.disqualified.insert(commitments.from);
+        } else {
+            self.commitments
+                .insert(commitments.from, commitments.commitments.clone());
+        }
+        self.advance(now);
+        Ok(())
+    }
+
+    /// Records the dealers a party complains about; empty if all its shares
+    /// checked out.
+    pub fn submit_verdict(&mut self, from: u32, complaints: &[u32], now: Instant) -> Result<()> {
+        self.tick(now);
+        self.expect(Phase::Verdicts, from)?;
+        if self.verdicts.contains_key(&from) {
+            return Err(Error::InvalidParameters("verdict already submitted"));
+        }
+        self.verdicts.insert(from, complaints.to_vec());
+        self.advance(now);
+        Ok(())
+    }
+
+    /// Records a share an accused dealer publishes to answer a complaint. A
+    /// share that does not match the dealer's commitments disqualifies it.
+    pub fn submit_justification(&mut self, share: &DealtShare, now: Instant) -> Result<()> {
+        self.tick(now);
+        self.expect(Phase::Justifications, share.from)?;
+        let complaint = Complaint {
+            from: share.to,
+            against: share.from,
+        };
+        if !self.open_complaints().contains(&complaint) {
+            return Err(Error::InvalidParameters("no open complaint for this share"));
+        }
+        if dkg::verify_dealt(&self.group, &self.commitments[&share.from], share) {
+            self.justifications.push(share.clone());
+        } else {
+            self.disqualified.insert(share.from);
+        }
+        self.advance(now);
+        Ok(())
+    }
+
+    /// Closes the current round if its deadline has passed, disqualifying
+    /// every party it was still waiting for.
+    pub fn tick(&mut self, now: Instant) -> &Phase {
+        if now >= self.deadline && !matches!(self.phase, Phase::Finished | Phase::Failed) {
+            let late = self.pending();
+            self.disqualified.extend(late);
+            self.advance(now);
+        }
+        &self.phase
+    }
+
+    fn expect(&self, phase: Phase, party: u32) -> Result<()> {
+        if self.phase != phase {
+            return Err(Error::InvalidParameters("message is for another round"));
+        }
+        if party == 0 || party as usize > self.total {
+            return Err(Error::InvalidParameters("party index out of range"));
+        }
+        if self.disqualified.contains(&party) {
+            return Err(Error::InvalidParameters("party is disqualified"));
+        }
+        Ok(())
+    }
+
+    /// Complaints against qualified dealers that no justification answers.
+    fn open_complaints(&self) -> BTreeSet<Complaint> {
+        self.complaints()
+            .into_iter()
+            .filter(|c| !self.disqualified.contains(&c.against))
+            .filter(|c| {
+                !self
+                    .justifications
+                    .iter()
+                    .any(|s| s.from == c.against && s.to == c.from)
+            })
+            .collect()
+    }
+
+    fn complaints(&self) -> BTreeSet<Complaint> {
+        self.verdicts
+            .iter()
+            .flat_map(|(&from, against)| {
+                against
+                    .iter()
+                    .filter(move |&&against| against != from)
+                    .map(move |&against| Complaint { from, against })
+            })
+            .filter(|c| self.commitments.contains_key(&c.against))
+            .collect()
+    }
+
+    /// Moves through every round that is no longer waiting for anyone.
+    fn advance(&mut self, now: Instant) {
+        while !matches!(self.phase, Phase::Finished | Phase::Failed) && self.pending().is_empty() {
+            if self.qualified().len() < self.threshold {
+                self.phase = Phase::Failed;
+                return;
+            }
+            self.phase = match self.phase {
+                Phase::Commitments => Phase::Verdicts,
+                Phase::Verdicts => Phase::Justifications,
+                _ => {
+                    self.transcript = Some(self.build_transcript());
+                    Phase::Finished
+                }
+            };
+            self.deadline = now + self.round_timeout;
+        }
+    }
+
+    fn build_transcript(&self) -> Transcript {
+        let qualified = self.qualified();
+        let commitments: BTreeMap<u32, Vec<BigUint>> = qualified
+            .iter()
+            .map(|party| (*party, self.commitments[party].clone()))
+            .collect();
+        Transcript {
+            group: self.group.clone(),
+            threshold: self.threshold,
+            total: self.total,
+            public_key: dkg::public_key(&self.group, commitments.values()),
+            qualified,
+            disqualified: self.disqualified.iter().copied().collect(),
+            commitments,
+            complaints: self.complaints().into_iter().collect(),
+            justifications: self.justifications.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::Party;
+
+    #[test]
+    fn test_ceremony_with_timeout_and_complaint() {
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let start = Instant::now();
+        let timeout = Duration::from_secs(60);
+        let mut coordinator = Coordinator::new(&group, 2, 4, timeout, start).unwrap();
+        let mut parties: Vec<Party> = (1..=4)
+            .map(|index| Party::new(&group, index, 2, 4).unwrap())
+            .collect();
+
+        // Party 4 never sends its commitments and is disqualified at the deadline
+        for party in &parties[..3] {
+            coordinator
+                .submit_commitments(&party.commitments(), start)
+                .unwrap();
+        }
+        assert_eq!(coordinator.pending(), vec![4]);
+        assert_eq!(coordinator.tick(start + timeout), &Phase::Verdicts);
+        let dealers = coordinator.qualified();
+        assert_eq!(dealers, vec![1, 2, 3]);
+
+        let broadcasts: Vec<_> = parties.iter().map(Party::commitments).collect();
+        let dealt: Vec<DealtShare> = parties[..3].iter().flat_map(Party::shares).collect();
+        let now = start + timeout;
+        for party in &mut parties[..3] {
+            for broadcast in &broadcasts[..3] {
+                party.receive_commitments(broadcast);
+            }
+            let index = party.index();
+            for share in dealt.iter().filter(|share| share.to == index) {
+                let mut share = share.clone();
+                if share.from == 3 && share.to == 1 {
+                    share.value += 1u32;
+                }
+                let _ = party.receive_share(&share);
+            }
+            let complaints = party.missing(dealers.iter().copied());
+            coordinator
+                .submit_verdict(party.index(), &complaints, now)
+                .unwrap();
+        }
+
+        // Dealer 3 answers party 1's complaint by publishing the real share
+        assert_eq!(coordinator.phase(), &Phase::Justifications);
+        assert_eq!(coordinator.pending(), vec![3]);
+        let disputed = dealt.iter().find(|s| (s.from, s.to) == (3, 1)).unwrap();
+        coordinator.submit_justification(disputed, now).unwrap();
+        assert_eq!(coordinator.phase(), &Phase::Finished);
+
+        let transcript =
+            Transcript::from_json(&coordinator.transcript().unwrap().to_json()).unwrap();
+        assert_eq!(&transcript, coordinator.transcript().unwrap());
+        assert_eq!(
+            transcript.complaints,
+            vec![Complaint {
+                from: 1,
+                against: 3
+            }]
+        );
+        let shares: Vec<_> = parties[..3]
+            .iter()
+            .map(|party| party.finish(&transcript).unwrap())
+            .collect();
+        for pair in [[0, 1], [1, 2], [0, 2]] {
+            let secret = dkg::combine(&group, &[shares[pair[0]].clone(), shares[pair[1]].clone()]);
+            assert_eq!(group.g.modpow(&secret, &group.p), transcript.public_key);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// File: src/custody.rs
+//! Custody of individual shares under keys held outside the host.
+//!
+//! A [`ShareCustody`] backend encrypts a share under a key it never releases,
+//! such as an AES key inside an HSM or smartcard ([`crate::pkcs11`] with the
+//! `pkcs11` feature). The result is a [`WrappedShare`]: the key's label, the
+//! IV and the ciphertext, with the cleartext header authenticated by the
+//! backend's AEAD so it cannot be swapped without detection.
+
+use crate::encoding::{armor, dearmor, put_bytes, take_bytes};
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+
+const ARMOR_LABEL: &str = "WRAPPED SHARE";
+const MAGIC: &[u8; 3] = b"SHH";
+const WRAPPED_VERSION: u8 = 1;
+
+/// A backend that wraps shares under a key it holds.
+pub trait ShareCustody {
+    fn wrap(&self, share: &SerializedShare) -> Result<WrappedShare>;
+
+    /// Decrypts a share wrapped by this backend; a wrong key or tampered
+    /// header yields [`Error::DecryptionFailed`].
+    fn unwrap(&self, wrapped: &WrappedShare) -> Result<SerializedShare>;
+}
+
+/// A share encrypted under a custody key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WrappedShare {
+    /// The label of the wrapping key, e.g. the `CKA_LABEL` of a token object.
+    pub key_label: String,
+    pub iv: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+impl WrappedShare {
+    /// Starts a wrapping; backends fill in `ciphertext`, authenticating
+    /// [`WrappedShare::header`].
+    pub fn new(key_label: impl Into<String>, iv: Vec<u8>) -> Self {
+        WrappedShare {
+            key_label: key_label.into(),
+            iv,
+            ciphertext: Vec::new(),
+        }
+    }
+
+    /// The cleartext header, to be passed to the AEAD as associated data.
+    pub fn header(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(WRAPPED_VERSION);
+        put_bytes(&mut out, self.key_label.as_bytes());
+        put_bytes(&mut out, &self.iv);
+        out
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.header();
+        out.extend_from_slice(&self.ciphertext);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if version != WRAPPED_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let key_label = String::from_utf8(take_bytes(&mut input)?.to_vec())
+            .map_err(|_| Error::MalformedShare)?;
+        let iv = take_bytes(&mut input)?.to_vec();
+        Ok(WrappedShare {
+            key_label,
+            iv,
+            ciphertext: input.to_vec(),
+        })
+    }
+
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::split_bytes;
+    use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+    use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+
+    /// Stands in for a hardware backend.
+    struct SoftwareCustody([u8; 32]);
+
+    impl ShareCustody for SoftwareCustody {
+        fn wrap(&self, share: &SerializedShare) -> Result<WrappedShare> {
+            let mut wrapped = WrappedShare::new("test-key", vec![7; 12]);
+            wrapped.ciphertext = ChaCha20Poly1305::new(&self.0.into())
+                .encrypt(
+                    Nonce::from_slice(&wrapped.iv),
+                    Payload {
+                        msg: &share.to_bytes(),
+                        aad: &wrapped.header(),
+                    },
+                )
+                .unwrap();
+            Ok(wrapped)
+        }
+
+        fn unwrap(&self, wrapped: &WrappedShare) -> Result<SerializedShare> {
+            let plaintext = ChaCha20Poly1305::new(&self.0.into())
+                .decrypt(
+                    Nonce::from_slice(&wrapped.iv),
+                    Payload {
+                        msg: &wrapped.ciphertext,
+                        aad: &wrapped.header(),
+                    },
+                )
+                .map_err(|_| Error::DecryptionFailed)?;
+            SerializedShare::from_bytes(&plaintext)
+        }
+    }
+
+    #[test]
+    fn test_wrapped_share_round_trip() {
+        let share = split_bytes(b"hardware bound", 2, 3).unwrap().remove(0);
+        let custody = SoftwareCustody([1; 32]);
+        let wrapped = custody.wrap(&share).unwrap();
+
+        let decoded = WrappedShare::from_armored(&wrapped.to_armored()).unwrap();
+        assert_eq!(decoded, wrapped);
+        assert_eq!(custody.unwrap(&decoded).unwrap(), share);
+
+        let mut relabeled = decoded.clone();
+        relabeled.key_label = "other-key".into();
+        assert!(matches!(
+            custody.unwrap(&relabeled),
+            Err(Error::DecryptionFailed)
+        ));
+        assert!(SoftwareCustody([2; 32]).unwrap(&decoded).is_err());
+    }
+}
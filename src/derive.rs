@@ -0,0 +1,147 @@
+// File: src/derive.rs
+//! Deterministic dealings derived from a master seed.
+//!
+//! A [`SeededDealer`] derives the random coefficients of a dealing from a
+//! master seed and a [`DerivationPath`] such as `m/2024/7`, so a backup
+//! system that keeps the seed can re-derive any past dealing and audit the
+//! shares it issued. Each coefficient is HKDF-SHA256 output, expanded with
+//! the path and the coefficient's position and reduced modulo the prime;
+//! dealings under different paths are independent.
+//!
+//! Anyone holding the seed and a share's secret can recompute every other
+//! share of that dealing, so keep the seed at least as safe as the secrets,
+//! and keep production dealings random.
+
+use std::fmt;
+use std::str::FromStr;
+
+use hkdf::Hkdf;
+use num_bigint::BigUint;
+use sha2::Sha256;
+
+use crate::error::{Error, Result};
+use crate::sss::{Polynomial, SecretSharer, Share};
+
+const HKDF_SALT: &[u8] = b"sss-derive-v1";
+/// Extra output bytes per coefficient, making the bias of the modular
+/// reduction negligible.
+const EXTRA_BYTES: usize = 16;
+
+/// A path of numbered steps below the master seed, written `m/1/2/3`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivationPath(pub Vec<u32>);
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        for step in &self.0 {
+            write!(f, "/{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut parts = text.trim().split('/');
+        if parts.next() != Some("m") {
+            return Err(Error::InvalidEncoding("derivation paths start with m"));
+        }
+        parts
+            .map(|step| {
+                step.parse()
+                    .map_err(|_| Error::InvalidEncoding("invalid derivation path step"))
+            })
+            .collect::<Result<_>>()
+            .map(DerivationPath)
+    }
+}
+
+/// Derives dealings from a master seed.
+pub struct SeededDealer {
+    hkdf: Hkdf<Sha256>,
+}
+
+impl SeededDealer {
+    /// Seeds shorter than 16 bytes are refused.
+    pub fn new(seed: &[u8]) -> Result<Self> {
+        if seed.len() < 16 {
+            return Err(Error::InvalidParameters("seeds must be at least 16 bytes"));
+        }
+        Ok(SeededDealer {
+            hkdf: Hkdf::new(Some(HKDF_SALT), seed),
+        })
+    }
+
+    /// The polynomial hiding `secret` for the dealing at `path`.
+    pub fn polynomial(
+        &self,
+        sharer: &SecretSharer,
+        path: &DerivationPath,
+        secret: &BigUint,
+    ) -> Polynomial {
+        let prime = sharer.prime();
+        let mut coefficients = vec![secret % prime];
+        for position in 1..sharer.threshold() as u32 {
+            coefficients.push(self.coefficient(prime, path, position));
+        }
+        Polynomial::new(prime.clone(), coefficients)
+    }
+
+    /// Like [`SecretSharer::split_secret`], reproducibly.
+    pub fn split_secret(
+        &self,
+        sharer: &SecretSharer,
+        path: &DerivationPath,
+        secret: &BigUint,
+    ) -> Vec<Share> {
+        let polynomial = self.polynomial(sharer, path, secret);
+        (1..=sharer.total_shares() as u32)
+            .map(|x| {
+                polynomial
+                    .share_at(&x.into())
+                    .expect("indices 1..=n are nonzero")
+            })
+            .collect()
+    }
+
+    fn coefficient(&self, prime: &BigUint, path: &DerivationPath, position: u32) -> BigUint {
+        let mut info = path.to_string().into_bytes();
+        info.push(0);
+        info.extend_from_slice(&position.to_be_bytes());
+        let mut okm = vec![0u8; (prime.bits() as usize).div_ceil(8) + EXTRA_BYTES];
+        self.hkdf
+            .expand(&info, &mut okm)
+            .expect("coefficients fit in HKDF-SHA256 output");
+        BigUint::from_bytes_be(&okm) % prime
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_dealings_rederive() {
+        let sharer = SecretSharer::new(3, 5);
+        let dealer = SeededDealer::new(b"backup master seed 0001").unwrap();
+        let secret = BigUint::from(424242u32);
+        let path: DerivationPath = "m/2024/7".parse().unwrap();
+        assert_eq!(path.to_string(), "m/2024/7");
+
+        let shares = dealer.split_secret(&sharer, &path, &secret);
+        let again = dealer.split_secret(&sharer, &path, &secret);
+        assert!(shares.iter().zip(&again).all(|(a, b)| a.y == b.y));
+        assert_eq!(
+            sharer.reconstruct_secret(&shares[2..]),
+            Some(secret.clone())
+        );
+
+        let other = dealer.split_secret(&sharer, &"m/2024/8".parse().unwrap(), &secret);
+        assert_ne!(shares[0].y, other[0].y);
+        assert!("2024/7".parse::<DerivationPath>().is_err());
+        assert!(SeededDealer::new(b"short").is_err());
+    }
+}
@@ -0,0 +1,314 @@
+// File: src/dkg.rs
+//! Joint-Feldman distributed key generation.
+//!
+//! Every party deals a random secret with Feldman VSS: it broadcasts
+//! [`Commitments`] to its polynomial and sends each other party a
+//! [`DealtShare`] privately. Each party checks what it received, complains
+//! about bad or missing shares, and once the dealers that survive the
+//! complaints are known, sums their shares into its [`KeyShare`]. No party
+//! ever learns the joint secret `x`; the public key is `g^x`.
+//!
+//! [`Party`] holds one participant's state. The choreography around it is
+//! handled by [`crate::coordinator::Coordinator`]. A party that may crash
+//! between rounds can save a [`Party::checkpoint`] and [`Party::resume`]
+//! from it.
+
+use std::collections::BTreeMap;
+
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, Zero};
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+
+use crate::checkpoint;
+use crate::coordinator::Transcript;
+use crate::encoding::{hex, unhex};
+use crate::error::{Error, Result};
+use crate::frost::KeyPackage;
+use crate::params::GroupParameters;
+
+const CHECKPOINT_KIND: &str = "dkg-party";
+
+/// A dealer's broadcast commitments `g^a_k` to its polynomial coefficients.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commitments {
+    pub from: u32,
+    pub commitments: Vec<BigUint>,
+}
+
+/// A share of a dealer's secret for one recipient, sent privately.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DealtShare {
+    pub from: u32,
+    pub to: u32,
+    pub value: BigUint,
+}
+
+/// A participant's share of the jointly generated key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyShare {
+    pub index: u32,
+    pub value: BigUint,
+    pub public_key: BigUint,
+}
+
+/// The JSON shape of a [`Party`] checkpoint; integers are big-endian hex.
+#[derive(Serialize, Deserialize)]
+struct PartyState {
+    p: String,
+    q: String,
+    g: String,
+    index: u32,
+    total: u32,
+    coefficients: Vec<String>,
+    commitments: BTreeMap<u32, Vec<String>>,
+    received: BTreeMap<u32, String>,
+}
+
+/// One participant's side of a DKG run; indices run from 1 to `total`.
+pub struct Party {
+    group: GroupParameters,
+    index: u32,
+    coefficients: Vec<BigUint>,
+    commitments: BTreeMap<u32, Vec<BigUint>>,
+    received: BTreeMap<u32, BigUint>,
+    total: u32,
+}
+
+impl Party {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(group), fields(bits = group.p.bits())))]
+    pub fn new(
+        group: &GroupParameters,
+        index: u32,
+        threshold: usize,
+        total: usize,
+    ) -> Result<Self> {
+        if threshold == 0 || threshold > total {
+            return Err(Error::InvalidParameters(
+                "threshold must be between 1 and the total",
+            ));
+        }
+        if index == 0 || index as usize > total {
+            return Err(Error::InvalidParameters("party index out of range"));
+        }
+        let mut rng = thread_rng();
+        let coefficients = (0..threshold)
+            .map(|_| rng.gen_biguint_below(&group.q))
+            .collect();
+        Ok(Party {
+            group: group.clone(),
+            index,
+            coefficients,
+            commitments: BTreeMap::new(),
+            received: BTreeMap::new(),
+            total: total as u32,
+        })
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// This party's polynomial and everything it has received, tagged under
+    /// `key`, for [`resume`](Self::resume). It holds the party's secret
+    /// polynomial: store it like a share.
+    pub fn checkpoint(&self, key: &[u8]) -> String {
+        let int = |value: &BigUint| hex(&value.to_bytes_be());
+        let state = PartyState {
+            p: int(&self.group.p),
+            q: int(&self.group.q),
+            g: int(&self.group.g),
+            index: self.index,
+            total: self.total,
+            coefficients: self.coefficients.iter().map(int).collect(),
+            commitments: self
+                .commitments
+                .iter()
+                .map(|(dealer, commitments)| (*dealer, commitments.iter().map(int).collect()))
+                .collect(),
+            received: self
+                .received
+                .iter()
+                .map(|(dealer, share)| (*dealer, int(share)))
+                .collect(),
+        };
+        checkpoint::seal(CHECKPOINT_KIND, &state, key)
+    }
+
+    /// Restores a party from a [`checkpoint`](Self::checkpoint) sealed under `key`.
+    pub fn resume(text: &str, key: &[u8]) -> Result<Self> {
+        let state: PartyState = checkpoint::open(text, CHECKPOINT_KIND, key)?;
+        let int = |text: &str| unhex(text).map(|bytes| BigUint::from_bytes_be(&bytes));
+        let ints = |texts: &[String]| texts.iter().map(|text| int(text)).collect::<Result<_>>();
+        if state.index == 0 || state.index > state.total {
+            return Err(Error::InvalidParameters("party index out of range"));
+        }
+        if state.coefficients.is_empty() {
+            return Err(Error::InvalidEncoding("invalid checkpoint state"));
+        }
+        Ok(Party {
+            group: GroupParameters {
+                p: int(&state.p)?,
+                q: int(&state.q)?,
+                g: int(&state.g)?,
+            },
+            index: state.index,
+            coefficients: ints(&state.coefficients)?,
+            commitments: state
+                .commitments
+                .iter()
+                .map(|(dealer, commitments)| Ok((*dealer, ints(commitments)?)))
+                .collect::<Result<_>>()?,
+            received: state
+                .received
+                .iter()
+                .map(|(dealer, share)| Ok((*dealer, int(share)?)))
+                .collect::<Result<_>>()?,
+            total: state.total,
+        })
+    }
+
+    /// This party's broadcast for the first round.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(index = self.index)))]
+    pub fn commitments(&self) -> Commitments {
+        Commitments {
+            from: self.index,
+            commitments: self
+                .coefficients
+                .iter()
+                .map(|a| self.group.g.modpow(a, &self.group.p))
+                .collect(),
+        }
+    }
+
+    /// This party's shares for every participant, itself included.
+    pub fn shares(&self) -> Vec<DealtShare> {
+        (1..=self.total)
+            .map(|to| DealtShare {
+                from: self.index,
+                to,
+                value: self.evaluate(to),
+            })
+            .collect()
+    }
+
+    /// Records a dealer's broadcast commitments.
+    pub fn receive_commitments(&mut self, commitments: &Commitments) {
+        self.commitments
+            .insert(commitments.from, commitments.commitments.clone());
+    }
+
+    /// Checks and records a share addressed to this party. An error means the
+    /// party should complain about the dealer.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(from = share.from, to = share.to)))]
+    pub fn receive_share(&mut self, share: &DealtShare) -> Result<()> {
+        if share.to != self.index {
+            return Err(Error::InvalidParameters(
+                "share is addressed to another party",
+            ));
+        }
+        let commitments = self
+            .commitments
+            .get(&share.from)
+            .ok_or(Error::InconsistentShares("no commitments from this dealer"))?;
+        if !verify_dealt(&self.group, commitments, share) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                dealer = share.from,
+                "share does not match the dealer's commitments"
+            );
+            return Err(Error::InconsistentShares(
+                "share does not match the dealer's commitments",
+            ));
+        }
+        self.received.insert(share.from, share.value.clone());
+        Ok(())
+    }
+
+    /// The dealers this party holds no valid share from, to complain about.
+    pub fn missing(&self, dealers: impl IntoIterator<Item = u32>) -> Vec<u32> {
+        dealers
+            .into_iter()
+            .filter(|dealer| !self.received.contains_key(dealer))
+            .collect()
+    }
+
+    /// Combines the shares of the qualified dealers in a finished transcript,
+    /// taking shares published to answer complaints where needed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(index = self.index, qualified = transcript.qualified.len())))]
+    pub fn finish(&self, transcript: &Transcript) -> Result<KeyShare> {
+        let mut value = BigUint::zero();
+        for dealer in &transcript.qualified {
+            let share = match self.received.get(dealer) {
+                Some(share) => share,
+                None => {
+                    &transcript
+                        .justifications
+                        .iter()
+                        .find(|s| s.from == *dealer && s.to == self.index)
+                        .ok_or(Error::InsufficientShares)?
+                        .value
+                }
+            };
+            value = (value + share) % &self.group.q;
+        }
+        Ok(KeyShare {
+            index: self.index,
+            value,
+            public_key: transcript.public_key.clone(),
+        })
+    }
+
+    /// [`Party::finish`] packaged in the layout of FROST implementations.
+    pub fn key_package(&self, transcript: &Transcript) -> Result<KeyPackage> {
+        KeyPackage::new(&self.finish(transcript)?, transcript)
+    }
+
+    fn evaluate(&self, x: u32) -> BigUint {
+        let x = BigUint::from(x);
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, a| (acc * &x + a) % &self.group.q)
+    }
+}
+
+/// Checks `g^share == prod C_k^(to^k)` for a dealer's commitments.
+pub fn verify_dealt(group: &GroupParameters, commitments: &[BigUint], share: &DealtShare) -> bool {
+    let x = BigUint::from(share.to);
+    let mut power = BigUint::one();
+    let mut expected = BigUint::one();
+    for commitment in commitments {
+        expected = expected * commitment.modpow(&power, &group.p) % &group.p;
+        power = power * &x % &group.q;
+    }
+    group.g.modpow(&share.value, &group.p) == expected
+}
+
+/// The joint public key `prod C_0` over the given dealers' commitments.
+pub fn public_key<'a>(
+    group: &GroupParameters,
+    commitments: impl IntoIterator<Item = &'a Vec<BigUint>>,
+) -> BigUint {
+    commitments
+        .into_iter()
+        .fold(BigUint::one(), |acc, c| acc * &c[0] % &group.p)
+}
+
+/// Recovers the joint secret from `threshold` key shares; for tests and
+/// emergency recovery only.
+pub fn combine(group: &GroupParameters, shares: &[KeyShare]) -> BigUint {
+    let q = &group.q;
+    shares.iter().fold(BigUint::zero(), |acc, share| {
+        let xi = BigUint::from(share.index);
+        let (num, den) = shares
+            .iter()
+            .filter(|other| other.index != share.index)
+            .fold((BigUint::one(), BigUint::one()), |(num, den), other| {
+                let xj = BigUint::from(other.index);
+                (num * &xj % q, den * ((&xj + q - &xi) % q) % q)
+            });
+        let inverse = den.modpow(&(q - 2u32), q);
+        (acc + &share.value * num % q * inverse) % q
+    })
+}
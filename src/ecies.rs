@@ -0,0 +1,343 @@
+// File: src/ecies.rs
+//! Per-participant share encryption to X25519 public keys.
+//!
+//! Each share is sealed with a fresh ephemeral key: the X25519 shared secret
+//! is expanded with HKDF-SHA256 into a ChaCha20-Poly1305 key, and both public
+//! keys are bound in as associated data. The resulting [`ShareBundle`] can be
+//! posted on an untrusted channel; each recipient opens only their own entry.
+//! The dealer can sign the whole bundle with Ed25519, and recipients open
+//! with [`ShareBundle::open_signed`] to refuse bundles an attacker assembled
+//! or altered.
+//!
+//! Bundle version 2 widened the ciphertext length prefixes from u16 to u32,
+//! so shares of 64 KiB and more can be sealed; version 1 bundles still open.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use crate::encoding::{armor, dearmor, put_bytes_u32, take_bytes, take_bytes_u32};
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+use crate::signing::DealerSignature;
+
+const ARMOR_LABEL: &str = "ENCRYPTED SHARE BUNDLE";
+const MAGIC: &[u8; 3] = b"SHB";
+const BUNDLE_VERSION: u8 = 2;
+const HKDF_INFO: &[u8] = b"secret-sharing x25519 share v1";
+
+/// A share sealed to a single recipient.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedShare {
+    pub recipient: [u8; 32],
+    pub ephemeral: [u8; 32],
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedShare {
+    /// Seals `share` so only the holder of `recipient`'s secret key can read it.
+    pub fn seal(share: &SerializedShare, recipient: &PublicKey) -> Result<Self> {
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let shared = ephemeral.diffie_hellman(recipient);
+        if !shared.was_contributory() {
+            return Err(Error::InvalidParameters("recipient key has low order"));
+        }
+
+        let (ephemeral, recipient) = (ephemeral_public.to_bytes(), recipient.to_bytes());
+        let cipher = cipher(shared.as_bytes(), &ephemeral, &recipient);
+        let ciphertext = cipher
+            .encrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: &share.to_bytes(),
+                    aad: &associated_data(&ephemeral, &recipient),
+                },
+            )
+            .expect("a share fits in a single ChaCha20-Poly1305 message");
+        Ok(EncryptedShare {
+            recipient,
+            ephemeral,
+            ciphertext,
+        })
+    }
+
+    /// Decrypts and authenticates the share with the recipient's secret key.
+    pub fn open(&self, secret: &StaticSecret) -> Result<SerializedShare> {
+        if PublicKey::from(secret).to_bytes() != self.recipient {
+            return Err(Error::InvalidParameters("share is sealed to another key"));
+        }
+        let shared = secret.diffie_hellman(&PublicKey::from(self.ephemeral));
+        let cipher = cipher(shared.as_bytes(), &self.ephemeral, &self.recipient);
+        let plaintext = cipher
+            .decrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &associated_data(&self.ephemeral, &self.recipient),
+                },
+            )
+            .map_err(|_| Error::DecryptionFailed)?;
+        SerializedShare::from_bytes(&plaintext)
+    }
+}
+
+/// Encrypted shares for a set of recipients, safe to publish.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShareBundle {
+    pub shares: Vec<EncryptedShare>,
+    /// The dealer's signature over every entry, trailing the encoding.
+    pub signature: Option<DealerSignature>,
+}
+
+impl ShareBundle {
+    /// Seals `shares[i]` to `recipients[i]`.
+    pub fn seal(shares: &[SerializedShare], recipients: &[PublicKey]) -> Result<Self> {
+        if shares.len() != recipients.len() {
+            return Err(Error::InvalidParameters(
+                "need exactly one recipient key per share",
+            ));
+        }
+        let shares = shares
+            .iter()
+            .zip(recipients)
+            .map(|(share, recipient)| EncryptedShare::seal(share, recipient))
+            .collect::<Result<_>>()?;
+        Ok(ShareBundle {
+            shares,
+            signature: None,
+        })
+    }
+
+    /// Signs the bundle as the dealer, covering every recipient, ephemeral
+    /// key and ciphertext, and with them each sealed share's session.
+    pub fn sign(&mut self, key: &SigningKey) {
+        self.signature = Some(DealerSignature::sign(key, &self.signed_bytes()));
+    }
+
+    /// Checks that the bundle carries a valid signature by `dealer`.
+    pub fn verify_signature(&self, dealer: &VerifyingKey) -> Result<()> {
+        self.signature
+            .as_ref()
+            .ok_or(Error::InvalidSignature)?
+            .verify(dealer, &self.signed_bytes())
+    }
+
+    /// Like [`open`](Self::open), but first requires a valid signature by
+    /// `dealer` over the bundle, so substituted entries are refused.
+    pub fn open_signed(
+        &self,
+        secret: &StaticSecret,
+        dealer: &VerifyingKey,
+    ) -> Result<SerializedShare> {
+        self.verify_signature(dealer)?;
+        self.open(secret)
+    }
+
+    /// Finds and opens the entry addressed to `secret`'s public key.
+    pub fn open(&self, secret: &StaticSecret) -> Result<SerializedShare> {
+        let public = PublicKey::from(secret).to_bytes();
+        self.shares
+            .iter()
+            .find(|share| share.recipient == public)
+            .ok_or(Error::InvalidParameters("no share addressed to this key"))?
+            .open(secret)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.signed_bytes();
+        if let Some(signature) = &self.signature {
+            signature.put(&mut out);
+        }
+        out
+    }
+
+    /// The encoding without the trailing dealer signature.
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(BUNDLE_VERSION);
+        out.extend_from_slice(&(self.shares.len() as u16).to_be_bytes());
+        for share in &self.shares {
+            out.extend_from_slice(&share.recipient);
+            out.extend_from_slice(&share.ephemeral);
+            put_bytes_u32(&mut out, &share.ciphertext);
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if !(1..=BUNDLE_VERSION).contains(&version) {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        if input.len() < 2 {
+            return Err(Error::MalformedShare);
+        }
+        let count = u16::from_be_bytes([input[0], input[1]]);
+        input = &input[2..];
+
+        let mut shares = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            if input.len() < 64 {
+                return Err(Error::MalformedShare);
+            }
+            let mut recipient = [0u8; 32];
+            let mut ephemeral = [0u8; 32];
+            recipient.copy_from_slice(&input[..32]);
+            ephemeral.copy_from_slice(&input[32..64]);
+            input = &input[64..];
+            shares.push(EncryptedShare {
+                recipient,
+                ephemeral,
+                ciphertext: if version >= 2 {
+                    take_bytes_u32(&mut input)?
+                } else {
+                    take_bytes(&mut input)?
+                }
+                .to_vec(),
+            });
+        }
+        let signature = if input.is_empty() {
+            None
+        } else {
+            Some(DealerSignature::take(&mut input)?)
+        };
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(ShareBundle { shares, signature })
+    }
+
+    /// Encodes the bundle as an ASCII-armored text block.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+}
+
+fn cipher(shared: &[u8; 32], ephemeral: &[u8; 32], recipient: &[u8; 32]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&associated_data(ephemeral, recipient)), shared)
+        .expand(HKDF_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    // Every key is derived from a fresh ephemeral secret, so a fixed nonce is safe
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+fn associated_data(ephemeral: &[u8; 32], recipient: &[u8; 32]) -> [u8; 64] {
+    let mut aad = [0u8; 64];
+    aad[..32].copy_from_slice(ephemeral);
+    aad[32..].copy_from_slice(recipient);
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::reconstruct_auto;
+    use crate::sss::SecretSharer;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_bundle_seal_open() {
+        let sharer = SecretSharer::new(2, 3);
+        let secret = BigUint::from(424242u32);
+        let shares: Vec<SerializedShare> = sharer
+            .split_secret(&secret)
+            .iter()
+            .map(|share| sharer.serialize_share(share))
+            .collect();
+
+        let keys: Vec<StaticSecret> = (0..3)
+            .map(|_| StaticSecret::random_from_rng(OsRng))
+            .collect();
+        let publics: Vec<PublicKey> = keys.iter().map(PublicKey::from).collect();
+        let bundle = ShareBundle::seal(&shares, &publics).unwrap();
+        let bundle = ShareBundle::from_armored(&bundle.to_armored()).unwrap();
+
+        let opened: Vec<SerializedShare> =
+            keys[1..].iter().map(|k| bundle.open(k).unwrap()).collect();
+        assert_eq!(opened, shares[1..]);
+        assert_eq!(reconstruct_auto(&opened).unwrap(), secret.to_bytes_be());
+
+        // Tampering and the wrong key are both rejected
+        let mut tampered = bundle.shares[0].clone();
+        tampered.ciphertext[0] ^= 1;
+        assert!(matches!(
+            tampered.open(&keys[0]),
+            Err(Error::DecryptionFailed)
+        ));
+        assert!(bundle.shares[0].open(&keys[1]).is_err());
+    }
+
+    #[test]
+    fn test_signed_bundle() {
+        let dealer = SigningKey::generate(&mut OsRng);
+        let shares = crate::serialized::split_bytes(b"vault key", 2, 2).unwrap();
+        let keys: Vec<StaticSecret> = (0..2)
+            .map(|_| StaticSecret::random_from_rng(OsRng))
+            .collect();
+        let publics: Vec<PublicKey> = keys.iter().map(PublicKey::from).collect();
+        let mut bundle = ShareBundle::seal(&shares, &publics).unwrap();
+        assert!(bundle
+            .open_signed(&keys[0], &dealer.verifying_key())
+            .is_err());
+
+        bundle.sign(&dealer);
+        let bundle = ShareBundle::from_armored(&bundle.to_armored()).unwrap();
+        let opened = bundle
+            .open_signed(&keys[1], &dealer.verifying_key())
+            .unwrap();
+        assert_eq!(opened, shares[1]);
+
+        // An entry swapped in from another bundle breaks the signature
+        let mut spliced = bundle.clone();
+        spliced.shares[1] = ShareBundle::seal(&shares[..1], &publics[1..])
+            .unwrap()
+            .shares
+            .remove(0);
+        assert!(matches!(
+            spliced.open_signed(&keys[1], &dealer.verifying_key()),
+            Err(Error::InvalidSignature)
+        ));
+        let other = SigningKey::generate(&mut OsRng).verifying_key();
+        assert!(bundle.open_signed(&keys[1], &other).is_err());
+    }
+
+    #[test]
+    fn test_large_share_bundle() {
+        let secret: Vec<u8> = (0..70_000u32).map(|i| i as u8).collect();
+        let shares = crate::serialized::split_bytes(&secret, 2, 2).unwrap();
+        let keys: Vec<StaticSecret> = (0..2)
+            .map(|_| StaticSecret::random_from_rng(OsRng))
+            .collect();
+        let publics: Vec<PublicKey> = keys.iter().map(PublicKey::from).collect();
+        let bundle = ShareBundle::seal(&shares, &publics).unwrap();
+        assert!(bundle.shares[0].ciphertext.len() > 0xffff);
+        let bundle = ShareBundle::from_bytes(&bundle.to_bytes()).unwrap();
+        let opened: Vec<SerializedShare> = keys.iter().map(|k| bundle.open(k).unwrap()).collect();
+        assert_eq!(reconstruct_auto(&opened).unwrap(), secret);
+
+        // Version 1 bundles, with u16 lengths, still open
+        let small = crate::serialized::split_bytes(b"vault key", 1, 1).unwrap();
+        let sealed = EncryptedShare::seal(&small[0], &publics[0]).unwrap();
+        let mut v1 = MAGIC.to_vec();
+        v1.extend_from_slice(&[1, 0, 1]);
+        v1.extend_from_slice(&sealed.recipient);
+        v1.extend_from_slice(&sealed.ephemeral);
+        v1.extend_from_slice(&(sealed.ciphertext.len() as u16).to_be_bytes());
+        v1.extend_from_slice(&sealed.ciphertext);
+        let bundle = ShareBundle::from_bytes(&v1).unwrap();
+        assert_eq!(bundle.open(&keys[0]).unwrap(), small[0]);
+    }
+}
@@ -0,0 +1,277 @@
+// File: src/encoding.rs
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+const LINE_WIDTH: usize = 64;
+
+/// Wraps `data` in an ASCII armor block such as `-----BEGIN SHAMIR SHARE-----`.
+pub fn armor(label: &str, data: &[u8]) -> String {
+    armor_with_width(label, data, LINE_WIDTH)
+}
+
+/// Like [`armor`], wrapping the base64 body at `width` characters.
+pub(crate) fn armor_with_width(label: &str, data: &[u8], width: usize) -> String {
+    let body = STANDARD.encode(data);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(width) {
+        // Base64 output is always ASCII
+        out.push_str(std::str::from_utf8(line).unwrap());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Strips the armor produced by [`armor`] and returns the decoded payload.
+pub fn dearmor(label: &str, text: &str) -> Result<Vec<u8>> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    if lines.next() != Some(begin.as_str()) {
+        return Err(Error::InvalidArmor("missing BEGIN line"));
+    }
+
+    let mut body = String::new();
+    for line in lines.by_ref() {
+        if line == end {
+            return STANDARD
+                .decode(body)
+                .map_err(|_| Error::InvalidArmor("invalid base64 body"));
+        }
+        body.push_str(line);
+    }
+    Err(Error::InvalidArmor("missing END line"))
+}
+
+/// Formats `x` and `y` as the single-line form `prefix:x:y:checksum`.
+///
+/// `x` is decimal, `y` lowercase hex, and the checksum is the first four
+/// bytes of SHA-256 over everything before it, in hex.
+pub(crate) fn to_canonical(prefix: &str, x: &BigUint, y: &BigUint) -> String {
+    let body = format!("{}:{}:{}", prefix, x, y.to_str_radix(16));
+    let checksum = hex(&Sha256::digest(body.as_bytes())[..4]);
+    format!("{}:{}", body, checksum)
+}
+
+/// Parses a string produced by [`to_canonical`], verifying its checksum.
+pub(crate) fn from_canonical(prefix: &str, text: &str) -> Result<(BigUint, BigUint)> {
+    let text = text.trim();
+    let (body, checksum) = text.rsplit_once(':').ok_or(Error::InvalidEncoding(
+        "expected prefix:index:value:checksum",
+    ))?;
+    let mut fields = body.splitn(3, ':');
+    if fields.next() != Some(prefix) {
+        return Err(Error::InvalidEncoding("unexpected share prefix"));
+    }
+    let x = fields.next().and_then(|x| x.parse::<BigUint>().ok());
+    let y = fields
+        .next()
+        .and_then(|y| BigUint::parse_bytes(y.as_bytes(), 16));
+    let (x, y) = x
+        .zip(y)
+        .ok_or(Error::InvalidEncoding("invalid share index or value"))?;
+    if !checksum.eq_ignore_ascii_case(&hex(&Sha256::digest(body.as_bytes())[..4])) {
+        return Err(Error::ChecksumMismatch);
+    }
+    Ok((x, y))
+}
+
+/// Hex SHA-256 of a share's canonical form, safe to record in audit logs.
+pub(crate) fn fingerprint(canonical: &str) -> String {
+    hex(&Sha256::digest(canonical.as_bytes()))
+}
+
+/// Lowercase hex encoding.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decodes a hex string of either case.
+pub fn unhex(text: &str) -> Result<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidEncoding("invalid hex string"));
+    }
+    Ok((0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+/// Appends a u16 length prefix followed by `bytes` to `out`. Only for
+/// fields bounded well below 64 KiB; payloads use [`put_bytes_u32`].
+///
+/// # Panics
+///
+/// If `bytes` is 64 KiB or longer, rather than truncating its length.
+pub(crate) fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    let len = u16::try_from(bytes.len()).expect("u16-length fields stay below 64 KiB");
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// Reads a field written by [`put_bytes`], advancing `input` past it.
+pub(crate) fn take_bytes<'a>(input: &mut &'a [u8]) -> Result<&'a [u8]> {
+    if input.len() < 2 {
+        return Err(Error::MalformedShare);
+    }
+    let len = u16::from_be_bytes([input[0], input[1]]) as usize;
+    if input.len() < 2 + len {
+        return Err(Error::MalformedShare);
+    }
+    let bytes = &input[2..2 + len];
+    *input = &input[2 + len..];
+    Ok(bytes)
+}
+
+/// The version written after the magic of [`crate::sss::Share`] and
+/// [`crate::vss::Share`] encodings. Bare bodies predate it and count as
+/// version 1.
+pub const SHARE_VERSION: u8 = 2;
+
+/// Starts a share encoding with `magic` and [`SHARE_VERSION`].
+pub(crate) fn put_share_header(magic: &[u8; 3]) -> Vec<u8> {
+    let mut out = magic.to_vec();
+    out.push(SHARE_VERSION);
+    out
+}
+
+/// Splits a share encoding into its version and body, reporting version 1
+/// for a bare body without `magic`.
+pub(crate) fn take_share_header<'a>(magic: &[u8; 3], bytes: &'a [u8]) -> Result<(u8, &'a [u8])> {
+    match bytes.strip_prefix(magic.as_slice()) {
+        Some([version, body @ ..]) => Ok((*version, body)),
+        Some([]) => Err(Error::MalformedShare),
+        None => Ok((1, bytes)),
+    }
+}
+
+/// Appends a length-prefixed big-endian integer to `out`.
+pub(crate) fn put_biguint(out: &mut Vec<u8>, value: &BigUint) {
+    put_bytes(out, &value.to_bytes_be());
+}
+
+/// Reads an integer written by [`put_biguint`], advancing `input` past it.
+pub(crate) fn take_biguint(input: &mut &[u8]) -> Result<BigUint> {
+    take_bytes(input).map(BigUint::from_bytes_be)
+}
+
+/// The number of bytes needed to hold any value below `modulus`.
+pub fn byte_width(modulus: &BigUint) -> usize {
+    (modulus.bits() as usize).div_ceil(8)
+}
+
+/// The canonical numeric encoding: `value` big-endian, left-padded with
+/// zeros to exactly `width` bytes. Fails if `value` needs more.
+///
+/// Field elements use the [`byte_width`] of their modulus wherever the
+/// encoding carries or implies that modulus, so those encoders write the
+/// same element as the same bytes. Encodings without one, such as the bare
+/// [`crate::sss::Share`] and [`crate::vss::Share`] binaries, keep minimal
+/// length-prefixed integers.
+pub fn to_fixed_bytes(value: &BigUint, width: usize) -> Result<Vec<u8>> {
+    let bytes = value.to_bytes_be();
+    let padding = width
+        .checked_sub(bytes.len())
+        .ok_or(Error::InvalidEncoding(
+            "value does not fit in the encoded width",
+        ))?;
+    let mut out = vec![0u8; padding];
+    out.extend_from_slice(&bytes);
+    Ok(out)
+}
+
+/// `value` in the canonical fixed-width encoding when it fits `width`, for
+/// length-prefixed fields. Only invalid input holds wider values, and those
+/// are written whole for the decoder to refuse rather than truncated.
+pub(crate) fn padded(value: &BigUint, width: usize) -> Vec<u8> {
+    to_fixed_bytes(value, width).unwrap_or_else(|_| value.to_bytes_be())
+}
+
+/// Reads a [`to_fixed_bytes`] encoding, rejecting any length but `width`.
+pub fn from_fixed_bytes(bytes: &[u8], width: usize) -> Result<BigUint> {
+    if bytes.len() != width {
+        return Err(Error::InvalidEncoding("wrong encoded length"));
+    }
+    Ok(BigUint::from_bytes_be(bytes))
+}
+
+/// [`to_fixed_bytes`] at the width of `modulus`, failing for a value wider
+/// than it.
+pub(crate) fn to_fixed(value: &BigUint, modulus: &BigUint) -> Result<Vec<u8>> {
+    to_fixed_bytes(value, byte_width(modulus))
+}
+
+/// Reads an encoding written by [`to_fixed`], rejecting other widths and
+/// values outside `0..modulus`.
+pub(crate) fn from_fixed(bytes: &[u8], modulus: &BigUint) -> Result<BigUint> {
+    let value = from_fixed_bytes(bytes, byte_width(modulus))?;
+    if &value >= modulus {
+        return Err(Error::InvalidEncoding("value out of range"));
+    }
+    Ok(value)
+}
+
+/// Reads a big-endian u32, advancing `input` past it.
+pub(crate) fn take_u32(input: &mut &[u8]) -> Result<u32> {
+    if input.len() < 4 {
+        return Err(Error::MalformedShare);
+    }
+    let value = u32::from_be_bytes([input[0], input[1], input[2], input[3]]);
+    *input = &input[4..];
+    Ok(value)
+}
+
+/// Appends a u32 length prefix followed by `bytes` to `out`.
+pub(crate) fn put_bytes_u32(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// Reads a u32-length-prefixed field, as used by the SSH wire format.
+pub(crate) fn take_bytes_u32<'a>(input: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = take_u32(input)? as usize;
+    if input.len() < len {
+        return Err(Error::MalformedShare);
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_armor_roundtrip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let text = armor("TEST BLOCK", &data);
+        assert!(text.starts_with("-----BEGIN TEST BLOCK-----\n"));
+        assert_eq!(dearmor("TEST BLOCK", &text).unwrap(), data);
+
+        // Wrong label and truncated input are rejected
+        assert!(dearmor("OTHER BLOCK", &text).is_err());
+        let truncated: String = text.lines().take(2).collect::<Vec<_>>().join("\n");
+        assert!(dearmor("TEST BLOCK", &truncated).is_err());
+    }
+
+    #[test]
+    fn test_fixed_width_integers() {
+        let modulus = BigUint::from(0x1_0001u32);
+        assert_eq!(byte_width(&modulus), 3);
+        let bytes = to_fixed(&BigUint::from(5u8), &modulus).unwrap();
+        assert_eq!(bytes, [0, 0, 5]);
+        assert_eq!(from_fixed(&bytes, &modulus).unwrap(), BigUint::from(5u8));
+        assert!(from_fixed(&[0, 5], &modulus).is_err());
+        assert!(from_fixed(&[1, 0, 1], &modulus).is_err());
+
+        assert_eq!(to_fixed_bytes(&BigUint::from(0u8), 2).unwrap(), [0, 0]);
+        assert!(to_fixed_bytes(&BigUint::from(0x1_0000u32), 2).is_err());
+        assert_eq!(from_fixed_bytes(&[0, 0, 7], 3).unwrap(), BigUint::from(7u8));
+    }
+}
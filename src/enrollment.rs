@@ -0,0 +1,141 @@
+// File: src/enrollment.rs
+//! Issuing a Shamir share at a new point without a dealer.
+//!
+//! When the dealer's polynomial is gone, a threshold of shareholders can
+//! still give a new member the share `f(x)`: holder `i` owns the term
+//! `λ_i(x)·y_i` of the Lagrange interpolation at `x`. Sent as is, a term
+//! would reveal `y_i`, so each holder splits it into random additive pieces,
+//! one for every participating holder ([`Enrollment::contribute`]). Each
+//! holder sums the pieces it receives ([`Enrollment::relay`]) and passes the
+//! sum on; the new member adds the sums up ([`Enrollment::finish`]) and
+//! learns only `f(x)`. Pieces travel between holders over confidential
+//! channels.
+//!
+//! A dealer that kept its polynomial can instead call
+//! [`crate::sss::Polynomial::share_at`].
+
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, Zero};
+use rand::thread_rng;
+
+use crate::error::{Error, Result};
+use crate::sss::Share;
+
+/// One enrollment of a new point `x`, run by the holders of `participants`.
+pub struct Enrollment {
+    prime: BigUint,
+    x: BigUint,
+    participants: Vec<BigUint>,
+}
+
+impl Enrollment {
+    /// `participants` are the indices of the contributing holders, at least a
+    /// threshold of them; `x` must be a new, nonzero point.
+    pub fn new(prime: BigUint, x: BigUint, participants: Vec<BigUint>) -> Result<Self> {
+        if (&x % &prime).is_zero() {
+            return Err(Error::InvalidParameters(
+                "a share at zero would be the secret",
+            ));
+        }
+        for (i, id) in participants.iter().enumerate() {
+            if (id % &prime).is_zero() || participants[..i].contains(id) {
+                return Err(Error::InconsistentShares("duplicate share indices"));
+            }
+            if id == &x {
+                return Err(Error::InvalidParameters(
+                    "the new point already has a share",
+                ));
+            }
+        }
+        Ok(Enrollment {
+            prime,
+            x,
+            participants,
+        })
+    }
+
+    /// Splits a holder's term into pieces, the `j`-th for the `j`-th
+    /// participant (the holder keeps its own).
+    pub fn contribute(&self, share: &Share) -> Result<Vec<BigUint>> {
+        if !self.participants.contains(&share.x) {
+            return Err(Error::InvalidParameters(
+                "the share's holder is not a participant",
+            ));
+        }
+        let p = &self.prime;
+        let term = self.lagrange_at_x(&share.x) * &share.y % p;
+        let mut rng = thread_rng();
+        let mut pieces: Vec<BigUint> = (1..self.participants.len())
+            .map(|_| rng.gen_biguint_below(p))
+            .collect();
+        let masks = pieces
+            .iter()
+            .fold(BigUint::zero(), |acc, piece| (acc + piece) % p);
+        pieces.push((term + p - masks) % p);
+        Ok(pieces)
+    }
+
+    /// Sums the pieces a holder received, one from each participant.
+    pub fn relay(&self, pieces: &[BigUint]) -> BigUint {
+        pieces
+            .iter()
+            .fold(BigUint::zero(), |acc, piece| (acc + piece) % &self.prime)
+    }
+
+    /// The new member's share, from every holder's relayed sum.
+    pub fn finish(&self, sums: &[BigUint]) -> Result<Share> {
+        if sums.len() != self.participants.len() {
+            return Err(Error::InsufficientShares);
+        }
+        Ok(Share {
+            x: self.x.clone(),
+            y: self.relay(sums),
+        })
+    }
+
+    /// `λ_i(x) = Π_{j≠i} (x - x_j) / (x_i - x_j)` modulo the prime.
+    fn lagrange_at_x(&self, i: &BigUint) -> BigUint {
+        let p = &self.prime;
+        let sub = |a: &BigUint, b: &BigUint| (a % p + p - b % p) % p;
+        let (numerator, denominator) = self
+            .participants
+            .iter()
+            .filter(|j| *j != i)
+            .fold((BigUint::one(), BigUint::one()), |(num, den), j| {
+                (num * sub(&self.x, j) % p, den * sub(i, j) % p)
+            });
+        numerator * denominator.modpow(&(p - 2u32), p) % p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sss::SecretSharer;
+
+    #[test]
+    fn test_enrollment_without_dealer() {
+        let prime = BigUint::from(2u32).pow(127) - 1u32;
+        let sharer = SecretSharer::with_prime(prime.clone(), 3, 5);
+        let shares = sharer.split_secret(&BigUint::from(31337u32));
+        let holders = [&shares[0], &shares[2], &shares[4]];
+        let ids = holders.iter().map(|share| share.x.clone()).collect();
+        let enrollment = Enrollment::new(prime, BigUint::from(42u32), ids).unwrap();
+
+        let pieces: Vec<_> = holders
+            .iter()
+            .map(|share| enrollment.contribute(share).unwrap())
+            .collect();
+        let sums: Vec<_> = (0..holders.len())
+            .map(|j| enrollment.relay(&pieces.iter().map(|p| p[j].clone()).collect::<Vec<_>>()))
+            .collect();
+        let enrolled = enrollment.finish(&sums).unwrap();
+
+        let quorum = [enrolled, shares[1].clone(), shares[3].clone()];
+        assert_eq!(
+            sharer.reconstruct_secret(&quorum),
+            Some(BigUint::from(31337u32))
+        );
+        assert!(enrollment.contribute(&shares[1]).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// File: src/envelope.rs
+//! Hybrid envelopes for secrets too large to split directly.
+//!
+//! [`Envelope::seal`] encrypts the data with XChaCha20-Poly1305 under a fresh
+//! random key and splits only that 32-byte key, so each share stays small no
+//! matter how large the file is. The ciphertext can be stored anywhere; it is
+//! useless without a threshold of key shares.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::encoding::{armor, dearmor};
+use crate::error::{Error, Result};
+use crate::serialized::{reconstruct_auto, split_bytes, SerializedShare};
+
+const ARMOR_LABEL: &str = "ENCRYPTED ENVELOPE";
+const MAGIC: &[u8; 3] = b"SHE";
+const ENVELOPE_VERSION: u8 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 24;
+
+/// Metadata key marking shares that hold an envelope key.
+pub const ENVELOPE_METADATA: &str = "envelope";
+
+/// Data encrypted under a key that has been split into shares.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Envelope {
+    pub nonce: [u8; 24],
+    pub ciphertext: Vec<u8>,
+}
+
+impl Envelope {
+    /// Encrypts `data` and splits the key into `total` shares, any
+    /// `threshold` of which open the envelope.
+    pub fn seal(
+        data: &[u8],
+        threshold: usize,
+        total: usize,
+    ) -> Result<(Self, Vec<SerializedShare>)> {
+        let mut key = [0u8; 32];
+        let mut nonce = [0u8; 24];
+        OsRng.fill_bytes(&mut key);
+        OsRng.fill_bytes(&mut nonce);
+
+        let shares = split_bytes(&key, threshold, total)?
+            .into_iter()
+            .map(|share| share.with_metadata(ENVELOPE_METADATA, "xchacha20poly1305"))
+            .collect();
+        let mut envelope = Envelope {
+            nonce,
+            ciphertext: Vec::new(),
+        };
+        envelope.ciphertext = XChaCha20Poly1305::new(&key.into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: data,
+                    aad: &envelope.header(),
+                },
+            )
+            .map_err(|_| Error::InvalidParameters("data too large for a single envelope"))?;
+        Ok((envelope, shares))
+    }
+
+    /// Recovers the key from `shares` and decrypts the data.
+    pub fn open(&self, shares: &[SerializedShare]) -> Result<Vec<u8>> {
+        let key: [u8; 32] = reconstruct_auto(shares)?
+            .try_into()
+            .map_err(|_| Error::InconsistentShares("shares do not hold an envelope key"))?;
+        XChaCha20Poly1305::new(&key.into())
+            .decrypt(
+                XNonce::from_slice(&self.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &self.header(),
+                },
+            )
+            .map_err(|_| Error::DecryptionFailed)
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(ENVELOPE_VERSION);
+        out.extend_from_slice(&self.nonce);
+        out
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.header();
+        out.extend_from_slice(&self.ciphertext);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if version != ENVELOPE_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(Error::MalformedShare);
+        }
+        let mut nonce = [0u8; 24];
+        nonce.copy_from_slice(&input[..24]);
+        Ok(Envelope {
+            nonce,
+            ciphertext: input[24..].to_vec(),
+        })
+    }
+
+    /// Encodes the envelope as an ASCII-armored text block.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i * 7) as u8).collect();
+        let (envelope, shares) = Envelope::seal(&data, 3, 5).unwrap();
+        assert!(shares.iter().all(|share| share.value.len() == 32));
+
+        let decoded = Envelope::from_bytes(&envelope.to_bytes()).unwrap();
+        assert_eq!(decoded.open(&shares[1..4]).unwrap(), data);
+        assert!(decoded.open(&shares[..2]).is_err());
+
+        let mut tampered = decoded.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert!(matches!(
+            tampered.open(&shares[..3]),
+            Err(Error::DecryptionFailed)
+        ));
+    }
+}
@@ -0,0 +1,61 @@
+// File: src/error.rs
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid armor: {0}")]
+    InvalidArmor(&'static str),
+    #[error("malformed share encoding")]
+    MalformedShare,
+    #[error("invalid encoding: {0}")]
+    InvalidEncoding(&'static str),
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(&'static str),
+    #[error("unknown word {word:?} at position {position}")]
+    UnknownWord { position: usize, word: String },
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+    #[error("checksum mismatch; likely typos at character positions {0:?}")]
+    TranscriptionErrors(Vec<usize>),
+    #[error("invalid parameters: {0}")]
+    InvalidParameters(&'static str),
+    #[error("inconsistent shares: {0}")]
+    InconsistentShares(&'static str),
+    #[error("not enough shares to reconstruct the secret")]
+    InsufficientShares,
+    #[error("digest of the recovered secret does not match")]
+    DigestMismatch,
+    #[error("missing or invalid dealer signature")]
+    InvalidSignature,
+    #[error("decryption failed: wrong key or tampered ciphertext")]
+    DecryptionFailed,
+    #[error("share is outside its validity window")]
+    ShareExpired,
+    #[error("share has been revoked")]
+    ShareRevoked,
+    #[error("checkpoint failed authentication: wrong key or tampered state")]
+    CheckpointTampered,
+    #[error("operation cancelled")]
+    Cancelled,
+    #[error("unsupported format version {0}")]
+    UnsupportedVersion(u8),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "qr")]
+    #[error("QR code error: {0}")]
+    Qr(String),
+    #[cfg(feature = "keyring")]
+    #[error("keychain error: {0}")]
+    Keyring(String),
+    #[cfg(feature = "pkcs11")]
+    #[error("PKCS#11 error: {0}")]
+    Pkcs11(String),
+    #[cfg(feature = "fido2")]
+    #[error("FIDO2 error: {0}")]
+    Fido2(String),
+    #[cfg(any(feature = "aws-kms", feature = "gcp-kms"))]
+    #[error("KMS error: {0}")]
+    Kms(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,232 @@
+// File: src/escrow.rs
+//! Envelope encryption of shares to cloud key management services.
+//!
+//! An [`EscrowBackend`] issues data keys wrapped under a key it manages. A
+//! share is encrypted locally with ChaCha20-Poly1305 under a fresh data key,
+//! and only the wrapped data key travels to the service, so escrowing each
+//! share to a different account keeps every share in its own trust domain.
+//! The share's fingerprint is bound to the data key as KMS context and, with
+//! the rest of the [`EscrowedShare`] header, authenticated by the local AEAD.
+//!
+//! Reference backends live in [`crate::kms`] behind the `aws-kms` and
+//! `gcp-kms` features.
+
+use std::future::Future;
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::encoding::{armor, dearmor, put_bytes, take_bytes};
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+
+const ARMOR_LABEL: &str = "ESCROWED SHARE";
+const MAGIC: &[u8; 3] = b"SHK";
+const ESCROWED_VERSION: u8 = 1;
+
+/// A plaintext data key and its wrapped form.
+pub struct DataKey {
+    pub plaintext: [u8; 32],
+    pub wrapped: Vec<u8>,
+}
+
+impl Drop for DataKey {
+    fn drop(&mut self) {
+        self.plaintext.fill(0);
+    }
+}
+
+/// A key management service that wraps data keys.
+///
+/// `context` is authenticated by the service: a data key only unwraps with
+/// the context it was issued under.
+pub trait EscrowBackend: Sync {
+    /// Identifies the wrapping key, e.g. a KMS key ARN or resource name.
+    fn key_id(&self) -> &str;
+
+    fn generate_data_key(&self, context: &str) -> impl Future<Output = Result<DataKey>> + Send;
+
+    fn unwrap_data_key(
+        &self,
+        wrapped: &[u8],
+        context: &str,
+    ) -> impl Future<Output = Result<[u8; 32]>> + Send;
+
+    /// Encrypts a share under a fresh data key from this backend.
+    fn wrap_share(
+        &self,
+        share: &SerializedShare,
+    ) -> impl Future<Output = Result<EscrowedShare>> + Send {
+        async move {
+            let fingerprint = share.fingerprint();
+            let key = self.generate_data_key(&fingerprint).await?;
+            let mut nonce = [0u8; 12];
+            OsRng.fill_bytes(&mut nonce);
+            let mut escrowed = EscrowedShare {
+                key_id: self.key_id().into(),
+                fingerprint,
+                wrapped_key: key.wrapped.clone(),
+                nonce,
+                ciphertext: Vec::new(),
+            };
+            escrowed.ciphertext = ChaCha20Poly1305::new(&key.plaintext.into())
+                .encrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &share.to_bytes(),
+                        aad: &escrowed.header(),
+                    },
+                )
+                .expect("a share fits in a single ChaCha20-Poly1305 message");
+            Ok(escrowed)
+        }
+    }
+
+    /// Recovers a share escrowed with [`EscrowBackend::wrap_share`].
+    fn unwrap_share(
+        &self,
+        escrowed: &EscrowedShare,
+    ) -> impl Future<Output = Result<SerializedShare>> + Send {
+        async move {
+            if escrowed.key_id != self.key_id() {
+                return Err(Error::DecryptionFailed);
+            }
+            let mut key = self
+                .unwrap_data_key(&escrowed.wrapped_key, &escrowed.fingerprint)
+                .await?;
+            let plaintext = ChaCha20Poly1305::new(&key.into()).decrypt(
+                Nonce::from_slice(&escrowed.nonce),
+                Payload {
+                    msg: &escrowed.ciphertext,
+                    aad: &escrowed.header(),
+                },
+            );
+            key.fill(0);
+            let share =
+                SerializedShare::from_bytes(&plaintext.map_err(|_| Error::DecryptionFailed)?)?;
+            if share.fingerprint() != escrowed.fingerprint {
+                return Err(Error::DecryptionFailed);
+            }
+            Ok(share)
+        }
+    }
+}
+
+/// A share encrypted under a data key wrapped by an [`EscrowBackend`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscrowedShare {
+    pub key_id: String,
+    /// The fingerprint of the escrowed share, also its KMS context.
+    pub fingerprint: String,
+    pub wrapped_key: Vec<u8>,
+    pub nonce: [u8; 12],
+    pub ciphertext: Vec<u8>,
+}
+
+impl EscrowedShare {
+    fn header(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(ESCROWED_VERSION);
+        put_bytes(&mut out, self.key_id.as_bytes());
+        put_bytes(&mut out, self.fingerprint.as_bytes());
+        put_bytes(&mut out, &self.wrapped_key);
+        out.extend_from_slice(&self.nonce);
+        out
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.header();
+        out.extend_from_slice(&self.ciphertext);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if version != ESCROWED_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let mut text = || {
+            String::from_utf8(take_bytes(&mut input)?.to_vec()).map_err(|_| Error::MalformedShare)
+        };
+        let key_id = text()?;
+        let fingerprint = text()?;
+        let wrapped_key = take_bytes(&mut input)?.to_vec();
+        if input.len() < 12 {
+            return Err(Error::MalformedShare);
+        }
+        let (nonce, ciphertext) = input.split_at(12);
+        Ok(EscrowedShare {
+            key_id,
+            fingerprint,
+            wrapped_key,
+            nonce: nonce.try_into().expect("nonce is 12 bytes"),
+            ciphertext: ciphertext.to_vec(),
+        })
+    }
+
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::split_bytes;
+
+    /// Wraps data keys by XOR with a fixed key, tagged with the context.
+    struct LocalBackend([u8; 32]);
+
+    impl EscrowBackend for LocalBackend {
+        fn key_id(&self) -> &str {
+            "local"
+        }
+
+        async fn generate_data_key(&self, context: &str) -> Result<DataKey> {
+            let mut plaintext = [0u8; 32];
+            OsRng.fill_bytes(&mut plaintext);
+            let mut wrapped: Vec<u8> = plaintext.iter().zip(self.0).map(|(a, b)| a ^ b).collect();
+            wrapped.extend_from_slice(context.as_bytes());
+            Ok(DataKey { plaintext, wrapped })
+        }
+
+        async fn unwrap_data_key(&self, wrapped: &[u8], context: &str) -> Result<[u8; 32]> {
+            if &wrapped[32..] != context.as_bytes() {
+                return Err(Error::DecryptionFailed);
+            }
+            let mut key = [0u8; 32];
+            for (out, (a, b)) in key.iter_mut().zip(wrapped.iter().zip(self.0)) {
+                *out = a ^ b;
+            }
+            Ok(key)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_escrow_round_trip() {
+        let shares = split_bytes(b"cloud escrow", 2, 3).unwrap();
+        let backend = LocalBackend([5; 32]);
+        let escrowed = backend.wrap_share(&shares[1]).await.unwrap();
+        assert_eq!(escrowed.fingerprint, shares[1].fingerprint());
+
+        let decoded = EscrowedShare::from_armored(&escrowed.to_armored()).unwrap();
+        assert_eq!(decoded, escrowed);
+        assert_eq!(backend.unwrap_share(&decoded).await.unwrap(), shares[1]);
+
+        let mut swapped = decoded.clone();
+        swapped.fingerprint = shares[0].fingerprint();
+        assert!(matches!(
+            backend.unwrap_share(&swapped).await,
+            Err(Error::DecryptionFailed)
+        ));
+    }
+}
@@ -0,0 +1,353 @@
+// File: src/ethereum.rs
+//! Splitting of Ethereum private keys and V3 keystore files.
+//!
+//! The 32-byte secp256k1 private key is shared over the secp256k1 scalar
+//! field, so the shares are the ones [`crate::service::convert`] and the
+//! threshold signing code expect. The account address rides along as share
+//! metadata and is checked when the key is rebuilt.
+//!
+//! Keystores follow the Web3 Secret Storage definition: AES-128-CTR under a
+//! scrypt- or PBKDF2-SHA256-derived key, with a Keccak-256 MAC. Reading
+//! accepts either KDF; written keystores use scrypt.
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::SecretKey;
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+use crate::encoding::{hex, unhex};
+use crate::error::{Error, Result};
+use crate::keys::{check_format, META_FORMAT};
+use crate::serialized::{reconstruct_auto, SerializedShare};
+use crate::sss::{named_prime, SecretSharer};
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+const KEY_FORMAT: &str = "ethereum";
+const META_ADDRESS: &str = "key.address";
+
+/// scrypt cost parameters for written keystores.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScryptParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for ScryptParams {
+    /// geth's standard parameters (N = 2^18, r = 8, p = 1).
+    fn default() -> Self {
+        ScryptParams {
+            log_n: 18,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+impl ScryptParams {
+    /// geth's light parameters (N = 2^12, r = 8, p = 6), for constrained
+    /// devices.
+    pub fn light() -> Self {
+        ScryptParams {
+            log_n: 12,
+            r: 8,
+            p: 6,
+        }
+    }
+
+    fn derive_key(&self, password: &[u8], salt: &[u8]) -> Result<[u8; 32]> {
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, 32)
+            .map_err(|_| Error::InvalidParameters("invalid scrypt parameters"))?;
+        let mut key = [0u8; 32];
+        scrypt::scrypt(password, salt, &params, &mut key)
+            .map_err(|_| Error::InvalidParameters("invalid scrypt parameters"))?;
+        Ok(key)
+    }
+}
+
+/// The JSON shape of a V3 keystore; byte strings are unprefixed hex.
+#[derive(Serialize, Deserialize)]
+struct Keystore {
+    version: u8,
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(alias = "Crypto")]
+    crypto: CryptoSection,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CryptoSection {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: serde_json::Value,
+    mac: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+#[derive(Deserialize)]
+struct ScryptKdf {
+    dklen: usize,
+    n: u64,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+#[derive(Deserialize)]
+struct Pbkdf2Kdf {
+    dklen: usize,
+    c: u32,
+    prf: String,
+    salt: String,
+}
+
+/// The account address of a private key: `0x` and 40 lowercase hex digits.
+pub fn address(key: &[u8; 32]) -> Result<String> {
+    let secret = SecretKey::from_slice(key)
+        .map_err(|_| Error::InvalidParameters("not a valid secp256k1 private key"))?;
+    let point = secret.public_key().to_encoded_point(false);
+    let digest = Keccak256::digest(&point.as_bytes()[1..]);
+    Ok(format!("0x{}", hex(&digest[12..])))
+}
+
+/// Decrypts a V3 keystore; a wrong password yields [`Error::DecryptionFailed`].
+pub fn decrypt_keystore(json: &str, password: &[u8]) -> Result<[u8; 32]> {
+    let keystore: Keystore = serde_json::from_str(json)
+        .map_err(|_| Error::InvalidEncoding("invalid V3 keystore JSON"))?;
+    if keystore.version != 3 {
+        return Err(Error::UnsupportedVersion(keystore.version));
+    }
+    let crypto = &keystore.crypto;
+    if crypto.cipher != "aes-128-ctr" {
+        return Err(Error::InvalidParameters(
+            "only aes-128-ctr keystores are supported",
+        ));
+    }
+    let invalid = |_| Error::InvalidEncoding("invalid keystore KDF parameters");
+    let derived = match crypto.kdf.as_str() {
+        "scrypt" => {
+            let kdf: ScryptKdf =
+                serde_json::from_value(crypto.kdfparams.clone()).map_err(invalid)?;
+            if kdf.dklen != 32 || !kdf.n.is_power_of_two() {
+                return Err(Error::InvalidParameters("unsupported scrypt parameters"));
+            }
+            let params = ScryptParams {
+                log_n: kdf.n.trailing_zeros() as u8,
+                r: kdf.r,
+                p: kdf.p,
+            };
+            params.derive_key(password, &unhex(&kdf.salt)?)?
+        }
+        "pbkdf2" => {
+            let kdf: Pbkdf2Kdf =
+                serde_json::from_value(crypto.kdfparams.clone()).map_err(invalid)?;
+            if kdf.dklen != 32 || kdf.prf != "hmac-sha256" {
+                return Err(Error::InvalidParameters("unsupported PBKDF2 parameters"));
+            }
+            let mut key = [0u8; 32];
+            pbkdf2::pbkdf2_hmac::<Sha256>(password, &unhex(&kdf.salt)?, kdf.c, &mut key);
+            key
+        }
+        _ => return Err(Error::InvalidParameters("unsupported keystore KDF")),
+    };
+
+    let ciphertext = unhex(&crypto.ciphertext)?;
+    if hex(&mac(&derived, &ciphertext)) != crypto.mac.to_lowercase() {
+        return Err(Error::DecryptionFailed);
+    }
+    let mut key: [u8; 32] = ciphertext
+        .try_into()
+        .map_err(|_| Error::InvalidEncoding("keystore ciphertext must be 32 bytes"))?;
+    let iv: [u8; 16] = unhex(&crypto.cipherparams.iv)?
+        .try_into()
+        .map_err(|_| Error::InvalidEncoding("keystore IV must be 16 bytes"))?;
+    Aes128Ctr::new(derived[..16].into(), &iv.into()).apply_keystream(&mut key);
+    Ok(key)
+}
+
+/// Encrypts `key` into a V3 keystore under `password`.
+pub fn encrypt_keystore(key: &[u8; 32], password: &[u8], kdf: ScryptParams) -> Result<String> {
+    let address = address(key)?;
+    let mut salt = [0u8; 32];
+    let mut iv = [0u8; 16];
+    let mut id = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut iv);
+    OsRng.fill_bytes(&mut id);
+
+    let derived = kdf.derive_key(password, &salt)?;
+    let mut ciphertext = *key;
+    Aes128Ctr::new(derived[..16].into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+    // A version 4 UUID
+    id[6] = (id[6] & 0x0f) | 0x40;
+    id[8] = (id[8] & 0x3f) | 0x80;
+    let id = hex(&id);
+    let keystore = Keystore {
+        version: 3,
+        id: format!(
+            "{}-{}-{}-{}-{}",
+            &id[..8],
+            &id[8..12],
+            &id[12..16],
+            &id[16..20],
+            &id[20..]
+        ),
+        address: Some(address[2..].to_string()),
+        crypto: CryptoSection {
+            cipher: "aes-128-ctr".into(),
+            cipherparams: CipherParams { iv: hex(&iv) },
+            ciphertext: hex(&ciphertext),
+            kdf: "scrypt".into(),
+            kdfparams: serde_json::json!({
+                "dklen": 32,
+                "n": 1u64 << kdf.log_n,
+                "r": kdf.r,
+                "p": kdf.p,
+                "salt": hex(&salt),
+            }),
+            mac: hex(&mac(&derived, &ciphertext)),
+        },
+    };
+    Ok(serde_json::to_string_pretty(&keystore).expect("keystores serialize"))
+}
+
+/// Splits a private key over the secp256k1 scalar field.
+pub fn split_private_key(
+    key: &[u8; 32],
+    threshold: usize,
+    total: usize,
+) -> Result<Vec<SerializedShare>> {
+    let address = address(key)?;
+    if threshold == 0 || threshold > total {
+        return Err(Error::InvalidParameters(
+            "threshold must be between 1 and the total",
+        ));
+    }
+    let sharer = SecretSharer::with_prime(named_prime("secp256k1")?, threshold, total);
+    Ok(sharer
+        .split_secret(&BigUint::from_bytes_be(key))
+        .iter()
+        .map(|share| {
+            sharer
+                .serialize_share(share)
+                .with_metadata(META_FORMAT, KEY_FORMAT)
+                .with_metadata(META_ADDRESS, address.clone())
+        })
+        .collect())
+}
+
+/// Decrypts a V3 keystore and splits its private key.
+pub fn split_keystore(
+    json: &str,
+    password: &[u8],
+    threshold: usize,
+    total: usize,
+) -> Result<Vec<SerializedShare>> {
+    split_private_key(&decrypt_keystore(json, password)?, threshold, total)
+}
+
+/// Rebuilds the private key from shares made by [`split_private_key`],
+/// checking it against the address the shares carry.
+pub fn reconstruct_private_key(shares: &[SerializedShare]) -> Result<[u8; 32]> {
+    let first = check_format(shares, KEY_FORMAT)?;
+    let secret = reconstruct_auto(shares)?;
+    let mut key = [0u8; 32];
+    let offset = key
+        .len()
+        .checked_sub(secret.len())
+        .ok_or(Error::InconsistentShares(
+            "recovered key is longer than 32 bytes",
+        ))?;
+    key[offset..].copy_from_slice(&secret);
+    if first.metadata.get(META_ADDRESS) != Some(&address(&key)?) {
+        return Err(Error::DigestMismatch);
+    }
+    Ok(key)
+}
+
+/// Rebuilds the private key and encrypts it into a fresh V3 keystore.
+pub fn reconstruct_keystore(
+    shares: &[SerializedShare],
+    password: &[u8],
+    kdf: ScryptParams,
+) -> Result<String> {
+    encrypt_keystore(&reconstruct_private_key(shares)?, password, kdf)
+}
+
+fn mac(derived: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+    Keccak256::new()
+        .chain_update(&derived[16..])
+        .chain_update(ciphertext)
+        .finalize()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // PBKDF2 test vector from the Web3 Secret Storage definition
+    const PBKDF2_KEYSTORE: &str = r#"{"crypto":{"cipher":"aes-128-ctr","cipherparams":{"iv":"6087dab2f9fdbbfaddc31a909735c1e6"},"ciphertext":"5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46","kdf":"pbkdf2","kdfparams":{"c":262144,"dklen":32,"prf":"hmac-sha256","salt":"ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"},"mac":"517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"},"id":"3198bc9c-6672-5ab3-d995-4942343ae5b6","version":3}"#;
+
+    const FAST: ScryptParams = ScryptParams {
+        log_n: 10,
+        r: 8,
+        p: 1,
+    };
+
+    #[test]
+    fn test_keystore_split_roundtrip() {
+        assert_eq!(
+            hex(&decrypt_keystore(PBKDF2_KEYSTORE, b"testpassword").unwrap()),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            address(&one).unwrap(),
+            "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+        );
+        assert!(address(&[0; 32]).is_err());
+
+        let keystore = encrypt_keystore(&one, b"hunter2", FAST).unwrap();
+        assert!(matches!(
+            decrypt_keystore(&keystore, b"hunter3"),
+            Err(Error::DecryptionFailed)
+        ));
+        let shares = split_keystore(&keystore, b"hunter2", 2, 3).unwrap();
+        assert!(matches!(
+            shares[0].scheme,
+            crate::serialized::Scheme::Shamir { .. }
+        ));
+        // The leading zero bytes of the key come back
+        assert_eq!(reconstruct_private_key(&shares[1..]).unwrap(), one);
+
+        let rebuilt = reconstruct_keystore(&shares[..2], b"new", FAST).unwrap();
+        assert!(rebuilt.contains("\"address\": \"7e5f4552091a69125d5dfcb7b8c2659029395bdf\""));
+        assert_eq!(decrypt_keystore(&rebuilt, b"new").unwrap(), one);
+
+        let mut forged = shares.clone();
+        for share in &mut forged {
+            share.metadata.insert(META_ADDRESS.into(), "0x00".into());
+        }
+        assert!(matches!(
+            reconstruct_private_key(&forged),
+            Err(Error::DigestMismatch)
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+// File: src/expiry.rs
+//! Validity windows and rotation schedules carried in share metadata.
+//!
+//! A dealer stamps each share with a [`Validity`]: when it was issued, when
+//! it expires and how often the dealing should be rotated, stored as Unix
+//! seconds under the `issued`, `expires` and `rotate-every` metadata keys.
+//! [`check`] compares a set of shares against the clock and reports each
+//! problem as a [`Notice`]; under [`Enforcement::Refuse`] an expired or
+//! not-yet-valid share is an error instead, while a due rotation is only
+//! ever reported.
+//!
+//! [`crate::serialized::reconstruct_auto`] and
+//! [`crate::service::verify_share`] refuse expired shares. [`reconstruct`]
+//! takes the enforcement explicitly, for warning-only use or to override the
+//! window, e.g. to recover from an archive. Shares without validity metadata
+//! are never affected.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use num_bigint::BigUint;
+
+use crate::error::{Error, Result};
+use crate::serialized::{self, SerializedShare};
+
+/// Metadata key holding the Unix time a share was issued.
+pub const ISSUED_KEY: &str = "issued";
+/// Metadata key holding the Unix time a share stops being valid.
+pub const EXPIRES_KEY: &str = "expires";
+/// Metadata key holding the rotation interval in seconds.
+pub const ROTATE_KEY: &str = "rotate-every";
+
+/// The validity window and rotation schedule of a dealing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Validity {
+    pub issued: Option<u64>,
+    pub expires: Option<u64>,
+    pub rotate_every: Option<u64>,
+}
+
+impl Validity {
+    /// Valid from `issued` for `lifetime` seconds.
+    pub fn new(issued: u64, lifetime: u64) -> Self {
+        Validity {
+            issued: Some(issued),
+            expires: Some(issued.saturating_add(lifetime)),
+            rotate_every: None,
+        }
+    }
+
+    pub fn with_rotation(mut self, every: u64) -> Self {
+        self.rotate_every = Some(every);
+        self
+    }
+
+    /// Writes the window into the share's metadata.
+    pub fn apply(&self, mut share: SerializedShare) -> SerializedShare {
+        for (key, value) in [
+            (ISSUED_KEY, self.issued),
+            (EXPIRES_KEY, self.expires),
+            (ROTATE_KEY, self.rotate_every),
+        ] {
+            match value {
+                Some(value) => share = share.with_metadata(key, value.to_string()),
+                None => {
+                    share.metadata.remove(key);
+                }
+            }
+        }
+        share
+    }
+
+    /// Reads the window from the share's metadata.
+    pub fn of(share: &SerializedShare) -> Result<Self> {
+        let field = |key| {
+            share
+                .metadata
+                .get(key)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| Error::InvalidEncoding("invalid validity timestamp"))
+                })
+                .transpose()
+        };
+        Ok(Validity {
+            issued: field(ISSUED_KEY)?,
+            expires: field(EXPIRES_KEY)?,
+            rotate_every: field(ROTATE_KEY)?,
+        })
+    }
+}
+
+/// What to do with shares outside their validity window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Enforcement {
+    Refuse,
+    Warn,
+    Ignore,
+}
+
+/// A validity problem with the share at index `x`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Notice {
+    Expired { x: BigUint },
+    NotYetValid { x: BigUint },
+    RotationDue { x: BigUint },
+}
+
+/// The current Unix time in seconds.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Checks every share's window against `now`.
+pub fn check(
+    shares: &[SerializedShare],
+    now: u64,
+    enforcement: Enforcement,
+) -> Result<Vec<Notice>> {
+    if enforcement == Enforcement::Ignore {
+        return Ok(Vec::new());
+    }
+    let mut notices = Vec::new();
+    for share in shares {
+        let validity = Validity::of(share)?;
+        let x = share.x.clone();
+        let notice = if validity.expires.is_some_and(|expires| now >= expires) {
+            Notice::Expired { x }
+        } else if validity.issued.is_some_and(|issued| now < issued) {
+            Notice::NotYetValid { x }
+        } else if let (Some(issued), Some(every)) = (validity.issued, validity.rotate_every) {
+            if now >= issued.saturating_add(every) {
+                notices.push(Notice::RotationDue { x });
+            }
+            continue;
+        } else {
+            continue;
+        };
+        if enforcement == Enforcement::Refuse {
+            return Err(Error::ShareExpired);
+        }
+        notices.push(notice);
+    }
+    Ok(notices)
+}
+
+/// Like [`serialized::reconstruct_auto`], with an explicit enforcement,
+/// returning the notices alongside the secret.
+pub fn reconstruct(
+    shares: &[SerializedShare],
+    enforcement: Enforcement,
+) -> Result<(Vec<u8>, Vec<Notice>)> {
+    let notices = check(shares, now(), enforcement)?;
+    Ok((serialized::reconstruct_unchecked(shares)?, notices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::{reconstruct_auto, split_bytes};
+
+    #[test]
+    fn test_validity_enforcement() {
+        let issued = now() - 100;
+        let current: Vec<_> = split_bytes(b"rotating key", 2, 3)
+            .unwrap()
+            .into_iter()
+            .map(|share| Validity::new(issued, 1000).with_rotation(50).apply(share))
+            .collect();
+        let decoded = SerializedShare::from_bytes(&current[0].to_bytes()).unwrap();
+        assert_eq!(
+            Validity::of(&decoded).unwrap(),
+            Validity::new(issued, 1000).with_rotation(50)
+        );
+        let (secret, notices) = reconstruct(&current[..2], Enforcement::Refuse).unwrap();
+        assert_eq!(secret, b"rotating key");
+        assert_eq!(notices.len(), 2);
+        assert!(matches!(notices[0], Notice::RotationDue { .. }));
+
+        let expired: Vec<_> = current
+            .iter()
+            .map(|share| Validity::new(issued, 10).apply(share.clone()))
+            .collect();
+        assert!(matches!(
+            reconstruct_auto(&expired),
+            Err(Error::ShareExpired)
+        ));
+        let (secret, notices) = reconstruct(&expired[1..], Enforcement::Warn).unwrap();
+        assert_eq!(secret, b"rotating key");
+        assert!(matches!(notices[0], Notice::Expired { .. }));
+        assert!(reconstruct(&expired, Enforcement::Ignore).is_ok());
+    }
+}
@@ -0,0 +1,272 @@
+// File: src/ffi.rs
+//! A C ABI for embedding the byte-wise sharing scheme (`ffi` feature).
+//!
+//! The header `include/sss.h` is generated from this module with cbindgen.
+//! Shares are handled through an opaque [`SssShares`] collection and cross
+//! the boundary as binary share encodings in [`SssBuffer`]s. Every fallible
+//! function returns an [`SssStatus`], whose values match the `sss` exit
+//! statuses. Buffers and collections returned by the library must be
+//! released with [`sss_buffer_free`] and [`sss_shares_free`].
+
+use std::ffi::c_char;
+use std::ptr;
+use std::slice;
+
+use crate::error::Error;
+use crate::serialized::{reconstruct_auto, split_bytes, SerializedShare};
+
+/// Result codes; the values match the exit statuses of the `sss` tool.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SssStatus {
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    InvalidInput = 3,
+    InvalidParameters = 4,
+    InconsistentShares = 5,
+    /// A signature, digest or decryption check failed.
+    CryptoFailure = 6,
+    Io = 7,
+}
+
+impl From<&Error> for SssStatus {
+    fn from(err: &Error) -> Self {
+        match err {
+            Error::InvalidParameters(_) => SssStatus::InvalidParameters,
+            Error::InconsistentShares(_)
+            | Error::InsufficientShares
+            | Error::ShareExpired
+            | Error::ShareRevoked => SssStatus::InconsistentShares,
+            Error::DigestMismatch
+            | Error::InvalidSignature
+            | Error::DecryptionFailed
+            | Error::CheckpointTampered => SssStatus::CryptoFailure,
+            Error::Io(_) => SssStatus::Io,
+            _ => SssStatus::InvalidInput,
+        }
+    }
+}
+
+/// A byte buffer owned by the library.
+#[repr(C)]
+pub struct SssBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl SssBuffer {
+    fn new(bytes: Vec<u8>) -> Self {
+        let mut bytes = bytes.into_boxed_slice();
+        let buffer = SssBuffer {
+            data: bytes.as_mut_ptr(),
+            len: bytes.len(),
+        };
+        std::mem::forget(bytes);
+        buffer
+    }
+}
+
+/// An opaque collection of shares.
+pub struct SssShares(Vec<SerializedShare>);
+
+/// Returns a static, NUL-terminated description of a status.
+#[no_mangle]
+pub extern "C" fn sss_status_message(status: SssStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        SssStatus::Ok => b"ok\0",
+        SssStatus::NullPointer => b"a required pointer was null\0",
+        SssStatus::InvalidInput => b"unreadable, corrupt or mistyped input\0",
+        SssStatus::InvalidParameters => b"unusable threshold, share count or other parameter\0",
+        SssStatus::InconsistentShares => b"shares do not fit together\0",
+        SssStatus::CryptoFailure => b"a cryptographic check failed\0",
+        SssStatus::Io => b"I/O failure\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// Splits `secret_len` bytes at `secret` into `total` shares, any
+/// `threshold` of which recover it, and stores a new collection in `*out`.
+///
+/// # Safety
+///
+/// `secret` must point to `secret_len` readable bytes and `out` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sss_split(
+    secret: *const u8,
+    secret_len: usize,
+    threshold: usize,
+    total: usize,
+    out: *mut *mut SssShares,
+) -> SssStatus {
+    if secret.is_null() || out.is_null() {
+        return SssStatus::NullPointer;
+    }
+    match split_bytes(slice::from_raw_parts(secret, secret_len), threshold, total) {
+        Ok(shares) => {
+            *out = Box::into_raw(Box::new(SssShares(shares)));
+            SssStatus::Ok
+        }
+        Err(err) => SssStatus::from(&err),
+    }
+}
+
+/// Creates an empty collection, to fill with [`sss_shares_push`].
+#[no_mangle]
+pub extern "C" fn sss_shares_new() -> *mut SssShares {
+    Box::into_raw(Box::new(SssShares(Vec::new())))
+}
+
+/// The number of shares in a collection, or 0 for null.
+///
+/// # Safety
+///
+/// `shares` must be null or a collection returned by this library.
+#[no_mangle]
+pub unsafe extern "C" fn sss_shares_len(shares: *const SssShares) -> usize {
+    shares.as_ref().map_or(0, |shares| shares.0.len())
+}
+
+/// Writes the binary encoding of share `index` into `*out`.
+///
+/// # Safety
+///
+/// `shares` must be a collection returned by this library and `out` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sss_shares_get(
+    shares: *const SssShares,
+    index: usize,
+    out: *mut SssBuffer,
+) -> SssStatus {
+    let (Some(shares), false) = (shares.as_ref(), out.is_null()) else {
+        return SssStatus::NullPointer;
+    };
+    match shares.0.get(index) {
+        Some(share) => {
+            *out = SssBuffer::new(share.to_bytes());
+            SssStatus::Ok
+        }
+        None => SssStatus::InvalidParameters,
+    }
+}
+
+/// Parses a binary share encoding and appends it to the collection.
+///
+/// # Safety
+///
+/// `shares` must be a collection returned by this library and `share` must
+/// point to `share_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sss_shares_push(
+    shares: *mut SssShares,
+    share: *const u8,
+    share_len: usize,
+) -> SssStatus {
+    let (Some(shares), false) = (shares.as_mut(), share.is_null()) else {
+        return SssStatus::NullPointer;
+    };
+    match SerializedShare::from_bytes(slice::from_raw_parts(share, share_len)) {
+        Ok(share) => {
+            shares.0.push(share);
+            SssStatus::Ok
+        }
+        Err(err) => SssStatus::from(&err),
+    }
+}
+
+/// Recovers the secret from a collection into `*out`.
+///
+/// # Safety
+///
+/// `shares` must be a collection returned by this library and `out` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sss_combine(shares: *const SssShares, out: *mut SssBuffer) -> SssStatus {
+    let (Some(shares), false) = (shares.as_ref(), out.is_null()) else {
+        return SssStatus::NullPointer;
+    };
+    match reconstruct_auto(&shares.0) {
+        Ok(secret) => {
+            *out = SssBuffer::new(secret);
+            SssStatus::Ok
+        }
+        Err(err) => SssStatus::from(&err),
+    }
+}
+
+/// Frees a collection; null is ignored.
+///
+/// # Safety
+///
+/// `shares` must be null or a collection returned by this library that has
+/// not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn sss_shares_free(shares: *mut SssShares) {
+    if !shares.is_null() {
+        drop(Box::from_raw(shares));
+    }
+}
+
+/// Frees a buffer, wiping its contents first; a null buffer is ignored.
+///
+/// # Safety
+///
+/// `buffer` must have been returned by this library and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn sss_buffer_free(buffer: SssBuffer) {
+    if !buffer.data.is_null() {
+        let mut bytes = Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len));
+        bytes.fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_combine_through_the_c_abi() {
+        unsafe {
+            let secret = b"embedded secret";
+            let mut shares = ptr::null_mut();
+            let status = sss_split(secret.as_ptr(), secret.len(), 2, 3, &mut shares);
+            assert_eq!(status, SssStatus::Ok);
+            assert_eq!(sss_shares_len(shares), 3);
+
+            let picked = sss_shares_new();
+            for index in [2, 0] {
+                let mut buffer = SssBuffer {
+                    data: ptr::null_mut(),
+                    len: 0,
+                };
+                assert_eq!(sss_shares_get(shares, index, &mut buffer), SssStatus::Ok);
+                assert_eq!(
+                    sss_shares_push(picked, buffer.data, buffer.len),
+                    SssStatus::Ok
+                );
+                sss_buffer_free(buffer);
+            }
+            assert_eq!(
+                sss_shares_push(picked, secret.as_ptr(), secret.len()),
+                SssStatus::InvalidInput
+            );
+
+            let mut recovered = SssBuffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            assert_eq!(sss_combine(picked, &mut recovered), SssStatus::Ok);
+            assert_eq!(slice::from_raw_parts(recovered.data, recovered.len), secret);
+            sss_buffer_free(recovered);
+            sss_shares_free(picked);
+            sss_shares_free(shares);
+
+            assert_eq!(
+                sss_split(ptr::null(), 0, 2, 3, &mut shares),
+                SssStatus::NullPointer
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+// File: src/fido2.rs
+//! FIDO2 security key custody of shares (`fido2` feature).
+//!
+//! [`Fido2Custody`] binds a custodian's share to a YubiKey or other CTAP2
+//! authenticator through the `hmac-secret` extension: for a credential made
+//! on the key and a random salt, the authenticator returns
+//! HMAC-SHA-256(CredRandom, salt), where CredRandom never leaves the device.
+//! That output keys ChaCha20-Poly1305 over the share. Each wrap draws a fresh
+//! salt, which travels in the [`WrappedShare`] IV next to the nonce, and both
+//! wrapping and unwrapping ask for a touch, so a share cannot be opened
+//! without the key physically present and a person at it.
+//!
+//! The credential ID is not secret but must be kept: it becomes the wrapped
+//! share's key label, and [`Fido2Custody::open`] needs it to find the
+//! credential again.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use ctap_hid_fido2::fidokey::{
+    AssertionExtension, CredentialExtension, GetAssertionArgsBuilder, MakeCredentialArgsBuilder,
+};
+use ctap_hid_fido2::{verifier, Cfg, FidoKeyHid, FidoKeyHidFactory};
+use rand::{thread_rng, RngCore};
+
+use crate::custody::{ShareCustody, WrappedShare};
+use crate::encoding::hex;
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+
+const LABEL_PREFIX: &str = "fido2:";
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Wraps shares under the `hmac-secret` of a credential on a FIDO2 key.
+pub struct Fido2Custody {
+    device: FidoKeyHid,
+    rpid: String,
+    credential_id: Vec<u8>,
+    pin: Option<String>,
+}
+
+impl Fido2Custody {
+    /// Makes a new `hmac-secret` credential for relying party `rpid` on the
+    /// single connected security key, which asks for a touch.
+    pub fn enroll(rpid: &str, pin: Option<&str>) -> Result<Self> {
+        let device = connect()?;
+        let challenge = verifier::create_challenge();
+        let extensions = [CredentialExtension::HmacSecret(Some(true))];
+        let mut args = MakeCredentialArgsBuilder::new(rpid, &challenge).extensions(&extensions);
+        args = match pin {
+            Some(pin) => args.pin(pin),
+            None => args.without_pin_and_uv(),
+        };
+        let attestation = device
+            .make_credential_with_args(&args.build())
+            .map_err(fido2_error)?;
+        let supported = attestation
+            .extensions
+            .iter()
+            .any(|ext| matches!(ext, CredentialExtension::HmacSecret(Some(true))));
+        if !supported {
+            return Err(Error::Fido2(
+                "security key does not support hmac-secret".into(),
+            ));
+        }
+        Ok(Fido2Custody {
+            device,
+            rpid: rpid.into(),
+            credential_id: attestation.credential_descriptor.id,
+            pin: pin.map(String::from),
+        })
+    }
+
+    /// Uses an existing credential on the single connected security key.
+    pub fn open(rpid: &str, credential_id: &[u8], pin: Option<&str>) -> Result<Self> {
+        Ok(Fido2Custody {
+            device: connect()?,
+            rpid: rpid.into(),
+            credential_id: credential_id.to_vec(),
+            pin: pin.map(String::from),
+        })
+    }
+
+    /// The credential to keep for [`Fido2Custody::open`].
+    pub fn credential_id(&self) -> &[u8] {
+        &self.credential_id
+    }
+
+    fn key_label(&self) -> String {
+        format!("{}{}", LABEL_PREFIX, hex(&self.credential_id))
+    }
+
+    /// Asks the authenticator, with a touch, for the key belonging to `salt`.
+    fn derive_key(&self, salt: &[u8; SALT_LEN]) -> Result<[u8; 32]> {
+        let challenge = verifier::create_challenge();
+        let extensions = [AssertionExtension::HmacSecret(Some(*salt))];
+        let mut args = GetAssertionArgsBuilder::new(&self.rpid, &challenge)
+            .credential_id(&self.credential_id)
+            .extensions(&extensions);
+        args = match &self.pin {
+            Some(pin) => args.pin(pin),
+            None => args.without_pin_and_uv(),
+        };
+        let assertions = self
+            .device
+            .get_assertion_with_args(&args.build())
+            .map_err(fido2_error)?;
+        assertions
+            .iter()
+            .flat_map(|assertion| &assertion.extensions)
+            .find_map(|ext| match ext {
+                AssertionExtension::HmacSecret(Some(output)) => Some(*output),
+                _ => None,
+            })
+            .ok_or_else(|| Error::Fido2("security key returned no hmac-secret".into()))
+    }
+}
+
+impl ShareCustody for Fido2Custody {
+    fn wrap(&self, share: &SerializedShare) -> Result<WrappedShare> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        thread_rng().fill_bytes(&mut salt);
+        thread_rng().fill_bytes(&mut nonce);
+        let key = self.derive_key(&salt)?;
+
+        let mut wrapped = WrappedShare::new(self.key_label(), [salt.as_slice(), &nonce].concat());
+        let header = wrapped.header();
+        wrapped.ciphertext = ChaCha20Poly1305::new(&key.into())
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &share.to_bytes(),
+                    aad: &header,
+                },
+            )
+            .map_err(|_| Error::Fido2("encryption failed".into()))?;
+        Ok(wrapped)
+    }
+
+    fn unwrap(&self, wrapped: &WrappedShare) -> Result<SerializedShare> {
+        if wrapped.key_label != self.key_label() || wrapped.iv.len() != SALT_LEN + NONCE_LEN {
+            return Err(Error::DecryptionFailed);
+        }
+        let (salt, nonce) = wrapped.iv.split_at(SALT_LEN);
+        let key = self.derive_key(salt.try_into().expect("salt length checked"))?;
+        let plaintext = ChaCha20Poly1305::new(&key.into())
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: &wrapped.ciphertext,
+                    aad: &wrapped.header(),
+                },
+            )
+            .map_err(|_| Error::DecryptionFailed)?;
+        SerializedShare::from_bytes(&plaintext)
+    }
+}
+
+fn connect() -> Result<FidoKeyHid> {
+    FidoKeyHidFactory::create(&Cfg::init()).map_err(fido2_error)
+}
+
+fn fido2_error(err: impl std::fmt::Display) -> Error {
+    Error::Fido2(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_key_is_reported() {
+        // No authenticator is attached where the tests run
+        let err = Fido2Custody::open("sss.example", &[1; 16], None)
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Fido2(_)));
+    }
+}
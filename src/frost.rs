@@ -0,0 +1,285 @@
+// File: src/frost.rs
+//! DKG output in the key package layout of FROST implementations.
+//!
+//! A finished [`crate::dkg`] ceremony yields, per participant, a
+//! [`KeyPackage`] (identifier, signing share, verifying share, group
+//! verifying key, `min_signers`) and one [`PublicKeyPackage`] for everyone
+//! (the verifying share of every participant and the group key). Their JSON
+//! follows the serde layout of `frost-core`: a `header` with version and
+//! ciphersuite, scalars and elements as fixed-width big-endian hex, and
+//! verifying shares keyed by serialized identifier.
+//!
+//! The keys live in the Schnorr group of [`GroupParameters`], named by the
+//! ciphersuite [`CIPHERSUITE`], so a signing stack consumes them through a
+//! ciphersuite implementation for that group rather than a curve one.
+
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::coordinator::Transcript;
+use crate::dkg::KeyShare;
+use crate::encoding::{from_fixed, hex, to_fixed, unhex};
+use crate::error::{Error, Result};
+use crate::params::GroupParameters;
+use crate::vss::Commitment;
+
+/// The ciphersuite id written into package headers.
+pub const CIPHERSUITE: &str = "FROST-FELDMAN-MODP-SHA256-v1";
+const HEADER_VERSION: u8 = 0;
+
+/// One participant's signing key material.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyPackage {
+    pub identifier: u32,
+    pub signing_share: BigUint,
+    pub verifying_share: BigUint,
+    pub verifying_key: BigUint,
+    pub min_signers: u16,
+}
+
+/// The public keys of a ceremony, shared by every participant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKeyPackage {
+    pub verifying_shares: BTreeMap<u32, BigUint>,
+    pub verifying_key: BigUint,
+    pub min_signers: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonHeader {
+    version: u8,
+    ciphersuite: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonKeyPackage {
+    header: JsonHeader,
+    identifier: String,
+    signing_share: String,
+    verifying_share: String,
+    verifying_key: String,
+    min_signers: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonPublicKeyPackage {
+    header: JsonHeader,
+    verifying_shares: BTreeMap<String, String>,
+    verifying_key: String,
+    min_signers: Option<u16>,
+}
+
+impl KeyPackage {
+    /// Packages a participant's key share, checking it against the public
+    /// keys the transcript commits to.
+    pub fn new(share: &KeyShare, transcript: &Transcript) -> Result<Self> {
+        let public = PublicKeyPackage::new(transcript)?;
+        let verifying_share = public
+            .verifying_shares
+            .get(&share.index)
+            .ok_or(Error::InvalidParameters("party index out of range"))?
+            .clone();
+        let group = &transcript.group;
+        if share.public_key != public.verifying_key
+            || group.g.modpow(&share.value, &group.p) != verifying_share
+        {
+            return Err(Error::InconsistentShares(
+                "key share does not match the transcript",
+            ));
+        }
+        Ok(KeyPackage {
+            identifier: share.index,
+            signing_share: share.value.clone(),
+            verifying_share,
+            verifying_key: public.verifying_key,
+            min_signers: public.min_signers,
+        })
+    }
+
+    pub fn to_json(&self, group: &GroupParameters) -> Result<String> {
+        let document = JsonKeyPackage {
+            header: header(),
+            identifier: scalar(group, &self.identifier.into())?,
+            signing_share: scalar(group, &self.signing_share)?,
+            verifying_share: element(group, &self.verifying_share)?,
+            verifying_key: element(group, &self.verifying_key)?,
+            min_signers: self.min_signers,
+        };
+        Ok(serde_json::to_string_pretty(&document).expect("key packages serialize"))
+    }
+
+    pub fn from_json(text: &str, group: &GroupParameters) -> Result<Self> {
+        let document: JsonKeyPackage = serde_json::from_str(text)
+            .map_err(|_| Error::InvalidEncoding("invalid key package JSON"))?;
+        check_header(&document.header)?;
+        Ok(KeyPackage {
+            identifier: identifier(group, &document.identifier)?,
+            signing_share: parse(&document.signing_share, &group.q)?,
+            verifying_share: parse(&document.verifying_share, &group.p)?,
+            verifying_key: parse(&document.verifying_key, &group.p)?,
+            min_signers: document.min_signers,
+        })
+    }
+}
+
+impl PublicKeyPackage {
+    /// The verifying share `g^s_i` of every participant, from the summed
+    /// commitments of the qualified dealers.
+    pub fn new(transcript: &Transcript) -> Result<Self> {
+        let group = &transcript.group;
+        let mut joint = Commitment(Vec::new());
+        for dealer in &transcript.qualified {
+            let commitments = transcript
+                .commitments
+                .get(dealer)
+                .ok_or(Error::InconsistentShares("no commitments from this dealer"))?;
+            joint = joint.add(&Commitment(commitments.clone()), &group.p);
+        }
+        let verifying_key = joint.public_key().ok_or(Error::InsufficientShares)?.0;
+        if verifying_key != transcript.public_key {
+            return Err(Error::InconsistentShares(
+                "public key does not match the commitments",
+            ));
+        }
+        let verifying_shares = (1..=transcript.total as u32)
+            .map(|i| (i, joint.public_share_for(&i.into(), &group.p, &group.q).0))
+            .collect();
+        Ok(PublicKeyPackage {
+            verifying_shares,
+            verifying_key,
+            min_signers: u16::try_from(transcript.threshold)
+                .map_err(|_| Error::InvalidParameters("threshold too large"))?,
+        })
+    }
+
+    pub fn to_json(&self, group: &GroupParameters) -> Result<String> {
+        let document = JsonPublicKeyPackage {
+            header: header(),
+            verifying_shares: self
+                .verifying_shares
+                .iter()
+                .map(|(id, share)| Ok((scalar(group, &(*id).into())?, element(group, share)?)))
+                .collect::<Result<_>>()?,
+            verifying_key: element(group, &self.verifying_key)?,
+            min_signers: Some(self.min_signers),
+        };
+        Ok(serde_json::to_string_pretty(&document).expect("key packages serialize"))
+    }
+
+    pub fn from_json(text: &str, group: &GroupParameters) -> Result<Self> {
+        let document: JsonPublicKeyPackage = serde_json::from_str(text)
+            .map_err(|_| Error::InvalidEncoding("invalid key package JSON"))?;
+        check_header(&document.header)?;
+        Ok(PublicKeyPackage {
+            verifying_shares: document
+                .verifying_shares
+                .iter()
+                .map(|(id, share)| Ok((identifier(group, id)?, parse(share, &group.p)?)))
+                .collect::<Result<_>>()?,
+            verifying_key: parse(&document.verifying_key, &group.p)?,
+            min_signers: document
+                .min_signers
+                .ok_or(Error::InvalidParameters("min_signers is required"))?,
+        })
+    }
+}
+
+fn header() -> JsonHeader {
+    JsonHeader {
+        version: HEADER_VERSION,
+        ciphersuite: CIPHERSUITE.into(),
+    }
+}
+
+fn check_header(header: &JsonHeader) -> Result<()> {
+    if header.version != HEADER_VERSION {
+        return Err(Error::UnsupportedVersion(header.version));
+    }
+    if header.ciphersuite != CIPHERSUITE {
+        return Err(Error::InvalidParameters("unsupported ciphersuite"));
+    }
+    Ok(())
+}
+
+/// Big-endian hex, padded to the width of `modulus`.
+fn fixed(value: &BigUint, modulus: &BigUint) -> Result<String> {
+    Ok(hex(&to_fixed(value, modulus)?))
+}
+
+fn scalar(group: &GroupParameters, value: &BigUint) -> Result<String> {
+    fixed(value, &group.q)
+}
+
+fn element(group: &GroupParameters, value: &BigUint) -> Result<String> {
+    fixed(value, &group.p)
+}
+
+/// Parses fixed-width hex, rejecting values outside `0..modulus`.
+fn parse(text: &str, modulus: &BigUint) -> Result<BigUint> {
+    from_fixed(&unhex(text)?, modulus)
+}
+
+fn identifier(group: &GroupParameters, text: &str) -> Result<u32> {
+    u32::try_from(parse(text, &group.q)?)
+        .ok()
+        .filter(|id| *id != 0)
+        .ok_or(Error::InvalidEncoding("invalid identifier"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::{self, Party};
+
+    #[test]
+    fn test_packages_from_dkg() {
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let mut parties: Vec<Party> = (1..=3)
+            .map(|index| Party::new(&group, index, 2, 3).unwrap())
+            .collect();
+        let broadcasts: Vec<_> = parties.iter().map(Party::commitments).collect();
+        let dealt: Vec<_> = parties.iter().flat_map(Party::shares).collect();
+        for party in &mut parties {
+            broadcasts.iter().for_each(|b| party.receive_commitments(b));
+            let index = party.index();
+            for share in dealt.iter().filter(|share| share.to == index) {
+                party.receive_share(share).unwrap();
+            }
+        }
+        let commitments: BTreeMap<_, _> = broadcasts
+            .iter()
+            .map(|b| (b.from, b.commitments.clone()))
+            .collect();
+        let transcript = Transcript {
+            group: group.clone(),
+            threshold: 2,
+            total: 3,
+            qualified: vec![1, 2, 3],
+            disqualified: Vec::new(),
+            public_key: dkg::public_key(&group, commitments.values()),
+            commitments,
+            complaints: Vec::new(),
+            justifications: Vec::new(),
+        };
+
+        let public = PublicKeyPackage::new(&transcript).unwrap();
+        let json = public.to_json(&group).unwrap();
+        assert!(json.contains(CIPHERSUITE));
+        assert_eq!(PublicKeyPackage::from_json(&json, &group).unwrap(), public);
+        for party in &parties {
+            let package = party.key_package(&transcript).unwrap();
+            assert_eq!(
+                public.verifying_shares[&package.identifier],
+                package.verifying_share
+            );
+            let json = package.to_json(&group).unwrap();
+            assert_eq!(KeyPackage::from_json(&json, &group).unwrap(), package);
+        }
+
+        let mut share = parties[0].finish(&transcript).unwrap();
+        share.value += 1u32;
+        assert!(KeyPackage::new(&share, &transcript).is_err());
+    }
+}
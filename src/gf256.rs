@@ -0,0 +1,309 @@
+// File: src/gf256.rs
+//! Byte-wise Shamir secret sharing over GF(2^8).
+//!
+//! Each byte of the secret is shared independently with its own random
+//! polynomial, so share payloads are exactly as long as the secret. The
+//! reduction polynomial is configurable because deployed tools disagree on it.
+
+use std::io::{Read, Write};
+
+use rand::RngCore;
+
+use crate::error::{Error, Result};
+use crate::progress::Progress;
+
+/// Bytes read per step of [`split_stream`] and [`combine_stream`].
+const STREAM_CHUNK: usize = 64 * 1024;
+
+/// The binary field GF(2^8) defined by a degree-8 reduction polynomial.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gf256 {
+    poly: u16,
+}
+
+impl Gf256 {
+    /// x^8 + x^4 + x^3 + x + 1, the Rijndael field used by SLIP-0039 and Vault.
+    pub const RIJNDAEL: Gf256 = Gf256 { poly: 0x11b };
+
+    pub const fn new(poly: u16) -> Self {
+        Gf256 { poly }
+    }
+
+    /// The reduction polynomial, including the x^8 term.
+    pub const fn poly(self) -> u16 {
+        self.poly
+    }
+
+    pub fn add(self, a: u8, b: u8) -> u8 {
+        a ^ b
+    }
+
+    /// Multiplies without data-dependent branches or table lookups.
+    pub fn mul(self, a: u8, b: u8) -> u8 {
+        let reduce = (self.poly & 0xff) as u8;
+        let (mut a, mut r) = (a, 0u8);
+        for i in 0..8 {
+            r ^= (0u8.wrapping_sub((b >> i) & 1)) & a;
+            a = (a << 1) ^ (0u8.wrapping_sub(a >> 7) & reduce);
+        }
+        r
+    }
+
+    /// Returns the multiplicative inverse, computed as `a^254`. Zero maps to zero.
+    pub fn inv(self, a: u8) -> u8 {
+        let mut result = 1u8;
+        let mut base = a;
+        let mut exp = 254u8;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    pub fn div(self, a: u8, b: u8) -> u8 {
+        self.mul(a, self.inv(b))
+    }
+
+    /// Evaluates the polynomial with the given coefficients (constant term first).
+    pub fn evaluate(self, coefficients: &[u8], x: u8) -> u8 {
+        coefficients
+            .iter()
+            .rev()
+            .fold(0u8, |acc, &coeff| self.mul(acc, x) ^ coeff)
+    }
+
+    /// Lagrange-interpolates the points and evaluates the result at `x`.
+    pub fn interpolate(self, points: &[(u8, u8)], x: u8) -> u8 {
+        points.iter().enumerate().fold(0u8, |acc, (i, &(xi, yi))| {
+            let (num, den) = points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| i != j)
+                .fold((1u8, 1u8), |(num, den), (_, &(xj, _))| {
+                    (self.mul(num, x ^ xj), self.mul(den, xi ^ xj))
+                });
+            acc ^ self.mul(yi, self.div(num, den))
+        })
+    }
+}
+
+/// Splits `secret` byte-wise, returning one payload per x-coordinate in `xs`.
+///
+/// The x-coordinates must be distinct and non-zero since the secret sits at 0.
+pub fn split(
+    field: Gf256,
+    secret: &[u8],
+    threshold: usize,
+    xs: &[u8],
+    rng: &mut impl RngCore,
+) -> Result<Vec<Vec<u8>>> {
+    if threshold == 0 || threshold > xs.len() {
+        return Err(Error::InvalidParameters(
+            "threshold must be between 1 and the number of shares",
+        ));
+    }
+    check_coordinates(xs.iter().copied())?;
+    if xs.contains(&0) {
+        return Err(Error::InvalidParameters("x-coordinate 0 is reserved"));
+    }
+
+    let mut shares = vec![Vec::with_capacity(secret.len()); xs.len()];
+    let mut coefficients = vec![0u8; threshold];
+    for &byte in secret {
+        coefficients[0] = byte;
+        rng.fill_bytes(&mut coefficients[1..]);
+        for (share, &x) in shares.iter_mut().zip(xs) {
+            share.push(field.evaluate(&coefficients, x));
+        }
+    }
+    Ok(shares)
+}
+
+/// Like [`split`], streaming: reads `secret` in chunks and appends each
+/// chunk's payloads to the writer at the same position as its
+/// x-coordinate, so secrets of any size split in constant memory. Reports
+/// the bytes read to `progress`, without a total, and stops if it is
+/// cancelled. Returns the number of bytes split.
+pub fn split_stream<R: Read, W: Write>(
+    field: Gf256,
+    secret: &mut R,
+    threshold: usize,
+    xs: &[u8],
+    writers: &mut [W],
+    rng: &mut impl RngCore,
+    progress: &mut Progress,
+) -> Result<u64> {
+    if writers.len() != xs.len() {
+        return Err(Error::InvalidParameters("need one writer per x-coordinate"));
+    }
+    let mut buffer = vec![0u8; STREAM_CHUNK];
+    let mut done = 0u64;
+    loop {
+        progress.check()?;
+        let read = fill(secret, &mut buffer)?;
+        if read == 0 {
+            return Ok(done);
+        }
+        let payloads = split(field, &buffer[..read], threshold, xs, rng)?;
+        for (writer, payload) in writers.iter_mut().zip(payloads) {
+            writer.write_all(&payload)?;
+        }
+        done += read as u64;
+        progress.step(done, None)?;
+    }
+}
+
+/// Like [`interpolate`] at 0, streaming: reads the `(x, payload)` shares
+/// chunk by chunk and writes the recovered secret to `out`. Reports the
+/// bytes written to `progress` and stops if it is cancelled.
+pub fn combine_stream<R: Read, W: Write>(
+    field: Gf256,
+    shares: &mut [(u8, R)],
+    out: &mut W,
+    progress: &mut Progress,
+) -> Result<u64> {
+    let mut buffers = vec![vec![0u8; STREAM_CHUNK]; shares.len()];
+    let mut done = 0u64;
+    loop {
+        progress.check()?;
+        let mut lengths = Vec::with_capacity(shares.len());
+        for ((_, reader), buffer) in shares.iter_mut().zip(&mut buffers) {
+            lengths.push(fill(reader, buffer)?);
+        }
+        let read = lengths.first().copied().unwrap_or(0);
+        if lengths.iter().any(|&len| len != read) {
+            return Err(Error::InconsistentShares("share payload lengths differ"));
+        }
+        if read == 0 {
+            return Ok(done);
+        }
+        let chunks: Vec<(u8, &[u8])> = shares
+            .iter()
+            .zip(&buffers)
+            .map(|((x, _), buffer)| (*x, &buffer[..read]))
+            .collect();
+        out.write_all(&interpolate(field, &chunks, 0)?)?;
+        done += read as u64;
+        progress.step(done, None)?;
+    }
+}
+
+/// Reads until `buffer` is full or the input ends.
+pub(crate) fn fill(reader: &mut impl Read, buffer: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(filled)
+}
+
+/// Interpolates byte-wise through `(x, payload)` shares and evaluates at `x`.
+///
+/// Passing `x = 0` reconstructs the secret.
+pub fn interpolate(field: Gf256, shares: &[(u8, &[u8])], x: u8) -> Result<Vec<u8>> {
+    let len = match shares.first() {
+        Some((_, payload)) => payload.len(),
+        None => return Err(Error::InvalidParameters("no shares provided")),
+    };
+    if shares.iter().any(|(_, payload)| payload.len() != len) {
+        return Err(Error::InconsistentShares("share payload lengths differ"));
+    }
+    check_coordinates(shares.iter().map(|&(x, _)| x))?;
+
+    let mut points: Vec<(u8, u8)> = shares.iter().map(|&(x, _)| (x, 0)).collect();
+    Ok((0..len)
+        .map(|i| {
+            for (point, (_, payload)) in points.iter_mut().zip(shares) {
+                point.1 = payload[i];
+            }
+            field.interpolate(&points, x)
+        })
+        .collect())
+}
+
+fn check_coordinates(xs: impl Iterator<Item = u8>) -> Result<()> {
+    let mut seen = [false; 256];
+    for x in xs {
+        if std::mem::replace(&mut seen[x as usize], true) {
+            return Err(Error::InconsistentShares("duplicate x-coordinate"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_gf256_split_and_interpolate() {
+        let field = Gf256::RIJNDAEL;
+        // Known product in the AES field (FIPS-197, section 4.2)
+        assert_eq!(field.mul(0x57, 0x83), 0xc1);
+        assert!((1..=255u8).all(|a| field.mul(a, field.inv(a)) == 1));
+
+        let secret = b"attack at dawn!";
+        let xs = [1u8, 2, 3, 4, 5];
+        let shares = split(field, secret, 3, &xs, &mut thread_rng()).unwrap();
+
+        let subset: Vec<(u8, &[u8])> = vec![(5, &shares[4]), (1, &shares[0]), (3, &shares[2])];
+        assert_eq!(interpolate(field, &subset, 0).unwrap(), secret);
+        // Interpolating at an existing coordinate reproduces that share
+        assert_eq!(interpolate(field, &subset, 2).unwrap(), shares[1]);
+        assert_ne!(interpolate(field, &subset[..2], 0).unwrap(), secret);
+
+        assert!(split(field, secret, 3, &[1, 1, 2], &mut thread_rng()).is_err());
+    }
+
+    #[test]
+    fn test_streaming_split_and_combine() {
+        use crate::progress::CancellationToken;
+
+        let field = Gf256::RIJNDAEL;
+        let secret: Vec<u8> = (0..3 * STREAM_CHUNK + 17)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let mut payloads = vec![Vec::new(); 3];
+        let mut reported = Vec::new();
+        let mut progress = Progress::new().on_progress(|done, _| reported.push(done));
+        let len = split_stream(
+            field,
+            &mut &secret[..],
+            2,
+            &[1, 2, 3],
+            &mut payloads,
+            &mut thread_rng(),
+            &mut progress,
+        )
+        .unwrap();
+        drop(progress);
+        assert_eq!(len, secret.len() as u64);
+        assert_eq!(reported.len(), 4);
+
+        let mut recovered = Vec::new();
+        let mut shares = [(3, &payloads[2][..]), (1, &payloads[0][..])];
+        combine_stream(field, &mut shares, &mut recovered, &mut Progress::new()).unwrap();
+        assert_eq!(recovered, secret);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut progress = Progress::new().with_token(token);
+        let mut shares = [(3, &payloads[2][..]), (1, &payloads[0][..])];
+        assert!(matches!(
+            combine_stream(field, &mut shares, &mut Vec::new(), &mut progress),
+            Err(Error::Cancelled)
+        ));
+        let mut shares = [(3, &payloads[2][1..]), (1, &payloads[0][..])];
+        assert!(combine_stream(field, &mut shares, &mut Vec::new(), &mut Progress::new()).is_err());
+    }
+}
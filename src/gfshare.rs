@@ -0,0 +1,115 @@
+// File: src/gfshare.rs
+//! libgfshare (`gfsplit`/`gfcombine`) compatible shares.
+//!
+//! libgfshare works over GF(2^8) reduced by x^8 + x^4 + x^3 + x^2 + 1 and
+//! stores each share as a raw file named `STEM.NNN`, where `NNN` is the
+//! share's x-coordinate as three decimal digits.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::error::{Error, Result};
+use crate::gf256::{self, Gf256};
+
+/// The field libgfshare uses for its log/exp tables.
+pub const FIELD: Gf256 = Gf256::new(0x11d);
+
+/// A gfshare share: its x-coordinate and the raw file contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GfShare {
+    pub x: u8,
+    pub data: Vec<u8>,
+}
+
+impl GfShare {
+    /// The file name `gfsplit` would give this share for the given stem.
+    pub fn file_name(&self, stem: &str) -> String {
+        format!("{}.{:03}", stem, self.x)
+    }
+
+    /// Reads a share file, taking the x-coordinate from its `.NNN` suffix.
+    pub fn read(path: &Path) -> Result<Self> {
+        let x = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .filter(|ext| ext.len() == 3)
+            .and_then(|ext| ext.parse::<u8>().ok())
+            .filter(|&x| x != 0)
+            .ok_or(Error::InvalidEncoding(
+                "gfshare file names must end in a .NNN share number",
+            ))?;
+        Ok(GfShare {
+            x,
+            data: fs::read(path)?,
+        })
+    }
+
+    /// Writes the share into `dir` using the `gfsplit` naming scheme.
+    pub fn write(&self, dir: &Path, stem: &str) -> Result<PathBuf> {
+        let path = dir.join(self.file_name(stem));
+        fs::write(&path, &self.data)?;
+        Ok(path)
+    }
+}
+
+/// Splits `secret` like `gfsplit -n threshold -m count`, with random share numbers.
+pub fn split(secret: &[u8], threshold: usize, count: usize) -> Result<Vec<GfShare>> {
+    if !(2..=255).contains(&count) || !(2..=count).contains(&threshold) {
+        return Err(Error::InvalidParameters(
+            "count must be 2..=255 and threshold between 2 and count",
+        ));
+    }
+
+    let mut rng = thread_rng();
+    let mut xs: Vec<u8> = (1..=255).collect();
+    xs.shuffle(&mut rng);
+    xs.truncate(count);
+
+    let payloads = gf256::split(FIELD, secret, threshold, &xs, &mut rng)?;
+    Ok(xs
+        .into_iter()
+        .zip(payloads)
+        .map(|(x, data)| GfShare { x, data })
+        .collect())
+}
+
+/// Recombines shares like `gfcombine`; every provided share is used.
+pub fn combine(shares: &[GfShare]) -> Result<Vec<u8>> {
+    let points: Vec<(u8, &[u8])> = shares.iter().map(|s| (s.x, s.data.as_slice())).collect();
+    gf256::interpolate(FIELD, &points, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gfshare_files_roundtrip() {
+        // 0x11d reduces x^8 to 0x1d, unlike the Rijndael field
+        assert_eq!(FIELD.mul(0x80, 2), 0x1d);
+
+        let secret = b"libgfshare compatible secret";
+        let shares = split(secret, 3, 5).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("gfshare-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<PathBuf> = shares[..3]
+            .iter()
+            .map(|s| s.write(&dir, "secret.bin").unwrap())
+            .collect();
+        assert!(paths[0]
+            .to_str()
+            .unwrap()
+            .ends_with(&format!(".{:03}", shares[0].x)));
+
+        let read: Vec<GfShare> = paths.iter().map(|p| GfShare::read(p).unwrap()).collect();
+        assert_eq!(read, shares[..3]);
+        assert_eq!(combine(&read).unwrap(), secret);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(GfShare::read(Path::new("secret.bin")).is_err());
+    }
+}
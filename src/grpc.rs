@@ -0,0 +1,230 @@
+// File: src/grpc.rs
+//! A gRPC service for dealing, share verification and quorum reconstruction
+//! (`grpc` feature).
+//!
+//! The service is defined in `proto/sss.proto`, so services written in any
+//! language can generate a client for it. [`serve`] runs the server on a
+//! listener; [`SecretSharingClient`] is the generated Rust client.
+//! Reconstruction sessions live in a [`crate::store::ShareStore`], see
+//! [`crate::service`].
+
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status};
+
+use crate::error::{Error, Result};
+use crate::params::GroupParameters;
+use crate::serialized::SerializedShare;
+use crate::service::{self, SessionState, Sessions};
+use crate::store::ShareStore;
+
+pub mod proto {
+    tonic::include_proto!("sss.v1");
+}
+
+pub use proto::secret_sharing_client::SecretSharingClient;
+use proto::secret_sharing_server::{SecretSharing, SecretSharingServer};
+use proto::{
+    DealRequest, DealResponse, GetSessionRequest, OpenSessionRequest, SessionStatus,
+    SubmitShareRequest, VerifyShareRequest, VerifyShareResponse,
+};
+
+/// The service implementation, holding the open reconstruction sessions.
+#[derive(Default)]
+pub struct SecretSharingService {
+    sessions: Sessions,
+}
+
+impl SecretSharingService {
+    /// Keeps reconstruction sessions in `store` rather than in memory.
+    pub fn with_store(store: Box<dyn ShareStore>) -> Self {
+        SecretSharingService {
+            sessions: Sessions::new(store),
+        }
+    }
+}
+
+/// Serves the gRPC service on `listener` until the connection fails.
+pub async fn serve(listener: TcpListener, store: Box<dyn ShareStore>) -> Result<()> {
+    tonic::transport::Server::builder()
+        .add_service(SecretSharingServer::new(SecretSharingService::with_store(
+            store,
+        )))
+        .serve_with_incoming(TcpListenerStream::new(listener))
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+#[tonic::async_trait]
+impl SecretSharing for SecretSharingService {
+    async fn deal(
+        &self,
+        request: Request<DealRequest>,
+    ) -> std::result::Result<Response<DealResponse>, Status> {
+        let request = request.into_inner();
+        let group = request
+            .group_parameters
+            .as_deref()
+            .map(GroupParameters::from_json)
+            .transpose()
+            .map_err(status)?;
+        let dealing = service::deal(
+            &request.secret,
+            request.threshold as usize,
+            request.total as usize,
+            group.as_ref(),
+        )
+        .map_err(status)?;
+        Ok(Response::new(DealResponse {
+            session: dealing.session,
+            shares: dealing.shares.iter().map(|s| s.to_armored()).collect(),
+            commitments: dealing
+                .commitments
+                .map(|c| c.to_json())
+                .transpose()
+                .map_err(status)?,
+        }))
+    }
+
+    async fn verify_share(
+        &self,
+        request: Request<VerifyShareRequest>,
+    ) -> std::result::Result<Response<VerifyShareResponse>, Status> {
+        let request = request.into_inner();
+        let valid = service::verify_share(&request.share, &request.commitments).map_err(status)?;
+        Ok(Response::new(VerifyShareResponse { valid }))
+    }
+
+    async fn open_session(
+        &self,
+        _request: Request<OpenSessionRequest>,
+    ) -> std::result::Result<Response<SessionStatus>, Status> {
+        Ok(Response::new(session_status(
+            self.sessions.open().map_err(status)?,
+        )))
+    }
+
+    async fn submit_share(
+        &self,
+        request: Request<SubmitShareRequest>,
+    ) -> std::result::Result<Response<SessionStatus>, Status> {
+        let request = request.into_inner();
+        let share = SerializedShare::from_armored(&request.share)
+            .or_else(|_| SerializedShare::from_bech32(&request.share))
+            .map_err(status)?;
+        let state = self
+            .sessions
+            .submit(&request.session_id, share)
+            .map_err(status)?;
+        Ok(Response::new(session_status(state)))
+    }
+
+    async fn get_session(
+        &self,
+        request: Request<GetSessionRequest>,
+    ) -> std::result::Result<Response<SessionStatus>, Status> {
+        let state = self
+            .sessions
+            .get(&request.into_inner().session_id)
+            .map_err(status)?
+            .ok_or_else(|| Status::not_found("no such session"))?;
+        Ok(Response::new(session_status(state)))
+    }
+}
+
+fn session_status(state: SessionState) -> SessionStatus {
+    SessionStatus {
+        session_id: state.id,
+        received: state.received as u32,
+        threshold: state.threshold as u32,
+        complete: state.secret.is_some(),
+        secret: state.secret,
+    }
+}
+
+fn status(err: Error) -> Status {
+    match err {
+        Error::Io(_) => Status::internal(err.to_string()),
+        Error::InconsistentShares(_)
+        | Error::InsufficientShares
+        | Error::DigestMismatch
+        | Error::InvalidSignature => Status::failed_precondition(err.to_string()),
+        _ => Status::invalid_argument(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deal_verify_and_reconstruct() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(
+            listener,
+            Box::new(crate::store::MemoryStore::default()),
+        ));
+        let mut client = SecretSharingClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let dealt = client
+            .deal(DealRequest {
+                secret: vec![0x12, 0x34],
+                threshold: 2,
+                total: 3,
+                group_parameters: Some(group.to_json().unwrap()),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        let verdict = client
+            .verify_share(VerifyShareRequest {
+                share: dealt.shares[1].clone(),
+                commitments: dealt.commitments.unwrap(),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(verdict.valid);
+
+        let dealt = client
+            .deal(DealRequest {
+                secret: b"quorum".to_vec(),
+                threshold: 2,
+                total: 3,
+                group_parameters: None,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        let session_id = client
+            .open_session(OpenSessionRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .session_id;
+        let submit = |share: &String| SubmitShareRequest {
+            session_id: session_id.clone(),
+            share: share.clone(),
+        };
+        let first = client.submit_share(submit(&dealt.shares[2])).await.unwrap();
+        assert_eq!(
+            (first.get_ref().received, first.get_ref().complete),
+            (1, false)
+        );
+        assert!(client.submit_share(submit(&dealt.shares[2])).await.is_err());
+        let done = client
+            .submit_share(submit(&dealt.shares[0]))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(done.secret.as_deref(), Some(&b"quorum"[..]));
+        assert!(client
+            .get_session(GetSessionRequest { session_id })
+            .await
+            .is_err());
+    }
+}
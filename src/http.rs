@@ -0,0 +1,651 @@
+// File: src/http.rs
+//! An authenticated HTTP API for key ceremonies (`http` feature).
+//!
+//! Every request must carry `Authorization: Bearer <token>`. Endpoints:
+//!
+//! - `POST /dealings` deals a secret and returns the shares;
+//! - `GET /dealings/:id/commitments` fetches the commitments of a VSS dealing;
+//! - `POST /sessions` opens a reconstruction session;
+//! - `POST /sessions/:id/shares` submits a share to it;
+//! - `GET /sessions/:id` polls its status;
+//! - `GET /sessions/:id/ws` submits shares over a WebSocket instead;
+//! - `POST /ceremonies` opens a Feldman VSS ceremony dealt to participants
+//!   joining at `GET /ceremonies/:id/ws`, and `GET /ceremonies/:id` reports
+//!   their acks and complaints.
+//!
+//! Bodies are JSON, with binary secrets in base64. Sessions and commitments
+//! live in a [`crate::store::ShareStore`]; see [`crate::service`].
+//!
+//! Ceremony sockets carry one [`crate::ceremony::Message`] per binary
+//! WebSocket message, so a browser drives its side with
+//! `CeremonyParticipant` from the `wasm` feature. Session sockets take
+//! armored or bech32 shares as text and answer each with the session status.
+//! Browsers cannot set headers on WebSocket requests, so upgrades may pass
+//! the token as `?access_token=` instead.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use num_bigint::BigUint;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::ceremony::{self, Message, Reply, Transport};
+use crate::encoding::hex;
+use crate::error::{Error, Result};
+use crate::params::GroupParameters;
+use crate::serialized::SerializedShare;
+use crate::service::{self, SessionState, Sessions};
+use crate::store::ShareStore;
+
+struct AppState {
+    /// SHA-256 of the bearer token; digests are compared, so timing reveals nothing about it.
+    token_digest: [u8; 32],
+    sessions: Sessions,
+    ceremonies: Mutex<HashMap<String, Arc<Ceremony>>>,
+}
+
+/// A dealing to participants joining over WebSockets.
+struct Ceremony {
+    /// Hands each joining participant's socket to the dealer.
+    joiners: UnboundedSender<SocketTransport>,
+    /// Set when the dealing ends: each participant's reply, or the failure.
+    outcome: Mutex<Option<std::result::Result<Vec<Reply>, String>>>,
+}
+
+/// The dealer's end of a participant's socket, used from a blocking thread.
+struct SocketTransport {
+    outgoing: UnboundedSender<Message>,
+    incoming: UnboundedReceiver<Message>,
+}
+
+impl Transport for SocketTransport {
+    fn send(&mut self, message: &Message) -> Result<()> {
+        self.outgoing
+            .send(message.clone())
+            .map_err(|_| disconnected())
+    }
+
+    fn receive(&mut self) -> Result<Message> {
+        self.incoming.blocking_recv().ok_or_else(disconnected)
+    }
+}
+
+fn disconnected() -> Error {
+    Error::Io(std::io::ErrorKind::ConnectionAborted.into())
+}
+
+#[derive(Deserialize)]
+struct DealBody {
+    secret_base64: String,
+    threshold: usize,
+    total: usize,
+    /// A parameter document as written by `sss gen-params`.
+    group_parameters: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct DealReply {
+    id: String,
+    shares: Vec<String>,
+    commitments: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct CeremonyBody {
+    secret_base64: String,
+    threshold: usize,
+    total: usize,
+    group_parameters: Value,
+}
+
+#[derive(Serialize)]
+struct CeremonyReply {
+    id: String,
+    complete: bool,
+    /// `"ack"` or the complaint of each participant, in index order.
+    replies: Option<Vec<String>>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ShareBody {
+    share: String,
+}
+
+#[derive(Serialize)]
+struct SessionReply {
+    id: String,
+    received: usize,
+    threshold: usize,
+    complete: bool,
+    secret_base64: Option<String>,
+}
+
+impl From<SessionState> for SessionReply {
+    fn from(state: SessionState) -> Self {
+        SessionReply {
+            id: state.id,
+            received: state.received,
+            threshold: state.threshold,
+            complete: state.secret.is_some(),
+            secret_base64: state.secret.map(|secret| BASE64.encode(secret)),
+        }
+    }
+}
+
+/// An error response with a JSON body.
+struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        let status = match err {
+            Error::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::InconsistentShares(_)
+            | Error::InsufficientShares
+            | Error::DigestMismatch
+            | Error::InvalidSignature => StatusCode::CONFLICT,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        ApiError(status, err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// The API routes, accepting only requests bearing `token` and keeping
+/// ceremony state in `store`.
+pub fn router(token: &str, store: Box<dyn ShareStore>) -> Router {
+    let state = Arc::new(AppState {
+        token_digest: Sha256::digest(token.as_bytes()).into(),
+        sessions: Sessions::new(store),
+        ceremonies: Mutex::new(HashMap::new()),
+    });
+    Router::new()
+        .route("/dealings", post(create_dealing))
+        .route("/dealings/:id/commitments", get(dealing_commitments))
+        .route("/sessions", post(open_session))
+        .route("/sessions/:id", get(session_status))
+        .route("/sessions/:id/shares", post(submit_share))
+        .route("/sessions/:id/ws", get(session_socket))
+        .route("/ceremonies", post(create_ceremony))
+        .route("/ceremonies/:id", get(ceremony_status))
+        .route("/ceremonies/:id/ws", get(ceremony_socket))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .with_state(state)
+}
+
+/// Serves the API on `listener` until the connection fails.
+pub async fn serve(listener: TcpListener, token: &str, store: Box<dyn ShareStore>) -> Result<()> {
+    Ok(axum::serve(listener, router(token, store)).await?)
+}
+
+async fn authenticate(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_owned)
+        .or_else(|| websocket_token(&request));
+    match presented {
+        Some(token) if <[u8; 32]>::from(Sha256::digest(token.as_bytes())) == state.token_digest => {
+            next.run(request).await
+        }
+        _ => ApiError(
+            StatusCode::UNAUTHORIZED,
+            "missing or wrong bearer token".into(),
+        )
+        .into_response(),
+    }
+}
+
+/// The `access_token` query parameter of a WebSocket upgrade.
+fn websocket_token(request: &Request) -> Option<String> {
+    let upgrade = request.headers().get(header::UPGRADE)?.to_str().ok()?;
+    if !upgrade.eq_ignore_ascii_case("websocket") {
+        return None;
+    }
+    let Query(mut query) = Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok()?;
+    query.remove("access_token")
+}
+
+async fn create_dealing(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<DealBody>,
+) -> ApiResult<(StatusCode, Json<DealReply>)> {
+    let secret = BASE64
+        .decode(&body.secret_base64)
+        .map_err(|_| Error::InvalidEncoding("secret_base64 is not base64"))?;
+    let group = body
+        .group_parameters
+        .map(|document| GroupParameters::from_json(&document.to_string()))
+        .transpose()?;
+    let dealing = service::deal(&secret, body.threshold, body.total, group.as_ref())?;
+
+    let commitments = match dealing.commitments {
+        Some(document) => {
+            let store = state.sessions.store();
+            store.put_commitments(&dealing.session, &document)?;
+            Some(serde_json::from_str(&document.to_json()?).expect("documents are JSON"))
+        }
+        None => None,
+    };
+    let reply = DealReply {
+        id: dealing.session,
+        shares: dealing.shares.iter().map(|s| s.to_armored()).collect(),
+        commitments,
+    };
+    Ok((StatusCode::CREATED, Json(reply)))
+}
+
+async fn dealing_commitments(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<Response> {
+    let document = state
+        .sessions
+        .store()
+        .get_commitments(&id)?
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, "no VSS dealing with this id".into()))?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/json")],
+        document.to_json()?,
+    )
+        .into_response())
+}
+
+async fn open_session(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<(StatusCode, Json<SessionReply>)> {
+    Ok((StatusCode::CREATED, Json(state.sessions.open()?.into())))
+}
+
+async fn session_status(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<SessionReply>> {
+    let session = state
+        .sessions
+        .get(&id)?
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, "no open session with this id".into()))?;
+    Ok(Json(session.into()))
+}
+
+async fn submit_share(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(body): Json<ShareBody>,
+) -> ApiResult<Json<SessionReply>> {
+    let share = SerializedShare::from_armored(&body.share)
+        .or_else(|_| SerializedShare::from_bech32(&body.share))?;
+    if state.sessions.get(&id)?.is_none() {
+        return Err(ApiError(
+            StatusCode::NOT_FOUND,
+            "no open session with this id".into(),
+        ));
+    }
+    Ok(Json(state.sessions.submit(&id, share)?.into()))
+}
+
+async fn session_socket(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> ApiResult<Response> {
+    if state.sessions.get(&id)?.is_none() {
+        return Err(ApiError(
+            StatusCode::NOT_FOUND,
+            "no open session with this id".into(),
+        ));
+    }
+    Ok(upgrade.on_upgrade(move |socket| relay_session(socket, state, id)))
+}
+
+/// Submits each text message as a share, answering with the session status
+/// until the quorum completes.
+async fn relay_session(mut socket: WebSocket, state: Arc<AppState>, id: String) {
+    while let Some(Ok(message)) = socket.recv().await {
+        let WsMessage::Text(text) = message else {
+            continue;
+        };
+        let submitted = SerializedShare::from_armored(&text)
+            .or_else(|_| SerializedShare::from_bech32(&text))
+            .and_then(|share| state.sessions.submit(&id, share));
+        let (reply, complete) = match submitted {
+            Ok(session) => {
+                let complete = session.secret.is_some();
+                (json!(SessionReply::from(session)), complete)
+            }
+            Err(err) => (json!({ "error": err.to_string() }), false),
+        };
+        if socket
+            .send(WsMessage::Text(reply.to_string()))
+            .await
+            .is_err()
+            || complete
+        {
+            break;
+        }
+    }
+    let _ = socket.send(WsMessage::Close(None)).await;
+}
+
+async fn create_ceremony(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CeremonyBody>,
+) -> ApiResult<(StatusCode, Json<CeremonyReply>)> {
+    let secret = BASE64
+        .decode(&body.secret_base64)
+        .map_err(|_| Error::InvalidEncoding("secret_base64 is not base64"))?;
+    let group = GroupParameters::from_json(&body.group_parameters.to_string())?;
+    group.vss(body.threshold, body.total)?;
+    let secret = BigUint::from_bytes_be(&secret);
+    if secret >= group.q {
+        return Err(
+            Error::InvalidParameters("secret must be smaller than the group order q").into(),
+        );
+    }
+
+    let mut id = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut id);
+    let id = hex(&id);
+    let (joiners, mut joining) = unbounded_channel();
+    let ceremony = Arc::new(Ceremony {
+        joiners,
+        outcome: Mutex::new(None),
+    });
+    state
+        .ceremonies
+        .lock()
+        .expect("ceremony lock")
+        .insert(id.clone(), ceremony.clone());
+
+    let session = id.clone();
+    tokio::task::spawn_blocking(move || {
+        let accept = || joining.blocking_recv().ok_or_else(disconnected);
+        let (threshold, total) = (body.threshold, body.total);
+        let outcome = ceremony::deal_over(
+            accept,
+            &group,
+            threshold,
+            total,
+            &secret,
+            session.as_bytes(),
+        );
+        *ceremony.outcome.lock().expect("ceremony lock") = Some(outcome.map_err(|e| e.to_string()));
+    });
+    let reply = CeremonyReply {
+        id,
+        complete: false,
+        replies: None,
+        error: None,
+    };
+    Ok((StatusCode::CREATED, Json(reply)))
+}
+
+async fn ceremony_status(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<CeremonyReply>> {
+    let ceremony = find_ceremony(&state, &id)?;
+    let outcome = ceremony.outcome.lock().expect("ceremony lock");
+    let (replies, error) = match outcome.as_ref() {
+        Some(Ok(replies)) => {
+            let replies = replies
+                .iter()
+                .map(|reply| match reply {
+                    Reply::Ack => "ack".to_string(),
+                    Reply::Complaint(reason) => reason.clone(),
+                })
+                .collect();
+            (Some(replies), None)
+        }
+        Some(Err(err)) => (None, Some(err.clone())),
+        None => (None, None),
+    };
+    Ok(Json(CeremonyReply {
+        id,
+        complete: outcome.is_some(),
+        replies,
+        error,
+    }))
+}
+
+async fn ceremony_socket(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> ApiResult<Response> {
+    let joiners = find_ceremony(&state, &id)?.joiners.clone();
+    Ok(upgrade.on_upgrade(move |socket| relay_ceremony(socket, joiners)))
+}
+
+fn find_ceremony(state: &AppState, id: &str) -> ApiResult<Arc<Ceremony>> {
+    let ceremonies = state.ceremonies.lock().expect("ceremony lock");
+    ceremonies
+        .get(id)
+        .cloned()
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, "no ceremony with this id".into()))
+}
+
+/// Moves ceremony messages between a participant's socket and the dealer
+/// until either side is done.
+async fn relay_ceremony(mut socket: WebSocket, joiners: UnboundedSender<SocketTransport>) {
+    let (to_dealer, incoming) = unbounded_channel();
+    let (outgoing, mut from_dealer) = unbounded_channel();
+    if joiners
+        .send(SocketTransport { outgoing, incoming })
+        .is_err()
+    {
+        let _ = socket.send(WsMessage::Close(None)).await;
+        return;
+    }
+    loop {
+        tokio::select! {
+            received = socket.recv() => match received {
+                Some(Ok(WsMessage::Binary(bytes))) => {
+                    let forwarded = Message::from_bytes(&bytes)
+                        .is_ok_and(|message| to_dealer.send(message).is_ok());
+                    if !forwarded {
+                        break;
+                    }
+                }
+                Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            sent = from_dealer.recv() => match sent {
+                Some(message) => {
+                    if socket.send(WsMessage::Binary(message.to_bytes())).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+    let _ = socket.send(WsMessage::Close(None)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use tower::ServiceExt;
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let request = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_dealing_and_reconstruction_session() {
+        let app = router("s3cret", Box::new(crate::store::MemoryStore::default()));
+        let (status, _) = call(&app, "POST", "/sessions", "wrong", Value::Null).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let body = json!({
+            "secret_base64": BASE64.encode([0x12, 0x34]),
+            "threshold": 2,
+            "total": 3,
+            "group_parameters": serde_json::from_str::<Value>(&group.to_json().unwrap()).unwrap(),
+        });
+        let (status, dealt) = call(&app, "POST", "/dealings", "s3cret", body).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let uri = format!("/dealings/{}/commitments", dealt["id"].as_str().unwrap());
+        let (status, commitments) = call(&app, "GET", &uri, "s3cret", Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(commitments, dealt["commitments"]);
+
+        let (_, session) = call(&app, "POST", "/sessions", "s3cret", Value::Null).await;
+        let uri = format!("/sessions/{}/shares", session["id"].as_str().unwrap());
+        for (i, complete) in [(0, false), (2, true)] {
+            let share = json!({ "share": dealt["shares"][i] });
+            let (status, reply) = call(&app, "POST", &uri, "s3cret", share).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(reply["complete"], json!(complete));
+            if complete {
+                assert_eq!(reply["secret_base64"], json!(BASE64.encode([0x12, 0x34])));
+            }
+        }
+        let (status, _) = call(
+            &app,
+            "GET",
+            &uri.replace("/shares", ""),
+            "s3cret",
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_ceremony_and_session() {
+        use crate::ceremony::{reconstructor, Participant};
+        use futures_util::{SinkExt, StreamExt};
+        use rand::rngs::OsRng;
+        use tokio_tungstenite::connect_async;
+        use tokio_tungstenite::tungstenite::Message as Frame;
+        use x25519_dalek::StaticSecret;
+
+        let app = router("s3cret", Box::new(crate::store::MemoryStore::default()));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("ws://{}", listener.local_addr().unwrap());
+        let served = app.clone();
+        tokio::spawn(async move { axum::serve(listener, served).await });
+
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let body = json!({
+            "secret_base64": BASE64.encode([0x0a, 0x0b]),
+            "threshold": 2,
+            "total": 3,
+            "group_parameters": serde_json::from_str::<Value>(&group.to_json().unwrap()).unwrap(),
+        });
+        let (status, created) = call(&app, "POST", "/ceremonies", "s3cret", body).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = created["id"].as_str().unwrap().to_string();
+        let url = format!("{base}/ceremonies/{id}/ws");
+        assert!(connect_async(url.clone()).await.is_err());
+
+        let mut joined = Vec::new();
+        for index in 1..=3u32 {
+            let url = format!("{url}?access_token=s3cret");
+            joined.push(tokio::spawn(async move {
+                let (mut socket, _) = connect_async(url).await.unwrap();
+                let key = StaticSecret::random_from_rng(OsRng);
+                let mut participant = Participant::new(index, key, None);
+                let hello = participant.hello().to_bytes();
+                socket.send(Frame::Binary(hello)).await.unwrap();
+                loop {
+                    let Frame::Binary(bytes) = socket.next().await.unwrap().unwrap() else {
+                        continue;
+                    };
+                    let message = Message::from_bytes(&bytes).unwrap();
+                    if let Some(reply) = participant.receive(message).unwrap() {
+                        socket.send(Frame::Binary(reply.to_bytes())).await.unwrap();
+                        return participant.finish().unwrap();
+                    }
+                }
+            }));
+        }
+        let mut received = Vec::new();
+        for participant in joined {
+            received.push(participant.await.unwrap());
+        }
+        let status = loop {
+            let (_, status) = call(
+                &app,
+                "GET",
+                &format!("/ceremonies/{id}"),
+                "s3cret",
+                Value::Null,
+            )
+            .await;
+            if status["complete"] == json!(true) {
+                break status;
+            }
+            tokio::task::yield_now().await;
+        };
+        assert_eq!(status["replies"], json!(["ack", "ack", "ack"]));
+
+        let (_, session) = call(&app, "POST", "/sessions", "s3cret", Value::Null).await;
+        let url = format!(
+            "{base}/sessions/{}/ws?access_token=s3cret",
+            session["id"].as_str().unwrap()
+        );
+        let (mut socket, _) = connect_async(url).await.unwrap();
+        let vss = reconstructor(&received[0].1);
+        for (share, _) in [&received[2], &received[0]] {
+            let armored = vss.serialize_share(share).to_armored();
+            socket.send(Frame::Text(armored)).await.unwrap();
+        }
+        let mut replies = Vec::new();
+        while let Some(Ok(Frame::Text(text))) = socket.next().await {
+            replies.push(serde_json::from_str::<Value>(&text).unwrap());
+        }
+        assert_eq!(replies[0]["received"], json!(1));
+        assert_eq!(
+            replies[1]["secret_base64"],
+            json!(BASE64.encode([0x0a, 0x0b]))
+        );
+    }
+}
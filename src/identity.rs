@@ -0,0 +1,113 @@
+// File: src/identity.rs
+//! Share indices derived from participant identities.
+//!
+//! Positional indices `1..=n` tie a share to a slot in the roster, so
+//! reordering or growing the roster reshuffles who holds which point. A
+//! [`ShareId`] instead hashes a stable identity string such as
+//! `"alice@example.com"` into a nonzero field element, so the same person
+//! always holds the share at the same point of a dealing.
+//!
+//! The hash is SHA-256 in counter mode under a domain tag, widened 16 bytes
+//! past the modulus before reduction; a result of zero is rehashed with the
+//! next counter. [`assign`] and [`crate::sss::SecretSharer::split_for`] refuse rosters
+//! in which two identities land on the same point.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+use crate::sss::named_prime;
+
+const DOMAIN: &[u8] = b"sss-share-id-v1";
+
+/// A participant identity and its share index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShareId {
+    pub identity: String,
+    pub x: BigUint,
+}
+
+impl ShareId {
+    /// The index of `identity` in the default field of [`crate::sss::SecretSharer::new`].
+    pub fn from_identity(identity: &str) -> Self {
+        let prime = named_prime("mersenne-521").expect("built-in prime");
+        Self::in_field(identity, &prime)
+    }
+
+    /// The index of `identity` modulo `modulus`, never zero.
+    pub fn in_field(identity: &str, modulus: &BigUint) -> Self {
+        let width = (modulus.bits() as usize).div_ceil(8) + 16;
+        for attempt in 0u32.. {
+            let mut wide = Vec::with_capacity(width + 32);
+            for block in 0u32.. {
+                if wide.len() >= width {
+                    break;
+                }
+                let mut hasher = Sha256::new();
+                hasher.update(DOMAIN);
+                hasher.update(attempt.to_be_bytes());
+                hasher.update(block.to_be_bytes());
+                hasher.update(identity.as_bytes());
+                wide.extend_from_slice(&hasher.finalize());
+            }
+            let x = BigUint::from_bytes_be(&wide[..width]) % modulus;
+            if !x.is_zero() {
+                return ShareId {
+                    identity: identity.to_string(),
+                    x,
+                };
+            }
+        }
+        unreachable!("a nonzero index turns up long before the counter wraps")
+    }
+}
+
+/// Indices for a roster of identities modulo `modulus`, refusing duplicate
+/// identities and colliding indices.
+pub fn assign<S: AsRef<str>>(identities: &[S], modulus: &BigUint) -> Result<Vec<ShareId>> {
+    let ids: Vec<ShareId> = identities
+        .iter()
+        .map(|identity| ShareId::in_field(identity.as_ref(), modulus))
+        .collect();
+    for (i, id) in ids.iter().enumerate() {
+        if ids[..i].iter().any(|other| other.identity == id.identity) {
+            return Err(Error::InvalidParameters("duplicate identity"));
+        }
+        if ids[..i].iter().any(|other| other.x == id.x) {
+            return Err(Error::InconsistentShares("identities collide in the field"));
+        }
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sss::SecretSharer;
+
+    #[test]
+    fn test_identity_shares() {
+        let alice = ShareId::from_identity("alice@example.com");
+        assert_eq!(alice, ShareId::from_identity("alice@example.com"));
+        assert_ne!(alice.x, ShareId::from_identity("bob@example.com").x);
+
+        let sharer = SecretSharer::new(2, 3);
+        let roster = ["alice@example.com", "bob@example.com", "carol@example.com"];
+        let secret = BigUint::from(31337u32);
+        let shares = sharer.split_for(&secret, &roster).unwrap();
+        assert_eq!(shares[0].x, alice.x);
+        assert_eq!(sharer.reconstruct_secret(&shares[1..]), Some(secret));
+        assert!(sharer
+            .split_for(&BigUint::from(1u32), &["alice", "bob", "alice"])
+            .is_err());
+
+        // In a tiny field, distinct identities are bound to collide.
+        let small = BigUint::from(5u32);
+        let ids: Vec<String> = (0..6).map(|i| format!("user{}", i)).collect();
+        assert!(ids
+            .iter()
+            .all(|i| !ShareId::in_field(i, &small).x.is_zero()));
+        assert!(assign(&ids, &small).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+// File: src/jrss.rs
+//! Joint random secret sharing without a dealer.
+//!
+//! Every party deals Shamir shares of a random value of its own
+//! ([`JointRandom::deal`]) and sends each other party its share privately.
+//! Each party adds up the shares it received ([`JointRandom::combine`]),
+//! giving a Shamir share of the sum of all dealt values: a uniformly random
+//! secret that no party knows, as long as one dealer is honest. All parties
+//! must sum the contributions of the same dealers.
+//!
+//! This is the bare building block, with no commitments or complaint rounds;
+//! [`crate::dkg`] adds both for key generation.
+
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::Zero;
+use rand::thread_rng;
+
+use crate::error::{Error, Result};
+use crate::sss::{SecretSharer, Share};
+
+/// Parameters of a joint sharing among parties `1..=total`.
+pub struct JointRandom {
+    prime: BigUint,
+    threshold: usize,
+    total: usize,
+}
+
+impl JointRandom {
+    pub fn new(prime: BigUint, threshold: usize, total: usize) -> Result<Self> {
+        if threshold == 0 || threshold > total {
+            return Err(Error::InvalidParameters(
+                "threshold must be between 1 and the total",
+            ));
+        }
+        Ok(JointRandom {
+            prime,
+            threshold,
+            total,
+        })
+    }
+
+    /// A party's contribution: shares of a fresh random value, one per
+    /// party in index order, itself included.
+    pub fn deal(&self) -> Vec<Share> {
+        let value = thread_rng().gen_biguint_below(&self.prime);
+        SecretSharer::with_prime(self.prime.clone(), self.threshold, self.total)
+            .split_secret(&value)
+    }
+
+    /// Sums the shares a party received from the dealers, one each, into its
+    /// share of the joint random value.
+    pub fn combine(&self, received: &[Share]) -> Result<Share> {
+        let first = received.first().ok_or(Error::InsufficientShares)?;
+        if received.iter().any(|share| share.x != first.x) {
+            return Err(Error::InconsistentShares("shares have different indices"));
+        }
+        Ok(Share {
+            x: first.x.clone(),
+            y: received
+                .iter()
+                .fold(BigUint::zero(), |acc, share| (acc + &share.y) % &self.prime),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_joint_random_sharing() {
+        let prime = BigUint::from(2u32).pow(127) - 1u32;
+        let joint = JointRandom::new(prime.clone(), 2, 3).unwrap();
+        let sharer = SecretSharer::with_prime(prime.clone(), 2, 3);
+        let dealings: Vec<_> = (0..3).map(|_| joint.deal()).collect();
+
+        let shares: Vec<_> = (0..3)
+            .map(|i| {
+                let received: Vec<_> = dealings.iter().map(|d| d[i].clone()).collect();
+                joint.combine(&received).unwrap()
+            })
+            .collect();
+        let expected = dealings.iter().fold(BigUint::zero(), |acc, d| {
+            (acc + sharer.reconstruct_secret(d).unwrap()) % &prime
+        });
+        assert_eq!(sharer.reconstruct_secret(&shares[1..]), Some(expected));
+        assert!(joint
+            .combine(&[dealings[0][0].clone(), dealings[1][1].clone()])
+            .is_err());
+        assert!(JointRandom::new(prime, 4, 3).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+// File: src/keychain.rs
+//! Storage of a custodian's own share in the platform keychain (`keyring` feature).
+//!
+//! Shares are kept as bech32 strings under the [`SERVICE`] service name in
+//! macOS Keychain, Windows Credential Manager or the Secret Service, so a
+//! custodian's share does not have to sit in a loose file.
+
+use keyring::Entry;
+
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+
+/// The keychain service name every share is stored under.
+pub const SERVICE: &str = "sss";
+
+/// The keychain entry holding the share named `name`.
+pub fn entry(name: &str) -> Result<Entry> {
+    Entry::new(SERVICE, name).map_err(keyring_error)
+}
+
+/// Stores `share` in the entry, replacing any share kept there before.
+pub fn keep(entry: &Entry, share: &SerializedShare) -> Result<()> {
+    entry
+        .set_password(&share.to_bech32())
+        .map_err(keyring_error)
+}
+
+/// Reads back the share stored in the entry.
+pub fn fetch(entry: &Entry) -> Result<SerializedShare> {
+    SerializedShare::from_bech32(&entry.get_password().map_err(keyring_error)?)
+}
+
+/// Deletes the share stored in the entry.
+pub fn remove(entry: &Entry) -> Result<()> {
+    entry.delete_credential().map_err(keyring_error)
+}
+
+fn keyring_error(err: keyring::Error) -> Error {
+    Error::Keyring(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::split_bytes;
+
+    #[test]
+    fn test_keep_fetch_remove() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let share = split_bytes(b"custodian secret", 2, 3).unwrap().remove(1);
+        let entry = entry("test-share").unwrap();
+
+        assert!(fetch(&entry).is_err());
+        keep(&entry, &share).unwrap();
+        assert_eq!(fetch(&entry).unwrap(), share);
+        remove(&entry).unwrap();
+        assert!(matches!(fetch(&entry), Err(Error::Keyring(_))));
+    }
+}
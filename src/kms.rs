@@ -0,0 +1,292 @@
+// File: src/kms.rs
+//! Cloud KMS escrow backends (`aws-kms` and `gcp-kms` features).
+//!
+//! [`AwsKms`] asks AWS KMS for data keys with `GenerateDataKey`, binding the
+//! share fingerprint as encryption context. [`GcpKms`] generates data keys
+//! locally and wraps them with Cloud KMS `encrypt`, binding the fingerprint
+//! as additional authenticated data. Either one plugs into
+//! [`crate::escrow::EscrowBackend::wrap_share`].
+
+#[cfg(feature = "gcp-kms")]
+use base64::engine::general_purpose::STANDARD as BASE64;
+#[cfg(feature = "gcp-kms")]
+use base64::Engine;
+#[cfg(feature = "gcp-kms")]
+use rand::{rngs::OsRng, RngCore};
+#[cfg(feature = "gcp-kms")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::escrow::{DataKey, EscrowBackend};
+
+/// Escrows data keys under an AWS KMS key.
+#[cfg(feature = "aws-kms")]
+pub struct AwsKms {
+    client: aws_sdk_kms::Client,
+    key_id: String,
+}
+
+#[cfg(feature = "aws-kms")]
+const CONTEXT_KEY: &str = "sss-share-fingerprint";
+
+#[cfg(feature = "aws-kms")]
+impl AwsKms {
+    /// Uses the default credential chain and region of the environment.
+    pub async fn new(key_id: impl Into<String>) -> Self {
+        let config = aws_config::load_from_env().await;
+        Self::with_client(aws_sdk_kms::Client::new(&config), key_id)
+    }
+
+    /// Uses a configured client, e.g. one assuming a role in another account.
+    pub fn with_client(client: aws_sdk_kms::Client, key_id: impl Into<String>) -> Self {
+        AwsKms {
+            client,
+            key_id: key_id.into(),
+        }
+    }
+}
+
+#[cfg(feature = "aws-kms")]
+impl EscrowBackend for AwsKms {
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    async fn generate_data_key(&self, context: &str) -> Result<DataKey> {
+        let output = self
+            .client
+            .generate_data_key()
+            .key_id(&self.key_id)
+            .key_spec(aws_sdk_kms::types::DataKeySpec::Aes256)
+            .encryption_context(CONTEXT_KEY, context)
+            .send()
+            .await
+            .map_err(|err| kms_error(aws_sdk_kms::error::DisplayErrorContext(err)))?;
+        let plaintext = output
+            .plaintext()
+            .and_then(|key| <[u8; 32]>::try_from(key.as_ref()).ok())
+            .ok_or_else(|| kms_error("GenerateDataKey returned no 256-bit key"))?;
+        let wrapped = output
+            .ciphertext_blob()
+            .ok_or_else(|| kms_error("GenerateDataKey returned no ciphertext"))?
+            .as_ref()
+            .to_vec();
+        Ok(DataKey { plaintext, wrapped })
+    }
+
+    async fn unwrap_data_key(&self, wrapped: &[u8], context: &str) -> Result<[u8; 32]> {
+        let output = self
+            .client
+            .decrypt()
+            .key_id(&self.key_id)
+            .ciphertext_blob(aws_sdk_kms::primitives::Blob::new(wrapped))
+            .encryption_context(CONTEXT_KEY, context)
+            .send()
+            .await
+            .map_err(|err| match err.into_service_error() {
+                err if err.is_invalid_ciphertext_exception() => Error::DecryptionFailed,
+                err => kms_error(aws_sdk_kms::error::DisplayErrorContext(err)),
+            })?;
+        output
+            .plaintext()
+            .and_then(|key| <[u8; 32]>::try_from(key.as_ref()).ok())
+            .ok_or(Error::DecryptionFailed)
+    }
+}
+
+/// Escrows data keys under a Google Cloud KMS symmetric key.
+#[cfg(feature = "gcp-kms")]
+pub struct GcpKms {
+    http: reqwest::Client,
+    endpoint: String,
+    key_name: String,
+    access_token: String,
+}
+
+#[cfg(feature = "gcp-kms")]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptRequest {
+    plaintext: String,
+    additional_authenticated_data: String,
+}
+
+#[cfg(feature = "gcp-kms")]
+#[derive(Deserialize)]
+struct EncryptResponse {
+    ciphertext: String,
+}
+
+#[cfg(feature = "gcp-kms")]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DecryptRequest {
+    ciphertext: String,
+    additional_authenticated_data: String,
+}
+
+#[cfg(feature = "gcp-kms")]
+#[derive(Deserialize)]
+struct DecryptResponse {
+    plaintext: String,
+}
+
+#[cfg(feature = "gcp-kms")]
+impl GcpKms {
+    /// `key_name` is the key's resource name,
+    /// `projects/P/locations/L/keyRings/R/cryptoKeys/K`; `access_token` is an
+    /// OAuth token with the `cloudkms` scope, e.g. from
+    /// `gcloud auth print-access-token`.
+    pub fn new(key_name: impl Into<String>, access_token: impl Into<String>) -> Self {
+        GcpKms {
+            http: reqwest::Client::new(),
+            endpoint: "https://cloudkms.googleapis.com".into(),
+            key_name: key_name.into(),
+            access_token: access_token.into(),
+        }
+    }
+
+    /// Sends requests to another endpoint, e.g. a private service connection.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    async fn call(&self, method: &str, body: &impl Serialize) -> Result<reqwest::Response> {
+        self.http
+            .post(format!("{}/v1/{}:{}", self.endpoint, self.key_name, method))
+            .bearer_auth(&self.access_token)
+            .json(body)
+            .send()
+            .await
+            .map_err(kms_error)
+    }
+}
+
+#[cfg(feature = "gcp-kms")]
+impl EscrowBackend for GcpKms {
+    fn key_id(&self) -> &str {
+        &self.key_name
+    }
+
+    async fn generate_data_key(&self, context: &str) -> Result<DataKey> {
+        let mut plaintext = [0u8; 32];
+        OsRng.fill_bytes(&mut plaintext);
+        let request = EncryptRequest {
+            plaintext: BASE64.encode(plaintext),
+            additional_authenticated_data: BASE64.encode(context),
+        };
+        let response = self.call("encrypt", &request).await?;
+        if !response.status().is_success() {
+            return Err(kms_error(format!("encrypt returned {}", response.status())));
+        }
+        let response: EncryptResponse = response.json().await.map_err(kms_error)?;
+        let wrapped = BASE64
+            .decode(response.ciphertext)
+            .map_err(|_| kms_error("encrypt returned invalid base64"))?;
+        Ok(DataKey { plaintext, wrapped })
+    }
+
+    async fn unwrap_data_key(&self, wrapped: &[u8], context: &str) -> Result<[u8; 32]> {
+        let request = DecryptRequest {
+            ciphertext: BASE64.encode(wrapped),
+            additional_authenticated_data: BASE64.encode(context),
+        };
+        let response = self.call("decrypt", &request).await?;
+        match response.status() {
+            status if status.is_success() => {}
+            reqwest::StatusCode::BAD_REQUEST => return Err(Error::DecryptionFailed),
+            status => return Err(kms_error(format!("decrypt returned {status}"))),
+        }
+        let response: DecryptResponse = response.json().await.map_err(kms_error)?;
+        BASE64
+            .decode(response.plaintext)
+            .ok()
+            .and_then(|key| <[u8; 32]>::try_from(key).ok())
+            .ok_or(Error::DecryptionFailed)
+    }
+}
+
+fn kms_error(err: impl std::fmt::Display) -> Error {
+    Error::Kms(err.to_string())
+}
+
+#[cfg(all(test, feature = "gcp-kms"))]
+mod tests {
+    use super::*;
+    use crate::escrow::EscrowedShare;
+    use crate::serialized::split_bytes;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Answers Cloud KMS calls, "wrapping" by prefixing the AAD.
+    fn mock_cloud_kms() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut reader = BufReader::new(stream.unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let aad = body["additionalAuthenticatedData"].as_str().unwrap();
+
+                let (status, response) = if request_line.contains(":encrypt ") {
+                    let wrapped = format!("{aad}.{}", body["plaintext"].as_str().unwrap());
+                    let ciphertext = BASE64.encode(wrapped);
+                    ("200 OK", serde_json::json!({ "ciphertext": ciphertext }))
+                } else {
+                    let wrapped = BASE64.decode(body["ciphertext"].as_str().unwrap()).unwrap();
+                    let wrapped = String::from_utf8(wrapped).unwrap();
+                    match wrapped.split_once('.') {
+                        Some((bound, key)) if bound == aad => {
+                            ("200 OK", serde_json::json!({ "plaintext": key }))
+                        }
+                        _ => ("400 Bad Request", serde_json::json!({})),
+                    }
+                };
+                let response = response.to_string();
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                )
+                .unwrap();
+            }
+        });
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn test_gcp_escrow_against_mock_service() {
+        let kms = GcpKms::new("projects/p/locations/l/keyRings/r/cryptoKeys/k", "token")
+            .with_endpoint(mock_cloud_kms());
+        let share = split_bytes(b"independent trust domain", 2, 3)
+            .unwrap()
+            .remove(2);
+
+        let escrowed = kms.wrap_share(&share).await.unwrap();
+        let escrowed = EscrowedShare::from_bytes(&escrowed.to_bytes()).unwrap();
+        assert_eq!(kms.unwrap_share(&escrowed).await.unwrap(), share);
+
+        let other = kms.generate_data_key("other context").await.unwrap();
+        assert!(matches!(
+            kms.unwrap_data_key(&other.wrapped, &escrowed.fingerprint)
+                .await,
+            Err(Error::DecryptionFailed)
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+// File: src/lib.rs
+pub mod advice;
+pub mod age_plugin;
+#[cfg(feature = "arkworks")]
+pub mod arkworks;
+pub mod audit;
+pub mod beaver;
+pub mod bech32;
+pub mod ceremony;
+pub mod checkpoint;
+pub mod chunked;
+pub mod codex32;
+pub mod commitment;
+pub mod coordinator;
+pub mod custody;
+pub mod derive;
+pub mod dkg;
+pub mod ecies;
+pub mod encoding;
+pub mod enrollment;
+pub mod envelope;
+pub mod error;
+pub mod escrow;
+pub mod ethereum;
+pub mod expiry;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "fido2")]
+pub mod fido2;
+pub mod frost;
+pub mod gf256;
+pub mod gfshare;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod identity;
+pub mod jrss;
+#[cfg(feature = "keyring")]
+pub mod keychain;
+pub mod keys;
+#[cfg(any(feature = "aws-kms", feature = "gcp-kms"))]
+pub mod kms;
+pub mod manifest;
+pub mod merkle;
+pub mod mnemonic;
+#[cfg(feature = "mobile")]
+pub mod mobile;
+pub mod nested;
+pub mod noise;
+pub mod openpgp;
+pub mod params;
+pub mod partial;
+pub mod passphrase;
+#[cfg(feature = "pkcs11")]
+pub mod pkcs11;
+pub mod progress;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "qr")]
+pub mod qr;
+pub mod recovery;
+pub mod refresh;
+pub mod reshare;
+pub mod revocation;
+pub mod serialized;
+pub mod service;
+pub mod sharks;
+pub mod signing;
+pub mod slip39;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod sss;
+pub mod ssss;
+pub mod store;
+pub mod test_vectors;
+pub mod vault;
+pub mod vss;
+pub mod vsss_rs;
+pub mod wallet;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::{Error, Result};
+
+#[cfg(feature = "mobile")]
+uniffi::setup_scaffolding!("sss");
@@ -1,98 +1,1982 @@
 // File: src/main.rs
-use crate::sss::SecretSharer;
-use crate::vss::FeldmanVSS;
-use num_bigint::ToBigUint;
+mod config;
+#[cfg(feature = "tui")]
+mod wizard;
 
-mod sss;
-mod vss;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-fn main() {
-    demo_shamir_secret_sharing();
-    demo_verifiable_secret_sharing();
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use config::{Config, ShareEncoding};
+use cryptographic_algorithms::advice;
+use cryptographic_algorithms::commitment::CommitmentDocument;
+use cryptographic_algorithms::encoding::{hex, unhex};
+use cryptographic_algorithms::envelope::Envelope;
+use cryptographic_algorithms::gf256::Gf256;
+#[cfg(feature = "keyring")]
+use cryptographic_algorithms::keychain;
+use cryptographic_algorithms::keys;
+use cryptographic_algorithms::manifest::{AuditFile, AuditItem, AuditManifest, BatchManifest};
+use cryptographic_algorithms::mnemonic;
+use cryptographic_algorithms::params::{GroupParameters, PRESETS};
+use cryptographic_algorithms::refresh::{self, RefreshMessage};
+use cryptographic_algorithms::reshare::{self, ReshareMessage};
+use cryptographic_algorithms::serialized::{
+    insecure_seeded_rng, reconstruct_auto, split_bytes, split_bytes_with_rng, Scheme,
+    SerializedShare, FORMAT_VERSION,
+};
+use cryptographic_algorithms::service;
+use cryptographic_algorithms::sss::named_prime;
+use cryptographic_algorithms::test_vectors;
+use cryptographic_algorithms::vss;
+use cryptographic_algorithms::{Error, Result};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use num_bigint::BigUint;
+use rand::RngCore;
+use serde_json::{json, Value};
+
+#[derive(Parser)]
+#[command(
+    name = "sss",
+    version,
+    about = "Split secrets into shares and recombine them"
+)]
+struct Cli {
+    /// Print nothing but requested output and errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
+    /// Print a single JSON object describing the result or error on stdout
+    #[arg(long, global = true)]
+    json: bool,
+    /// Read default flag values from this TOML file instead of
+    /// `~/.config/sss/config.toml`
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Split a secret file into armored share files
+    Split(SplitArgs),
+    /// Recombine share files into the secret
+    Combine(CombineArgs),
+    /// Check a Feldman VSS share against the dealer's published commitments
+    Verify(VerifyArgs),
+    /// Generate a random secret and split it at once, so it never exists elsewhere
+    GenSecret(GenSecretArgs),
+    /// Generate and validate Feldman VSS group parameters
+    GenParams(GenParamsArgs),
+    /// Write the known-answer test vectors other implementations check against
+    TestVectors(TestVectorsArgs),
+    /// Take part in a proactive refresh round without reassembling the secret
+    ///
+    /// Every holder runs this once to post contributions into the round
+    /// directory, then again once all holders have done so to collect their
+    /// new share.
+    Refresh(RefreshArgs),
+    /// Move a secret to a new threshold or set of custodians without reassembling it
+    Reshare {
+        #[command(subcommand)]
+        step: ReshareStep,
+    },
+    /// Move a shared secret to another field or scheme, reassembling it here
+    ///
+    /// A quorum of the current shares recovers the secret, which is dealt
+    /// again as shares of the target: a named prime field, a Feldman group
+    /// or GF(2^8) byte-wise shares.
+    Convert(ConvertArgs),
+    /// Split a TLS certificate's private key among operators, or rebuild it
+    TlsKey {
+        #[command(subcommand)]
+        step: TlsKeyStep,
+    },
+    /// Describe a share without revealing its payload
+    Inspect(InspectArgs),
+    /// Walk custodians through a split or combine ceremony interactively
+    #[cfg(feature = "tui")]
+    Wizard,
+    /// Serve the dealing, verification and reconstruction gRPC service
+    #[cfg(feature = "grpc")]
+    GrpcServe(GrpcServeArgs),
+    /// Serve the authenticated HTTP ceremony API
+    #[cfg(feature = "http")]
+    Serve(ServeArgs),
+    /// Store your own share in the platform keychain
+    #[cfg(feature = "keyring")]
+    Keep(KeepArgs),
+    /// Retrieve a share stored with `keep`
+    #[cfg(feature = "keyring")]
+    Fetch(FetchArgs),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SecretFormat {
+    Hex,
+    Base64,
+    /// A standard BIP-39 seed phrase; needs 16, 20, 24, 28 or 32 bytes
+    Bip39,
+}
+
+#[derive(Subcommand)]
+enum ReshareStep {
+    /// Run by each participating old holder: deal contributions to the new custodians
+    Deal(ReshareDealArgs),
+    /// Run by each new custodian once every dealer has dealt
+    Collect(ReshareCollectArgs),
+}
+
+#[derive(Subcommand)]
+enum TlsKeyStep {
+    /// Split the RSA or ECDSA private key in a PEM file, which may also hold the certificate chain
+    Split(TlsKeySplitArgs),
+    /// Rebuild the PEM key file a server can load
+    Combine(TlsKeyCombineArgs),
+}
+
+/// Arguments of `sss split`.
+#[derive(Args)]
+struct SplitArgs {
+    /// Number of shares needed to recover the secret
+    #[arg(short, long)]
+    threshold: Option<usize>,
+    /// Number of shares to create
+    #[arg(short = 'n', long)]
+    shares: Option<usize>,
+    /// File holding the secret, or `-` for stdin
+    #[arg(value_name = "INPUT", conflicts_with = "input")]
+    source: Option<PathBuf>,
+    /// File holding the secret, or `-` for stdin (the default)
+    #[arg(long = "in")]
+    input: Option<PathBuf>,
+    /// Directory to write `share-N.txt` files into; shares go to stdout if omitted
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+    /// Encrypt the secret into this file and split only its 32-byte key
+    #[arg(long)]
+    envelope: Option<PathBuf>,
+    /// INSECURE: derive all randomness from this seed, for reproducible test fixtures
+    #[arg(long, value_name = "SEED", conflicts_with = "envelope")]
+    insecure_seed: Option<String>,
+    /// Deal Feldman VSS shares over the group in this parameter document,
+    /// writing `commitments.json` next to the shares
+    #[arg(long, conflicts_with_all = ["envelope", "insecure_seed"])]
+    params: Option<PathBuf>,
+    /// How to write the shares; defaults to the config file, then `armor`
+    #[arg(long, value_enum)]
+    encoding: Option<ShareEncoding>,
+    /// Split every item listed in this TOML batch manifest
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "threshold", "shares", "source", "input", "out_dir", "envelope",
+            "insecure_seed", "params", "encoding",
+        ]
+    )]
+    manifest: Option<PathBuf>,
+    /// Sign the shares and audit manifest of a batch with this hex Ed25519 key file
+    #[arg(long, requires = "manifest")]
+    dealer_key: Option<PathBuf>,
+    /// File to write the batch audit manifest to; stdout if omitted or `-`
+    #[arg(long, requires = "manifest")]
+    audit: Option<PathBuf>,
+}
+
+/// Arguments of `sss combine`.
+#[derive(Args)]
+struct CombineArgs {
+    /// Share files produced by `split`; `-` reads concatenated shares from stdin
+    #[arg(required = true)]
+    shares: Vec<PathBuf>,
+    /// File to write the recovered secret to; stdout if omitted or `-`
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Decrypt this envelope with the key held by the shares
+    #[arg(long)]
+    envelope: Option<PathBuf>,
+}
+
+/// Arguments of `sss verify`.
+#[derive(Args)]
+struct VerifyArgs {
+    /// Commitment document, as JSON or ASCII armor
+    #[arg(long)]
+    commitments: PathBuf,
+    /// Require the document to be signed by this hex Ed25519 dealer key
+    #[arg(long)]
+    dealer: Option<String>,
+    /// Require the document to use the group in this parameter document
+    #[arg(long)]
+    params: Option<PathBuf>,
+    /// The share to check
+    share: PathBuf,
+}
+
+/// Arguments of `sss gen-secret`.
+#[derive(Args)]
+struct GenSecretArgs {
+    /// Size of the secret in bytes
+    #[arg(long, default_value_t = 32)]
+    bytes: usize,
+    /// Number of shares needed to recover the secret
+    #[arg(short, long)]
+    threshold: Option<usize>,
+    /// Number of shares to create
+    #[arg(short = 'n', long)]
+    shares: Option<usize>,
+    /// Directory to write `share-N.txt` files into; shares go to stdout if omitted
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+    /// Also print the secret in this format; needs an output directory
+    #[arg(long, value_enum)]
+    show: Option<SecretFormat>,
+    /// How to write the shares; defaults to the config file, then `armor`
+    #[arg(long, value_enum)]
+    encoding: Option<ShareEncoding>,
+}
+
+/// Arguments of `sss gen-params`.
+#[derive(Args)]
+struct GenParamsArgs {
+    /// Bit length of the prime modulus p [default: 3072]
+    #[arg(long)]
+    bits: Option<u64>,
+    /// Bit length of the subgroup order q [default: 256]
+    #[arg(long)]
+    q_bits: Option<u64>,
+    /// Use a well-known group instead of generating one (`modp-2048`);
+    /// defaults to the config file's preset unless --bits or --q-bits is given
+    #[arg(long, conflicts_with_all = ["bits", "q_bits"])]
+    preset: Option<String>,
+    /// File to write the parameter document to; stdout if omitted or `-`
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+/// Arguments of `sss test-vectors`.
+#[derive(Args)]
+struct TestVectorsArgs {
+    /// File to write the vectors to; stdout if omitted or `-`
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+/// Arguments of `sss refresh`.
+#[derive(Args)]
+struct RefreshArgs {
+    /// This holder's current share
+    #[arg(long)]
+    share: PathBuf,
+    /// Directory shared by all holders for this round
+    #[arg(long)]
+    round_dir: PathBuf,
+    /// File to write the refreshed share to; stdout if omitted or `-`
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+/// Arguments of `sss convert`.
+#[derive(Args)]
+struct ConvertArgs {
+    /// Share files of the current sharing; `-` reads concatenated shares from stdin
+    #[arg(required = true)]
+    shares: Vec<PathBuf>,
+    /// Number of new shares needed to recover the secret
+    #[arg(short, long)]
+    threshold: usize,
+    /// Number of new shares to create
+    #[arg(short = 'n', long = "shares")]
+    total: usize,
+    /// Deal Shamir shares over this prime field (`mersenne-521`, `secp256k1`)
+    #[arg(long, conflicts_with = "params")]
+    field: Option<String>,
+    /// Deal Feldman VSS shares over the group in this parameter document,
+    /// writing `commitments.json` next to the shares
+    #[arg(long, requires = "out_dir")]
+    params: Option<PathBuf>,
+    /// Directory to write `share-N.txt` files into; shares go to stdout if omitted
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+}
+
+/// Arguments of `sss inspect`.
+#[derive(Args)]
+struct InspectArgs {
+    /// Share file, ASCII-armored or bech32
+    share: PathBuf,
+}
+
+#[cfg(feature = "grpc")]
+/// Arguments of `sss grpc-serve`.
+#[derive(Args)]
+struct GrpcServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:50051")]
+    listen: String,
+    /// Where to keep sessions: `memory`, a directory or `sqlite:PATH`
+    #[arg(long, default_value = "memory")]
+    store: String,
+}
+
+#[cfg(feature = "http")]
+/// Arguments of `sss serve`.
+#[derive(Args)]
+struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: String,
+    /// File holding the bearer token clients must present
+    #[arg(long)]
+    token_file: PathBuf,
+    /// Where to keep sessions and commitments: `memory`, a directory or `sqlite:PATH`
+    #[arg(long, default_value = "memory")]
+    store: String,
+}
+
+#[cfg(feature = "keyring")]
+/// Arguments of `sss keep`.
+#[derive(Args)]
+struct KeepArgs {
+    /// Share file, ASCII-armored or bech32; `-` for stdin
+    share: PathBuf,
+    /// Name to store the share under
+    #[arg(long, default_value = "default")]
+    name: String,
+}
+
+#[cfg(feature = "keyring")]
+/// Arguments of `sss fetch`.
+#[derive(Args)]
+struct FetchArgs {
+    /// Name the share was stored under
+    #[arg(long, default_value = "default")]
+    name: String,
+    /// File to write the armored share to; stdout if omitted or `-`
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Delete the share from the keychain once written
+    #[arg(long)]
+    remove: bool,
+}
+
+/// Arguments of `sss reshare deal`.
+#[derive(Args)]
+struct ReshareDealArgs {
+    /// This holder's current share
+    #[arg(long)]
+    share: PathBuf,
+    /// Indices of all participating old holders, e.g. `1,3,4`
+    #[arg(long, value_delimiter = ',', required = true)]
+    dealers: Vec<u8>,
+    /// Threshold of the new sharing
+    #[arg(short, long)]
+    threshold: usize,
+    /// Number of new custodians
+    #[arg(short = 'n', long)]
+    shares: usize,
+    /// Directory shared by all participants for this round
+    #[arg(long)]
+    round_dir: PathBuf,
+}
+
+/// Arguments of `sss reshare collect`.
+#[derive(Args)]
+struct ReshareCollectArgs {
+    /// This custodian's new index, from 1 to the new share count
+    #[arg(long)]
+    index: u8,
+    /// Directory shared by all participants for this round
+    #[arg(long)]
+    round_dir: PathBuf,
+    /// File to write the new share to; stdout if omitted or `-`
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+/// Arguments of `sss tls-key split`.
+#[derive(Args)]
+struct TlsKeySplitArgs {
+    /// PEM file holding the key; `-` for stdin
+    key: PathBuf,
+    /// Number of shares needed to rebuild the key
+    #[arg(short, long)]
+    threshold: usize,
+    /// Number of shares to create
+    #[arg(short = 'n', long)]
+    shares: usize,
+    /// Directory to write `share-N.txt` files into; shares go to stdout if omitted
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+}
+
+/// Arguments of `sss tls-key combine`.
+#[derive(Args)]
+struct TlsKeyCombineArgs {
+    /// Share files produced by `tls-key split`; `-` reads concatenated shares from stdin
+    #[arg(required = true)]
+    shares: Vec<PathBuf>,
+    /// File to write the key to; stdout if omitted or `-`
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let json = cli.json;
+    match run(cli) {
+        Ok(mut report) => {
+            if json {
+                report["ok"] = json!(true);
+                println!("{}", report);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            let (code, status) = error_code(&err);
+            if json {
+                let error = json!({ "code": code, "message": err.to_string() });
+                println!(
+                    "{}",
+                    json!({ "ok": false, "exit_code": status, "error": error })
+                );
+            } else {
+                eprintln!("sss: {}", err);
+            }
+            ExitCode::from(status)
+        }
+    }
+}
+
+/// How a command reports its progress and results.
+struct Output {
+    quiet: bool,
+    json: bool,
+}
+
+impl Output {
+    /// Prints an informational line, unless quiet or reporting JSON.
+    fn note(&self, text: impl std::fmt::Display) {
+        if !self.quiet && !self.json {
+            eprintln!("{}", text);
+        }
+    }
+
+    /// Writes `bytes` to `path`, or to stdout without one. In JSON mode stdout
+    /// carries the report, so the bytes are embedded in it under `field`.
+    fn deliver(
+        &self,
+        path: Option<&Path>,
+        bytes: &[u8],
+        report: &mut Value,
+        field: &str,
+        embed: impl FnOnce(&[u8]) -> Value,
+    ) -> Result<()> {
+        match path {
+            Some(path) if path != Path::new("-") => {
+                fs::write(path, bytes)?;
+                report["out"] = json!(path.display().to_string());
+                Ok(())
+            }
+            _ if self.json => {
+                report[field] = embed(bytes);
+                Ok(())
+            }
+            _ => write_output(None, bytes),
+        }
+    }
+
+    /// Writes `share-N.txt` files into `out_dir`, or all shares to stdout
+    /// without one, and records them in the report.
+    fn emit_shares(
+        &self,
+        shares: &[SerializedShare],
+        out_dir: Option<&Path>,
+        encoding: ShareEncoding,
+        report: &mut Value,
+    ) -> Result<()> {
+        let encode = |share: &SerializedShare| match encoding {
+            ShareEncoding::Armor => share.to_armored(),
+            ShareEncoding::Bech32 => share.to_bech32() + "\n",
+        };
+        if let Some(first) = shares.first() {
+            report["threshold"] = json!(first.threshold);
+        }
+        report["total"] = json!(shares.len());
+        match out_dir {
+            Some(out_dir) => {
+                fs::create_dir_all(out_dir)?;
+                let mut files = Vec::new();
+                for share in shares {
+                    let path = out_dir.join(format!("share-{}.txt", share.x));
+                    create_new(&path, encode(share).as_bytes())?;
+                    files.push(path.display().to_string());
+                }
+                report["files"] = json!(files);
+                self.note(format!(
+                    "wrote {} shares to {}",
+                    shares.len(),
+                    out_dir.display()
+                ));
+                Ok(())
+            }
+            None if self.json => {
+                report["shares"] = shares.iter().map(|s| json!(encode(s))).collect();
+                Ok(())
+            }
+            None => {
+                let text: String = shares.iter().map(encode).collect();
+                write_output(None, text.as_bytes())
+            }
+        }
+    }
+}
+
+/// Splits every item of a batch manifest and writes the audit manifest.
+fn split_batch(
+    output: &Output,
+    manifest: &Path,
+    dealer_key: Option<&Path>,
+    audit: Option<&Path>,
+) -> Result<Value> {
+    let batch = BatchManifest::from_toml(&fs::read_to_string(manifest)?)?;
+    let base = manifest.parent().unwrap_or(Path::new("."));
+    let key = dealer_key.map(read_signing_key).transpose()?;
+
+    let mut items = Vec::new();
+    for item in &batch.items {
+        let secret = fs::read(base.join(&item.input))?;
+        let mut session = [0u8; 8];
+        rand::thread_rng().fill_bytes(&mut session);
+        let out_dir = base.join(&item.out_dir);
+        fs::create_dir_all(&out_dir)?;
+
+        let mut files = Vec::new();
+        for share in split_bytes(&secret, item.threshold, item.shares)? {
+            let mut share = share.with_session(hex(&session));
+            if let Some(label) = &item.label {
+                share = share.with_metadata("item", label.clone());
+            }
+            if let Some(key) = &key {
+                share.sign(key)?;
+            }
+            let path = out_dir.join(format!("share-{}.txt", share.x));
+            create_new(&path, share.to_armored().as_bytes())?;
+            files.push(AuditFile {
+                path: path.display().to_string(),
+                index: share.x.to_string(),
+                fingerprint: share.fingerprint(),
+            });
+        }
+        output.note(format!(
+            "wrote {} shares of {} to {}",
+            files.len(),
+            item.input.display(),
+            out_dir.display()
+        ));
+        items.push(AuditItem {
+            input: item.input.display().to_string(),
+            label: item.label.clone(),
+            session: hex(&session),
+            threshold: item.threshold,
+            total: item.shares,
+            files,
+        });
+    }
+
+    let mut record = AuditManifest::new(items);
+    if let Some(key) = &key {
+        record.sign(key);
+    }
+    let mut report = json!({
+        "command": "split",
+        "items": record.items.len(),
+        "signed": key.is_some(),
+    });
+    output.deliver(
+        audit,
+        record.to_json().as_bytes(),
+        &mut report,
+        "audit",
+        |bytes| serde_json::from_slice(bytes).unwrap_or_default(),
+    )?;
+    Ok(report)
+}
+
+/// Reads an Ed25519 signing key stored as a hex seed.
+fn read_signing_key(path: &Path) -> Result<SigningKey> {
+    let seed: [u8; 32] = unhex(fs::read_to_string(path)?.trim())?
+        .try_into()
+        .map_err(|_| Error::InvalidEncoding("dealer key must be a 32-byte hex seed"))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Writes a new file, never clobbering shares or documents from an earlier run.
+fn create_new(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    Ok(file.write_all(bytes)?)
+}
+
+/// Runs a command, returning its JSON report.
+fn run(cli: Cli) -> Result<Value> {
+    let output = Output {
+        quiet: cli.quiet,
+        json: cli.json,
+    };
+    let config = Config::load(cli.config.as_deref())?;
+    match cli.command {
+        Command::Split(args) => run_split(&output, config, args),
+        Command::GenSecret(args) => run_gen_secret(&output, config, args),
+        Command::Combine(args) => run_combine(&output, args),
+        Command::Verify(args) => run_verify(&output, args),
+        Command::GenParams(args) => run_gen_params(&output, config, args),
+        Command::TestVectors(args) => run_test_vectors(&output, args),
+        Command::Refresh(args) => run_refresh(&output, args),
+        Command::Reshare {
+            step: ReshareStep::Deal(args),
+        } => run_reshare_deal(&output, args),
+        Command::Reshare {
+            step: ReshareStep::Collect(args),
+        } => run_reshare_collect(&output, args),
+        Command::Convert(args) => run_convert(&output, args),
+        Command::TlsKey {
+            step: TlsKeyStep::Split(args),
+        } => run_tls_key_split(&output, args),
+        Command::TlsKey {
+            step: TlsKeyStep::Combine(args),
+        } => run_tls_key_combine(&output, args),
+        Command::Inspect(args) => run_inspect(&output, args),
+        #[cfg(feature = "tui")]
+        Command::Wizard => run_wizard(),
+        #[cfg(feature = "grpc")]
+        Command::GrpcServe(args) => run_grpc_serve(&output, args),
+        #[cfg(feature = "http")]
+        Command::Serve(args) => run_serve(&output, args),
+        #[cfg(feature = "keyring")]
+        Command::Keep(args) => run_keep(&output, args),
+        #[cfg(feature = "keyring")]
+        Command::Fetch(args) => run_fetch(&output, args),
+    }
+}
+
+/// Splits a secret file, or every item of a batch manifest.
+fn run_split(output: &Output, config: Config, args: SplitArgs) -> Result<Value> {
+    let SplitArgs {
+        threshold,
+        shares,
+        source,
+        input,
+        out_dir,
+        envelope,
+        insecure_seed,
+        params,
+        encoding,
+        manifest,
+        dealer_key,
+        audit,
+    } = args;
+    if let Some(manifest) = manifest {
+        return split_batch(output, &manifest, dealer_key.as_deref(), audit.as_deref());
+    }
+    let out_dir = out_dir.or(config.out_dir);
+    if params.is_some() && out_dir.is_none() {
+        return Err(Error::InvalidParameters("--params requires --out-dir"));
+    }
+    let threshold = threshold.or(config.threshold);
+    let (Some(threshold), Some(shares)) = (threshold, shares.or(config.shares)) else {
+        return Err(Error::InvalidParameters(
+            "--threshold and --shares are required",
+        ));
+    };
+    let secret = read_input(&source.or(input).unwrap_or_else(|| "-".into()))?;
+    let mut session = [0u8; 8];
+    let mut report = json!({ "command": "split" });
+    let warnings = advice::advise(&secret);
+    for warning in &warnings {
+        output.note(format!("sss: warning: {}", warning));
+    }
+    report["warnings"] = warnings
+        .iter()
+        .map(|warning| json!({ "code": warning.code(), "message": warning.to_string() }))
+        .collect();
+    let mut commitments = None;
+    let shares = match (envelope, insecure_seed, params) {
+        (_, _, Some(path)) => {
+            let group = GroupParameters::from_json(&fs::read_to_string(path)?)?;
+            let mut dealer = group.vss(threshold, shares)?;
+            let (shares, published) = dealer
+                .split_secret(&BigUint::from_bytes_be(&secret))
+                .map_err(|_| {
+                    Error::InvalidParameters("secret must be smaller than the group order q")
+                })?;
+            commitments = Some(dealer.commitment_document(&published));
+            rand::thread_rng().fill_bytes(&mut session);
+            shares
+                .iter()
+                .map(|share| {
+                    dealer
+                        .serialize_share(share)
+                        .with_secret_length(secret.len())
+                })
+                .collect::<Result<_>>()?
+        }
+        (_, Some(seed), None) => {
+            output.note(
+                "sss: warning: --insecure-seed makes every share predictable; \
+                 never use it for real secrets",
+            );
+            let mut rng = insecure_seeded_rng(seed.as_bytes());
+            rng.fill_bytes(&mut session);
+            split_bytes_with_rng(&secret, threshold, shares, &mut rng)?
+        }
+        (Some(path), None, None) => {
+            let (sealed, shares) = Envelope::seal(&secret, threshold, shares)?;
+            create_new(&path, sealed.to_armored().as_bytes())?;
+            report["envelope"] = json!(path.display().to_string());
+            rand::thread_rng().fill_bytes(&mut session);
+            shares
+        }
+        (None, None, None) => {
+            rand::thread_rng().fill_bytes(&mut session);
+            split_bytes(&secret, threshold, shares)?
+        }
+    };
+    let shares: Vec<SerializedShare> = shares
+        .into_iter()
+        .map(|share| share.with_session(hex(&session)))
+        .collect();
+    report["session"] = json!(hex(&session));
+    let encoding = encoding.or(config.encoding).unwrap_or_default();
+    output.emit_shares(&shares, out_dir.as_deref(), encoding, &mut report)?;
+    if let (Some(document), Some(out_dir)) = (&commitments, &out_dir) {
+        let path = out_dir.join("commitments.json");
+        create_new(&path, document.to_json()?.as_bytes())?;
+        report["commitments"] = json!(path.display().to_string());
+    }
+    Ok(report)
+}
+
+/// Generates a random secret and splits it.
+fn run_gen_secret(output: &Output, config: Config, args: GenSecretArgs) -> Result<Value> {
+    let GenSecretArgs {
+        bytes,
+        threshold,
+        shares,
+        out_dir,
+        show,
+        encoding,
+    } = args;
+    let out_dir = out_dir.or(config.out_dir);
+    if show.is_some() && out_dir.is_none() {
+        return Err(Error::InvalidParameters("--show requires --out-dir"));
+    }
+    let threshold = threshold.or(config.threshold);
+    let (Some(threshold), Some(shares)) = (threshold, shares.or(config.shares)) else {
+        return Err(Error::InvalidParameters(
+            "--threshold and --shares are required",
+        ));
+    };
+    let mut secret = vec![0u8; bytes];
+    rand::rngs::OsRng.fill_bytes(&mut secret);
+    let shown = match show {
+        Some(SecretFormat::Hex) => Some(hex(&secret)),
+        Some(SecretFormat::Base64) => Some(BASE64.encode(&secret)),
+        Some(SecretFormat::Bip39) => Some(mnemonic::entropy_to_phrase(&secret)?.join(" ")),
+        None => None,
+    };
+    let mut session = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut session);
+    let shares: Vec<SerializedShare> = split_bytes(&secret, threshold, shares)?
+        .into_iter()
+        .map(|share| share.with_session(hex(&session)))
+        .collect();
+
+    let mut report = json!({ "command": "gen-secret", "bytes": bytes, "session": hex(&session) });
+    let encoding = encoding.or(config.encoding).unwrap_or_default();
+    output.emit_shares(&shares, out_dir.as_deref(), encoding, &mut report)?;
+    if let Some(shown) = shown {
+        if output.json {
+            report["secret"] = json!(shown);
+        } else {
+            println!("{}", shown);
+        }
+    }
+    Ok(report)
+}
+
+/// Recombines shares into the secret, opening an envelope if given.
+fn run_combine(output: &Output, args: CombineArgs) -> Result<Value> {
+    let CombineArgs {
+        shares,
+        out,
+        envelope,
+    } = args;
+    let mut parsed = Vec::new();
+    for path in &shares {
+        parsed.extend(read_shares(path)?);
+    }
+    let secret = match envelope {
+        Some(path) => Envelope::from_armored(&fs::read_to_string(path)?)?.open(&parsed)?,
+        None => reconstruct_auto(&parsed)?,
+    };
+    let mut report = json!({ "command": "combine", "shares": parsed.len() });
+    output.deliver(out.as_deref(), &secret, &mut report, "secret_base64", |b| {
+        json!(BASE64.encode(b))
+    })?;
+    Ok(report)
+}
+
+/// Checks a Feldman VSS share against published commitments.
+fn run_verify(output: &Output, args: VerifyArgs) -> Result<Value> {
+    let VerifyArgs {
+        commitments,
+        dealer,
+        params,
+        share,
+    } = args;
+    let text = fs::read_to_string(commitments)?;
+    let document = if text.trim_start().starts_with('{') {
+        CommitmentDocument::from_json(&text)?
+    } else {
+        CommitmentDocument::from_armored(&text)?
+    };
+    if let Some(path) = params {
+        let group = GroupParameters::from_json(&fs::read_to_string(path)?)?;
+        if (&group.p, &group.q, &group.g) != (&document.p, &document.q, &document.g) {
+            return Err(Error::InconsistentShares(
+                "commitments use a different group than the parameters",
+            ));
+        }
+    }
+    if let Some(dealer) = &dealer {
+        let key: [u8; 32] = unhex(dealer.trim())?
+            .try_into()
+            .map_err(|_| Error::InvalidEncoding("dealer key must be 32 bytes"))?;
+        let key = VerifyingKey::from_bytes(&key)
+            .map_err(|_| Error::InvalidEncoding("invalid Ed25519 dealer key"))?;
+        document.verify_signature(&key)?;
+    }
+
+    let share = parse_vss_share(&fs::read_to_string(share)?, &document)?;
+    if !document.verify_share(&share) {
+        return Err(Error::InconsistentShares(
+            "share does not match the published commitments",
+        ));
+    }
+    if !output.quiet && !output.json {
+        println!(
+            "share {} is consistent with the published commitments",
+            share.id
+        );
+    }
+    Ok(json!({
+        "command": "verify",
+        "share": share.id.to_string(),
+        "verified": true,
+        "dealer_checked": dealer.is_some(),
+    }))
+}
+
+/// Generates or looks up Feldman VSS group parameters.
+fn run_gen_params(output: &Output, config: Config, args: GenParamsArgs) -> Result<Value> {
+    let GenParamsArgs {
+        bits,
+        q_bits,
+        preset,
+        out,
+    } = args;
+    let preset = match (bits, q_bits) {
+        (None, None) => preset.or(config.preset),
+        _ => preset,
+    };
+    let (bits, q_bits) = (bits.unwrap_or(3072), q_bits.unwrap_or(256));
+    let group = match &preset {
+        Some(name) => GroupParameters::preset(name).inspect_err(|_| {
+            output.note(format!("known presets: {}", PRESETS.join(", ")));
+        })?,
+        None => {
+            output.note(format!(
+                "generating a {}-bit group with a {}-bit subgroup...",
+                bits, q_bits
+            ));
+            GroupParameters::generate(bits, q_bits)?
+        }
+    };
+    group.validate()?;
+    let mut report = json!({
+        "command": "gen-params",
+        "bits": group.p.bits(),
+        "q_bits": group.q.bits(),
+        "preset": preset,
+    });
+    output.deliver(
+        out.as_deref(),
+        group.to_json()?.as_bytes(),
+        &mut report,
+        "parameters",
+        |bytes| serde_json::from_slice(bytes).unwrap_or_default(),
+    )?;
+    Ok(report)
+}
+
+/// Writes the known-answer test vectors.
+fn run_test_vectors(output: &Output, args: TestVectorsArgs) -> Result<Value> {
+    let TestVectorsArgs { out } = args;
+    let vectors = test_vectors::generate()?;
+    let mut report = json!({
+        "command": "test-vectors",
+        "vectors": vectors.len(),
+    });
+    output.deliver(
+        out.as_deref(),
+        test_vectors::to_json(&vectors).as_bytes(),
+        &mut report,
+        "vectors",
+        |bytes| serde_json::from_slice(bytes).unwrap_or_default(),
+    )?;
+    Ok(report)
+}
+
+/// Posts this holder's refresh contributions, then collects the new share once all have arrived.
+fn run_refresh(output: &Output, args: RefreshArgs) -> Result<Value> {
+    let RefreshArgs {
+        share,
+        round_dir,
+        out,
+    } = args;
+    let share = SerializedShare::from_armored(&fs::read_to_string(share)?)?;
+    fs::create_dir_all(&round_dir)?;
+    let message_path = |from: &dyn std::fmt::Display, to: &dyn std::fmt::Display| {
+        round_dir.join(format!("from-{}-to-{}.txt", from, to))
+    };
+    if !message_path(&share.x, &share.x).exists() {
+        for message in refresh::contributions(&share)? {
+            create_new(
+                &message_path(&message.from, &message.to),
+                message.to_armored().as_bytes(),
+            )?;
+        }
+    }
+
+    let mut inbox = Vec::new();
+    for from in 1..=share.total {
+        let path = message_path(&from, &share.x);
+        if path.exists() {
+            inbox.push(RefreshMessage::from_armored(&fs::read_to_string(path)?)?);
+        }
+    }
+    let mut report = json!({
+        "command": "refresh",
+        "received": inbox.len(),
+        "expected": share.total,
+        "complete": inbox.len() == share.total,
+    });
+    if inbox.len() < share.total {
+        output.note(format!(
+            "{} of {} contributions received; run again once every holder has",
+            inbox.len(),
+            share.total
+        ));
+        return Ok(report);
+    }
+    let refreshed = refresh::apply(&share, &inbox)?;
+    output.deliver(
+        out.as_deref(),
+        refreshed.to_armored().as_bytes(),
+        &mut report,
+        "share",
+        armored,
+    )?;
+    Ok(report)
+}
+
+/// Deals an old holder's reshare contributions into the round directory.
+fn run_reshare_deal(output: &Output, args: ReshareDealArgs) -> Result<Value> {
+    let ReshareDealArgs {
+        share,
+        dealers,
+        threshold,
+        shares,
+        round_dir,
+    } = args;
+    let share = SerializedShare::from_armored(&fs::read_to_string(share)?)?;
+    let messages = reshare::contributions(&share, &dealers, threshold, shares)?;
+    fs::create_dir_all(&round_dir)?;
+    for message in &messages {
+        let path = round_dir.join(format!("from-{}-to-{}.txt", message.from, message.to));
+        create_new(&path, message.to_armored().as_bytes())?;
+    }
+    output.note(format!(
+        "dealt {} contributions into {}",
+        messages.len(),
+        round_dir.display()
+    ));
+    Ok(json!({
+        "command": "reshare deal",
+        "messages": messages.len(),
+        "round_dir": round_dir.display().to_string(),
+    }))
+}
+
+/// Combines the reshare contributions addressed to a new custodian.
+fn run_reshare_collect(output: &Output, args: ReshareCollectArgs) -> Result<Value> {
+    let ReshareCollectArgs {
+        index,
+        round_dir,
+        out,
+    } = args;
+    let suffix = format!("-to-{}.txt", index);
+    let mut inbox = Vec::new();
+    for entry in fs::read_dir(&round_dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if name.starts_with("from-") && name.ends_with(&suffix) {
+            inbox.push(ReshareMessage::from_armored(&fs::read_to_string(&path)?)?);
+        }
+    }
+    let share = reshare::combine(&inbox)?;
+    let mut report = json!({ "command": "reshare collect", "index": index });
+    output.deliver(
+        out.as_deref(),
+        share.to_armored().as_bytes(),
+        &mut report,
+        "share",
+        armored,
+    )?;
+    Ok(report)
+}
+
+/// Recovers a secret and deals it again over another field or scheme.
+fn run_convert(output: &Output, args: ConvertArgs) -> Result<Value> {
+    let ConvertArgs {
+        shares,
+        threshold,
+        total,
+        field,
+        params,
+        out_dir,
+    } = args;
+    let mut parsed = Vec::new();
+    for path in &shares {
+        parsed.extend(read_shares(path)?);
+    }
+    let target = match (field, params) {
+        (Some(name), _) => Scheme::Shamir {
+            prime: named_prime(&name)?,
+        },
+        (None, Some(path)) => {
+            let group = GroupParameters::from_json(&fs::read_to_string(path)?)?;
+            Scheme::Feldman {
+                p: group.p,
+                q: group.q,
+                g: group.g,
+            }
+        }
+        (None, None) => Scheme::Gf256 {
+            poly: Gf256::RIJNDAEL.poly(),
+        },
+    };
+    let dealing = service::convert(&parsed, &target, threshold, total)?;
+    let mut report = json!({
+        "command": "convert",
+        "scheme": target.to_string(),
+        "session": dealing.session,
+    });
+    output.emit_shares(
+        &dealing.shares,
+        out_dir.as_deref(),
+        ShareEncoding::Armor,
+        &mut report,
+    )?;
+    if let (Some(document), Some(out_dir)) = (&dealing.commitments, &out_dir) {
+        let path = out_dir.join("commitments.json");
+        create_new(&path, document.to_json()?.as_bytes())?;
+        report["commitments"] = json!(path.display().to_string());
+    }
+    Ok(report)
+}
+
+/// Splits the private key of a PEM file.
+fn run_tls_key_split(output: &Output, args: TlsKeySplitArgs) -> Result<Value> {
+    let TlsKeySplitArgs {
+        key,
+        threshold,
+        shares,
+        out_dir,
+    } = args;
+    let text = String::from_utf8(read_input(&key)?)
+        .map_err(|_| Error::InvalidEncoding("key files must be PEM text"))?;
+    let mut session = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut session);
+    let shares: Vec<SerializedShare> = keys::split_tls_key(&text, threshold, shares)?
+        .into_iter()
+        .map(|share| share.with_session(hex(&session)))
+        .collect();
+    let mut report = json!({ "command": "tls-key split", "session": hex(&session) });
+    if let Some(first) = shares.first() {
+        report["algorithm"] = json!(first.metadata.get(keys::META_ALGORITHM));
+        report["certificate"] = json!(first.metadata.get(keys::META_CERTIFICATE));
+    }
+    output.emit_shares(
+        &shares,
+        out_dir.as_deref(),
+        ShareEncoding::Armor,
+        &mut report,
+    )?;
+    Ok(report)
+}
+
+/// Rebuilds a PEM private key from its shares.
+fn run_tls_key_combine(output: &Output, args: TlsKeyCombineArgs) -> Result<Value> {
+    let TlsKeyCombineArgs { shares, out } = args;
+    let mut parsed = Vec::new();
+    for path in &shares {
+        parsed.extend(read_shares(path)?);
+    }
+    let pem = keys::reconstruct_tls_key(&parsed)?;
+    let mut report = json!({ "command": "tls-key combine", "shares": parsed.len() });
+    output.deliver(out.as_deref(), pem.as_bytes(), &mut report, "key", armored)?;
+    Ok(report)
+}
+
+/// Describes a share without revealing its payload.
+fn run_inspect(output: &Output, args: InspectArgs) -> Result<Value> {
+    let InspectArgs { share } = args;
+    let text = fs::read_to_string(share)?;
+    let (share, checksum) = match SerializedShare::from_armored(&text) {
+        Ok(share) => (share, "none (armored)"),
+        Err(_) => (SerializedShare::from_bech32(&text)?, "valid (bech32m)"),
+    };
+    if !output.json {
+        print!("{}", describe(&share, checksum));
+    }
+    Ok(json!({
+        "command": "inspect",
+        "scheme": share.scheme.to_string(),
+        "version": FORMAT_VERSION,
+        "threshold": share.threshold,
+        "total": share.total,
+        "index": share.x.to_string(),
+        "label": share.label,
+        "metadata": share.metadata,
+        "dealer": share.signature.as_ref().map(|s| hex(&s.dealer)),
+        "checksum": checksum,
+        "fingerprint": share.fingerprint(),
+    }))
+}
+
+/// Runs the interactive ceremony wizard.
+#[cfg(feature = "tui")]
+fn run_wizard() -> Result<Value> {
+    wizard::run()?;
+    Ok(json!({ "command": "wizard" }))
+}
+
+/// Serves the gRPC API until interrupted.
+#[cfg(feature = "grpc")]
+fn run_grpc_serve(output: &Output, args: GrpcServeArgs) -> Result<Value> {
+    let GrpcServeArgs { listen, store } = args;
+    let store = cryptographic_algorithms::store::open(&store)?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(&listen).await?;
+        output.note(format!("serving gRPC on {}", listener.local_addr()?));
+        cryptographic_algorithms::grpc::serve(listener, store).await
+    })?;
+    Ok(json!({ "command": "grpc-serve" }))
+}
+
+/// Serves the HTTP ceremony API until interrupted.
+#[cfg(feature = "http")]
+fn run_serve(output: &Output, args: ServeArgs) -> Result<Value> {
+    let ServeArgs {
+        listen,
+        token_file,
+        store,
+    } = args;
+    let token = fs::read_to_string(token_file)?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(Error::InvalidParameters("the token file is empty"));
+    }
+    let store = cryptographic_algorithms::store::open(&store)?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(&listen).await?;
+        output.note(format!("serving HTTP on {}", listener.local_addr()?));
+        cryptographic_algorithms::http::serve(listener, token, store).await
+    })?;
+    Ok(json!({ "command": "serve" }))
 }
 
-fn demo_shamir_secret_sharing() {
-    println!("\n=== Demonstrating Shamir's Secret Sharing ===");
+/// Stores a share in the platform keychain.
+#[cfg(feature = "keyring")]
+fn run_keep(output: &Output, args: KeepArgs) -> Result<Value> {
+    let KeepArgs { share, name } = args;
+    let [share]: [SerializedShare; 1] = read_shares(&share)?
+        .try_into()
+        .map_err(|_| Error::InvalidParameters("keep stores exactly one share"))?;
+    keychain::keep(&keychain::entry(&name)?, &share)?;
+    output.note(format!("stored share {} as {:?}", share.x, name));
+    Ok(json!({
+        "command": "keep",
+        "name": name,
+        "fingerprint": share.fingerprint(),
+    }))
+}
 
-    // Create a secret
-    let secret = 22773311u64.to_biguint().unwrap();
-    println!("Original Secret: {}", secret);
+/// Writes a share stored in the keychain, removing it if asked.
+#[cfg(feature = "keyring")]
+fn run_fetch(output: &Output, args: FetchArgs) -> Result<Value> {
+    let FetchArgs { name, out, remove } = args;
+    let entry = keychain::entry(&name)?;
+    let share = keychain::fetch(&entry)?;
+    let mut report = json!({
+        "command": "fetch",
+        "name": name,
+        "fingerprint": share.fingerprint(),
+    });
+    output.deliver(
+        out.as_deref(),
+        share.to_armored().as_bytes(),
+        &mut report,
+        "share",
+        armored,
+    )?;
+    if remove {
+        keychain::remove(&entry)?;
+        output.note(format!("removed {:?} from the keychain", name));
+    }
+    report["removed"] = json!(remove);
+    Ok(report)
+}
 
-    // Initialize SSS
-    let sharer = SecretSharer::new(3, 5);
+/// The report value for text output embedded in a JSON report.
+fn armored(bytes: &[u8]) -> Value {
+    json!(String::from_utf8_lossy(bytes))
+}
 
-    // Generate shares
-    let shares = sharer.split_secret(&secret);
-    println!("\nGenerated {} shares:", shares.len());
-    for (i, share) in shares.iter().enumerate() {
-        println!("Share {}: x = {}, y = {}", i + 1, share.x, share.y);
+/// The stable error code and process exit status for an error.
+///
+/// Exit statuses: 0 success, 2 invalid command line, 3 unreadable or corrupt
+/// input, 4 invalid parameters, 5 shares that do not fit together or do not
+/// match their commitments, 6 failed signature, digest or decryption checks,
+/// 7 I/O, keychain, HSM or KMS failure. Cancellation only happens in
+/// library integrations and falls back to the generic status 1.
+fn error_code(err: &Error) -> (&'static str, u8) {
+    match err {
+        Error::InvalidArmor(_)
+        | Error::MalformedShare
+        | Error::InvalidEncoding(_)
+        | Error::InvalidMnemonic(_)
+        | Error::UnknownWord { .. }
+        | Error::UnsupportedVersion(_) => ("invalid_input", 3),
+        #[cfg(feature = "qr")]
+        Error::Qr(_) => ("invalid_input", 3),
+        Error::ChecksumMismatch | Error::TranscriptionErrors(_) => ("checksum_mismatch", 3),
+        Error::InvalidParameters(_) => ("invalid_parameters", 4),
+        Error::InconsistentShares(_) => ("inconsistent_shares", 5),
+        Error::InsufficientShares => ("insufficient_shares", 5),
+        Error::ShareExpired => ("share_expired", 5),
+        Error::ShareRevoked => ("share_revoked", 5),
+        Error::Cancelled => ("cancelled", 1),
+        Error::DigestMismatch => ("digest_mismatch", 6),
+        Error::InvalidSignature => ("invalid_signature", 6),
+        Error::DecryptionFailed => ("decryption_failed", 6),
+        Error::CheckpointTampered => ("checkpoint_tampered", 6),
+        Error::Io(_) => ("io", 7),
+        #[cfg(feature = "keyring")]
+        Error::Keyring(_) => ("keychain", 7),
+        #[cfg(feature = "pkcs11")]
+        Error::Pkcs11(_) => ("pkcs11", 7),
+        #[cfg(feature = "fido2")]
+        Error::Fido2(_) => ("fido2", 7),
+        #[cfg(any(feature = "aws-kms", feature = "gcp-kms"))]
+        Error::Kms(_) => ("kms", 7),
     }
+}
 
-    // Reconstruct with minimum shares
-    match sharer.reconstruct_secret(&shares[0..3]) {
-        Some(reconstructed) => {
-            println!("\nReconstructed secret: {}", reconstructed);
-            assert_eq!(reconstructed, secret, "Reconstruction failed!");
+/// Reads a whole file, or stdin for `-`, without any text conversion.
+fn read_input(path: &Path) -> Result<Vec<u8>> {
+    if path == Path::new("-") {
+        let mut bytes = Vec::new();
+        io::stdin().lock().read_to_end(&mut bytes)?;
+        Ok(bytes)
+    } else {
+        Ok(fs::read(path)?)
+    }
+}
+
+/// Writes to the file, or to stdout when no path or `-` is given.
+fn write_output(path: Option<&Path>, bytes: &[u8]) -> Result<()> {
+    match path {
+        Some(path) if path != Path::new("-") => Ok(fs::write(path, bytes)?),
+        _ => {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(bytes)?;
+            Ok(stdout.flush()?)
         }
-        None => println!("Failed to reconstruct secret"),
     }
 }
 
-fn demo_verifiable_secret_sharing() {
-    println!("\n=== Demonstrating Verifiable Secret Sharing ===");
+/// Reads the shares in a text file or, with the `qr` feature, a PNG or JPEG
+/// photo of share QR codes.
+fn read_shares(path: &Path) -> Result<Vec<SerializedShare>> {
+    let bytes = read_input(path)?;
+    #[cfg(feature = "qr")]
+    if image::guess_format(&bytes).is_ok() {
+        let mut shares = Vec::new();
+        for text in cryptographic_algorithms::qr::decode(&bytes)? {
+            shares.extend(parse_share_text(&text)?);
+        }
+        return Ok(shares);
+    }
+    let text =
+        String::from_utf8(bytes).map_err(|_| Error::InvalidEncoding("share files must be text"))?;
+    parse_share_text(&text)
+}
+
+/// Accepts concatenated armored shares or bech32 shares, one per line.
+fn parse_share_text(text: &str) -> Result<Vec<SerializedShare>> {
+    if text.contains("-----BEGIN") {
+        parse_armored_shares(text)
+    } else {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(SerializedShare::from_bech32)
+            .collect()
+    }
+}
+
+/// Parses one or more concatenated armored shares.
+fn parse_armored_shares(text: &str) -> Result<Vec<SerializedShare>> {
+    const END: &str = "-----END SECRET SHARE-----";
+    let blocks: Vec<&str> = text
+        .split_inclusive(END)
+        .filter(|block| !block.trim().is_empty())
+        .collect();
+    if blocks.is_empty() {
+        return Err(Error::InvalidArmor("no shares found"));
+    }
+    blocks
+        .into_iter()
+        .map(SerializedShare::from_armored)
+        .collect()
+}
+
+/// Summarises a share's header fields; the payload itself is never printed.
+fn describe(share: &SerializedShare, checksum: &str) -> String {
+    let mut out = String::new();
+    let mut field = |name: &str, value: &dyn std::fmt::Display| {
+        out.push_str(&format!("{:<13}{}\n", format!("{}:", name), value));
+    };
+    field("scheme", &share.scheme);
+    field("version", &FORMAT_VERSION);
+    field(
+        "threshold",
+        &format!("{} of {}", share.threshold, share.total),
+    );
+    field("index", &share.x);
+    field("label", &share.label.as_deref().unwrap_or("-"));
+    field("session", &share.session().unwrap_or("-"));
+    for (key, value) in share.metadata.iter().filter(|(k, _)| *k != "session") {
+        field(key, value);
+    }
+    let signature = match &share.signature {
+        Some(signature) => format!("present (dealer {})", hex(&signature.dealer)),
+        None => "absent".to_string(),
+    };
+    field("signature", &signature);
+    field("checksum", &checksum);
+    field("fingerprint", &share.fingerprint());
+    out
+}
+
+/// Accepts a VSS share as a serialized share, armor, bech32 or canonical line.
+fn parse_vss_share(text: &str, document: &CommitmentDocument) -> Result<vss::Share> {
+    if let Ok(share) = SerializedShare::from_armored(text) {
+        let Scheme::Feldman { p, q, g } = &share.scheme else {
+            return Err(Error::InvalidParameters("not a Feldman VSS share"));
+        };
+        if (p, q, g) != (&document.p, &document.q, &document.g) {
+            return Err(Error::InconsistentShares(
+                "share and commitments use different groups",
+            ));
+        }
+        return Ok(vss::Share {
+            id: share.x,
+            value: BigUint::from_bytes_be(&share.value),
+        });
+    }
+    vss::Share::from_armored(text)
+        .or_else(|_| vss::Share::from_bech32(text))
+        .or_else(|_| text.parse())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_combine_files() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let secret = dir.join("secret.bin");
+        fs::write(&secret, [0u8, 1, 2, 255, 254]).unwrap();
+
+        let shares_dir = dir.join("shares");
+        let split = Cli::parse_from([
+            "sss",
+            "split",
+            "--threshold",
+            "2",
+            "--shares",
+            "3",
+            "--in",
+            secret.to_str().unwrap(),
+            "--out-dir",
+            shares_dir.to_str().unwrap(),
+        ]);
+        run(split).unwrap();
+
+        let recovered = dir.join("recovered.bin");
+        let combine = Cli::parse_from([
+            "sss",
+            "combine",
+            shares_dir.join("share-1.txt").to_str().unwrap(),
+            shares_dir.join("share-3.txt").to_str().unwrap(),
+            "--out",
+            recovered.to_str().unwrap(),
+        ]);
+        run(combine).unwrap();
+        assert_eq!(fs::read(&recovered).unwrap(), fs::read(&secret).unwrap());
 
-    // System parameters (using SECP256k1 parameters for real-world example)
-    let p = "115792089237316195423570985008687907853269984665640564039457584007908834671663"
-        .parse::<num_bigint::BigUint>()
+        // A second split into the same directory refuses to overwrite shares
+        let again = Cli::parse_from([
+            "sss",
+            "split",
+            "-t",
+            "2",
+            "-n",
+            "3",
+            "--in",
+            secret.to_str().unwrap(),
+            "--out-dir",
+            shares_dir.to_str().unwrap(),
+        ]);
+        assert!(run(again).is_err());
+
+        // Envelope mode splits only the key and keeps the data in one file
+        let envelope = dir.join("secret.env");
+        let key_dir = dir.join("keys");
+        run(Cli::parse_from([
+            "sss",
+            "split",
+            "-t",
+            "2",
+            "-n",
+            "3",
+            secret.to_str().unwrap(),
+            "--out-dir",
+            key_dir.to_str().unwrap(),
+            "--envelope",
+            envelope.to_str().unwrap(),
+        ]))
         .unwrap();
-    let q = "115792089237316195423570985008687907852837564279074904382605163141518161494337"
-        .parse::<num_bigint::BigUint>()
+        run(Cli::parse_from([
+            "sss",
+            "combine",
+            key_dir.join("share-2.txt").to_str().unwrap(),
+            key_dir.join("share-3.txt").to_str().unwrap(),
+            "--envelope",
+            envelope.to_str().unwrap(),
+            "--out",
+            recovered.to_str().unwrap(),
+        ]))
         .unwrap();
-    let g = 2u32.to_biguint().unwrap();
+        assert_eq!(fs::read(&recovered).unwrap(), fs::read(&secret).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
 
-    // VSS parameters
-    let threshold = 3;
-    let total_shares = 5;
+    #[test]
+    fn test_verify_vss_share() {
+        use vss::FeldmanVSS;
 
-    // Initialize VSS
-    let mut vss = FeldmanVSS::new(p, q, g, threshold, total_shares);
+        let dir = std::env::temp_dir().join(format!("sss-cli-verify-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut dealing = FeldmanVSS::new(23u32.into(), 11u32.into(), 2u32.into(), 2, 3);
+        let (shares, commitments) = dealing.split_secret(&6u32.into()).unwrap();
+        let dealer = SigningKey::from_bytes(&[9; 32]);
+        let mut document = dealing.commitment_document(&commitments);
+        document.sign(&dealer);
 
-    // Create and share a secret
-    let secret = 123456789u64.to_biguint().unwrap();
-    println!("Original Secret: {}", secret);
+        let doc_path = dir.join("commitments.json");
+        let share_path = dir.join("share.txt");
+        fs::write(&doc_path, document.to_json().unwrap()).unwrap();
+        fs::write(&share_path, shares[1].to_string()).unwrap();
+        let dealer_hex = hex(&dealer.verifying_key().to_bytes());
 
-    // Generate shares and commitments
-    let (shares, commitments) = vss.split_secret(&secret).unwrap();
+        let verify = |share: &vss::Share| {
+            fs::write(&share_path, share.to_string()).unwrap();
+            let cli = Cli::parse_from([
+                "sss",
+                "verify",
+                "--commitments",
+                doc_path.to_str().unwrap(),
+                "--dealer",
+                &dealer_hex,
+                share_path.to_str().unwrap(),
+            ]);
+            run(cli)
+        };
+        verify(&shares[1]).unwrap();
 
-    // Display shares
-    println!("\nGenerated shares:");
-    for (i, share) in shares.iter().enumerate() {
-        println!(
-            "Share {}: ID = {}, Value = {}",
-            i + 1,
-            share.id,
-            share.value
+        let mut forged = shares[1].clone();
+        forged.value = (forged.value + 1u32) % 11u32;
+        assert!(verify(&forged).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_inspect_hides_payload() {
+        let share = split_bytes(b"very secret", 2, 3).unwrap()[0]
+            .clone()
+            .with_label("HSM-2")
+            .with_session("00ff");
+        let summary = describe(&share, "valid (bech32m)");
+        assert!(summary.contains("threshold:   2 of 3"));
+        assert!(summary.contains("label:       HSM-2"));
+        assert!(summary.contains("session:     00ff"));
+        assert!(!summary.contains(&hex(&share.value)));
+    }
+
+    #[test]
+    fn test_parse_concatenated_shares() {
+        let shares = split_bytes(b"piped", 2, 3).unwrap();
+        let text: String = shares.iter().map(|share| share.to_armored()).collect();
+        let parsed = parse_armored_shares(&text).unwrap();
+        assert_eq!(parsed, shares);
+        assert_eq!(reconstruct_auto(&parsed[1..]).unwrap(), b"piped");
+        assert!(parse_armored_shares("\n").is_err());
+    }
+
+    #[test]
+    fn test_refresh_round() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-refresh-{}", std::process::id()));
+        let round = dir.join("round1");
+        fs::create_dir_all(&dir).unwrap();
+        let shares = split_bytes(b"rotated", 2, 3).unwrap();
+        for share in &shares {
+            fs::write(dir.join(format!("old-{}.txt", share.x)), share.to_armored()).unwrap();
+        }
+        let refresh = |x: u32| {
+            run(Cli::parse_from([
+                "sss",
+                "refresh",
+                "--share",
+                dir.join(format!("old-{}.txt", x)).to_str().unwrap(),
+                "--round-dir",
+                round.to_str().unwrap(),
+                "--out",
+                dir.join(format!("new-{}.txt", x)).to_str().unwrap(),
+            ]))
+            .unwrap();
+        };
+
+        // The first holder has to wait for the others' contributions
+        refresh(1);
+        assert!(!dir.join("new-1.txt").exists());
+        for x in [2, 3, 1] {
+            refresh(x);
+        }
+        let new: Vec<_> = [1, 3]
+            .iter()
+            .map(|x| {
+                SerializedShare::from_armored(
+                    &fs::read_to_string(dir.join(format!("new-{}.txt", x))).unwrap(),
+                )
+                .unwrap()
+            })
+            .collect();
+        assert_ne!(new[0].value, shares[0].value);
+        assert_eq!(reconstruct_auto(&new).unwrap(), b"rotated");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reshare_round() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-reshare-{}", std::process::id()));
+        let round = dir.join("round");
+        fs::create_dir_all(&dir).unwrap();
+        let old = split_bytes(b"five to seven", 2, 5).unwrap();
+        for x in [2, 5] {
+            let path = dir.join(format!("old-{}.txt", x));
+            fs::write(&path, old[x - 1].to_armored()).unwrap();
+            run(Cli::parse_from([
+                "sss",
+                "reshare",
+                "deal",
+                "--share",
+                path.to_str().unwrap(),
+                "--dealers",
+                "2,5",
+                "-t",
+                "3",
+                "-n",
+                "7",
+                "--round-dir",
+                round.to_str().unwrap(),
+            ]))
+            .unwrap();
+        }
+
+        let new: Vec<SerializedShare> = [1, 4, 7]
+            .iter()
+            .map(|x| {
+                let out = dir.join(format!("new-{}.txt", x));
+                run(Cli::parse_from([
+                    "sss",
+                    "reshare",
+                    "collect",
+                    "--index",
+                    &x.to_string(),
+                    "--round-dir",
+                    round.to_str().unwrap(),
+                    "--out",
+                    out.to_str().unwrap(),
+                ]))
+                .unwrap();
+                SerializedShare::from_armored(&fs::read_to_string(out).unwrap()).unwrap()
+            })
+            .collect();
+        assert_eq!(new[0].total, 7);
+        assert_eq!(reconstruct_auto(&new).unwrap(), b"five to seven");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_combine_from_qr_photo() {
+        use cryptographic_algorithms::qr::QrFormat;
+
+        let dir = std::env::temp_dir().join(format!("sss-cli-qr-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let shares = split_bytes(b"paper backup", 2, 3).unwrap();
+        let photo = dir.join("share-1.png");
+        let typed = dir.join("share-3.txt");
+        fs::write(&photo, shares[0].to_qr(QrFormat::Png).unwrap()).unwrap();
+        fs::write(&typed, shares[2].to_bech32()).unwrap();
+
+        let recovered = dir.join("secret.bin");
+        run(Cli::parse_from([
+            "sss",
+            "combine",
+            photo.to_str().unwrap(),
+            typed.to_str().unwrap(),
+            "--out",
+            recovered.to_str().unwrap(),
+        ]))
+        .unwrap();
+        assert_eq!(fs::read(&recovered).unwrap(), b"paper backup");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_json_reports() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-json-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let share = dir.join("share.txt");
+        fs::write(&share, split_bytes(b"json", 2, 3).unwrap()[1].to_bech32()).unwrap();
+
+        let report = run(Cli::parse_from([
+            "sss",
+            "--json",
+            "inspect",
+            share.to_str().unwrap(),
+        ]))
+        .unwrap();
+        assert_eq!(report["command"], "inspect");
+        assert_eq!(report["index"], "2");
+        assert_eq!(report["threshold"], 2);
+
+        let err = run(Cli::parse_from([
+            "sss",
+            "combine",
+            share.to_str().unwrap(),
+            "--json",
+        ]))
+        .unwrap_err();
+        assert_eq!(error_code(&err), ("insufficient_shares", 5));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_gen_params_feeds_split_and_verify() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-params-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let group = dir.join("group.json");
+        let secret = dir.join("secret.bin");
+        let shares = dir.join("shares");
+        fs::write(&secret, [0x00, 0x12, 0x34]).unwrap();
+        let arg = |path: &Path| path.to_str().unwrap().to_string();
+
+        let report = run(Cli::parse_from([
+            "sss",
+            "-q",
+            "gen-params",
+            "--bits",
+            "256",
+            "--q-bits",
+            "128",
+            "--out",
+            &arg(&group),
+        ]))
+        .unwrap();
+        assert_eq!(report["bits"], 256);
+        run(Cli::parse_from([
+            "sss",
+            "split",
+            "-t",
+            "2",
+            "-n",
+            "3",
+            &arg(&secret),
+            "--out-dir",
+            &arg(&shares),
+            "--params",
+            &arg(&group),
+        ]))
+        .unwrap();
+
+        let verify = |params: &Path| {
+            run(Cli::parse_from([
+                "sss",
+                "-q",
+                "verify",
+                "--commitments",
+                &arg(&shares.join("commitments.json")),
+                "--params",
+                &arg(params),
+                &arg(&shares.join("share-2.txt")),
+            ]))
+        };
+        verify(&group).unwrap();
+        let other = dir.join("other.json");
+        fs::write(
+            &other,
+            GroupParameters::generate(256, 128)
+                .unwrap()
+                .to_json()
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(verify(&other).is_err());
+
+        let recovered = dir.join("recovered.bin");
+        run(Cli::parse_from([
+            "sss",
+            "combine",
+            &arg(&shares.join("share-1.txt")),
+            &arg(&shares.join("share-3.txt")),
+            "--out",
+            &arg(&recovered),
+        ]))
+        .unwrap();
+        assert_eq!(fs::read(&recovered).unwrap(), [0x00, 0x12, 0x34]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_convert_to_prime_field() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-convert-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let secret = dir.join("secret.bin");
+        let (old, new) = (dir.join("old"), dir.join("new"));
+        fs::write(&secret, [0x00, 0xAB, 0xCD, 0xEF]).unwrap();
+        let arg = |path: &Path| path.to_str().unwrap().to_string();
+
+        run(Cli::parse_from([
+            "sss",
+            "split",
+            "-t",
+            "2",
+            "-n",
+            "3",
+            &arg(&secret),
+            "--out-dir",
+            &arg(&old),
+        ]))
+        .unwrap();
+        let report = run(Cli::parse_from([
+            "sss",
+            "-q",
+            "convert",
+            &arg(&old.join("share-1.txt")),
+            &arg(&old.join("share-2.txt")),
+            "-t",
+            "3",
+            "-n",
+            "4",
+            "--field",
+            "secp256k1",
+            "--out-dir",
+            &arg(&new),
+        ]))
+        .unwrap();
+        assert_eq!(report["total"], 4);
+
+        let converted = read_shares(&new.join("share-4.txt")).unwrap();
+        assert!(matches!(converted[0].scheme, Scheme::Shamir { .. }));
+        let recovered = dir.join("recovered.bin");
+        run(Cli::parse_from([
+            "sss",
+            "combine",
+            &arg(&new.join("share-1.txt")),
+            &arg(&new.join("share-2.txt")),
+            &arg(&new.join("share-4.txt")),
+            "--out",
+            &arg(&recovered),
+        ]))
+        .unwrap();
+        assert_eq!(fs::read(&recovered).unwrap(), [0x00, 0xAB, 0xCD, 0xEF]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_gen_secret_splits_fresh_secret() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-gen-{}", std::process::id()));
+        let report = run(Cli::parse_from([
+            "sss",
+            "--json",
+            "gen-secret",
+            "--bytes",
+            "16",
+            "-t",
+            "2",
+            "-n",
+            "3",
+            "--out-dir",
+            dir.to_str().unwrap(),
+            "--show",
+            "bip39",
+        ]))
+        .unwrap();
+        assert_eq!(report["secret"].as_str().unwrap().split(' ').count(), 12);
+
+        let shares: Vec<SerializedShare> = [1, 2]
+            .iter()
+            .map(|x| {
+                let path = dir.join(format!("share-{}.txt", x));
+                SerializedShare::from_armored(&fs::read_to_string(path).unwrap()).unwrap()
+            })
+            .collect();
+        let secret = reconstruct_auto(&shares).unwrap();
+        assert_eq!(
+            mnemonic::entropy_to_phrase(&secret).unwrap().join(" "),
+            report["secret"]
         );
+        fs::remove_dir_all(&dir).unwrap();
     }
 
-    // Verify shares
-    println!("\nVerifying shares:");
-    for (i, share) in shares.iter().enumerate() {
-        let is_valid = vss.verify_share(share, &commitments);
-        println!(
-            "Share {} verification: {}",
-            i + 1,
-            if is_valid { "Valid" } else { "Invalid" }
+    #[test]
+    fn test_batch_split_with_signed_audit() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-batch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("keys")).unwrap();
+        fs::write(dir.join("keys/db.key"), b"db secret").unwrap();
+        fs::write(dir.join("keys/tls.key"), b"tls secret").unwrap();
+        fs::write(dir.join("dealer.hex"), hex(&[3; 32])).unwrap();
+        fs::write(
+            dir.join("batch.toml"),
+            "[[item]]\ninput = 'keys/db.key'\nthreshold = 2\nshares = 3\nout_dir = 'out/db'\n\n\
+             [[item]]\ninput = 'keys/tls.key'\nthreshold = 3\nshares = 4\nout_dir = 'out/tls'\nlabel = 'tls'\n",
+        )
+        .unwrap();
+
+        let audit = dir.join("audit.json");
+        run(Cli::parse_from([
+            "sss",
+            "-q",
+            "split",
+            "--manifest",
+            dir.join("batch.toml").to_str().unwrap(),
+            "--dealer-key",
+            dir.join("dealer.hex").to_str().unwrap(),
+            "--audit",
+            audit.to_str().unwrap(),
+        ]))
+        .unwrap();
+
+        let record = AuditManifest::from_json(&fs::read_to_string(&audit).unwrap()).unwrap();
+        let dealer = SigningKey::from_bytes(&[3; 32]).verifying_key();
+        record.verify_signature(&dealer).unwrap();
+        assert_eq!(record.items[1].files.len(), 4);
+
+        let shares: Vec<SerializedShare> = record.items[1].files[1..]
+            .iter()
+            .map(|file| {
+                let share = SerializedShare::from_armored(&fs::read_to_string(&file.path).unwrap())
+                    .unwrap();
+                assert_eq!(share.fingerprint(), file.fingerprint);
+                share
+            })
+            .collect();
+        assert_eq!(
+            cryptographic_algorithms::serialized::reconstruct_signed(&shares, &dealer).unwrap(),
+            b"tls secret"
         );
+        fs::remove_dir_all(&dir).unwrap();
     }
 
-    // Reconstruct secret
-    let reconstructed = vss.reconstruct_secret(&shares[0..threshold]);
-    match reconstructed {
-        Some(value) => {
-            println!("\nReconstructed secret: {}", value);
-            assert_eq!(value, secret, "Reconstruction failed!");
-        }
-        None => println!("Failed to reconstruct secret"),
+    #[test]
+    fn test_config_defaults_and_overrides() {
+        let dir = std::env::temp_dir().join(format!("sss-cli-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                "threshold = 2\nshares = 3\nout_dir = '{}'\nencoding = 'bech32'\n",
+                dir.join("shares").display()
+            ),
+        )
+        .unwrap();
+        fs::write(dir.join("secret.bin"), b"configured").unwrap();
+
+        let report = run(Cli::parse_from([
+            "sss",
+            "--config",
+            config.to_str().unwrap(),
+            "split",
+            "-n",
+            "4",
+            dir.join("secret.bin").to_str().unwrap(),
+        ]))
+        .unwrap();
+        assert_eq!(
+            (report["threshold"].clone(), report["total"].clone()),
+            (json!(2), json!(4))
+        );
+        let text = fs::read_to_string(dir.join("shares/share-4.txt")).unwrap();
+        assert!(!text.contains("-----BEGIN"));
+        let shares: Vec<_> = [2, 4]
+            .iter()
+            .flat_map(|x| read_shares(&dir.join(format!("shares/share-{}.txt", x))).unwrap())
+            .collect();
+        assert_eq!(reconstruct_auto(&shares).unwrap(), b"configured");
+
+        fs::write(&config, "threshold = 2\nshars = 3\n").unwrap();
+        let typo = Cli::parse_from(["sss", "--config", config.to_str().unwrap(), "gen-secret"]);
+        assert!(matches!(run(typo), Err(Error::InvalidEncoding(_))));
+        let missing = dir.join("missing.toml");
+        let missing = Cli::parse_from(["sss", "--config", missing.to_str().unwrap(), "gen-secret"]);
+        assert!(matches!(run(missing), Err(Error::Io(_))));
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,186 @@
+// File: src/manifest.rs
+//! Batch split manifests and the signed audit records they produce.
+//!
+//! A [`BatchManifest`] is a TOML file listing the secrets to split in one
+//! run, each with its own threshold and output directory. The run produces
+//! an [`AuditManifest`]: a JSON record of every share file written, with
+//! share fingerprints rather than contents, optionally signed by the dealer.
+
+use std::path::PathBuf;
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::{hex, unhex};
+use crate::error::{Error, Result};
+use crate::signing::DealerSignature;
+
+const AUDIT_VERSION: u8 = 1;
+
+/// The secrets to split in one batch run.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchManifest {
+    #[serde(rename = "item")]
+    pub items: Vec<BatchItem>,
+}
+
+/// One `[[item]]` table; relative paths are resolved against the manifest.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchItem {
+    pub input: PathBuf,
+    pub threshold: usize,
+    pub shares: usize,
+    pub out_dir: PathBuf,
+    pub label: Option<String>,
+}
+
+impl BatchManifest {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let manifest: BatchManifest =
+            toml::from_str(text).map_err(|_| Error::InvalidEncoding("invalid batch manifest"))?;
+        if manifest.items.is_empty() {
+            return Err(Error::InvalidParameters("batch manifest lists no items"));
+        }
+        Ok(manifest)
+    }
+}
+
+/// What a batch run wrote, for the audit trail.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditManifest {
+    pub version: u8,
+    pub items: Vec<AuditItem>,
+    pub signature: Option<AuditSignature>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditItem {
+    pub input: String,
+    pub label: Option<String>,
+    pub session: String,
+    pub threshold: usize,
+    pub total: usize,
+    pub files: Vec<AuditFile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditFile {
+    pub path: String,
+    pub index: String,
+    /// [`crate::serialized::SerializedShare::fingerprint`] of the share.
+    pub fingerprint: String,
+}
+
+/// A hex dealer key and Ed25519 signature over the unsigned manifest JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditSignature {
+    pub dealer: String,
+    pub signature: String,
+}
+
+impl AuditManifest {
+    pub fn new(items: Vec<AuditItem>) -> Self {
+        AuditManifest {
+            version: AUDIT_VERSION,
+            items,
+            signature: None,
+        }
+    }
+
+    /// Signs the manifest as the dealer, replacing any earlier signature.
+    pub fn sign(&mut self, key: &SigningKey) {
+        let signature = DealerSignature::sign(key, &self.body());
+        self.signature = Some(AuditSignature {
+            dealer: hex(&signature.dealer),
+            signature: hex(&signature.signature),
+        });
+    }
+
+    /// Checks that the manifest carries a valid signature by `dealer`.
+    pub fn verify_signature(&self, dealer: &VerifyingKey) -> Result<()> {
+        let signature = self.signature.as_ref().ok_or(Error::InvalidSignature)?;
+        DealerSignature {
+            dealer: unhex(&signature.dealer)?
+                .try_into()
+                .map_err(|_| Error::InvalidSignature)?,
+            signature: unhex(&signature.signature)?
+                .try_into()
+                .map_err(|_| Error::InvalidSignature)?,
+        }
+        .verify(dealer, &self.body())
+    }
+
+    /// The signed bytes: the compact JSON of the manifest without its signature.
+    fn body(&self) -> Vec<u8> {
+        let unsigned = AuditManifest {
+            signature: None,
+            ..self.clone()
+        };
+        serde_json::to_vec(&unsigned).expect("audit manifests serialize")
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("audit manifests serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        let manifest: AuditManifest = serde_json::from_str(text)
+            .map_err(|_| Error::InvalidEncoding("invalid audit manifest JSON"))?;
+        if manifest.version != AUDIT_VERSION {
+            return Err(Error::UnsupportedVersion(manifest.version));
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_and_audit_manifests() {
+        let batch = BatchManifest::from_toml(
+            r#"
+            [[item]]
+            input = "keys/db.key"
+            threshold = 2
+            shares = 3
+            out_dir = "shares/db"
+
+            [[item]]
+            input = "keys/tls.pem"
+            threshold = 3
+            shares = 5
+            out_dir = "shares/tls"
+            label = "tls"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(batch.items.len(), 2);
+        assert_eq!(batch.items[1].label.as_deref(), Some("tls"));
+        assert!(BatchManifest::from_toml("[[item]]\ninput = 'x'\nthreshold = 1").is_err());
+
+        let mut audit = AuditManifest::new(vec![AuditItem {
+            input: "keys/db.key".into(),
+            label: None,
+            session: "00ff".into(),
+            threshold: 2,
+            total: 3,
+            files: vec![AuditFile {
+                path: "shares/db/share-1.txt".into(),
+                index: "1".into(),
+                fingerprint: "ab".repeat(32),
+            }],
+        }]);
+        let dealer = SigningKey::from_bytes(&[7; 32]);
+        audit.sign(&dealer);
+        let parsed = AuditManifest::from_json(&audit.to_json()).unwrap();
+        parsed.verify_signature(&dealer.verifying_key()).unwrap();
+
+        let mut tampered = parsed.clone();
+        tampered.items[0].files[0].path = "elsewhere".into();
+        assert!(tampered.verify_signature(&dealer.verifying_key()).is_err());
+    }
+}
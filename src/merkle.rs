@@ -0,0 +1,198 @@
+// File: src/merkle.rs
+//! Merkle commitments over the shares of a dealing.
+//!
+//! A dealer that publishes the root of a [`MerkleTree`] over its shares, in
+//! dealing order, can hand each participant an [`InclusionProof`] with its
+//! share. Later, a coordinator holding only the root can check that a
+//! submitted share was part of the original dealing, which plain Shamir
+//! dealings otherwise cannot show. The tree commits to the shares only: unlike
+//! Feldman commitments it says nothing about whether they lie on one
+//! polynomial.
+//!
+//! Leaves are SHA-256 over `0x00` and the share's binary encoding, inner
+//! nodes SHA-256 over `0x01` and both children. A node without a sibling is
+//! carried up unchanged.
+
+use sha2::{Digest, Sha256};
+
+use crate::encoding::{armor, dearmor, take_u32};
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+
+const ARMOR_LABEL: &str = "MERKLE INCLUSION PROOF";
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// The tree over a dealing's shares.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// Every level from the leaves up to the root.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+/// The sibling hashes from a share's leaf to the root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    /// Position of the share in the dealing.
+    pub index: u32,
+    /// Number of shares in the dealing.
+    pub leaves: u32,
+    pub path: Vec<[u8; 32]>,
+}
+
+impl MerkleTree {
+    pub fn new(shares: &[SerializedShare]) -> Result<Self> {
+        if shares.is_empty() || shares.len() > u32::MAX as usize {
+            return Err(Error::InvalidParameters(
+                "a Merkle tree needs at least one share",
+            ));
+        }
+        let mut levels = vec![shares.iter().map(leaf).collect::<Vec<_>>()];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node(left, right),
+                    [only] => *only,
+                    _ => unreachable!("chunks of at most two"),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Ok(MerkleTree { levels })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// The proof for the share at `index` in dealing order.
+    pub fn proof(&self, index: usize) -> Option<InclusionProof> {
+        let leaves = self.levels[0].len();
+        if index >= leaves {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                path.push(*sibling);
+            }
+            position /= 2;
+        }
+        Some(InclusionProof {
+            index: index as u32,
+            leaves: leaves as u32,
+            path,
+        })
+    }
+}
+
+impl InclusionProof {
+    /// Checks that `share` sits at this proof's position under `root`.
+    pub fn verify(&self, root: &[u8; 32], share: &SerializedShare) -> Result<()> {
+        if self.index >= self.leaves {
+            return Err(Error::InvalidParameters("proof index out of range"));
+        }
+        let mut hash = leaf(share);
+        let mut siblings = self.path.iter();
+        let (mut position, mut width) = (self.index, self.leaves);
+        while width > 1 {
+            if position % 2 == 1 {
+                hash = node(siblings.next().ok_or(Error::DigestMismatch)?, &hash);
+            } else if position + 1 < width {
+                hash = node(&hash, siblings.next().ok_or(Error::DigestMismatch)?);
+            }
+            position /= 2;
+            width = width.div_ceil(2);
+        }
+        if siblings.next().is_some() || &hash != root {
+            return Err(Error::DigestMismatch);
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(12 + 32 * self.path.len());
+        out.extend_from_slice(&self.index.to_be_bytes());
+        out.extend_from_slice(&self.leaves.to_be_bytes());
+        out.extend_from_slice(&(self.path.len() as u32).to_be_bytes());
+        for hash in &self.path {
+            out.extend_from_slice(hash);
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut input = bytes;
+        let index = take_u32(&mut input)?;
+        let leaves = take_u32(&mut input)?;
+        let count = take_u32(&mut input)? as usize;
+        if input.len() != count * 32 {
+            return Err(Error::InvalidEncoding("truncated inclusion proof"));
+        }
+        let path = input
+            .chunks(32)
+            .map(|hash| hash.try_into().expect("chunks of 32 bytes"))
+            .collect();
+        Ok(InclusionProof {
+            index,
+            leaves,
+            path,
+        })
+    }
+
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+}
+
+fn leaf(share: &SerializedShare) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(share.to_bytes());
+    hasher.finalize().into()
+}
+
+fn node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sss::SecretSharer;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let sharer = SecretSharer::new(3, 5);
+        let shares: Vec<_> = sharer
+            .split_secret(&BigUint::from(8675309u32))
+            .iter()
+            .map(|share| sharer.serialize_share(share))
+            .collect();
+        let tree = MerkleTree::new(&shares).unwrap();
+        let root = tree.root();
+        for (i, share) in shares.iter().enumerate() {
+            let proof = InclusionProof::from_armored(&tree.proof(i).unwrap().to_armored()).unwrap();
+            assert!(proof.verify(&root, share).is_ok());
+        }
+        assert!(tree.proof(5).is_none());
+
+        let proof = tree.proof(4).unwrap();
+        assert!(proof.verify(&root, &shares[3]).is_err());
+        let forged = sharer.serialize_share(&sharer.split_secret(&BigUint::from(1u32))[4]);
+        assert!(proof.verify(&root, &forged).is_err());
+        let other = MerkleTree::new(&shares[..4]).unwrap().root();
+        assert!(tree.proof(0).unwrap().verify(&other, &shares[0]).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+// File: src/mnemonic.rs
+//! Word encoding of binary share payloads using the BIP-39 English wordlist.
+//!
+//! A phrase is laid out as `[length] [data words...] [checksum]`, where each
+//! word carries 11 bits. The length word holds the payload size in bytes and
+//! the checksum word holds the first 11 bits of SHA-256 over length and data.
+//!
+//! [`entropy_to_phrase`] instead produces a standard BIP-39 seed phrase, for
+//! secrets that wallets and other tools need to import, and
+//! [`phrase_to_entropy`] reads one back.
+
+use std::sync::OnceLock;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+const WORDLIST: &str = include_str!("wordlists/bip39_english.txt");
+const BITS_PER_WORD: usize = 11;
+const MAX_PAYLOAD: usize = (1 << BITS_PER_WORD) - 1;
+
+/// Returns the 2048-word BIP-39 English wordlist.
+pub fn wordlist() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORDLIST.lines().collect())
+}
+
+/// Encodes `data` as a sequence of words.
+pub fn encode(data: &[u8]) -> Result<Vec<&'static str>> {
+    if data.len() > MAX_PAYLOAD {
+        return Err(Error::InvalidMnemonic("payload too long"));
+    }
+    let words = wordlist();
+
+    let mut indices = vec![data.len() as u16];
+    let (mut acc, mut bits) = (0u32, 0usize);
+    for &byte in data {
+        acc = (acc << 8) | byte as u32;
+        bits += 8;
+        while bits >= BITS_PER_WORD {
+            bits -= BITS_PER_WORD;
+            indices.push(((acc >> bits) & 0x7ff) as u16);
+        }
+    }
+    if bits > 0 {
+        indices.push(((acc << (BITS_PER_WORD - bits)) & 0x7ff) as u16);
+    }
+    indices.push(checksum(data));
+
+    Ok(indices.into_iter().map(|i| words[i as usize]).collect())
+}
+
+/// Encodes 16 to 32 bytes of entropy as a standard BIP-39 phrase.
+pub fn entropy_to_phrase(entropy: &[u8]) -> Result<Vec<&'static str>> {
+    if !(16..=32).contains(&entropy.len()) || !entropy.len().is_multiple_of(4) {
+        return Err(Error::InvalidMnemonic(
+            "BIP-39 entropy must be 16, 20, 24, 28 or 32 bytes",
+        ));
+    }
+    let words = wordlist();
+    let checksum_bits = entropy.len() / 4;
+    let checksum = Sha256::digest(entropy)[0] >> (8 - checksum_bits);
+
+    let mut indices = Vec::with_capacity((entropy.len() * 8 + checksum_bits) / BITS_PER_WORD);
+    let (mut acc, mut bits) = (0u32, 0usize);
+    for &byte in entropy {
+        acc = (acc << 8) | byte as u32;
+        bits += 8;
+        while bits >= BITS_PER_WORD {
+            bits -= BITS_PER_WORD;
+            indices.push(((acc >> bits) & 0x7ff) as u16);
+        }
+    }
+    acc = (acc << checksum_bits) | checksum as u32;
+    bits += checksum_bits;
+    debug_assert_eq!(bits, BITS_PER_WORD);
+    indices.push((acc & 0x7ff) as u16);
+
+    Ok(indices.into_iter().map(|i| words[i as usize]).collect())
+}
+
+/// Decodes a standard BIP-39 phrase of 12 to 24 words to its entropy,
+/// checking the embedded checksum. Words are resolved as in [`decode`].
+pub fn phrase_to_entropy<S: AsRef<str>>(phrase: &[S]) -> Result<Vec<u8>> {
+    let indices = phrase
+        .iter()
+        .enumerate()
+        .map(|(position, word)| lookup(word.as_ref()).ok_or_else(|| unknown(position, word)))
+        .collect::<Result<Vec<u16>>>()?;
+    if !(12..=24).contains(&indices.len()) || !indices.len().is_multiple_of(3) {
+        return Err(Error::InvalidMnemonic(
+            "BIP-39 phrases have 12, 15, 18, 21 or 24 words",
+        ));
+    }
+    let checksum_bits = indices.len() / 3;
+    let len = checksum_bits * 4;
+
+    let mut entropy = Vec::with_capacity(len);
+    let (mut acc, mut bits) = (0u32, 0usize);
+    for &index in &indices {
+        acc = (acc << BITS_PER_WORD) | index as u32;
+        bits += BITS_PER_WORD;
+        while bits >= 8 && entropy.len() < len {
+            bits -= 8;
+            entropy.push((acc >> bits) as u8);
+        }
+    }
+    debug_assert_eq!(bits, checksum_bits);
+    if acc & ((1 << bits) - 1) != (Sha256::digest(&entropy)[0] >> (8 - checksum_bits)) as u32 {
+        return Err(Error::ChecksumMismatch);
+    }
+    Ok(entropy)
+}
+
+/// Decodes a word sequence produced by [`encode`].
+///
+/// Words are matched case-insensitively; a word that is not in the list is
+/// accepted if its first four letters, or a single-character edit of it,
+/// identify exactly one list word.
+pub fn decode<S: AsRef<str>>(phrase: &[S]) -> Result<Vec<u8>> {
+    let indices = phrase
+        .iter()
+        .enumerate()
+        .map(|(position, word)| lookup(word.as_ref()).ok_or_else(|| unknown(position, word)))
+        .collect::<Result<Vec<u16>>>()?;
+
+    if indices.len() < 2 {
+        return Err(Error::InvalidMnemonic("phrase too short"));
+    }
+    let len = indices[0] as usize;
+    let data_words = &indices[1..indices.len() - 1];
+    if data_words.len() != (len * 8).div_ceil(BITS_PER_WORD) {
+        return Err(Error::InvalidMnemonic("word count does not match length"));
+    }
+
+    let mut data = Vec::with_capacity(len);
+    let (mut acc, mut bits) = (0u32, 0usize);
+    for &index in data_words {
+        acc = (acc << BITS_PER_WORD) | index as u32;
+        bits += BITS_PER_WORD;
+        while bits >= 8 && data.len() < len {
+            bits -= 8;
+            data.push((acc >> bits) as u8);
+        }
+    }
+    if acc & ((1 << bits) - 1) != 0 {
+        return Err(Error::InvalidMnemonic("non-zero padding bits"));
+    }
+    if indices[indices.len() - 1] != checksum(&data) {
+        return Err(Error::ChecksumMismatch);
+    }
+    Ok(data)
+}
+
+/// Resolves a possibly mistyped word to its index in the wordlist.
+pub fn lookup(word: &str) -> Option<u16> {
+    let word = word.trim().to_lowercase();
+    let words = wordlist();
+
+    if let Ok(index) = words.binary_search(&word.as_str()) {
+        return Some(index as u16);
+    }
+    if word.chars().count() >= 4 {
+        let prefix: String = word.chars().take(4).collect();
+        if let Some(index) = unique(words.iter().map(|w| w.starts_with(&prefix))) {
+            return Some(index);
+        }
+    }
+    unique(words.iter().map(|w| edit_distance(w, &word) == 1))
+}
+
+fn unique(mut matches: impl Iterator<Item = bool>) -> Option<u16> {
+    let index = matches.position(|m| m)?;
+    if matches.any(|m| m) {
+        return None;
+    }
+    Some(index as u16)
+}
+
+fn unknown<S: AsRef<str>>(position: usize, word: &S) -> Error {
+    Error::UnknownWord {
+        position,
+        word: word.as_ref().to_string(),
+    }
+}
+
+fn checksum(data: &[u8]) -> u16 {
+    let digest = Sha256::new()
+        .chain_update((data.len() as u16).to_be_bytes())
+        .chain_update(data)
+        .finalize();
+    u16::from_be_bytes([digest[0], digest[1]]) >> (16 - BITS_PER_WORD)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mnemonic_roundtrip_with_typos() {
+        for len in [0usize, 1, 3, 11, 32, 70] {
+            let data: Vec<u8> = (0..len as u8).map(|b| b.wrapping_mul(37)).collect();
+            let words = encode(&data).unwrap();
+            assert_eq!(decode(&words).unwrap(), data);
+        }
+
+        let data = b"correct horse battery staple";
+        let mut words: Vec<String> = encode(data)
+            .unwrap()
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        // Abbreviated and uppercased words are tolerated
+        words[1] = words[1].chars().take(4).collect();
+        words[2] = words[2].to_uppercase();
+        assert_eq!(decode(&words).unwrap(), data);
+
+        // Single-character edits resolve when they identify exactly one word
+        assert_eq!(lookup("abandn"), Some(0));
+        assert_eq!(lookup("xoo"), Some(2047));
+        assert_eq!(lookup("bat"), None);
+
+        let mut corrupted = encode(data).unwrap();
+        corrupted.swap(1, 2);
+        assert!(decode(&corrupted).is_err());
+        assert!(matches!(
+            decode(&["abandon", "xyzzyx"]),
+            Err(Error::UnknownWord { position: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_bip39_entropy_vectors() {
+        let phrase = |entropy: &[u8]| entropy_to_phrase(entropy).unwrap().join(" ");
+        assert_eq!(
+            phrase(&[0; 16]),
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        );
+        assert_eq!(
+            phrase(&[0x7f; 16]),
+            "legal winner thank year wave sausage worth useful legal winner thank yellow"
+        );
+        assert_eq!(phrase(&[0xff; 32]).split(' ').next_back(), Some("vote"));
+        assert!(entropy_to_phrase(&[0; 15]).is_err());
+
+        let legal = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let words: Vec<&str> = legal.split(' ').collect();
+        assert_eq!(phrase_to_entropy(&words).unwrap(), [0x7f; 16]);
+        let mut swapped = words.clone();
+        swapped.swap(0, 1);
+        assert!(matches!(
+            phrase_to_entropy(&swapped),
+            Err(Error::ChecksumMismatch)
+        ));
+        assert!(phrase_to_entropy(&words[..11]).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+// File: src/nested.rs
+//! Two-level Shamir sharing: a secret split among groups, each group's share
+//! split again among its members.
+//!
+//! A [`Policy`] reads like its description, either uniform,
+//! `"2 of 3 departments, each 2 of 4 members"`, or listing each group,
+//! `"2 of [finance: 2 of 4, legal: 3 of 5, ops: 1 of 2]"`. [`split`] deals
+//! GF(2^8) group shares and splits each encoded group share among the
+//! members; every member share records its group in its metadata, so
+//! [`reconstruct`] can sort a pile of member shares back into groups,
+//! recover each group that reached its member threshold, and combine the
+//! group shares.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::serialized::{reconstruct_auto, split_bytes, SerializedShare};
+
+/// Metadata key naming the group of a member share.
+pub const GROUP_KEY: &str = "group";
+
+/// Member threshold and member count of one group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupPolicy {
+    pub name: String,
+    pub threshold: usize,
+    pub members: usize,
+}
+
+/// How many groups must cooperate, and what each group requires.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    pub threshold: usize,
+    pub groups: Vec<GroupPolicy>,
+}
+
+impl Policy {
+    /// `groups` groups named `group-1`, `group-2`, ... with the same member
+    /// threshold and count.
+    pub fn uniform(
+        threshold: usize,
+        groups: usize,
+        member_threshold: usize,
+        members: usize,
+    ) -> Self {
+        Policy {
+            threshold,
+            groups: (1..=groups)
+                .map(|i| GroupPolicy {
+                    name: format!("group-{}", i),
+                    threshold: member_threshold,
+                    members,
+                })
+                .collect(),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let within = |t: usize, n: usize| t >= 1 && t <= n && n <= 255;
+        if !within(self.threshold, self.groups.len())
+            || !self.groups.iter().all(|g| within(g.threshold, g.members))
+        {
+            return Err(Error::InvalidParameters(
+                "each threshold must be between 1 and its count, at most 255",
+            ));
+        }
+        for (i, group) in self.groups.iter().enumerate() {
+            if self.groups[..i].iter().any(|g| g.name == group.name) {
+                return Err(Error::InvalidParameters("group names must be unique"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of [", self.threshold)?;
+        for (i, group) in self.groups.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(
+                f,
+                "{}: {} of {}",
+                group.name, group.threshold, group.members
+            )?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl FromStr for Policy {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        const INVALID: Error = Error::InvalidEncoding("invalid policy");
+        let (threshold, rest) = text.trim().split_once(" of ").ok_or(INVALID)?;
+        let threshold = threshold.trim().parse().map_err(|_| INVALID)?;
+        let policy = if let Some(list) = rest.trim().strip_prefix('[') {
+            let groups = list
+                .strip_suffix(']')
+                .ok_or(INVALID)?
+                .split(',')
+                .map(|group| {
+                    let (name, rule) = group.split_once(':').ok_or(INVALID)?;
+                    let (t, n) = count(rule).ok_or(INVALID)?;
+                    Ok(GroupPolicy {
+                        name: name.trim().to_string(),
+                        threshold: t,
+                        members: n,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Policy { threshold, groups }
+        } else {
+            let (groups, each) = rest.split_once(',').ok_or(INVALID)?;
+            let groups = groups.split_whitespace().next().ok_or(INVALID)?;
+            let each = each.trim().strip_prefix("each").ok_or(INVALID)?;
+            let (t, n) = count(each).ok_or(INVALID)?;
+            Policy::uniform(threshold, groups.parse().map_err(|_| INVALID)?, t, n)
+        };
+        policy.validate()?;
+        Ok(policy)
+    }
+}
+
+/// Parses `"t of n"`, ignoring a trailing noun such as `members`.
+fn count(text: &str) -> Option<(usize, usize)> {
+    let mut words = text.split_whitespace();
+    let threshold = words.next()?.parse().ok()?;
+    (words.next()? == "of").then_some(())?;
+    Some((threshold, words.next()?.parse().ok()?))
+}
+
+/// Splits `secret` under `policy`, returning the member shares of each
+/// group in policy order.
+pub fn split(secret: &[u8], policy: &Policy) -> Result<Vec<Vec<SerializedShare>>> {
+    policy.validate()?;
+    let group_shares = split_bytes(secret, policy.threshold, policy.groups.len())?;
+    policy
+        .groups
+        .iter()
+        .zip(group_shares)
+        .map(|(group, share)| {
+            let members = split_bytes(&share.to_bytes(), group.threshold, group.members)?;
+            Ok(members
+                .into_iter()
+                .map(|member| member.with_metadata(GROUP_KEY, group.name.clone()))
+                .collect())
+        })
+        .collect()
+}
+
+/// Recovers the secret from member shares of any groups, in any order.
+///
+/// Groups short of their member threshold are skipped; enough complete
+/// groups must remain to meet the group threshold.
+pub fn reconstruct(shares: &[SerializedShare]) -> Result<Vec<u8>> {
+    let mut groups: BTreeMap<&str, Vec<SerializedShare>> = BTreeMap::new();
+    for share in shares {
+        let group = share.metadata.get(GROUP_KEY).ok_or(Error::MalformedShare)?;
+        groups.entry(group).or_default().push(share.clone());
+    }
+    let mut group_shares = Vec::new();
+    for members in groups.values() {
+        if members.len() >= members[0].threshold {
+            group_shares.push(SerializedShare::from_bytes(&reconstruct_auto(members)?)?);
+        }
+    }
+    match group_shares.first() {
+        Some(first) if group_shares.len() >= first.threshold => reconstruct_auto(&group_shares),
+        _ => Err(Error::InsufficientShares),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_policy() {
+        let policy: Policy = "2 of 3 departments, each 2 of 4 members".parse().unwrap();
+        assert_eq!(policy, Policy::uniform(2, 3, 2, 4));
+        let listed: Policy = "2 of [finance: 2 of 4, legal: 3 of 5]".parse().unwrap();
+        assert_eq!(listed.to_string().parse::<Policy>().unwrap(), listed);
+        assert!("3 of [a: 1 of 1, b: 1 of 1]".parse::<Policy>().is_err());
+
+        let secret = b"launch codes".to_vec();
+        let groups = split(&secret, &policy).unwrap();
+        assert_eq!(groups.len(), 3);
+        assert!(groups.iter().all(|members| members.len() == 4));
+
+        // Two members each of the first and last department, one of the second.
+        let mut quorum = vec![groups[2][3].clone(), groups[1][0].clone()];
+        quorum.extend_from_slice(&groups[0][1..3]);
+        quorum.push(groups[2][0].clone());
+        assert_eq!(reconstruct(&quorum).unwrap(), secret);
+        assert!(reconstruct(&quorum[..4]).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+// File: src/noise.rs
+//! Encrypted, mutually authenticated ceremony channels over the Noise
+//! protocol.
+//!
+//! A [`NoiseConnection`] runs the `Noise_XX_25519_ChaChaPoly_BLAKE2s`
+//! handshake over a TCP stream, using a party's X25519 key as its Noise
+//! static key, and then carries length-framed messages encrypted in
+//! transport mode. XX transmits both static keys inside the handshake, so no
+//! PKI is needed: each side pins the key it expects, exchanged out of band
+//! like a dealer's verifying key, and the ceremony binds a participant's
+//! `Hello` key to the one it authenticated with.
+//!
+//! Frames longer than a single Noise message are split into several; the
+//! first carries the frame length. [`NoiseConnection`] implements
+//! [`crate::ceremony::Transport`], and DKG messages can be sent as raw
+//! frames with [`NoiseConnection::send_frame`].
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+use snow::{Builder, HandshakeState, TransportState};
+use x25519_dalek::StaticSecret;
+
+use crate::ceremony::{Message, Transport};
+use crate::error::{Error, Result};
+
+const PATTERN: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+/// The largest Noise message, ciphertext and tag included.
+const MAX_MESSAGE: usize = 65535;
+const TAG_LEN: usize = 16;
+/// Frames larger than this are rejected before any allocation.
+const MAX_FRAME: usize = 1 << 20;
+
+/// A Noise transport session over TCP.
+pub struct NoiseConnection {
+    stream: TcpStream,
+    session: TransportState,
+    remote: [u8; 32],
+}
+
+impl NoiseConnection {
+    /// Connects to `addr` and runs the handshake as initiator.
+    pub fn connect(
+        addr: impl ToSocketAddrs,
+        key: &StaticSecret,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<Self> {
+        Self::initiate(TcpStream::connect(addr)?, key, expected)
+    }
+
+    /// Runs the handshake as initiator on an open stream. With `expected`,
+    /// the responder must authenticate with that static key.
+    pub fn initiate(
+        mut stream: TcpStream,
+        key: &StaticSecret,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<Self> {
+        let mut handshake = handshake(key, true)?;
+        write_handshake(&mut stream, &mut handshake)?;
+        read_handshake(&mut stream, &mut handshake)?;
+        write_handshake(&mut stream, &mut handshake)?;
+        Self::established(stream, handshake, expected)
+    }
+
+    /// Runs the handshake as responder on an accepted stream. With
+    /// `expected`, the initiator must authenticate with that static key.
+    pub fn respond(
+        mut stream: TcpStream,
+        key: &StaticSecret,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<Self> {
+        let mut handshake = handshake(key, false)?;
+        read_handshake(&mut stream, &mut handshake)?;
+        write_handshake(&mut stream, &mut handshake)?;
+        read_handshake(&mut stream, &mut handshake)?;
+        Self::established(stream, handshake, expected)
+    }
+
+    fn established(
+        stream: TcpStream,
+        handshake: HandshakeState,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<Self> {
+        let remote: [u8; 32] = handshake
+            .get_remote_static()
+            .and_then(|key| key.try_into().ok())
+            .ok_or(Error::DecryptionFailed)?;
+        if expected.is_some_and(|expected| *expected != remote) {
+            return Err(Error::InvalidParameters(
+                "the peer authenticated with an unexpected static key",
+            ));
+        }
+        Ok(NoiseConnection {
+            stream,
+            session: handshake.into_transport_mode().map_err(noise_error)?,
+            remote,
+        })
+    }
+
+    /// The static X25519 key the peer authenticated with.
+    pub fn remote_key(&self) -> &[u8; 32] {
+        &self.remote
+    }
+
+    /// Encrypts and sends one frame.
+    pub fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        if frame.len() > MAX_FRAME {
+            return Err(Error::InvalidEncoding("ceremony message too large"));
+        }
+        self.send_message(&(frame.len() as u32).to_be_bytes())?;
+        for chunk in frame.chunks(MAX_MESSAGE - TAG_LEN) {
+            self.send_message(chunk)?;
+        }
+        Ok(self.stream.flush()?)
+    }
+
+    /// Receives and decrypts one frame.
+    pub fn receive_frame(&mut self) -> Result<Vec<u8>> {
+        let len: [u8; 4] = self
+            .receive_message()?
+            .try_into()
+            .map_err(|_| Error::InvalidEncoding("invalid frame header"))?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_FRAME {
+            return Err(Error::InvalidEncoding("ceremony message too large"));
+        }
+        let mut frame = Vec::with_capacity(len);
+        while frame.len() < len {
+            frame.extend_from_slice(&self.receive_message()?);
+        }
+        if frame.len() != len {
+            return Err(Error::InvalidEncoding("frame longer than announced"));
+        }
+        Ok(frame)
+    }
+
+    fn send_message(&mut self, plaintext: &[u8]) -> Result<()> {
+        let mut buf = vec![0u8; plaintext.len() + TAG_LEN];
+        let len = self
+            .session
+            .write_message(plaintext, &mut buf)
+            .map_err(noise_error)?;
+        write_raw(&mut self.stream, &buf[..len])
+    }
+
+    fn receive_message(&mut self) -> Result<Vec<u8>> {
+        let ciphertext = read_raw(&mut self.stream)?;
+        let mut buf = vec![0u8; ciphertext.len()];
+        let len = self
+            .session
+            .read_message(&ciphertext, &mut buf)
+            .map_err(|_| Error::DecryptionFailed)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+}
+
+impl Transport for NoiseConnection {
+    fn send(&mut self, message: &Message) -> Result<()> {
+        self.send_frame(&message.to_bytes())
+    }
+
+    fn receive(&mut self) -> Result<Message> {
+        Message::from_bytes(&self.receive_frame()?)
+    }
+
+    fn peer_key(&self) -> Option<[u8; 32]> {
+        Some(self.remote)
+    }
+}
+
+fn handshake(key: &StaticSecret, initiator: bool) -> Result<HandshakeState> {
+    let mut private = key.to_bytes();
+    let builder = Builder::new(PATTERN.parse().map_err(noise_error)?).local_private_key(&private);
+    let handshake = if initiator {
+        builder.build_initiator()
+    } else {
+        builder.build_responder()
+    };
+    private.fill(0);
+    handshake.map_err(noise_error)
+}
+
+fn write_handshake(stream: &mut TcpStream, handshake: &mut HandshakeState) -> Result<()> {
+    let mut buf = vec![0u8; MAX_MESSAGE];
+    let len = handshake
+        .write_message(&[], &mut buf)
+        .map_err(noise_error)?;
+    write_raw(stream, &buf[..len])?;
+    Ok(stream.flush()?)
+}
+
+fn read_handshake(stream: &mut TcpStream, handshake: &mut HandshakeState) -> Result<()> {
+    let message = read_raw(stream)?;
+    let mut buf = vec![0u8; MAX_MESSAGE];
+    handshake
+        .read_message(&message, &mut buf)
+        .map_err(|_| Error::DecryptionFailed)?;
+    Ok(())
+}
+
+/// Writes one Noise message with its u16 length.
+fn write_raw(stream: &mut TcpStream, message: &[u8]) -> Result<()> {
+    stream.write_all(&(message.len() as u16).to_be_bytes())?;
+    Ok(stream.write_all(message)?)
+}
+
+fn read_raw(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    let mut len = [0u8; 2];
+    stream.read_exact(&mut len)?;
+    let mut message = vec![0u8; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut message)?;
+    Ok(message)
+}
+
+fn noise_error(_: snow::Error) -> Error {
+    Error::InvalidParameters("Noise protocol failure")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ceremony::{deal_over, participate_over, reconstructor, Reply};
+    use crate::params::GroupParameters;
+    use num_bigint::BigUint;
+    use rand::rngs::OsRng;
+    use std::net::TcpListener;
+    use x25519_dalek::PublicKey;
+
+    #[test]
+    fn test_ceremony_over_noise() {
+        let dealer_key = StaticSecret::random_from_rng(OsRng);
+        let dealer_public = PublicKey::from(&dealer_key).to_bytes();
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let participants: Vec<_> = (1..=3u32)
+            .map(|index| {
+                std::thread::spawn(move || {
+                    let key = StaticSecret::random_from_rng(OsRng);
+                    let connection =
+                        NoiseConnection::connect(addr, &key, Some(&dealer_public)).unwrap();
+                    participate_over(connection, index, &key, None).unwrap()
+                })
+            })
+            .collect();
+
+        let secret = BigUint::from(7654321u32);
+        let accept = || NoiseConnection::respond(listener.accept()?.0, &dealer_key, None);
+        let replies = deal_over(accept, &group, 2, 3, &secret, b"noise").unwrap();
+        assert_eq!(replies, vec![Reply::Ack; 3]);
+        let received: Vec<_> = participants
+            .into_iter()
+            .map(|p| p.join().unwrap())
+            .collect();
+        let shares = [received[1].0.clone(), received[2].0.clone()];
+        assert_eq!(
+            reconstructor(&received[0].1)
+                .reconstruct_secret(&shares)
+                .unwrap(),
+            secret
+        );
+
+        // Frames span several Noise messages; a pinned key must match.
+        let key = StaticSecret::random_from_rng(OsRng);
+        let peer = std::thread::spawn(move || {
+            let mut connection = NoiseConnection::connect(addr, &key, None).unwrap();
+            let frame = connection.receive_frame().unwrap();
+            connection.send_frame(&frame).unwrap();
+            NoiseConnection::connect(addr, &key, Some(&[0; 32])).is_err()
+        });
+        let mut connection =
+            NoiseConnection::respond(listener.accept().unwrap().0, &dealer_key, None).unwrap();
+        let frame: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        connection.send_frame(&frame).unwrap();
+        assert_eq!(connection.receive_frame().unwrap(), frame);
+        let _ = NoiseConnection::respond(listener.accept().unwrap().0, &dealer_key, None);
+        assert!(peer.join().unwrap());
+    }
+}
@@ -0,0 +1,242 @@
+// File: src/params.rs
+//! Feldman VSS group parameters.
+//!
+//! [`GroupParameters`] holds a prime `p`, a prime `q` dividing `p - 1`, and a
+//! generator `g` of the order-`q` subgroup of Z_p*. Parameters are generated
+//! as a Schnorr group or taken from a preset, checked by
+//! [`GroupParameters::validate`], and exchanged as a small JSON document
+//! with hex integers, like [`crate::commitment::CommitmentDocument`].
+
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::{hex, to_fixed};
+use crate::error::{Error, Result};
+use crate::vss::FeldmanVSS;
+
+const DOCUMENT_VERSION: u8 = 1;
+const MILLER_RABIN_ROUNDS: usize = 40;
+
+/// The 2048-bit MODP group of RFC 3526 (group 14); `q = (p - 1) / 2`, `g = 2`.
+const MODP_2048: &str = "\
+    FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74\
+    020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437\
+    4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
+    EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05\
+    98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB\
+    9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
+    E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718\
+    3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF";
+
+/// The names accepted by [`GroupParameters::preset`].
+pub const PRESETS: &[&str] = &["modp-2048"];
+
+#[derive(Serialize, Deserialize)]
+struct JsonDocument {
+    version: u8,
+    p: String,
+    q: String,
+    g: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupParameters {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl GroupParameters {
+    /// Generates a Schnorr group with a `p_bits`-bit `p` and `q_bits`-bit `q`.
+    pub fn generate(p_bits: u64, q_bits: u64) -> Result<Self> {
+        if q_bits < 16 || p_bits <= q_bits + 1 {
+            return Err(Error::InvalidParameters(
+                "p must be longer than q, and q at least 16 bits",
+            ));
+        }
+        let mut rng = thread_rng();
+        let q = random_prime(q_bits);
+        let step = &q << 1u32;
+        let p = loop {
+            // p = k * 2q + 1 with the top bit set
+            let mut candidate = rng.gen_biguint(p_bits);
+            candidate.set_bit(p_bits - 1, true);
+            candidate = &candidate - (&candidate % &step) + 1u32;
+            if candidate.bits() == p_bits && is_probable_prime(&candidate) {
+                break candidate;
+            }
+        };
+        let exponent = (&p - 1u32) / &q;
+        let g = loop {
+            let h = rng.gen_biguint_range(&BigUint::from(2u32), &(&p - 1u32));
+            let g = h.modpow(&exponent, &p);
+            if !g.is_one() {
+                break g;
+            }
+        };
+        Ok(GroupParameters { p, q, g })
+    }
+
+    /// Returns a well-known group by name; see [`PRESETS`].
+    pub fn preset(name: &str) -> Result<Self> {
+        match name {
+            "modp-2048" => {
+                let p = BigUint::parse_bytes(MODP_2048.as_bytes(), 16)
+                    .expect("preset primes are valid hex");
+                Ok(GroupParameters {
+                    q: (&p - 1u32) >> 1,
+                    p,
+                    g: BigUint::from(2u32),
+                })
+            }
+            _ => Err(Error::InvalidParameters("unknown parameter preset")),
+        }
+    }
+
+    /// Checks that `p` and `q` are prime, `q` divides `p - 1`, and `g`
+    /// generates the subgroup of order `q`.
+    pub fn validate(&self) -> Result<()> {
+        if !is_probable_prime(&self.q) || !is_probable_prime(&self.p) {
+            return Err(Error::InvalidParameters("p and q must be prime"));
+        }
+        if !(&self.p - 1u32).is_multiple_of(&self.q) {
+            return Err(Error::InvalidParameters("q must divide p - 1"));
+        }
+        if self.g <= BigUint::one() || self.g >= self.p || !self.g.modpow(&self.q, &self.p).is_one()
+        {
+            return Err(Error::InvalidParameters(
+                "g must generate the subgroup of order q",
+            ));
+        }
+        Ok(())
+    }
+
+    /// A dealer over this group for a `threshold`-of-`total` sharing.
+    pub fn vss(&self, threshold: usize, total: usize) -> Result<FeldmanVSS> {
+        if threshold == 0 || threshold > total {
+            return Err(Error::InvalidParameters(
+                "threshold must be between 1 and the total",
+            ));
+        }
+        Ok(FeldmanVSS::new(
+            self.p.clone(),
+            self.q.clone(),
+            self.g.clone(),
+            threshold,
+            total,
+        ))
+    }
+
+    /// Fails for a `g` wider than `p`, which no valid group has.
+    pub fn to_json(&self) -> Result<String> {
+        let document = JsonDocument {
+            version: DOCUMENT_VERSION,
+            p: hex(&to_fixed(&self.p, &self.p)?),
+            q: hex(&to_fixed(&self.q, &self.q)?),
+            g: hex(&to_fixed(&self.g, &self.p)?),
+        };
+        Ok(serde_json::to_string_pretty(&document).expect("parameter documents serialize"))
+    }
+
+    /// Parses and validates a parameter document.
+    pub fn from_json(text: &str) -> Result<Self> {
+        let document: JsonDocument = serde_json::from_str(text)
+            .map_err(|_| Error::InvalidEncoding("invalid parameter JSON"))?;
+        if document.version != DOCUMENT_VERSION {
+            return Err(Error::UnsupportedVersion(document.version));
+        }
+        let int = |text: &str| {
+            BigUint::parse_bytes(text.as_bytes(), 16)
+                .ok_or(Error::InvalidEncoding("invalid hex integer"))
+        };
+        let params = GroupParameters {
+            p: int(&document.p)?,
+            q: int(&document.q)?,
+            g: int(&document.g)?,
+        };
+        params.validate()?;
+        Ok(params)
+    }
+}
+
+fn random_prime(bits: u64) -> BigUint {
+    let mut rng = thread_rng();
+    loop {
+        let mut candidate = rng.gen_biguint(bits);
+        candidate.set_bit(bits - 1, true);
+        candidate.set_bit(0, true);
+        if is_probable_prime(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+/// Miller-Rabin with random bases, after trial division by small primes.
+fn is_probable_prime(n: &BigUint) -> bool {
+    const SMALL_PRIMES: [u32; 25] = [
+        2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+        97,
+    ];
+    if *n < BigUint::from(2u32) {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        if (n % p).is_zero() {
+            return *n == BigUint::from(p);
+        }
+    }
+
+    let n_minus_one = n - 1u32;
+    let shift = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> shift;
+    let mut rng = thread_rng();
+    'witness: for _ in 0..MILLER_RABIN_ROUNDS {
+        let a = rng.gen_biguint_range(&BigUint::from(2u32), &n_minus_one);
+        let mut x = a.modpow(&d, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..shift {
+            x = x.modpow(&BigUint::from(2u32), n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_and_preset_groups() {
+        let params = GroupParameters::generate(384, 160).unwrap();
+        assert_eq!((params.p.bits(), params.q.bits()), (384, 160));
+        params.validate().unwrap();
+        assert_eq!(
+            GroupParameters::from_json(&params.to_json().unwrap()).unwrap(),
+            params
+        );
+
+        let mut vss = params.vss(2, 3).unwrap();
+        let (shares, commitments) = vss.split_secret(&BigUint::from(42u32)).unwrap();
+        assert!(shares.iter().all(|s| vss.verify_share(s, &commitments)));
+
+        // Full validation of the 2048-bit preset is too slow for a debug build
+        let modp = GroupParameters::preset("modp-2048").unwrap();
+        assert!(modp.g.modpow(&modp.q, &modp.p).is_one());
+
+        let mut broken = params.clone();
+        broken.g = BigUint::one();
+        assert!(broken.validate().is_err());
+        broken.g = params.g.clone();
+        broken.p += 2u32;
+        assert!(GroupParameters::from_json(&broken.to_json().unwrap()).is_err());
+    }
+}
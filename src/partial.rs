@@ -0,0 +1,212 @@
+// File: src/partial.rs
+//! Verifiable partial contributions from Feldman shareholders.
+//!
+//! Threshold operations such as ElGamal decryption have every holder raise a
+//! common base to its share, `base^s_i mod p`, and combine a threshold of
+//! results in the exponent. A [`Contribution`] carries that value with a
+//! Chaum-Pedersen proof that its exponent is the same `s_i` as in the
+//! holder's public share `g^s_i`, which anyone derives from the dealing's
+//! commitments ([`CommitmentDocument::public_share_for`]). A holder that
+//! sends a wrong value is caught and identified by its index.
+//!
+//! The proof is made non-interactive with a SHA-256 challenge over the
+//! group, the holder's index, both bases and both values.
+
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, Zero};
+use rand::thread_rng;
+use sha2::{Digest, Sha256};
+
+use crate::commitment::CommitmentDocument;
+use crate::encoding::{put_biguint, take_biguint};
+use crate::error::{Error, Result};
+use crate::vss::Share;
+
+/// A holder's value `base^s_i mod p` with its proof of correctness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contribution {
+    pub id: BigUint,
+    pub value: BigUint,
+    challenge: BigUint,
+    response: BigUint,
+}
+
+impl Contribution {
+    /// Raises `base`, an element of the order-`q` subgroup, to `share`.
+    pub fn new(document: &CommitmentDocument, share: &Share, base: &BigUint) -> Result<Self> {
+        let (p, q, g) = (&document.p, &document.q, &document.g);
+        check_element(document, base)?;
+        let public = g.modpow(&share.value, p);
+        let value = base.modpow(&share.value, p);
+        let nonce = thread_rng().gen_biguint_below(q);
+        let challenge = challenge(
+            document,
+            &share.id,
+            base,
+            &public,
+            &value,
+            &g.modpow(&nonce, p),
+            &base.modpow(&nonce, p),
+        );
+        // z = k - c * s mod q
+        let response = (nonce + q - (&challenge * &share.value) % q) % q;
+        Ok(Contribution {
+            id: share.id.clone(),
+            value,
+            challenge,
+            response,
+        })
+    }
+
+    /// Checks the contribution against the holder's public share under the
+    /// dealing's commitments.
+    pub fn verify(&self, document: &CommitmentDocument, base: &BigUint) -> Result<()> {
+        let (p, g) = (&document.p, &document.g);
+        check_element(document, base)?;
+        check_element(document, &self.value)?;
+        let public = document.public_share_for(&self.id).0;
+        // g^z * P^c = g^k and base^z * V^c = base^k for an honest holder.
+        let a = g.modpow(&self.response, p) * public.modpow(&self.challenge, p) % p;
+        let b = base.modpow(&self.response, p) * self.value.modpow(&self.challenge, p) % p;
+        let expected = challenge(document, &self.id, base, &public, &self.value, &a, &b);
+        if expected == self.challenge {
+            Ok(())
+        } else {
+            Err(Error::InconsistentShares(
+                "contribution does not match the holder's public share",
+            ))
+        }
+    }
+
+    /// Minimal length-prefixed integers: a contribution carries no `p` to pad
+    /// to and is only meaningful next to its commitment document.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for field in [&self.id, &self.value, &self.challenge, &self.response] {
+            put_biguint(&mut out, field);
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut input = bytes;
+        let contribution = Contribution {
+            id: take_biguint(&mut input)?,
+            value: take_biguint(&mut input)?,
+            challenge: take_biguint(&mut input)?,
+            response: take_biguint(&mut input)?,
+        };
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(contribution)
+    }
+}
+
+/// Verifies every contribution and combines them into `base^secret mod p`,
+/// interpolating in the exponent. Needs at least a threshold of distinct
+/// holders.
+pub fn combine(
+    document: &CommitmentDocument,
+    base: &BigUint,
+    contributions: &[Contribution],
+) -> Result<BigUint> {
+    let (p, q) = (&document.p, &document.q);
+    if contributions.len() < document.threshold {
+        return Err(Error::InsufficientShares);
+    }
+    let contributions = &contributions[..document.threshold];
+    let mut result = BigUint::one();
+    for (i, contribution) in contributions.iter().enumerate() {
+        contribution.verify(document, base)?;
+        let (numerator, denominator) = contributions
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| i != j)
+            .fold(
+                (BigUint::one(), BigUint::one()),
+                |(num, den), (_, other)| {
+                    let diff = (&other.id % q + q - &contribution.id % q) % q;
+                    (num * &other.id % q, den * diff % q)
+                },
+            );
+        if denominator.is_zero() {
+            return Err(Error::InconsistentShares("duplicate share indices"));
+        }
+        let lagrange = numerator * denominator.modpow(&(q - 2u32), q) % q;
+        result = result * contribution.value.modpow(&lagrange, p) % p;
+    }
+    Ok(result)
+}
+
+/// Rejects values outside the order-`q` subgroup, where the proof says
+/// nothing about the exponent.
+fn check_element(document: &CommitmentDocument, element: &BigUint) -> Result<()> {
+    let (p, q) = (&document.p, &document.q);
+    if element <= &BigUint::one() || element >= p || !element.modpow(q, p).is_one() {
+        return Err(Error::InvalidParameters(
+            "base must lie in the order-q subgroup",
+        ));
+    }
+    Ok(())
+}
+
+fn challenge(
+    document: &CommitmentDocument,
+    id: &BigUint,
+    base: &BigUint,
+    public: &BigUint,
+    value: &BigUint,
+    a: &BigUint,
+    b: &BigUint,
+) -> BigUint {
+    let mut transcript = b"SHP1".to_vec();
+    for element in [
+        &document.p,
+        &document.q,
+        &document.g,
+        id,
+        base,
+        public,
+        value,
+        a,
+        b,
+    ] {
+        put_biguint(&mut transcript, element);
+    }
+    BigUint::from_bytes_be(&Sha256::digest(&transcript)) % &document.q
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::GroupParameters;
+
+    #[test]
+    fn test_partial_decryption() {
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let mut vss = group.vss(2, 3).unwrap();
+        let secret = BigUint::from(271828u32);
+        let (shares, commitments) = vss.split_secret(&secret).unwrap();
+        let document = vss.commitment_document(&commitments);
+        let base = group.g.modpow(&BigUint::from(99u32), &group.p);
+
+        let contributions: Vec<_> = shares
+            .iter()
+            .map(|share| Contribution::new(&document, share, &base).unwrap())
+            .map(|c| Contribution::from_bytes(&c.to_bytes()).unwrap())
+            .collect();
+        assert!(contributions
+            .iter()
+            .all(|c| c.verify(&document, &base).is_ok()));
+        assert_eq!(
+            combine(&document, &base, &contributions[1..]).unwrap(),
+            base.modpow(&secret, &group.p)
+        );
+
+        let mut forged = contributions[0].clone();
+        forged.value = forged.value * &group.g % &group.p;
+        assert!(forged.verify(&document, &base).is_err());
+        assert!(combine(&document, &base, &[forged, contributions[2].clone()]).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+// File: src/passphrase.rs
+//! Passphrase protection for individual shares.
+//!
+//! A [`ProtectedShare`] encrypts a [`SerializedShare`] with XChaCha20-Poly1305
+//! under a key stretched from the custodian's passphrase with Argon2id. The
+//! Argon2 parameters, salt and nonce live in a cleartext header that is also
+//! authenticated, so they cannot be downgraded without detection.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::encoding::{armor, dearmor, take_u32};
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+
+const ARMOR_LABEL: &str = "PROTECTED SHARE";
+const MAGIC: &[u8; 3] = b"SHP";
+const PROTECTED_VERSION: u8 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 12 + 16 + 24;
+
+/// Upper bounds on the Argon2id costs a header may ask for, so a crafted
+/// file cannot make unlocking allocate terabytes or spin for hours.
+const MAX_MEMORY_KIB: u32 = 4 * 1024 * 1024;
+const MAX_ITERATIONS: u32 = 64;
+const MAX_PARALLELISM: u32 = 16;
+
+/// Argon2id cost parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory cost in KiB.
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    /// The OWASP-recommended Argon2id baseline (19 MiB, 2 passes, 1 lane).
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Refuses costs above 4 GiB of memory, 64 passes or 16 lanes.
+    fn check(&self) -> Result<()> {
+        if self.memory_kib > MAX_MEMORY_KIB
+            || self.iterations > MAX_ITERATIONS
+            || self.parallelism > MAX_PARALLELISM
+        {
+            return Err(Error::InvalidParameters(
+                "Argon2id parameters exceed the supported maximum",
+            ));
+        }
+        Ok(())
+    }
+
+    fn derive_key(&self, passphrase: &str, salt: &[u8; 16]) -> Result<[u8; 32]> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+            .map_err(|_| Error::InvalidParameters("invalid Argon2id parameters"))?;
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|_| Error::InvalidParameters("invalid Argon2id parameters"))?;
+        Ok(key)
+    }
+}
+
+/// A share encrypted under a passphrase.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtectedShare {
+    pub kdf: KdfParams,
+    pub salt: [u8; 16],
+    pub nonce: [u8; 24],
+    pub ciphertext: Vec<u8>,
+}
+
+impl ProtectedShare {
+    pub fn seal(share: &SerializedShare, passphrase: &str, kdf: KdfParams) -> Result<Self> {
+        kdf.check()?;
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 24];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let mut protected = ProtectedShare {
+            kdf,
+            salt,
+            nonce,
+            ciphertext: Vec::new(),
+        };
+        let key = kdf.derive_key(passphrase, &salt)?;
+        protected.ciphertext = XChaCha20Poly1305::new(&key.into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &share.to_bytes(),
+                    aad: &protected.header(),
+                },
+            )
+            .expect("a share fits in a single XChaCha20-Poly1305 message");
+        Ok(protected)
+    }
+
+    /// Decrypts the share; a wrong passphrase yields [`Error::DecryptionFailed`].
+    pub fn open(&self, passphrase: &str) -> Result<SerializedShare> {
+        let key = self.kdf.derive_key(passphrase, &self.salt)?;
+        let plaintext = XChaCha20Poly1305::new(&key.into())
+            .decrypt(
+                XNonce::from_slice(&self.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &self.header(),
+                },
+            )
+            .map_err(|_| Error::DecryptionFailed)?;
+        SerializedShare::from_bytes(&plaintext)
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(PROTECTED_VERSION);
+        out.extend_from_slice(&self.kdf.memory_kib.to_be_bytes());
+        out.extend_from_slice(&self.kdf.iterations.to_be_bytes());
+        out.extend_from_slice(&self.kdf.parallelism.to_be_bytes());
+        out.extend_from_slice(&self.salt);
+        out.extend_from_slice(&self.nonce);
+        out
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.header();
+        out.extend_from_slice(&self.ciphertext);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if version != PROTECTED_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(Error::MalformedShare);
+        }
+        let kdf = KdfParams {
+            memory_kib: take_u32(&mut input)?,
+            iterations: take_u32(&mut input)?,
+            parallelism: take_u32(&mut input)?,
+        };
+        kdf.check()?;
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 24];
+        salt.copy_from_slice(&input[..16]);
+        nonce.copy_from_slice(&input[16..40]);
+        Ok(ProtectedShare {
+            kdf,
+            salt,
+            nonce,
+            ciphertext: input[40..].to_vec(),
+        })
+    }
+
+    /// Encodes the protected share as an ASCII-armored text block.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gf256::Gf256;
+
+    #[test]
+    fn test_passphrase_roundtrip() {
+        let share = SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, 1, b"payload".to_vec())
+            .with_label("alice@ops");
+        // Cheap parameters keep the test fast
+        let kdf = KdfParams {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let protected = ProtectedShare::seal(&share, "correct horse", kdf).unwrap();
+        let decoded = ProtectedShare::from_armored(&protected.to_armored()).unwrap();
+        assert_eq!(decoded.kdf, kdf);
+        assert_eq!(decoded.open("correct horse").unwrap(), share);
+        assert!(matches!(
+            decoded.open("wrong horse"),
+            Err(Error::DecryptionFailed)
+        ));
+
+        // Weakening the recorded parameters breaks authentication
+        let mut downgraded = decoded.clone();
+        downgraded.kdf.memory_kib = 32;
+        assert!(downgraded.open("correct horse").is_err());
+    }
+
+    #[test]
+    fn test_excessive_kdf_costs_are_refused() {
+        let share = SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, 1, b"payload".to_vec());
+        let kdf = KdfParams {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let bytes = ProtectedShare::seal(&share, "pw", kdf).unwrap().to_bytes();
+        // memory_kib, iterations and parallelism follow the magic and version
+        for offset in [4, 8, 12] {
+            let mut crafted = bytes.clone();
+            crafted[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+            assert!(matches!(
+                ProtectedShare::from_bytes(&crafted),
+                Err(Error::InvalidParameters(_))
+            ));
+        }
+        let greedy = KdfParams {
+            iterations: MAX_ITERATIONS + 1,
+            ..kdf
+        };
+        assert!(ProtectedShare::seal(&share, "pw", greedy).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+// File: src/pkcs11.rs
+//! HSM and smartcard custody of shares over PKCS#11 (`pkcs11` feature).
+//!
+//! [`Pkcs11Custody`] logs in to a token through its vendor module (for
+//! example SoftHSM's `libsofthsm2.so` or a YubiHSM connector) and wraps
+//! shares with `CKM_AES_GCM` under a secret key object found by label. The
+//! key never leaves the device, so a wrapped share can only be opened where
+//! the token is present and unlocked.
+
+use std::path::Path;
+
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::aead::GcmParams;
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+
+use crate::custody::{ShareCustody, WrappedShare};
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+
+const IV_LEN: u32 = 12;
+const TAG_BITS: u64 = 128;
+
+/// Wraps shares under an AES key held in a PKCS#11 token.
+pub struct Pkcs11Custody {
+    session: Session,
+    key: ObjectHandle,
+    key_label: String,
+}
+
+impl Pkcs11Custody {
+    /// Loads `module`, opens the token labelled `token`, logs in as the user
+    /// with `pin` and looks up the AES key labelled `key_label`.
+    pub fn open(module: impl AsRef<Path>, token: &str, pin: &str, key_label: &str) -> Result<Self> {
+        let context = Pkcs11::new(module.as_ref()).map_err(pkcs11_error)?;
+        context
+            .initialize(CInitializeArgs::OsThreads)
+            .map_err(pkcs11_error)?;
+
+        let mut slot = None;
+        for candidate in context.get_slots_with_token().map_err(pkcs11_error)? {
+            let info = context.get_token_info(candidate).map_err(pkcs11_error)?;
+            if info.label().trim_end() == token {
+                slot = Some(candidate);
+                break;
+            }
+        }
+        let slot = slot.ok_or_else(|| Error::Pkcs11(format!("no token labelled {token:?}")))?;
+
+        let session = context.open_ro_session(slot).map_err(pkcs11_error)?;
+        session
+            .login(UserType::User, Some(&AuthPin::new(pin.into())))
+            .map_err(pkcs11_error)?;
+        let key = match session
+            .find_objects(&[
+                Attribute::Class(ObjectClass::SECRET_KEY),
+                Attribute::KeyType(KeyType::AES),
+                Attribute::Label(key_label.as_bytes().to_vec()),
+            ])
+            .map_err(pkcs11_error)?
+            .as_slice()
+        {
+            [key] => *key,
+            [] => return Err(Error::Pkcs11(format!("no AES key labelled {key_label:?}"))),
+            _ => {
+                return Err(Error::Pkcs11(format!(
+                    "several AES keys are labelled {key_label:?}"
+                )))
+            }
+        };
+
+        Ok(Pkcs11Custody {
+            session,
+            key,
+            key_label: key_label.into(),
+        })
+    }
+}
+
+impl ShareCustody for Pkcs11Custody {
+    fn wrap(&self, share: &SerializedShare) -> Result<WrappedShare> {
+        let iv = self
+            .session
+            .generate_random_vec(IV_LEN)
+            .map_err(pkcs11_error)?;
+        let mut wrapped = WrappedShare::new(self.key_label.as_str(), iv);
+        let header = wrapped.header();
+        let mechanism = Mechanism::AesGcm(GcmParams::new(&wrapped.iv, &header, TAG_BITS.into()));
+        wrapped.ciphertext = self
+            .session
+            .encrypt(&mechanism, self.key, &share.to_bytes())
+            .map_err(pkcs11_error)?;
+        Ok(wrapped)
+    }
+
+    fn unwrap(&self, wrapped: &WrappedShare) -> Result<SerializedShare> {
+        if wrapped.key_label != self.key_label {
+            return Err(Error::DecryptionFailed);
+        }
+        let header = wrapped.header();
+        let mechanism = Mechanism::AesGcm(GcmParams::new(&wrapped.iv, &header, TAG_BITS.into()));
+        let plaintext = self
+            .session
+            .decrypt(&mechanism, self.key, &wrapped.ciphertext)
+            .map_err(|_| Error::DecryptionFailed)?;
+        SerializedShare::from_bytes(&plaintext)
+    }
+}
+
+fn pkcs11_error(err: cryptoki::error::Error) -> Error {
+    Error::Pkcs11(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_module_is_reported() {
+        let err = Pkcs11Custody::open("/nonexistent/libpkcs11.so", "token", "1234", "key")
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Pkcs11(_)));
+    }
+}
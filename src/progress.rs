@@ -0,0 +1,123 @@
+// File: src/progress.rs
+//! Progress reporting and cancellation for long operations.
+//!
+//! Streaming splits of large files and dealings with many shares over big
+//! fields take long enough that GUI and server integrations want a progress
+//! bar and a way to stop. The operations that support it take a
+//! [`Progress`], call its callback with the units done so far and the total
+//! when known, and stop with [`Error::Cancelled`] at the next step once its
+//! [`CancellationToken`] is cancelled, e.g. from another thread.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+
+/// A shared flag that asks running operations to stop.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every operation holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Called with the units done and the total, if known.
+type Callback<'a> = Box<dyn FnMut(u64, Option<u64>) + 'a>;
+
+/// The observer of a long operation.
+#[derive(Default)]
+pub struct Progress<'a> {
+    callback: Option<Callback<'a>>,
+    token: Option<CancellationToken>,
+}
+
+impl<'a> Progress<'a> {
+    /// Reports nothing and never cancels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `callback` with the units done and the total, if known, after
+    /// every step.
+    pub fn on_progress(mut self, callback: impl FnMut(u64, Option<u64>) + 'a) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    pub fn with_token(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Fails with [`Error::Cancelled`] if the token was cancelled.
+    pub fn check(&self) -> Result<()> {
+        match &self.token {
+            Some(token) if token.is_cancelled() => Err(Error::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reports that `done` units are finished, then checks for cancellation.
+    pub fn step(&mut self, done: u64, total: Option<u64>) -> Result<()> {
+        if let Some(callback) = &mut self.callback {
+            callback(done, total);
+        }
+        self.check()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sss::SecretSharer;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_progress_and_cancellation() {
+        let sharer = SecretSharer::new(3, 20);
+        let secret = BigUint::from(77u32);
+        let mut steps = Vec::new();
+        let shares = sharer
+            .split_secret_with_progress(
+                &secret,
+                &mut Progress::new().on_progress(|done, total| steps.push((done, total))),
+            )
+            .unwrap();
+        assert_eq!(shares.len(), 20);
+        assert_eq!(steps.len(), 20);
+        assert_eq!(steps[19], (20, Some(20)));
+        assert_eq!(
+            sharer.reconstruct_secret(&shares[5..8]),
+            Some(secret.clone())
+        );
+
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let mut seen = 0;
+        let mut progress = Progress::new()
+            .on_progress(|done, _| {
+                seen = done;
+                if done == 5 {
+                    canceller.cancel();
+                }
+            })
+            .with_token(token);
+        assert!(matches!(
+            sharer.split_secret_with_progress(&secret, &mut progress),
+            Err(Error::Cancelled)
+        ));
+        drop(progress);
+        assert_eq!(seen, 5);
+    }
+}
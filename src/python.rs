@@ -0,0 +1,187 @@
+// File: src/python.rs
+//! Python bindings (`python` feature).
+//!
+//! Built with `maturin build` (see `pyproject.toml`), this produces an `sss`
+//! extension module for ops tooling: `split`, `split_vss`, `combine` and
+//! `verify`, plus a `Share` class for moving shares between the armored,
+//! bech32 and binary encodings. Secrets and binary encodings are `bytes`;
+//! every library error is raised as `sss.SssError`.
+
+use num_bigint::BigUint;
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::error::Error;
+use crate::params::GroupParameters;
+use crate::serialized::{reconstruct_auto, split_bytes, SerializedShare};
+use crate::service;
+
+create_exception!(sss, SssError, PyValueError);
+
+impl From<Error> for PyErr {
+    fn from(err: Error) -> Self {
+        SssError::new_err(err.to_string())
+    }
+}
+
+/// A self-describing share of any scheme.
+#[pyclass(name = "Share", module = "sss", frozen)]
+pub struct PyShare(SerializedShare);
+
+#[pymethods]
+impl PyShare {
+    /// Reads an armored or bech32 share.
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        Ok(PyShare(parse(text)?))
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(PyShare(SerializedShare::from_bytes(bytes)?))
+    }
+
+    fn to_armored(&self) -> String {
+        self.0.to_armored()
+    }
+
+    fn to_bech32(&self) -> String {
+        self.0.to_bech32()
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.to_bytes())
+    }
+
+    #[getter]
+    fn index(&self) -> BigUint {
+        self.0.x.clone()
+    }
+
+    #[getter]
+    fn threshold(&self) -> usize {
+        self.0.threshold
+    }
+
+    #[getter]
+    fn total(&self) -> usize {
+        self.0.total
+    }
+
+    #[getter]
+    fn label(&self) -> Option<String> {
+        self.0.label.clone()
+    }
+
+    #[getter]
+    fn session(&self) -> Option<String> {
+        self.0.session().map(String::from)
+    }
+
+    fn fingerprint(&self) -> String {
+        self.0.fingerprint()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Share(index={}, threshold={}, total={}, fingerprint='{}')",
+            self.0.x,
+            self.0.threshold,
+            self.0.total,
+            self.0.fingerprint()
+        )
+    }
+}
+
+/// Splits a secret into `shares` armored byte-wise shares, any `threshold`
+/// of which recover it.
+#[pyfunction]
+fn split(secret: &[u8], threshold: usize, shares: usize) -> PyResult<Vec<String>> {
+    Ok(split_bytes(secret, threshold, shares)?
+        .iter()
+        .map(SerializedShare::to_armored)
+        .collect())
+}
+
+/// Deals Feldman VSS shares over a parameter document from `sss gen-params`,
+/// returning the armored shares and the commitment document as JSON.
+#[pyfunction]
+fn split_vss(
+    secret: &[u8],
+    threshold: usize,
+    shares: usize,
+    group_parameters: &str,
+) -> PyResult<(Vec<String>, String)> {
+    let group = GroupParameters::from_json(group_parameters)?;
+    let dealing = service::deal(secret, threshold, shares, Some(&group))?;
+    Ok((
+        dealing.shares.iter().map(|s| s.to_armored()).collect(),
+        dealing
+            .commitments
+            .expect("VSS dealings have commitments")
+            .to_json()?,
+    ))
+}
+
+/// Recovers the secret from armored or bech32 shares of any scheme.
+#[pyfunction]
+fn combine<'py>(py: Python<'py>, shares: Vec<String>) -> PyResult<Bound<'py, PyBytes>> {
+    let shares = shares
+        .iter()
+        .map(|text| parse(text))
+        .collect::<crate::Result<Vec<_>>>()?;
+    Ok(PyBytes::new(py, &reconstruct_auto(&shares)?))
+}
+
+/// Checks a Feldman share against a commitment document (JSON or armor).
+#[pyfunction]
+fn verify(share: &str, commitments: &str) -> PyResult<bool> {
+    Ok(service::verify_share(share, commitments)?)
+}
+
+fn parse(text: &str) -> crate::Result<SerializedShare> {
+    SerializedShare::from_armored(text).or_else(|_| SerializedShare::from_bech32(text))
+}
+
+#[pymodule]
+#[pyo3(name = "sss")]
+fn sss_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("SssError", m.py().get_type::<SssError>())?;
+    m.add_class::<PyShare>()?;
+    m.add_function(wrap_pyfunction!(split, m)?)?;
+    m.add_function(wrap_pyfunction!(split_vss, m)?)?;
+    m.add_function(wrap_pyfunction!(combine, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let shares = split(b"ops tooling", 2, 3).unwrap();
+            let share = PyShare::parse(&shares[2]).unwrap();
+            let bech32 = share.to_bech32();
+            let binary = share.to_bytes(py);
+            assert_eq!(
+                PyShare::from_bytes(binary.as_bytes()).unwrap().to_armored(),
+                shares[2]
+            );
+
+            let recovered = combine(py, vec![bech32, shares[0].clone()]).unwrap();
+            assert_eq!(recovered.as_bytes(), b"ops tooling");
+            let err = combine(py, vec!["not a share".into()]).unwrap_err();
+            assert!(err.is_instance_of::<SssError>(py));
+
+            let group = GroupParameters::generate(256, 96).unwrap();
+            let (shares, commitments) = split_vss(&[9], 2, 3, &group.to_json().unwrap()).unwrap();
+            assert!(verify(&shares[1], &commitments).unwrap());
+        });
+    }
+}
@@ -0,0 +1,84 @@
+// File: src/qr.rs
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::render::svg;
+use qrcode::{EcLevel, QrCode};
+
+use crate::error::{Error, Result};
+
+/// Output image format for rendered QR codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QrFormat {
+    Png,
+    Svg,
+}
+
+/// Finds and decodes every QR code in a PNG or JPEG image.
+pub fn decode(image: &[u8]) -> Result<Vec<String>> {
+    let image = image::load_from_memory(image)
+        .map_err(|e| Error::Qr(e.to_string()))?
+        .into_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32)[0],
+    );
+    let texts = prepared
+        .detect_grids()
+        .iter()
+        .map(|grid| {
+            grid.decode()
+                .map(|(_, text)| text)
+                .map_err(|e| Error::Qr(e.to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if texts.is_empty() {
+        return Err(Error::Qr("no QR code found in image".into()));
+    }
+    Ok(texts)
+}
+
+/// Renders `text` as a QR code with medium error correction.
+pub fn render(text: &str, format: QrFormat) -> Result<Vec<u8>> {
+    let code = QrCode::with_error_correction_level(text.as_bytes(), EcLevel::M)
+        .map_err(|e| Error::Qr(e.to_string()))?;
+
+    match format {
+        QrFormat::Svg => Ok(code
+            .render::<svg::Color>()
+            .min_dimensions(300, 300)
+            .build()
+            .into_bytes()),
+        QrFormat::Png => {
+            let image = code.render::<Luma<u8>>().min_dimensions(300, 300).build();
+            let mut out = Cursor::new(Vec::new());
+            DynamicImage::ImageLuma8(image)
+                .write_to(&mut out, ImageFormat::Png)
+                .map_err(|e| Error::Qr(e.to_string()))?;
+            Ok(out.into_inner())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sss::SecretSharer;
+    use num_bigint::ToBigUint;
+
+    #[test]
+    fn test_share_qr_rendering() {
+        let sharer = SecretSharer::new(2, 3);
+        let shares = sharer.split_secret(&42u32.to_biguint().unwrap());
+
+        let png = shares[0].to_qr(QrFormat::Png).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+
+        let svg = String::from_utf8(shares[0].to_qr(QrFormat::Svg).unwrap()).unwrap();
+        assert!(svg.contains("<svg"));
+
+        assert_eq!(decode(&png).unwrap(), vec![shares[0].to_armored()]);
+        assert!(decode(&render("", QrFormat::Svg).unwrap()).is_err());
+    }
+}
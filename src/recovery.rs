@@ -0,0 +1,243 @@
+// File: src/recovery.rs
+//! Social recovery of a secret through guardians.
+//!
+//! The owner registers each guardian's X25519 public key in a
+//! [`RecoveryPlan`], deals the secret with [`RecoveryPlan::deal`] (one share
+//! sealed to each guardian) and records each guardian's acknowledgment, the
+//! fingerprint of the share it opened. To recover, a new device starts a
+//! [`RecoveryRequest`] under its own key; each approving guardian
+//! [`release`]s its share resealed to that key, and once a threshold have
+//! approved the device opens them and reconstructs the secret.
+//!
+//! Released shares are checked against the fingerprints recorded at dealing
+//! time, so a guardian cannot substitute a different share. Guardians should
+//! confirm the request id and requester key with the owner out of band before
+//! approving: anyone can start a request.
+
+use std::collections::BTreeMap;
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::ecies::EncryptedShare;
+use crate::encoding::hex;
+use crate::error::{Error, Result};
+use crate::serialized::{reconstruct_auto, split_bytes};
+
+/// A registered guardian and the state of its share.
+#[derive(Clone, Debug)]
+pub struct Guardian {
+    pub name: String,
+    pub key: PublicKey,
+    /// Fingerprint of the share dealt to this guardian.
+    pub fingerprint: Option<String>,
+    pub acknowledged: bool,
+}
+
+/// The owner's view of its guardians and the dealing.
+#[derive(Clone, Debug)]
+pub struct RecoveryPlan {
+    threshold: usize,
+    guardians: Vec<Guardian>,
+}
+
+impl RecoveryPlan {
+    pub fn new(threshold: usize) -> Self {
+        RecoveryPlan {
+            threshold,
+            guardians: Vec::new(),
+        }
+    }
+
+    pub fn guardians(&self) -> &[Guardian] {
+        &self.guardians
+    }
+
+    /// Adds a guardian. Names and keys must be unique; registering after a
+    /// dealing requires dealing again.
+    pub fn register(&mut self, name: impl Into<String>, key: PublicKey) -> Result<()> {
+        let name = name.into();
+        if self
+            .guardians
+            .iter()
+            .any(|g| g.name == name || g.key == key)
+        {
+            return Err(Error::InvalidParameters("guardian already registered"));
+        }
+        self.guardians.push(Guardian {
+            name,
+            key,
+            fingerprint: None,
+            acknowledged: false,
+        });
+        Ok(())
+    }
+
+    /// Splits `secret` among the registered guardians and seals each share
+    /// to its guardian, clearing earlier acknowledgments.
+    pub fn deal(&mut self, secret: &[u8]) -> Result<Vec<(String, EncryptedShare)>> {
+        if self.threshold == 0 || self.threshold > self.guardians.len() {
+            return Err(Error::InvalidParameters(
+                "threshold must be between 1 and the number of guardians",
+            ));
+        }
+        let shares = split_bytes(secret, self.threshold, self.guardians.len())?;
+        let mut sealed = Vec::with_capacity(shares.len());
+        for (guardian, share) in self.guardians.iter_mut().zip(shares) {
+            let share = share.with_label(guardian.name.clone());
+            sealed.push((
+                guardian.name.clone(),
+                EncryptedShare::seal(&share, &guardian.key)?,
+            ));
+            guardian.fingerprint = Some(share.fingerprint());
+            guardian.acknowledged = false;
+        }
+        Ok(sealed)
+    }
+
+    /// Records that `name` opened its share, which had `fingerprint`.
+    pub fn acknowledge(&mut self, name: &str, fingerprint: &str) -> Result<()> {
+        let guardian = self
+            .guardians
+            .iter_mut()
+            .find(|g| g.name == name)
+            .ok_or(Error::InvalidParameters("unknown guardian"))?;
+        if guardian.fingerprint.as_deref() != Some(fingerprint) {
+            return Err(Error::DigestMismatch);
+        }
+        guardian.acknowledged = true;
+        Ok(())
+    }
+
+    /// Guardians that have not acknowledged their share yet.
+    pub fn unacknowledged(&self) -> Vec<&str> {
+        self.guardians
+            .iter()
+            .filter(|g| !g.acknowledged)
+            .map(|g| g.name.as_str())
+            .collect()
+    }
+
+    /// Opens a recovery request for a device holding `requester`'s secret key.
+    pub fn start_recovery(&self, requester: PublicKey) -> Result<RecoveryRequest> {
+        let expected = self
+            .guardians
+            .iter()
+            .map(|g| Some((g.name.clone(), g.fingerprint.clone()?)))
+            .collect::<Option<_>>()
+            .ok_or(Error::InvalidParameters("the secret has not been dealt"))?;
+        let mut id = [0u8; 16];
+        OsRng.fill_bytes(&mut id);
+        Ok(RecoveryRequest {
+            id: hex(&id),
+            requester,
+            threshold: self.threshold,
+            expected,
+            approvals: BTreeMap::new(),
+        })
+    }
+}
+
+/// A recovery in progress, collecting released shares.
+#[derive(Clone, Debug)]
+pub struct RecoveryRequest {
+    pub id: String,
+    pub requester: PublicKey,
+    threshold: usize,
+    expected: BTreeMap<String, String>,
+    approvals: BTreeMap<String, EncryptedShare>,
+}
+
+impl RecoveryRequest {
+    /// Accepts `name`'s share, released to the requester.
+    pub fn approve(&mut self, name: &str, share: EncryptedShare) -> Result<()> {
+        if !self.expected.contains_key(name) {
+            return Err(Error::InvalidParameters("unknown guardian"));
+        }
+        if share.recipient != self.requester.to_bytes() {
+            return Err(Error::InvalidParameters(
+                "share is not sealed to the requester",
+            ));
+        }
+        self.approvals.insert(name.to_string(), share);
+        Ok(())
+    }
+
+    pub fn approvals(&self) -> usize {
+        self.approvals.len()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.approvals.len() >= self.threshold
+    }
+
+    /// Opens the released shares with the requester's secret key, checks
+    /// each against its dealt fingerprint and reconstructs the secret.
+    pub fn complete(&self, requester: &StaticSecret) -> Result<Vec<u8>> {
+        if !self.is_ready() {
+            return Err(Error::InsufficientShares);
+        }
+        let mut shares = Vec::with_capacity(self.approvals.len());
+        for (name, sealed) in &self.approvals {
+            let share = sealed.open(requester)?;
+            if share.fingerprint() != self.expected[name] {
+                return Err(Error::DigestMismatch);
+            }
+            shares.push(share);
+        }
+        reconstruct_auto(&shares)
+    }
+}
+
+/// A guardian's approval: opens its share and reseals it to the requester.
+pub fn release(
+    share: &EncryptedShare,
+    guardian: &StaticSecret,
+    request: &RecoveryRequest,
+) -> Result<EncryptedShare> {
+    EncryptedShare::seal(&share.open(guardian)?, &request.requester)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_social_recovery() {
+        let keys: Vec<_> = (0..3)
+            .map(|_| StaticSecret::random_from_rng(OsRng))
+            .collect();
+        let mut plan = RecoveryPlan::new(2);
+        for (name, key) in ["alice", "bob", "carol"].iter().zip(&keys) {
+            plan.register(*name, PublicKey::from(key)).unwrap();
+        }
+        assert!(plan.register("alice", PublicKey::from(&keys[1])).is_err());
+
+        let secret = b"wallet seed".to_vec();
+        let dealt = plan.deal(&secret).unwrap();
+        for ((name, sealed), key) in dealt.iter().zip(&keys).take(2) {
+            let fingerprint = sealed.open(key).unwrap().fingerprint();
+            plan.acknowledge(name, &fingerprint).unwrap();
+        }
+        assert_eq!(plan.unacknowledged(), vec!["carol"]);
+
+        let device = StaticSecret::random_from_rng(OsRng);
+        let mut request = plan.start_recovery(PublicKey::from(&device)).unwrap();
+        let released = release(&dealt[0].1, &keys[0], &request).unwrap();
+        request.approve("alice", released).unwrap();
+        assert!(request.complete(&device).is_err());
+
+        // A guardian releasing someone else's share is caught.
+        let swapped = release(&dealt[2].1, &keys[2], &request).unwrap();
+        request.approve("bob", swapped).unwrap();
+        assert!(matches!(
+            request.complete(&device),
+            Err(Error::DigestMismatch)
+        ));
+
+        let released = release(&dealt[1].1, &keys[1], &request).unwrap();
+        request.approve("bob", released).unwrap();
+        assert_eq!(request.complete(&device).unwrap(), secret);
+    }
+}
@@ -0,0 +1,263 @@
+// File: src/refresh.rs
+//! Proactive refresh of GF(2^8) shares.
+//!
+//! Every shareholder deals a random polynomial with a zero constant term and
+//! sends its value at each other holder's index as a [`RefreshMessage`].
+//! Adding all contributions to a share yields a fresh sharing of the same
+//! secret, so shares stolen before the refresh stop combining with shares
+//! taken after it. The secret is never reassembled.
+//!
+//! Participants are the indices `1..=total` produced by
+//! [`crate::serialized::split_bytes`], and all of them must contribute. Each
+//! refresh advances the share's `epoch` metadata, and shares from different
+//! epochs are refused by [`crate::serialized::reconstruct_auto`].
+//!
+//! Messages are not encrypted: anyone who sees the messages addressed to a
+//! holder can undo that holder's refresh, so deliver them privately.
+
+use num_traits::ToPrimitive;
+use rand::thread_rng;
+
+use crate::encoding::{
+    armor, dearmor, put_bytes, put_bytes_u32, take_bytes, take_bytes_u32, take_u32,
+};
+use crate::error::{Error, Result};
+use crate::gf256::{self, Gf256};
+use crate::serialized::{Scheme, SerializedShare};
+
+const ARMOR_LABEL: &str = "REFRESH MESSAGE";
+const MAGIC: &[u8; 3] = b"SHM";
+/// Version 2 widened the delta's length prefix from u16 to u32; version 1
+/// messages still decode.
+const MESSAGE_VERSION: u8 = 2;
+
+/// Metadata key counting how many refreshes a share has been through.
+pub const EPOCH_KEY: &str = "epoch";
+
+/// One holder's contribution to another holder's refreshed share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefreshMessage {
+    pub session: String,
+    /// The epoch being refreshed; the new shares carry `epoch + 1`.
+    pub epoch: u32,
+    pub from: u8,
+    pub to: u8,
+    pub delta: Vec<u8>,
+}
+
+/// The refresh epoch recorded on a share; unrefreshed shares are at epoch 0.
+pub fn epoch(share: &SerializedShare) -> Result<u32> {
+    match share.metadata.get(EPOCH_KEY) {
+        Some(epoch) => epoch
+            .parse()
+            .map_err(|_| Error::InvalidEncoding("share epoch is not a number")),
+        None => Ok(0),
+    }
+}
+
+/// Generates this holder's messages for every participant, itself included.
+pub fn contributions(share: &SerializedShare) -> Result<Vec<RefreshMessage>> {
+    let (field, from) = check_share(share)?;
+    let xs: Vec<u8> = (1..=share.total as u8).collect();
+    let zero = vec![0u8; share.value.len()];
+    let deltas = gf256::split(field, &zero, share.threshold, &xs, &mut thread_rng())?;
+    let session = share.session().unwrap_or_default().to_string();
+    let epoch = epoch(share)?;
+    Ok(xs
+        .into_iter()
+        .zip(deltas)
+        .map(|(to, delta)| RefreshMessage {
+            session: session.clone(),
+            epoch,
+            from,
+            to,
+            delta,
+        })
+        .collect())
+}
+
+/// Applies one message from each participant, returning the refreshed share.
+pub fn apply(share: &SerializedShare, messages: &[RefreshMessage]) -> Result<SerializedShare> {
+    let (_, x) = check_share(share)?;
+    let session = share.session().unwrap_or_default();
+    let epoch = epoch(share)?;
+
+    let mut seen = vec![false; share.total + 1];
+    let mut value = share.value.clone();
+    for message in messages {
+        if message.to != x || message.session != session || message.epoch != epoch {
+            return Err(Error::InconsistentShares(
+                "refresh message is for another share or epoch",
+            ));
+        }
+        if message.delta.len() != value.len() {
+            return Err(Error::InconsistentShares("refresh message length differs"));
+        }
+        match seen.get_mut(message.from as usize) {
+            Some(seen) if message.from != 0 && !*seen => *seen = true,
+            _ => {
+                return Err(Error::InconsistentShares(
+                    "unexpected or duplicate refresh sender",
+                ))
+            }
+        }
+        for (byte, delta) in value.iter_mut().zip(&message.delta) {
+            *byte ^= delta;
+        }
+    }
+    if seen[1..].contains(&false) {
+        return Err(Error::InsufficientShares);
+    }
+
+    let mut refreshed = share.clone();
+    refreshed.value = value;
+    refreshed.signature = None;
+    Ok(refreshed.with_metadata(EPOCH_KEY, (epoch + 1).to_string()))
+}
+
+fn check_share(share: &SerializedShare) -> Result<(Gf256, u8)> {
+    let Scheme::Gf256 { poly } = share.scheme else {
+        return Err(Error::InvalidParameters(
+            "only GF(2^8) shares can be refreshed",
+        ));
+    };
+    if share.total > 255 || share.threshold == 0 || share.threshold > share.total {
+        return Err(Error::InvalidParameters(
+            "threshold must be between 1 and the total",
+        ));
+    }
+    match share.x.to_u8() {
+        Some(x) if x != 0 && x as usize <= share.total => Ok((Gf256::new(poly), x)),
+        _ => Err(Error::MalformedShare),
+    }
+}
+
+impl RefreshMessage {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(MESSAGE_VERSION);
+        put_bytes(&mut out, self.session.as_bytes());
+        out.extend_from_slice(&self.epoch.to_be_bytes());
+        out.push(self.from);
+        out.push(self.to);
+        put_bytes_u32(&mut out, &self.delta);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if !(1..=MESSAGE_VERSION).contains(&version) {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let session = String::from_utf8(take_bytes(&mut input)?.to_vec())
+            .map_err(|_| Error::InvalidEncoding("session is not UTF-8"))?;
+        let epoch = take_u32(&mut input)?;
+        let [from, to, ref rest @ ..] = *input else {
+            return Err(Error::MalformedShare);
+        };
+        input = rest;
+        let delta = if version >= 2 {
+            take_bytes_u32(&mut input)?
+        } else {
+            take_bytes(&mut input)?
+        }
+        .to_vec();
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(RefreshMessage {
+            session,
+            epoch,
+            from,
+            to,
+            delta,
+        })
+    }
+
+    /// Encodes the message as an ASCII-armored text block.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::{reconstruct_auto, split_bytes};
+
+    #[test]
+    fn test_refresh_preserves_secret() {
+        let shares: Vec<_> = split_bytes(b"rotate me", 2, 3)
+            .unwrap()
+            .into_iter()
+            .map(|share| share.with_session("abcd"))
+            .collect();
+        let messages: Vec<RefreshMessage> = shares
+            .iter()
+            .flat_map(|share| contributions(share).unwrap())
+            .map(|m| RefreshMessage::from_armored(&m.to_armored()).unwrap())
+            .collect();
+        let refreshed: Vec<_> = shares
+            .iter()
+            .map(|share| {
+                let inbox: Vec<_> = messages
+                    .iter()
+                    .filter(|m| share.x == m.to.into())
+                    .cloned()
+                    .collect();
+                apply(share, &inbox).unwrap()
+            })
+            .collect();
+
+        assert!(refreshed
+            .iter()
+            .zip(&shares)
+            .all(|(new, old)| new.value != old.value));
+        assert_eq!(epoch(&refreshed[0]).unwrap(), 1);
+        assert_eq!(reconstruct_auto(&refreshed[1..]).unwrap(), b"rotate me");
+        // Old and new shares no longer mix
+        assert!(reconstruct_auto(&[shares[0].clone(), refreshed[1].clone()]).is_err());
+        // Every participant must contribute
+        let partial: Vec<_> = messages
+            .iter()
+            .filter(|m| m.to == 1 && m.from != 3)
+            .cloned()
+            .collect();
+        assert!(matches!(
+            apply(&shares[0], &partial),
+            Err(Error::InsufficientShares)
+        ));
+    }
+
+    #[test]
+    fn test_large_delta_message() {
+        let message = RefreshMessage {
+            session: "abcd".into(),
+            epoch: 0,
+            from: 1,
+            to: 2,
+            delta: vec![7; 70_000],
+        };
+        assert_eq!(
+            RefreshMessage::from_bytes(&message.to_bytes()).unwrap(),
+            message
+        );
+
+        // Version 1 messages, with a u16 delta length, still decode
+        let mut v1 = MAGIC.to_vec();
+        v1.push(1);
+        v1.extend_from_slice(&[0, 4]);
+        v1.extend_from_slice(b"abcd");
+        v1.extend_from_slice(&0u32.to_be_bytes());
+        v1.extend_from_slice(&[1, 2, 0, 1, 7]);
+        let decoded = RefreshMessage::from_bytes(&v1).unwrap();
+        assert_eq!(decoded.delta, [7]);
+    }
+}
@@ -0,0 +1,255 @@
+// File: src/reshare.rs
+//! Resharing GF(2^8) shares to a new threshold or set of custodians.
+//!
+//! A quorum of old holders (the dealers) each scale their share by its
+//! Lagrange coefficient for that quorum and deal the result to the new
+//! custodians as a [`ReshareMessage`]. Each new custodian adds up one message
+//! from every dealer; the sums form a fresh `threshold`-of-`total` sharing of
+//! the same secret, which is never reassembled.
+//!
+//! New shares advance the `epoch` metadata like a refresh does, so they do
+//! not combine with the old ones. As with refresh, messages must reach their
+//! custodian privately.
+
+use num_traits::ToPrimitive;
+use rand::thread_rng;
+
+use crate::encoding::{
+    armor, dearmor, put_bytes, put_bytes_u32, take_bytes, take_bytes_u32, take_u32,
+};
+use crate::error::{Error, Result};
+use crate::gf256::{self, Gf256};
+use crate::refresh::{epoch, EPOCH_KEY};
+use crate::serialized::{Scheme, SerializedShare};
+
+const ARMOR_LABEL: &str = "RESHARE MESSAGE";
+const MAGIC: &[u8; 3] = b"SHX";
+/// Version 2 widened the delta's length prefix from u16 to u32; version 1
+/// messages still decode.
+const MESSAGE_VERSION: u8 = 2;
+
+/// One dealer's contribution to a new custodian's share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReshareMessage {
+    pub session: String,
+    /// The epoch of the old shares; the new shares carry `epoch + 1`.
+    pub epoch: u32,
+    pub poly: u16,
+    /// Indices of the old holders taking part, in ascending order.
+    pub dealers: Vec<u8>,
+    pub threshold: usize,
+    pub total: usize,
+    pub from: u8,
+    pub to: u8,
+    pub delta: Vec<u8>,
+}
+
+/// Deals this holder's part of a `threshold`-of-`total` resharing.
+///
+/// `dealers` lists the old indices taking part, this holder's included; it
+/// must reach the old threshold and be identical for every dealer.
+pub fn contributions(
+    share: &SerializedShare,
+    dealers: &[u8],
+    threshold: usize,
+    total: usize,
+) -> Result<Vec<ReshareMessage>> {
+    let Scheme::Gf256 { poly } = share.scheme else {
+        return Err(Error::InvalidParameters(
+            "only GF(2^8) shares can be reshared",
+        ));
+    };
+    if total > 255 {
+        return Err(Error::InvalidParameters("at most 255 shares are supported"));
+    }
+    let mut dealers = dealers.to_vec();
+    dealers.sort_unstable();
+    dealers.dedup();
+    if dealers.len() < share.threshold {
+        return Err(Error::InsufficientShares);
+    }
+    let from = share.x.to_u8().ok_or(Error::MalformedShare)?;
+    if from == 0 || dealers.contains(&0) || !dealers.contains(&from) {
+        return Err(Error::InvalidParameters(
+            "dealers must be non-zero and include this share",
+        ));
+    }
+
+    let field = Gf256::new(poly);
+    let lambda = dealers
+        .iter()
+        .filter(|&&x| x != from)
+        .fold(1, |acc, &x| field.mul(acc, field.div(x, x ^ from)));
+    let scaled: Vec<u8> = share.value.iter().map(|&b| field.mul(lambda, b)).collect();
+    let xs: Vec<u8> = (1..=total as u8).collect();
+    let deltas = gf256::split(field, &scaled, threshold, &xs, &mut thread_rng())?;
+
+    let session = share.session().unwrap_or_default().to_string();
+    let epoch = epoch(share)?;
+    Ok(xs
+        .into_iter()
+        .zip(deltas)
+        .map(|(to, delta)| ReshareMessage {
+            session: session.clone(),
+            epoch,
+            poly,
+            dealers: dealers.clone(),
+            threshold,
+            total,
+            from,
+            to,
+            delta,
+        })
+        .collect())
+}
+
+/// Adds up one message from every dealer into the custodian's new share.
+pub fn combine(messages: &[ReshareMessage]) -> Result<SerializedShare> {
+    let first = messages.first().ok_or(Error::InsufficientShares)?;
+    let mut value = vec![0u8; first.delta.len()];
+    let mut senders = Vec::with_capacity(messages.len());
+    for message in messages {
+        let same_round = message.session == first.session
+            && message.epoch == first.epoch
+            && message.poly == first.poly
+            && message.dealers == first.dealers
+            && (message.threshold, message.total) == (first.threshold, first.total)
+            && message.to == first.to;
+        if !same_round || message.delta.len() != value.len() {
+            return Err(Error::InconsistentShares(
+                "reshare messages belong to different rounds",
+            ));
+        }
+        senders.push(message.from);
+        for (byte, delta) in value.iter_mut().zip(&message.delta) {
+            *byte ^= delta;
+        }
+    }
+    senders.sort_unstable();
+    if senders.windows(2).any(|w| w[0] == w[1]) {
+        return Err(Error::InconsistentShares("duplicate reshare sender"));
+    }
+    if senders != first.dealers {
+        return Err(Error::InsufficientShares);
+    }
+
+    let mut share = SerializedShare::gf256(
+        Gf256::new(first.poly),
+        first.threshold,
+        first.total,
+        first.to,
+        value,
+    )
+    .with_metadata(EPOCH_KEY, (first.epoch + 1).to_string());
+    if !first.session.is_empty() {
+        share = share.with_session(first.session.clone());
+    }
+    Ok(share)
+}
+
+impl ReshareMessage {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(MESSAGE_VERSION);
+        put_bytes(&mut out, self.session.as_bytes());
+        out.extend_from_slice(&self.epoch.to_be_bytes());
+        out.extend_from_slice(&self.poly.to_be_bytes());
+        put_bytes(&mut out, &self.dealers);
+        out.extend_from_slice(&(self.threshold as u32).to_be_bytes());
+        out.extend_from_slice(&(self.total as u32).to_be_bytes());
+        out.push(self.from);
+        out.push(self.to);
+        put_bytes_u32(&mut out, &self.delta);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if !(1..=MESSAGE_VERSION).contains(&version) {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let session = String::from_utf8(take_bytes(&mut input)?.to_vec())
+            .map_err(|_| Error::InvalidEncoding("session is not UTF-8"))?;
+        let epoch = take_u32(&mut input)?;
+        let [p0, p1, ref rest @ ..] = *input else {
+            return Err(Error::MalformedShare);
+        };
+        input = rest;
+        let dealers = take_bytes(&mut input)?.to_vec();
+        let threshold = take_u32(&mut input)? as usize;
+        let total = take_u32(&mut input)? as usize;
+        let [from, to, ref rest @ ..] = *input else {
+            return Err(Error::MalformedShare);
+        };
+        input = rest;
+        let delta = if version >= 2 {
+            take_bytes_u32(&mut input)?
+        } else {
+            take_bytes(&mut input)?
+        }
+        .to_vec();
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(ReshareMessage {
+            session,
+            epoch,
+            poly: u16::from_be_bytes([p0, p1]),
+            dealers,
+            threshold,
+            total,
+            from,
+            to,
+            delta,
+        })
+    }
+
+    /// Encodes the message as an ASCII-armored text block.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::{reconstruct_auto, split_bytes};
+
+    #[test]
+    fn test_reshare_to_more_custodians() {
+        let old = split_bytes(b"grow the quorum", 3, 5).unwrap();
+        let dealers = [2, 4, 5];
+        let messages: Vec<ReshareMessage> = dealers
+            .iter()
+            .flat_map(|&x| contributions(&old[x as usize - 1], &dealers, 4, 7).unwrap())
+            .map(|m| ReshareMessage::from_armored(&m.to_armored()).unwrap())
+            .collect();
+        let new: Vec<SerializedShare> = (1..=7)
+            .map(|to| {
+                let inbox: Vec<_> = messages.iter().filter(|m| m.to == to).cloned().collect();
+                combine(&inbox).unwrap()
+            })
+            .collect();
+
+        assert_eq!((new[0].threshold, new[0].total), (4, 7));
+        assert_eq!(reconstruct_auto(&new[3..]).unwrap(), b"grow the quorum");
+        assert!(reconstruct_auto(&new[..3]).is_err());
+
+        // A missing dealer leaves the custodian unable to finish
+        let partial: Vec<_> = messages
+            .iter()
+            .filter(|m| m.to == 1 && m.from != 4)
+            .cloned()
+            .collect();
+        assert!(matches!(combine(&partial), Err(Error::InsufficientShares)));
+        // Too few dealers cannot reshare at all
+        assert!(contributions(&old[1], &[2, 4], 2, 3).is_err());
+    }
+}
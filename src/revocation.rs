@@ -0,0 +1,277 @@
+// File: src/revocation.rs
+//! Signed lists of retired shares.
+//!
+//! After a refresh or reshare the old shares still reconstruct the old
+//! secret, and copies of them linger in backups. A [`RevocationList`] names
+//! the share indices of a dealing that are retired, either outright or below
+//! a refresh epoch, and is signed by the dealer or by a quorum of holders.
+//! [`reconstruct`] and [`verify_share`] refuse any share the list covers with
+//! [`Error::ShareRevoked`], so a stale share cannot sneak into a ceremony.
+//!
+//! A list scoped to a session only covers shares tagged with that session;
+//! an unscoped one covers every share presented with it. Check the list's
+//! signatures with [`RevocationList::verify`] before relying on it.
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use num_bigint::BigUint;
+
+use crate::encoding::{armor, dearmor, put_biguint, put_bytes, take_biguint, take_bytes, take_u32};
+use crate::error::{Error, Result};
+use crate::expiry;
+use crate::refresh;
+use crate::serialized::{reconstruct_auto, SerializedShare};
+use crate::service;
+use crate::signing::DealerSignature;
+
+const ARMOR_LABEL: &str = "SHARE REVOCATION LIST";
+const MAGIC: &[u8; 3] = b"SRL";
+const LIST_VERSION: u8 = 1;
+
+/// A retired share index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Revocation {
+    pub x: BigUint,
+    /// Only shares from refresh epochs below this are retired; `None`
+    /// retires the index at every epoch.
+    pub before_epoch: Option<u32>,
+}
+
+/// The retired shares of a dealing, with the signatures vouching for them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevocationList {
+    /// The dealing's session, or `None` for a list covering any share.
+    pub session: Option<String>,
+    /// Unix seconds.
+    pub issued: u64,
+    pub entries: Vec<Revocation>,
+    pub signatures: Vec<DealerSignature>,
+}
+
+impl RevocationList {
+    pub fn new(session: Option<String>) -> Self {
+        RevocationList {
+            session,
+            issued: expiry::now(),
+            entries: Vec::new(),
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Retires the share at `x` for good. Existing signatures no longer
+    /// cover the list.
+    pub fn revoke(&mut self, x: BigUint) {
+        self.entries.push(Revocation {
+            x,
+            before_epoch: None,
+        });
+    }
+
+    /// Retires the shares at `x` from refresh epochs below `epoch`, e.g. the
+    /// pre-refresh copies once every holder has moved to `epoch`.
+    pub fn revoke_before(&mut self, x: BigUint, epoch: u32) {
+        self.entries.push(Revocation {
+            x,
+            before_epoch: Some(epoch),
+        });
+    }
+
+    /// Adds `key`'s signature over the list.
+    pub fn sign(&mut self, key: &SigningKey) {
+        let signature = DealerSignature::sign(key, &self.body());
+        self.signatures
+            .retain(|existing| existing.dealer != signature.dealer);
+        self.signatures.push(signature);
+    }
+
+    /// Checks that at least `required` of `signers` validly signed the list:
+    /// `1` with the dealer's key alone, or a quorum of holder keys.
+    pub fn verify(&self, signers: &[VerifyingKey], required: usize) -> Result<()> {
+        if required == 0 {
+            return Err(Error::InvalidParameters(
+                "at least one signature must be required",
+            ));
+        }
+        let body = self.body();
+        let valid = signers
+            .iter()
+            .filter(|signer| {
+                self.signatures
+                    .iter()
+                    .any(|signature| signature.verify(signer, &body).is_ok())
+            })
+            .count();
+        if valid < required {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    pub fn is_revoked(&self, share: &SerializedShare) -> Result<bool> {
+        if self.session.is_some() && share.session() != self.session.as_deref() {
+            return Ok(false);
+        }
+        let epoch = refresh::epoch(share)?;
+        Ok(self.entries.iter().any(|entry| {
+            entry.x == share.x && entry.before_epoch.is_none_or(|before| epoch < before)
+        }))
+    }
+
+    /// Fails with [`Error::ShareRevoked`] if any of `shares` is retired.
+    pub fn check(&self, shares: &[SerializedShare]) -> Result<()> {
+        for share in shares {
+            if self.is_revoked(share)? {
+                return Err(Error::ShareRevoked);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.body();
+        out.push(self.signatures.len() as u8);
+        for signature in &self.signatures {
+            signature.put(&mut out);
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let input = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(Error::MalformedShare)?;
+        let (&version, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        if version != LIST_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let session = match take_bytes(&mut input)? {
+            [] => None,
+            bytes => Some(
+                String::from_utf8(bytes.to_vec())
+                    .map_err(|_| Error::InvalidEncoding("session is not UTF-8"))?,
+            ),
+        };
+        let (issued, mut input) = input.split_at_checked(8).ok_or(Error::MalformedShare)?;
+        let issued = u64::from_be_bytes(issued.try_into().expect("eight bytes"));
+        let count = take_u32(&mut input)?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let x = take_biguint(&mut input)?;
+            let before_epoch = match input.split_first() {
+                Some((0, rest)) => {
+                    input = rest;
+                    None
+                }
+                Some((1, rest)) => {
+                    input = rest;
+                    Some(take_u32(&mut input)?)
+                }
+                _ => return Err(Error::MalformedShare),
+            };
+            entries.push(Revocation { x, before_epoch });
+        }
+        let (&count, mut input) = input.split_first().ok_or(Error::MalformedShare)?;
+        let signatures = (0..count)
+            .map(|_| DealerSignature::take(&mut input))
+            .collect::<Result<_>>()?;
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(RevocationList {
+            session,
+            issued,
+            entries,
+            signatures,
+        })
+    }
+
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+
+    /// The signed portion of the encoding: everything except the signatures.
+    fn body(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(LIST_VERSION);
+        put_bytes(&mut out, self.session.as_deref().unwrap_or("").as_bytes());
+        out.extend_from_slice(&self.issued.to_be_bytes());
+        out.extend_from_slice(&(self.entries.len() as u32).to_be_bytes());
+        for entry in &self.entries {
+            put_biguint(&mut out, &entry.x);
+            match entry.before_epoch {
+                Some(epoch) => {
+                    out.push(1);
+                    out.extend_from_slice(&epoch.to_be_bytes());
+                }
+                None => out.push(0),
+            }
+        }
+        out
+    }
+}
+
+/// [`reconstruct_auto`], refusing shares retired by `list`.
+pub fn reconstruct(shares: &[SerializedShare], list: &RevocationList) -> Result<Vec<u8>> {
+    list.check(shares)?;
+    reconstruct_auto(shares)
+}
+
+/// [`service::verify_share`], refusing a serialized share retired by `list`.
+pub fn verify_share(share: &str, commitments: &str, list: &RevocationList) -> Result<bool> {
+    if let Ok(serialized) = SerializedShare::from_armored(share) {
+        list.check(std::slice::from_ref(&serialized))?;
+    }
+    service::verify_share(share, commitments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::split_bytes;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_revoked_shares_refused() {
+        let shares: Vec<_> = split_bytes(b"retired", 2, 4)
+            .unwrap()
+            .into_iter()
+            .map(|share| share.with_session("a1b2"))
+            .collect();
+        let refreshed: Vec<_> = shares
+            .iter()
+            .map(|share| share.clone().with_metadata(refresh::EPOCH_KEY, "1"))
+            .collect();
+
+        let dealer = SigningKey::generate(&mut OsRng);
+        let mut list = RevocationList::new(Some("a1b2".into()));
+        list.revoke(4u32.into());
+        list.revoke_before(1u32.into(), 1);
+        list.sign(&dealer);
+        let list = RevocationList::from_armored(&list.to_armored()).unwrap();
+        list.verify(&[dealer.verifying_key()], 1).unwrap();
+
+        assert!(matches!(
+            reconstruct(&shares[..2], &list),
+            Err(Error::ShareRevoked)
+        ));
+        assert!(reconstruct(&refreshed[3..], &list).is_err());
+        assert_eq!(reconstruct(&refreshed[..2], &list).unwrap(), b"retired");
+        assert_eq!(reconstruct(&shares[1..3], &list).unwrap(), b"retired");
+        let elsewhere = shares[3].clone().with_session("ffff");
+        assert!(!list.is_revoked(&elsewhere).unwrap());
+
+        // A quorum of holders can vouch for a list instead of the dealer.
+        let holders: Vec<_> = (0..3).map(|_| SigningKey::generate(&mut OsRng)).collect();
+        let keys: Vec<_> = holders.iter().map(SigningKey::verifying_key).collect();
+        let mut quorum = list.clone();
+        quorum.sign(&holders[0]);
+        assert!(quorum.verify(&keys, 2).is_err());
+        quorum.sign(&holders[2]);
+        quorum.verify(&keys, 2).unwrap();
+        quorum.revoke(2u32.into());
+        assert!(quorum.verify(&keys, 2).is_err());
+    }
+}
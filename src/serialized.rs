@@ -0,0 +1,1061 @@
+// File: src/serialized.rs
+//! Self-describing shares.
+//!
+//! A [`SerializedShare`] carries the scheme, its field parameters, and the
+//! threshold/total alongside the share itself, so a combiner needs nothing
+//! but the shares to recover the secret. Dealers may also attach a
+//! participant label and free-form metadata, which travel with the share.
+//!
+//! Since format version 2 every encoding starts with the magic `SHR` and a
+//! version byte. Version 3 widened the field length prefixes from u16 to u32,
+//! so payloads of 64 KiB and more survive. Version 1 (no magic) and 2
+//! encodings can be upgraded with [`migrate`]; versions newer than
+//! [`FORMAT_VERSION`] are rejected.
+//!
+//! The dealer can sign each share with Ed25519. The signature trails the
+//! encoding, stays inside any [`crate::ecies::ShareBundle`] the share is
+//! sealed into, and is checked by [`reconstruct_signed`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use rand::{thread_rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+
+use crate::bech32;
+use crate::encoding::{
+    armor, byte_width, dearmor, hex, padded, put_bytes_u32, take_bytes, take_bytes_u32, take_u32,
+    to_fixed, to_fixed_bytes,
+};
+use crate::error::{Error, Result};
+use crate::expiry::{self, Enforcement};
+use crate::gf256::{self, Gf256};
+#[cfg(feature = "qr")]
+use crate::qr::{self, QrFormat};
+use crate::refresh::EPOCH_KEY;
+use crate::signing::DealerSignature;
+use crate::sss::{self, SecretSharer};
+use crate::vss::{self, FeldmanVSS};
+
+const ARMOR_LABEL: &str = "SECRET SHARE";
+const BECH32_HRP: &str = "shr";
+const SESSION_KEY: &str = "session";
+const LENGTH_KEY: &str = "length";
+
+const MAGIC: &[u8; 3] = b"SHR";
+
+/// The share format version written by this crate.
+pub const FORMAT_VERSION: u8 = 3;
+
+const TAG_SHAMIR: u8 = 1;
+const TAG_FELDMAN: u8 = 2;
+const TAG_GF256: u8 = 3;
+
+/// The sharing scheme and the parameters needed to recombine it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Scheme {
+    /// Shamir over the integers modulo `prime` ([`crate::sss`]).
+    Shamir { prime: BigUint },
+    /// Feldman VSS in the order-`q` subgroup of Z_p* generated by `g` ([`crate::vss`]).
+    Feldman { p: BigUint, q: BigUint, g: BigUint },
+    /// Byte-wise Shamir over GF(2^8) with the given reduction polynomial ([`crate::gf256`]).
+    Gf256 { poly: u16 },
+}
+
+/// A share together with everything needed to combine it.
+///
+/// `value` holds the big-endian share value for the prime-field schemes and
+/// the raw payload for [`Scheme::Gf256`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedShare {
+    pub scheme: Scheme,
+    pub threshold: usize,
+    pub total: usize,
+    pub x: BigUint,
+    pub value: Vec<u8>,
+    /// Who holds this share, e.g. `"alice@ops"` or `"HSM-2"`.
+    pub label: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+    pub signature: Option<DealerSignature>,
+}
+
+impl SerializedShare {
+    /// Prime-field values are stored in the canonical fixed-width encoding
+    /// ([`crate::encoding::to_fixed_bytes`]) of the field; a value too wide
+    /// for it is refused.
+    pub fn new(
+        scheme: Scheme,
+        threshold: usize,
+        total: usize,
+        x: BigUint,
+        value: Vec<u8>,
+    ) -> Result<Self> {
+        let value = match &scheme {
+            Scheme::Shamir { prime: modulus } | Scheme::Feldman { q: modulus, .. } => {
+                to_fixed_bytes(&BigUint::from_bytes_be(&value), byte_width(modulus))?
+            }
+            Scheme::Gf256 { .. } => value,
+        };
+        Ok(SerializedShare {
+            scheme,
+            threshold,
+            total,
+            x,
+            value,
+            label: None,
+            metadata: BTreeMap::new(),
+            signature: None,
+        })
+    }
+
+    /// Wraps a payload produced by [`gf256::split`].
+    pub fn gf256(field: Gf256, threshold: usize, total: usize, x: u8, payload: Vec<u8>) -> Self {
+        Self::new(
+            Scheme::Gf256 { poly: field.poly() },
+            threshold,
+            total,
+            BigUint::from(x),
+            payload,
+        )
+        .expect("byte-wise payloads are stored as-is")
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Signs the share as the dealer, covering every field including the
+    /// label and the session, so a signed share cannot be replayed into
+    /// another dealing by the same dealer. Record the session with
+    /// [`with_session`](Self::with_session) first.
+    pub fn sign(&mut self, key: &SigningKey) -> Result<()> {
+        if self.session().is_none() {
+            return Err(Error::InvalidParameters(
+                "record the dealing's session before signing a share",
+            ));
+        }
+        self.signature = Some(DealerSignature::sign(key, &self.signed_bytes()));
+        Ok(())
+    }
+
+    /// Checks that the share carries a valid signature by `dealer`.
+    pub fn verify_signature(&self, dealer: &VerifyingKey) -> Result<()> {
+        self.signature
+            .as_ref()
+            .ok_or(Error::InvalidSignature)?
+            .verify(dealer, &self.signed_bytes())
+    }
+
+    /// Encodes the share in the current format version.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.signed_bytes();
+        if let Some(signature) = &self.signature {
+            signature.put(&mut out);
+        }
+        out
+    }
+
+    /// The encoding without the trailing dealer signature.
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(FORMAT_VERSION);
+        self.put_body(&mut out);
+        out
+    }
+
+    /// Decodes a share in the current format version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        match format_version(bytes)? {
+            FORMAT_VERSION => Self::take_body(&bytes[MAGIC.len() + 1..], FORMAT_VERSION),
+            version => Err(Error::UnsupportedVersion(version)),
+        }
+    }
+
+    fn put_body(&self, out: &mut Vec<u8>) {
+        match &self.scheme {
+            Scheme::Shamir { prime } => {
+                out.push(TAG_SHAMIR);
+                put_bytes_u32(out, &prime.to_bytes_be());
+            }
+            Scheme::Feldman { p, q, g } => {
+                out.push(TAG_FELDMAN);
+                put_bytes_u32(out, &p.to_bytes_be());
+                put_bytes_u32(out, &padded(q, byte_width(p)));
+                put_bytes_u32(out, &padded(g, byte_width(p)));
+            }
+            Scheme::Gf256 { poly } => {
+                out.push(TAG_GF256);
+                out.extend_from_slice(&poly.to_be_bytes());
+            }
+        }
+        out.extend_from_slice(&(self.threshold as u32).to_be_bytes());
+        out.extend_from_slice(&(self.total as u32).to_be_bytes());
+        let x_width = match &self.scheme {
+            Scheme::Shamir { prime: modulus } | Scheme::Feldman { q: modulus, .. } => {
+                byte_width(modulus)
+            }
+            Scheme::Gf256 { .. } => 1,
+        };
+        put_bytes_u32(out, &padded(&self.x, x_width));
+        put_bytes_u32(out, &self.value);
+        match &self.label {
+            Some(label) => {
+                out.push(1);
+                put_bytes_u32(out, label.as_bytes());
+            }
+            None => out.push(0),
+        }
+        out.extend_from_slice(&(self.metadata.len() as u16).to_be_bytes());
+        for (key, value) in &self.metadata {
+            put_bytes_u32(out, key.as_bytes());
+            put_bytes_u32(out, value.as_bytes());
+        }
+    }
+
+    /// Decodes the body of a `version` encoding, after any magic and version.
+    fn take_body(bytes: &[u8], version: u8) -> Result<Self> {
+        let (&tag, mut input) = bytes.split_first().ok_or(Error::MalformedShare)?;
+        let scheme = match tag {
+            TAG_SHAMIR => Scheme::Shamir {
+                prime: take_biguint(&mut input, version)?,
+            },
+            TAG_FELDMAN => Scheme::Feldman {
+                p: take_biguint(&mut input, version)?,
+                q: take_biguint(&mut input, version)?,
+                g: take_biguint(&mut input, version)?,
+            },
+            TAG_GF256 => {
+                let poly = take_bytes_exact::<2>(&mut input)?;
+                Scheme::Gf256 {
+                    poly: u16::from_be_bytes(poly),
+                }
+            }
+            _ => return Err(Error::InvalidEncoding("unknown share scheme")),
+        };
+        let two = BigUint::from(2u32);
+        let degenerate = match &scheme {
+            Scheme::Shamir { prime } => prime < &two,
+            Scheme::Feldman { p, q, g } => p < &two || q < &two || g < &two || g >= p,
+            Scheme::Gf256 { .. } => false,
+        };
+        if degenerate {
+            return Err(Error::MalformedShare);
+        }
+        let threshold = take_u32(&mut input)? as usize;
+        let total = take_u32(&mut input)? as usize;
+        let x = take_biguint(&mut input, version)?;
+        if !index_in_field(&scheme, &x) {
+            return Err(Error::MalformedShare);
+        }
+        let value = take_field(&mut input, version)?.to_vec();
+        let label = match take_bytes_exact::<1>(&mut input)? {
+            [0] => None,
+            [1] => Some(take_string(&mut input, version)?),
+            _ => return Err(Error::MalformedShare),
+        };
+        let count = u16::from_be_bytes(take_bytes_exact::<2>(&mut input)?);
+        let mut metadata = BTreeMap::new();
+        for _ in 0..count {
+            let key = take_string(&mut input, version)?;
+            metadata.insert(key, take_string(&mut input, version)?);
+        }
+        let signature = if input.is_empty() {
+            None
+        } else {
+            Some(DealerSignature::take(&mut input)?)
+        };
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(SerializedShare {
+            scheme,
+            threshold,
+            total,
+            x,
+            value,
+            label,
+            metadata,
+            signature,
+        })
+    }
+
+    /// Encodes the share as an ASCII-armored text block.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+
+    /// Encodes the share as a bech32m string whose checksum can locate typos.
+    pub fn to_bech32(&self) -> String {
+        bech32::encode(BECH32_HRP, &self.to_bytes())
+    }
+
+    pub fn from_bech32(text: &str) -> Result<Self> {
+        Self::from_bytes(&bech32::decode(BECH32_HRP, text)?)
+    }
+
+    /// The dealing this share belongs to, if the dealer recorded one.
+    pub fn session(&self) -> Option<&str> {
+        self.metadata.get(SESSION_KEY).map(String::as_str)
+    }
+
+    pub fn with_session(self, session: impl Into<String>) -> Self {
+        self.with_metadata(SESSION_KEY, session)
+    }
+
+    /// The byte length of the secret, if the dealer recorded it. Prime-field
+    /// values drop leading zero bytes, which reconstruction restores up to
+    /// this length.
+    pub fn secret_length(&self) -> Result<Option<usize>> {
+        self.metadata
+            .get(LENGTH_KEY)
+            .map(|length| length.parse().map_err(|_| Error::MalformedShare))
+            .transpose()
+    }
+
+    /// Records the secret's byte length; a prime-field secret may not be
+    /// longer than its modulus, leading zeros included.
+    pub fn with_secret_length(self, length: usize) -> Result<Self> {
+        if self
+            .modulus()
+            .is_some_and(|modulus| length > byte_width(modulus))
+        {
+            return Err(Error::InvalidParameters(
+                "secret is longer than the field is wide",
+            ));
+        }
+        Ok(self.with_metadata(LENGTH_KEY, length.to_string()))
+    }
+
+    /// The prime or q that values are reduced by; `None` for byte-wise shares.
+    fn modulus(&self) -> Option<&BigUint> {
+        match &self.scheme {
+            Scheme::Shamir { prime: modulus } | Scheme::Feldman { q: modulus, .. } => Some(modulus),
+            Scheme::Gf256 { .. } => None,
+        }
+    }
+
+    /// Renders the bech32 form as a QR code; it is upper-cased so the code can
+    /// use the denser alphanumeric mode.
+    #[cfg(feature = "qr")]
+    pub fn to_qr(&self, format: QrFormat) -> Result<Vec<u8>> {
+        qr::render(&self.to_bech32().to_uppercase(), format)
+    }
+
+    /// Adds a share of another secret dealt under the same scheme and
+    /// parameters at the same index, giving a share of the sum of the two
+    /// secrets: modulo the prime or q for the prime-field schemes, and XOR
+    /// (addition in GF(2^8)) for byte-wise shares of equal length.
+    ///
+    /// The label and the metadata both shares agree on are kept; the dealer
+    /// signature is not.
+    pub fn add(&self, other: &SerializedShare) -> Result<SerializedShare> {
+        if self.scheme != other.scheme {
+            return Err(Error::InconsistentShares("shares use different schemes"));
+        }
+        if (self.threshold, self.total) != (other.threshold, other.total) {
+            return Err(Error::InconsistentShares(
+                "shares disagree on threshold or total",
+            ));
+        }
+        if self.x != other.x {
+            return Err(Error::InconsistentShares("shares have different indices"));
+        }
+        let value = match &self.scheme {
+            Scheme::Shamir { prime: modulus } | Scheme::Feldman { q: modulus, .. } => {
+                let sum =
+                    BigUint::from_bytes_be(&self.value) + BigUint::from_bytes_be(&other.value);
+                (sum % modulus).to_bytes_be()
+            }
+            Scheme::Gf256 { .. } => {
+                if self.value.len() != other.value.len() {
+                    return Err(Error::InconsistentShares("shares have different lengths"));
+                }
+                self.value
+                    .iter()
+                    .zip(&other.value)
+                    .map(|(a, b)| a ^ b)
+                    .collect()
+            }
+        };
+        let mut sum = SerializedShare::new(
+            self.scheme.clone(),
+            self.threshold,
+            self.total,
+            self.x.clone(),
+            value,
+        )?;
+        sum.label = self.label.clone();
+        sum.metadata = self
+            .metadata
+            .iter()
+            .filter(|(key, value)| other.metadata.get(*key) == Some(value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        Ok(sum)
+    }
+
+    /// A share of the secret multiplied by `c`, for the prime-field schemes.
+    pub fn scale(&self, c: &BigUint) -> Result<SerializedShare> {
+        self.map_value(|value, modulus| value * c % modulus)
+    }
+
+    /// A share of the secret plus `c`, for the prime-field schemes; e.g. a
+    /// BIP-32 non-hardened tweak applied to a shared private key.
+    pub fn add_constant(&self, c: &BigUint) -> Result<SerializedShare> {
+        self.map_value(|value, modulus| (value + c) % modulus)
+    }
+
+    /// Replaces the value of a prime-field share, keeping every other field
+    /// but the dealer signature.
+    fn map_value(&self, f: impl FnOnce(BigUint, &BigUint) -> BigUint) -> Result<SerializedShare> {
+        let modulus = match &self.scheme {
+            Scheme::Shamir { prime } => prime,
+            Scheme::Feldman { q, .. } => q,
+            Scheme::Gf256 { .. } => {
+                return Err(Error::InvalidParameters(
+                    "affine operations need a prime-field scheme",
+                ))
+            }
+        };
+        let mut mapped = self.clone();
+        mapped.value = to_fixed(&f(BigUint::from_bytes_be(&self.value), modulus), modulus)?;
+        mapped.signature = None;
+        Ok(mapped)
+    }
+
+    /// SHA-256 of the encoded share, for audit logs that must not hold the share.
+    pub fn fingerprint(&self) -> String {
+        hex(&Sha256::digest(self.to_bytes()))
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scheme::Shamir { prime } => write!(f, "shamir ({}-bit prime)", prime.bits()),
+            Scheme::Feldman { p, q, .. } => {
+                write!(f, "feldman ({}-bit p, {}-bit q)", p.bits(), q.bits())
+            }
+            Scheme::Gf256 { poly } => write!(f, "gf256 (poly {:#05x})", poly),
+        }
+    }
+}
+
+/// Reports the format version of an encoded share.
+///
+/// Encodings without the magic prefix predate versioning and report version 1.
+pub fn format_version(bytes: &[u8]) -> Result<u8> {
+    match bytes.strip_prefix(MAGIC.as_slice()) {
+        Some([version, ..]) => Ok(*version),
+        Some([]) => Err(Error::MalformedShare),
+        None if bytes.is_empty() => Err(Error::MalformedShare),
+        None => Ok(1),
+    }
+}
+
+/// Upgrades a version 1 or 2 encoding to the current layout. A dealer
+/// signature over an older encoding no longer verifies and must be renewed.
+pub fn migrate(bytes: &[u8]) -> Result<Vec<u8>> {
+    match format_version(bytes)? {
+        1 => Ok(SerializedShare::take_body(bytes, 1)?.to_bytes()),
+        2 => Ok(SerializedShare::take_body(&bytes[MAGIC.len() + 1..], 2)?.to_bytes()),
+        version => Err(Error::UnsupportedVersion(version)),
+    }
+}
+
+/// Upgrades a version 1 encoding to the current layout; [`migrate`] also
+/// takes version 2.
+pub fn migrate_v1_to_v2(bytes: &[u8]) -> Result<Vec<u8>> {
+    match format_version(bytes)? {
+        1 => migrate(bytes),
+        version => Err(Error::UnsupportedVersion(version)),
+    }
+}
+
+/// Splits arbitrary bytes over the Rijndael GF(2^8) field into shares numbered 1..=total.
+pub fn split_bytes(secret: &[u8], threshold: usize, total: usize) -> Result<Vec<SerializedShare>> {
+    split_bytes_with_rng(secret, threshold, total, &mut thread_rng())
+}
+
+/// Splits the UTF-8 bytes of a passphrase or recovery code; see [`reconstruct_str`].
+pub fn split_str(secret: &str, threshold: usize, total: usize) -> Result<Vec<SerializedShare>> {
+    split_bytes(secret.as_bytes(), threshold, total)
+}
+
+/// Like [`split_bytes`], drawing the polynomial coefficients from `rng`.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(len = secret.len(), threshold, total)))]
+pub fn split_bytes_with_rng(
+    secret: &[u8],
+    threshold: usize,
+    total: usize,
+    rng: &mut impl RngCore,
+) -> Result<Vec<SerializedShare>> {
+    if total > 255 {
+        return Err(Error::InvalidParameters("at most 255 shares are supported"));
+    }
+    let xs: Vec<u8> = (1..=total as u8).collect();
+    let field = Gf256::RIJNDAEL;
+    let payloads = gf256::split(field, secret, threshold, &xs, rng)?;
+    Ok(xs
+        .into_iter()
+        .zip(payloads)
+        .map(|(x, payload)| SerializedShare::gf256(field, threshold, total, x, payload))
+        .collect())
+}
+
+/// A ChaCha20 DRBG keyed by the SHA-256 of `seed`.
+///
+/// INSECURE: anyone who knows the seed can recompute every share. Use it only
+/// for reproducible test fixtures and documentation, never for real secrets.
+pub fn insecure_seeded_rng(seed: &[u8]) -> ChaCha20Rng {
+    ChaCha20Rng::from_seed(Sha256::digest(seed).into())
+}
+
+/// Like [`reconstruct_auto`], but first requires every share to carry a
+/// valid signature by `dealer` over the same session, so substituted shares
+/// and shares replayed from the dealer's other dealings are refused.
+pub fn reconstruct_signed(shares: &[SerializedShare], dealer: &VerifyingKey) -> Result<Vec<u8>> {
+    let session = shares.first().and_then(SerializedShare::session);
+    for share in shares {
+        share.verify_signature(dealer)?;
+        if share.session() != session {
+            return Err(Error::InconsistentShares(
+                "signed shares come from different dealings",
+            ));
+        }
+    }
+    reconstruct_auto(shares)
+}
+
+/// Recovers the secret using only the parameters embedded in the shares.
+///
+/// All shares must agree on scheme, threshold, total and refresh epoch, and
+/// carry distinct non-zero indices. Prime-field secrets are returned big-endian.
+/// Shares past their validity window ([`crate::expiry`]) are refused.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(shares = shares.len())))]
+pub fn reconstruct_auto(shares: &[SerializedShare]) -> Result<Vec<u8>> {
+    expiry::check(shares, expiry::now(), Enforcement::Refuse)?;
+    reconstruct_unchecked(shares)
+}
+
+/// [`reconstruct_auto`] for secrets split with [`split_str`], refusing a
+/// result that is not valid UTF-8.
+pub fn reconstruct_str(shares: &[SerializedShare]) -> Result<String> {
+    String::from_utf8(reconstruct_auto(shares)?)
+        .map_err(|_| Error::InvalidEncoding("secret is not valid UTF-8"))
+}
+
+/// [`reconstruct_auto`] without the validity check.
+pub(crate) fn reconstruct_unchecked(shares: &[SerializedShare]) -> Result<Vec<u8>> {
+    check_consistent(shares)?;
+    let first = &shares[0];
+    if shares.len() < first.threshold {
+        return Err(Error::InsufficientShares);
+    }
+
+    let shares = &shares[..first.threshold];
+    match &first.scheme {
+        Scheme::Shamir { prime } => {
+            let sharer = SecretSharer::with_prime(prime.clone(), first.threshold, first.total);
+            let shares: Vec<sss::Share> = shares
+                .iter()
+                .map(|s| sss::Share {
+                    x: s.x.clone(),
+                    y: BigUint::from_bytes_be(&s.value),
+                })
+                .collect();
+            sharer
+                .reconstruct_secret(&shares)
+                .map(|secret| secret.to_bytes_be())
+                .ok_or(Error::InconsistentShares(
+                    "share indices collide in the field",
+                ))
+                .and_then(|secret| restore_length(first, secret))
+        }
+        Scheme::Feldman { p, q, g } => {
+            let vss = FeldmanVSS::new(
+                p.clone(),
+                q.clone(),
+                g.clone(),
+                first.threshold,
+                first.total,
+            );
+            let shares: Vec<vss::Share> = shares
+                .iter()
+                .map(|s| vss::Share {
+                    id: s.x.clone(),
+                    value: BigUint::from_bytes_be(&s.value),
+                })
+                .collect();
+            vss.reconstruct_secret(&shares)
+                .map(|secret| secret.to_bytes_be())
+                .ok_or(Error::InconsistentShares(
+                    "share indices collide in the field",
+                ))
+                .and_then(|secret| restore_length(first, secret))
+        }
+        Scheme::Gf256 { poly } => {
+            let points = shares
+                .iter()
+                .map(|s| {
+                    let x = s.x.to_u8().ok_or(Error::MalformedShare)?;
+                    Ok((x, s.value.as_slice()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            gf256::interpolate(Gf256::new(*poly), &points, 0)
+        }
+    }
+}
+
+/// Checks that shares could be combined together: a sane threshold, and
+/// agreement on scheme, threshold, total and refresh epoch with distinct
+/// indices inside the field. Fewer shares than the threshold are fine.
+pub(crate) fn check_consistent(shares: &[SerializedShare]) -> Result<()> {
+    let first = shares.first().ok_or(Error::InsufficientShares)?;
+    if first.threshold == 0 || first.threshold > first.total {
+        return Err(Error::InvalidParameters(
+            "threshold must be between 1 and the total",
+        ));
+    }
+    for share in shares {
+        if share.scheme != first.scheme {
+            return Err(Error::InconsistentShares("shares use different schemes"));
+        }
+        if share.threshold != first.threshold || share.total != first.total {
+            return Err(Error::InconsistentShares(
+                "shares disagree on threshold or total",
+            ));
+        }
+        if !index_in_field(&share.scheme, &share.x) {
+            return Err(Error::MalformedShare);
+        }
+        if share.metadata.get(LENGTH_KEY) != first.metadata.get(LENGTH_KEY) {
+            return Err(Error::InconsistentShares(
+                "shares disagree on the secret length",
+            ));
+        }
+        if share.metadata.get(EPOCH_KEY) != first.metadata.get(EPOCH_KEY) {
+            return Err(Error::InconsistentShares(
+                "shares come from different refresh epochs",
+            ));
+        }
+    }
+    for (i, share) in shares.iter().enumerate() {
+        if shares[..i].iter().any(|other| other.x == share.x) {
+            return Err(Error::InconsistentShares("duplicate share indices"));
+        }
+    }
+    Ok(())
+}
+
+/// Left-pads a prime-field secret with the zero bytes its integer form
+/// dropped, up to the length the dealer recorded.
+fn restore_length(share: &SerializedShare, secret: Vec<u8>) -> Result<Vec<u8>> {
+    let Some(length) = share.secret_length()? else {
+        return Ok(secret);
+    };
+    let width = share.modulus().map_or(0, byte_width);
+    // Zero encodes as a single zero byte
+    let secret = &secret[secret.iter().take_while(|&&b| b == 0).count()..];
+    if length > width || length < secret.len() {
+        return Err(Error::MalformedShare);
+    }
+    let mut padded = vec![0; length - secret.len()];
+    padded.extend_from_slice(secret);
+    Ok(padded)
+}
+
+/// Whether `x` is a non-zero element of the scheme's field: below the prime
+/// or q, or a byte for [`Scheme::Gf256`]. Larger indices alias smaller ones
+/// and would reach the interpolation unreduced.
+fn index_in_field(scheme: &Scheme, x: &BigUint) -> bool {
+    let below = match scheme {
+        Scheme::Shamir { prime: modulus } | Scheme::Feldman { q: modulus, .. } => x < modulus,
+        Scheme::Gf256 { .. } => x.to_u8().is_some(),
+    };
+    below && !x.is_zero()
+}
+
+/// Reads a length-prefixed field: u32 lengths since version 3, u16 before.
+fn take_field<'a>(input: &mut &'a [u8], version: u8) -> Result<&'a [u8]> {
+    if version >= 3 {
+        take_bytes_u32(input)
+    } else {
+        take_bytes(input)
+    }
+}
+
+fn take_biguint(input: &mut &[u8], version: u8) -> Result<BigUint> {
+    take_field(input, version).map(BigUint::from_bytes_be)
+}
+
+fn take_string(input: &mut &[u8], version: u8) -> Result<String> {
+    String::from_utf8(take_field(input, version)?.to_vec())
+        .map_err(|_| Error::InvalidEncoding("share text field is not UTF-8"))
+}
+
+fn take_bytes_exact<const N: usize>(input: &mut &[u8]) -> Result<[u8; N]> {
+    if input.len() < N {
+        return Err(Error::MalformedShare);
+    }
+    let mut out = [0u8; N];
+    out.copy_from_slice(&input[..N]);
+    *input = &input[N..];
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn test_reconstruct_auto() {
+        let sharer = SecretSharer::new(3, 5);
+        let secret = BigUint::from(0xC0FFEEu32);
+        let shares: Vec<SerializedShare> = sharer
+            .split_secret(&secret)
+            .iter()
+            .map(|share| {
+                SerializedShare::from_armored(&sharer.serialize_share(share).to_armored()).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            reconstruct_auto(&shares[2..]).unwrap(),
+            secret.to_bytes_be()
+        );
+        assert!(matches!(
+            reconstruct_auto(&shares[..2]),
+            Err(Error::InsufficientShares)
+        ));
+
+        let xs = [7, 9, 200];
+        let payloads = gf256::split(Gf256::RIJNDAEL, b"bytes", 2, &xs, &mut thread_rng()).unwrap();
+        let gf: Vec<SerializedShare> = xs
+            .iter()
+            .zip(payloads)
+            .map(|(&x, p)| SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, x, p))
+            .collect();
+        let gf: Vec<SerializedShare> = gf
+            .iter()
+            .map(|s| SerializedShare::from_bytes(&s.to_bytes()).unwrap())
+            .collect();
+        assert_eq!(reconstruct_auto(&gf[1..]).unwrap(), b"bytes");
+
+        // Mixing schemes is caught before any interpolation happens
+        let mixed = [shares[0].clone(), gf[0].clone(), gf[1].clone()];
+        assert!(matches!(
+            reconstruct_auto(&mixed),
+            Err(Error::InconsistentShares(_))
+        ));
+    }
+
+    #[test]
+    fn test_string_roundtrip() {
+        let shares = split_str("pässwörd 🔑", 2, 3).unwrap();
+        assert_eq!(reconstruct_str(&shares[1..]).unwrap(), "pässwörd 🔑");
+        let binary = split_bytes(&[0xff, 0xfe], 2, 3).unwrap();
+        assert!(matches!(
+            reconstruct_str(&binary),
+            Err(Error::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_insecure_seed_is_reproducible() {
+        let split = |seed: &[u8]| {
+            split_bytes_with_rng(b"fixture", 2, 3, &mut insecure_seeded_rng(seed)).unwrap()
+        };
+        assert_eq!(split(b"ci"), split(b"ci"));
+        assert_ne!(split(b"ci"), split(b"docs"));
+        assert_eq!(reconstruct_auto(&split(b"ci")[1..]).unwrap(), b"fixture");
+    }
+
+    #[test]
+    fn test_label_and_metadata_roundtrip() {
+        let share = SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, 1, vec![1, 2, 3])
+            .with_label("HSM-2")
+            .with_metadata("site", "eu-west")
+            .with_metadata("rotated", "2024-01-01");
+        let decoded = SerializedShare::from_armored(&share.to_armored()).unwrap();
+        assert_eq!(decoded.label.as_deref(), Some("HSM-2"));
+        assert_eq!(decoded.metadata["site"], "eu-west");
+        assert_eq!(decoded, share);
+    }
+
+    #[test]
+    fn test_version_migration() {
+        let share = SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, 5, vec![9, 8, 7]);
+        let current = share.to_bytes();
+        assert_eq!(format_version(&current).unwrap(), FORMAT_VERSION);
+
+        // Versions 1 and 2 prefix fields with u16 lengths; version 1 also
+        // lacks the magic and version
+        let v1 = [
+            &[TAG_GF256, 0x01, 0x1b][..],
+            &[0, 0, 0, 2, 0, 0, 0, 3],
+            &[0, 1, 5, 0, 3, 9, 8, 7, 0, 0, 0],
+        ]
+        .concat();
+        let v2 = [&MAGIC[..], &[2], &v1].concat();
+        for (version, old) in [(1, &v1), (2, &v2)] {
+            assert!(matches!(
+                SerializedShare::from_bytes(old),
+                Err(Error::UnsupportedVersion(v)) if v == version
+            ));
+            assert_eq!(migrate(old).unwrap(), current);
+        }
+
+        let mut future = current.clone();
+        future[MAGIC.len()] = FORMAT_VERSION + 1;
+        assert!(matches!(
+            SerializedShare::from_bytes(&future),
+            Err(Error::UnsupportedVersion(v)) if v == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_degenerate_parameters_are_rejected() {
+        let share = |scheme| SerializedShare {
+            scheme,
+            ..SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, 1, vec![1])
+        };
+        let n = |v: u32| BigUint::from(v);
+        for scheme in [
+            Scheme::Shamir { prime: n(1) },
+            Scheme::Shamir { prime: n(0) },
+            Scheme::Feldman {
+                p: n(1),
+                q: n(11),
+                g: n(2),
+            },
+            Scheme::Feldman {
+                p: n(23),
+                q: n(1),
+                g: n(2),
+            },
+            Scheme::Feldman {
+                p: n(23),
+                q: n(11),
+                g: n(1),
+            },
+            Scheme::Feldman {
+                p: n(23),
+                q: n(11),
+                g: n(23),
+            },
+        ] {
+            assert!(matches!(
+                SerializedShare::from_bytes(&share(scheme).to_bytes()),
+                Err(Error::MalformedShare)
+            ));
+        }
+        let valid = share(Scheme::Feldman {
+            p: n(23),
+            q: n(11),
+            g: n(4),
+        });
+        assert!(SerializedShare::from_bytes(&valid.to_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_out_of_field_index_is_rejected() {
+        let scheme = Scheme::Shamir {
+            prime: BigUint::from(7919u32),
+        };
+        let share =
+            |x: u32| SerializedShare::new(scheme.clone(), 2, 3, BigUint::from(x), vec![1]).unwrap();
+        for x in [20000, 7919] {
+            let crafted = share(x);
+            assert!(matches!(
+                SerializedShare::from_bytes(&crafted.to_bytes()),
+                Err(Error::MalformedShare)
+            ));
+            assert!(matches!(
+                reconstruct_auto(&[crafted, share(2)]),
+                Err(Error::MalformedShare)
+            ));
+        }
+        let gf = SerializedShare {
+            x: BigUint::from(256u32),
+            ..SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, 1, vec![1])
+        };
+        assert!(SerializedShare::from_bytes(&gf.to_bytes()).is_err());
+
+        // The field arithmetic itself reduces indices instead of panicking
+        let sharer = SecretSharer::with_prime(BigUint::from(7919u32), 2, 3);
+        let shares = [
+            sss::Share {
+                x: BigUint::from(20000u32),
+                y: BigUint::from(1u32),
+            },
+            sss::Share {
+                x: BigUint::from(2u32),
+                y: BigUint::from(1u32),
+            },
+        ];
+        assert_eq!(
+            sharer.reconstruct_secret(&shares),
+            Some(BigUint::from(1u32))
+        );
+    }
+
+    #[test]
+    fn test_fixed_width_fields() {
+        let n = |v: u32| BigUint::from(v);
+        let scheme = Scheme::Feldman {
+            p: n(0x10007),
+            q: n(0x8003),
+            g: n(4),
+        };
+        let share = SerializedShare::new(scheme.clone(), 2, 3, n(1), vec![5]).unwrap();
+        assert_eq!(share.value, [0, 5]);
+        // p is three bytes wide, so q and g are too; x takes the width of q
+        let bytes = share.to_bytes();
+        let fields = &bytes[MAGIC.len() + 2..];
+        assert_eq!(fields[4..7], [1, 0, 7]);
+        assert_eq!(fields[7..18], [0, 0, 0, 3, 0, 0x80, 3, 0, 0, 0, 3]);
+        assert_eq!(fields[18..21], [0, 0, 4]);
+        assert_eq!(fields[29..35], [0, 0, 0, 2, 0, 1]);
+        assert_eq!(SerializedShare::from_bytes(&bytes).unwrap(), share);
+
+        assert!(matches!(
+            SerializedShare::new(scheme, 2, 3, n(1), vec![1, 0, 0]),
+            Err(Error::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_large_payload_roundtrip() {
+        let secret: Vec<u8> = (0..70_000u32).map(|i| i as u8).collect();
+        let shares: Vec<SerializedShare> = split_bytes(&secret, 2, 3)
+            .unwrap()
+            .iter()
+            .map(|share| SerializedShare::from_armored(&share.to_armored()).unwrap())
+            .collect();
+        assert_eq!(reconstruct_auto(&shares[1..]).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_reconstruct_signed() {
+        let dealer = SigningKey::generate(&mut rand::rngs::OsRng);
+        let xs = [1, 2, 3];
+        let payloads = gf256::split(Gf256::RIJNDAEL, b"key", 2, &xs, &mut thread_rng()).unwrap();
+        let mut shares: Vec<SerializedShare> = xs
+            .iter()
+            .zip(payloads)
+            .map(|(&x, p)| {
+                let mut share =
+                    SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, x, p).with_session("01");
+                share.sign(&dealer).unwrap();
+                SerializedShare::from_bytes(&share.to_bytes()).unwrap()
+            })
+            .collect();
+        let key = dealer.verifying_key();
+        assert_eq!(reconstruct_signed(&shares[..2], &key).unwrap(), b"key");
+
+        // A substituted payload no longer matches the dealer's signature
+        shares[1].value[0] ^= 0xff;
+        assert!(matches!(
+            reconstruct_signed(&shares[..2], &key),
+            Err(Error::InvalidSignature)
+        ));
+        shares[2].signature = None;
+        assert!(reconstruct_signed(&shares[2..], &key).is_err());
+
+        // A share the same dealer signed for another dealing is refused
+        let mut replayed =
+            SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, 2, vec![7; 3]).with_session("02");
+        replayed.sign(&dealer).unwrap();
+        assert!(matches!(
+            reconstruct_signed(&[shares[0].clone(), replayed], &key),
+            Err(Error::InconsistentShares(_))
+        ));
+        let mut sessionless = SerializedShare::gf256(Gf256::RIJNDAEL, 2, 3, 1, vec![1]);
+        assert!(sessionless.sign(&dealer).is_err());
+    }
+
+    #[test]
+    fn test_share_addition() {
+        let sharer = SecretSharer::new(2, 3);
+        let a = sharer.split_secret(&BigUint::from(1000u32));
+        let b = sharer.split_secret(&BigUint::from(234u32));
+        let sums: Vec<_> = a
+            .iter()
+            .zip(&b)
+            .map(|(a, b)| {
+                sharer
+                    .serialize_share(a)
+                    .add(&sharer.serialize_share(b))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            reconstruct_auto(&sums[1..]).unwrap(),
+            1234u32.to_be_bytes()[2..]
+        );
+        assert!(sums[0].add(&sums[1]).is_err());
+
+        let a = split_bytes(b"left", 2, 3).unwrap();
+        let b = split_bytes(&[1, 2, 3, 4], 2, 3).unwrap();
+        let sums = [a[0].add(&b[0]).unwrap(), a[2].add(&b[2]).unwrap()];
+        assert_eq!(
+            reconstruct_auto(&sums).unwrap(),
+            [b'l' ^ 1, b'e' ^ 2, b'f' ^ 3, b't' ^ 4]
+        );
+
+        let group = crate::params::GroupParameters::generate(256, 96).unwrap();
+        let mut vss = group.vss(2, 3).unwrap();
+        let (a, commit_a) = vss.split_secret(&BigUint::from(5u32)).unwrap();
+        let (b, commit_b) = vss.split_secret(&BigUint::from(7u32)).unwrap();
+        let document = vss
+            .commitment_document(&commit_a)
+            .add(&vss.commitment_document(&commit_b))
+            .unwrap();
+        let sums: Vec<_> = a.iter().zip(&b).map(|(a, b)| a.add(b).unwrap()).collect();
+        assert!(sums.iter().all(|share| document.verify_share(share)));
+        assert_eq!(
+            vss.reconstruct_secret(&sums[..2]),
+            Some(BigUint::from(12u32))
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn prop_split_bytes_roundtrip(
+            secret in prop::collection::vec(any::<u8>(), 1..64),
+            (threshold, total) in (1usize..6).prop_flat_map(|t| (Just(t), t..8)),
+            seed in any::<u64>(),
+        ) {
+            let shares = split_bytes(&secret, threshold, total).unwrap();
+            let mut rng = insecure_seeded_rng(&seed.to_be_bytes());
+            let chosen: Vec<_> = shares.choose_multiple(&mut rng, threshold).cloned().collect();
+            let decoded: Vec<_> = chosen
+                .iter()
+                .map(|share| SerializedShare::from_bytes(&share.to_bytes()).unwrap())
+                .collect();
+            prop_assert_eq!(reconstruct_auto(&decoded).unwrap(), secret);
+            prop_assert!(matches!(
+                reconstruct_auto(&decoded[..threshold - 1]),
+                Err(Error::InsufficientShares)
+            ));
+        }
+    }
+}
@@ -0,0 +1,389 @@
+// File: src/service.rs
+//! The backend shared by the service front ends: dealing, share
+//! verification and reconstruction sessions kept in a [`crate::store`].
+//!
+//! A session collects shares from custodians until the threshold of the
+//! lowest-indexed share is reached, then reconstructs the secret once and
+//! forgets the shares.
+
+use std::sync::{Mutex, PoisonError};
+
+use num_bigint::BigUint;
+use rand::RngCore;
+
+use crate::commitment::CommitmentDocument;
+use crate::encoding::hex;
+use crate::error::{Error, Result};
+use crate::expiry::{self, Enforcement};
+use crate::gf256::{self, Gf256};
+use crate::params::GroupParameters;
+use crate::serialized::{check_consistent, reconstruct_auto, split_bytes, Scheme, SerializedShare};
+use crate::sss::SecretSharer;
+use crate::store::{MemoryStore, ShareStore};
+use crate::vss;
+
+/// The result of a dealing.
+pub struct Dealing {
+    pub session: String,
+    pub shares: Vec<SerializedShare>,
+    /// Present for Feldman VSS dealings.
+    pub commitments: Option<CommitmentDocument>,
+}
+
+/// Splits `secret`, as Feldman VSS shares over `group` when one is given and
+/// as byte-wise shares otherwise.
+pub fn deal(
+    secret: &[u8],
+    threshold: usize,
+    total: usize,
+    group: Option<&GroupParameters>,
+) -> Result<Dealing> {
+    let (shares, commitments) = match group {
+        Some(group) => {
+            let mut dealer = group.vss(threshold, total)?;
+            let (shares, published) = dealer
+                .split_secret(&BigUint::from_bytes_be(secret))
+                .map_err(|_| {
+                    Error::InvalidParameters("secret must be smaller than the group order q")
+                })?;
+            let shares = shares
+                .iter()
+                .map(|share| {
+                    dealer
+                        .serialize_share(share)
+                        .with_secret_length(secret.len())
+                })
+                .collect::<Result<_>>()?;
+            (shares, Some(dealer.commitment_document(&published)))
+        }
+        None => (split_bytes(secret, threshold, total)?, None),
+    };
+    Ok(in_new_session(shares, commitments))
+}
+
+/// Moves a shared secret to another field or scheme, e.g. from the default
+/// 2^521 - 1 field to the secp256k1 scalar field: a quorum of `shares`
+/// reconstructs it, and it is dealt afresh as `threshold`-of-`total` shares
+/// of `target`, with commitments when the target is Feldman VSS.
+///
+/// The secret is reassembled in this process, so run it where combining
+/// would be trusted. Prime-field targets record the secret's length, so a
+/// byte-wise secret that starts with zeros keeps them.
+pub fn convert(
+    shares: &[SerializedShare],
+    target: &Scheme,
+    threshold: usize,
+    total: usize,
+) -> Result<Dealing> {
+    if threshold == 0 || threshold > total {
+        return Err(Error::InvalidParameters(
+            "threshold must be between 1 and the total",
+        ));
+    }
+    let secret = reconstruct_auto(shares)?;
+    match target {
+        Scheme::Feldman { p, q, g } => {
+            let group = GroupParameters {
+                p: p.clone(),
+                q: q.clone(),
+                g: g.clone(),
+            };
+            deal(&secret, threshold, total, Some(&group))
+        }
+        Scheme::Shamir { prime } => {
+            let value = BigUint::from_bytes_be(&secret);
+            if &value >= prime {
+                return Err(Error::InvalidParameters(
+                    "secret does not fit in the target field",
+                ));
+            }
+            let sharer = SecretSharer::with_prime(prime.clone(), threshold, total);
+            let shares = sharer
+                .split_secret(&value)
+                .iter()
+                .map(|share| {
+                    sharer
+                        .serialize_share(share)
+                        .with_secret_length(secret.len())
+                })
+                .collect::<Result<_>>()?;
+            Ok(in_new_session(shares, None))
+        }
+        Scheme::Gf256 { poly } => {
+            if total > 255 {
+                return Err(Error::InvalidParameters("at most 255 shares are supported"));
+            }
+            let field = Gf256::new(*poly);
+            let xs: Vec<u8> = (1..=total as u8).collect();
+            let payloads = gf256::split(field, &secret, threshold, &xs, &mut rand::thread_rng())?;
+            let shares = xs
+                .into_iter()
+                .zip(payloads)
+                .map(|(x, payload)| SerializedShare::gf256(field, threshold, total, x, payload))
+                .collect();
+            Ok(in_new_session(shares, None))
+        }
+    }
+}
+
+/// Tags freshly dealt shares with a random session id.
+fn in_new_session(
+    shares: Vec<SerializedShare>,
+    commitments: Option<CommitmentDocument>,
+) -> Dealing {
+    let mut session = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut session);
+    Dealing {
+        session: hex(&session),
+        shares: shares
+            .into_iter()
+            .map(|share| share.with_session(hex(&session)))
+            .collect(),
+        commitments,
+    }
+}
+
+/// Checks a Feldman share, serialized or bare, against a commitment document
+/// given as JSON or armor.
+pub fn verify_share(share: &str, commitments: &str) -> Result<bool> {
+    let document = CommitmentDocument::from_json(commitments)
+        .or_else(|_| CommitmentDocument::from_armored(commitments))?;
+    let share = match SerializedShare::from_armored(share) {
+        Ok(serialized) => {
+            expiry::check(
+                std::slice::from_ref(&serialized),
+                expiry::now(),
+                Enforcement::Refuse,
+            )?;
+            match &serialized.scheme {
+                Scheme::Feldman { p, q, g }
+                    if (p, q, g) == (&document.p, &document.q, &document.g) =>
+                {
+                    vss::Share {
+                        id: serialized.x,
+                        value: BigUint::from_bytes_be(&serialized.value),
+                    }
+                }
+                Scheme::Feldman { .. } => {
+                    return Err(Error::InconsistentShares(
+                        "share and commitments use different groups",
+                    ))
+                }
+                _ => return Err(Error::InvalidParameters("not a Feldman VSS share")),
+            }
+        }
+        Err(_) => vss::Share::from_armored(share).or_else(|_| vss::Share::from_bech32(share))?,
+    };
+    Ok(document.verify_share(&share))
+}
+
+/// Where a reconstruction session stands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionState {
+    pub id: String,
+    pub received: usize,
+    /// Zero until the first share arrives.
+    pub threshold: usize,
+    /// Set once, by the submission that completes the quorum.
+    pub secret: Option<Vec<u8>>,
+}
+
+/// Reconstruction sessions by id, kept in a [`ShareStore`].
+pub struct Sessions {
+    store: Box<dyn ShareStore>,
+    /// Serializes submissions, so only one of them completes a quorum.
+    submitting: Mutex<()>,
+}
+
+impl Default for Sessions {
+    fn default() -> Self {
+        Sessions::new(Box::new(MemoryStore::default()))
+    }
+}
+
+impl Sessions {
+    pub fn new(store: Box<dyn ShareStore>) -> Self {
+        Sessions {
+            store,
+            submitting: Mutex::new(()),
+        }
+    }
+
+    /// The backing store, which servers also use for dealing commitments.
+    pub fn store(&self) -> &dyn ShareStore {
+        self.store.as_ref()
+    }
+
+    pub fn open(&self) -> Result<SessionState> {
+        let mut id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id);
+        let id = hex(&id);
+        self.store.create_session(&id)?;
+        Ok(state(id, &[]))
+    }
+
+    /// The state of an open session; completed sessions are gone.
+    pub fn get(&self, id: &str) -> Result<Option<SessionState>> {
+        if !self.store.contains_session(id)? {
+            return Ok(None);
+        }
+        Ok(Some(state(id.into(), &self.store.list_shares(id)?)))
+    }
+
+    /// Adds a share, reconstructing and closing the session at the threshold.
+    ///
+    /// A share that cannot be combined with those already submitted is
+    /// refused without being stored, and one whose reconstruction fails is
+    /// dropped again, so the honest shares collected so far are kept.
+    pub fn submit(&self, id: &str, share: SerializedShare) -> Result<SessionState> {
+        let _submitting = self
+            .submitting
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if !self.store.contains_session(id)? {
+            return Err(Error::InvalidParameters("no such reconstruction session"));
+        }
+        if self.store.get_share(id, &share.x)?.is_some() {
+            return Err(Error::InconsistentShares(
+                "a share with this index was already submitted",
+            ));
+        }
+        let mut shares = self.store.list_shares(id)?;
+        shares.push(share.clone());
+        check_consistent(&shares)?;
+        self.store.put_share(id, &share)?;
+        if shares.len() < share.threshold {
+            return Ok(state(id.into(), &shares));
+        }
+
+        let secret = match reconstruct_auto(&shares) {
+            Ok(secret) => secret,
+            Err(err) => {
+                self.store.delete_share(id, &share.x)?;
+                return Err(err);
+            }
+        };
+        self.store.delete_session(id)?;
+        let mut done = state(id.into(), &shares);
+        done.secret = Some(secret);
+        Ok(done)
+    }
+}
+
+fn state(id: String, shares: &[SerializedShare]) -> SessionState {
+    SessionState {
+        id,
+        received: shares.len(),
+        threshold: shares.first().map_or(0, |share| share.threshold),
+        secret: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_reaches_quorum_once() {
+        let dealing = deal(b"quorum", 2, 3, None).unwrap();
+        assert!(dealing.commitments.is_none());
+
+        let sessions = Sessions::default();
+        let id = sessions.open().unwrap().id;
+        let first = sessions.submit(&id, dealing.shares[2].clone()).unwrap();
+        assert_eq!((first.received, first.threshold), (1, 2));
+        assert!(sessions.submit(&id, dealing.shares[2].clone()).is_err());
+
+        let done = sessions.submit(&id, dealing.shares[0].clone()).unwrap();
+        assert_eq!(done.secret.as_deref(), Some(&b"quorum"[..]));
+        assert!(sessions.get(&id).unwrap().is_none());
+        assert!(sessions.submit(&id, dealing.shares[1].clone()).is_err());
+    }
+
+    #[test]
+    fn test_bad_share_keeps_session() {
+        let dealing = deal(b"quorum", 2, 3, None).unwrap();
+        let sessions = Sessions::default();
+        let id = sessions.open().unwrap().id;
+        sessions.submit(&id, dealing.shares[0].clone()).unwrap();
+
+        // A share of another scheme is refused without being stored
+        let prime = BigUint::from(7u32);
+        let foreign =
+            SerializedShare::new(Scheme::Shamir { prime }, 2, 3, 2u32.into(), vec![3]).unwrap();
+        assert!(sessions.submit(&id, foreign.clone()).is_err());
+        assert_eq!(sessions.get(&id).unwrap().unwrap().received, 1);
+
+        // One whose reconstruction fails is dropped again: indices 1 and 8
+        // collide modulo 7
+        let other = sessions.open().unwrap().id;
+        let mut colliding = foreign.clone();
+        colliding.x = 1u32.into();
+        sessions.submit(&other, colliding).unwrap();
+        colliding = foreign;
+        colliding.x = 8u32.into();
+        assert!(sessions.submit(&other, colliding).is_err());
+        assert_eq!(sessions.get(&other).unwrap().unwrap().received, 1);
+
+        // A panic while holding the lock does not wedge later submissions
+        std::thread::scope(|scope| {
+            let holder = scope.spawn(|| {
+                let _held = sessions.submitting.lock().unwrap();
+                panic!("poison the session lock");
+            });
+            assert!(holder.join().is_err());
+        });
+        assert!(sessions.submitting.is_poisoned());
+        let done = sessions.submit(&id, dealing.shares[1].clone()).unwrap();
+        assert_eq!(done.secret.as_deref(), Some(&b"quorum"[..]));
+    }
+
+    #[test]
+    fn test_convert_between_fields() {
+        let sharer = SecretSharer::new(2, 3);
+        let secret = BigUint::from(0xDEADBEEFu32);
+        let shares: Vec<_> = sharer
+            .split_secret(&secret)
+            .iter()
+            .map(|share| sharer.serialize_share(share))
+            .collect();
+
+        let target = Scheme::Shamir {
+            prime: crate::sss::named_prime("secp256k1").unwrap(),
+        };
+        let converted = convert(&shares[1..], &target, 3, 5).unwrap();
+        assert!(converted.shares.iter().all(|share| share.scheme == target));
+        assert_eq!(
+            reconstruct_auto(&converted.shares[2..]).unwrap(),
+            secret.to_bytes_be()
+        );
+
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let target = Scheme::Feldman {
+            p: group.p.clone(),
+            q: group.q.clone(),
+            g: group.g.clone(),
+        };
+        let feldman = convert(&converted.shares[..3], &target, 2, 2).unwrap();
+        assert!(feldman.commitments.is_some());
+        assert_eq!(
+            reconstruct_auto(&feldman.shares).unwrap(),
+            secret.to_bytes_be()
+        );
+        let padded = split_bytes(b"\0\0key", 2, 2).unwrap();
+        let converted = convert(&padded, &target, 2, 2).unwrap();
+        assert_eq!(reconstruct_auto(&converted.shares).unwrap(), b"\0\0key");
+        let dealing = deal(b"\0key", 2, 3, Some(&group)).unwrap();
+        assert_eq!(reconstruct_auto(&dealing.shares[1..]).unwrap(), b"\0key");
+
+        assert!(convert(
+            &shares,
+            &Scheme::Shamir {
+                prime: 251u32.into()
+            },
+            2,
+            3
+        )
+        .is_err());
+    }
+}
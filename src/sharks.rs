@@ -0,0 +1,102 @@
+// File: src/sharks.rs
+//! Shares in the byte layout of the `sharks` crate.
+//!
+//! `sharks` works over GF(2^8) reduced by x^8 + x^4 + x^3 + x^2 + 1 (the
+//! libgfshare field, not Rijndael) and serializes a share as its x-coordinate
+//! byte followed by one byte per secret byte. Its dealer numbers shares
+//! 1, 2, 3, ... The bytes carry no threshold, so importers must supply it.
+//!
+//! Shares dealt here with [`split_bytes`](crate::serialized::split_bytes) use
+//! the Rijndael field and cannot be exported; deal with [`split`] instead.
+
+use num_traits::ToPrimitive;
+use rand::thread_rng;
+
+use crate::error::{Error, Result};
+use crate::gf256::{self, Gf256};
+use crate::serialized::{Scheme, SerializedShare};
+
+/// The field of `sharks`' log/exp tables.
+pub const FIELD: Gf256 = Gf256::new(0x11d);
+
+/// Splits `secret` over the `sharks` field into shares numbered 1..=total.
+pub fn split(secret: &[u8], threshold: usize, total: usize) -> Result<Vec<SerializedShare>> {
+    if total > 255 {
+        return Err(Error::InvalidParameters("at most 255 shares are supported"));
+    }
+    let xs: Vec<u8> = (1..=total as u8).collect();
+    let payloads = gf256::split(FIELD, secret, threshold, &xs, &mut thread_rng())?;
+    Ok(xs
+        .into_iter()
+        .zip(payloads)
+        .map(|(x, payload)| SerializedShare::gf256(FIELD, threshold, total, x, payload))
+        .collect())
+}
+
+/// Encodes a share the way `Vec::<u8>::from(&sharks::Share)` does.
+pub fn to_sharks(share: &SerializedShare) -> Result<Vec<u8>> {
+    if share.scheme != (Scheme::Gf256 { poly: FIELD.poly() }) {
+        return Err(Error::InvalidParameters(
+            "only shares over the sharks field can be exported",
+        ));
+    }
+    let x = share.x.to_u8().ok_or(Error::InvalidParameters(
+        "share index does not fit in a byte",
+    ))?;
+    let mut bytes = Vec::with_capacity(share.value.len() + 1);
+    bytes.push(x);
+    bytes.extend_from_slice(&share.value);
+    Ok(bytes)
+}
+
+/// Decodes a share produced by `sharks`, tagging it with the `threshold` and
+/// `total` it was dealt with.
+pub fn from_sharks(bytes: &[u8], threshold: usize, total: usize) -> Result<SerializedShare> {
+    match bytes {
+        [0, ..] => Err(Error::MalformedShare),
+        [x, payload @ ..] if !payload.is_empty() => Ok(SerializedShare::gf256(
+            FIELD,
+            threshold,
+            total,
+            *x,
+            payload.to_vec(),
+        )),
+        _ => Err(Error::InvalidEncoding(
+            "a sharks share is at least 2 bytes long",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::{reconstruct_auto, split_bytes};
+
+    #[test]
+    fn test_sharks_layout_roundtrip() {
+        // With a degree-1 polynomial s + a*x, the share at x = 2 is s ^ a*2,
+        // where doubling 0x80 wraps through the sharks field's 0x1d
+        let share = SerializedShare::gf256(FIELD, 2, 3, 2, vec![0x01 ^ FIELD.mul(0x80, 2)]);
+        assert_eq!(to_sharks(&share).unwrap(), [2, 0x1c]);
+        let other = from_sharks(&[1, 0x01 ^ 0x80], 2, 3).unwrap();
+        assert_eq!(reconstruct_auto(&[share, other]).unwrap(), [0x01]);
+
+        let secret = b"migrated from sharks";
+        let exported: Vec<Vec<u8>> = split(secret, 3, 5)
+            .unwrap()
+            .iter()
+            .map(|share| to_sharks(share).unwrap())
+            .collect();
+        assert_eq!(exported[4][0], 5);
+        assert_eq!(exported[4].len(), secret.len() + 1);
+        let imported: Vec<SerializedShare> = exported[1..4]
+            .iter()
+            .map(|bytes| from_sharks(bytes, 3, 5).unwrap())
+            .collect();
+        assert_eq!(reconstruct_auto(&imported).unwrap(), secret);
+
+        assert!(to_sharks(&split_bytes(secret, 2, 3).unwrap()[0]).is_err());
+        assert!(from_sharks(&[1], 2, 3).is_err());
+        assert!(from_sharks(&[0, 1], 2, 3).is_err());
+    }
+}
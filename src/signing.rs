@@ -0,0 +1,51 @@
+// File: src/signing.rs
+//! Ed25519 dealer signatures over published artifacts.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+use crate::error::{Error, Result};
+
+/// A dealer's Ed25519 public key and signature over some artifact.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DealerSignature {
+    pub dealer: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+impl DealerSignature {
+    pub const LEN: usize = 96;
+
+    pub fn sign(key: &SigningKey, message: &[u8]) -> Self {
+        DealerSignature {
+            dealer: key.verifying_key().to_bytes(),
+            signature: key.sign(message).to_bytes(),
+        }
+    }
+
+    /// Checks the signature over `message` and that it was made by `dealer`.
+    pub fn verify(&self, dealer: &VerifyingKey, message: &[u8]) -> Result<()> {
+        if dealer.to_bytes() != self.dealer {
+            return Err(Error::InvalidSignature);
+        }
+        dealer
+            .verify(message, &Signature::from_bytes(&self.signature))
+            .map_err(|_| Error::InvalidSignature)
+    }
+
+    pub(crate) fn put(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.dealer);
+        out.extend_from_slice(&self.signature);
+    }
+
+    pub(crate) fn take(input: &mut &[u8]) -> Result<Self> {
+        if input.len() < Self::LEN {
+            return Err(Error::MalformedShare);
+        }
+        let mut dealer = [0u8; 32];
+        let mut signature = [0u8; 64];
+        dealer.copy_from_slice(&input[..32]);
+        signature.copy_from_slice(&input[32..Self::LEN]);
+        *input = &input[Self::LEN..];
+        Ok(DealerSignature { dealer, signature })
+    }
+}
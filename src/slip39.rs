@@ -0,0 +1,559 @@
+// File: src/slip39.rs
+//! SLIP-0039 Shamir backups, interoperable with Trezor and other SLIP-39 tooling.
+//!
+//! The master secret is encrypted under a passphrase with a four-round Feistel
+//! cipher, split into groups, and each group share is split again among the
+//! group's members. Shares are exchanged as mnemonics of 10-bit words
+//! protected by an RS1024 checksum.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use hmac::{Hmac, Mac};
+use rand::{thread_rng, Rng, RngCore};
+use sha2::Sha256;
+
+use crate::error::{Error, Result};
+use crate::gf256::{self, Gf256};
+
+const WORDLIST: &str = include_str!("wordlists/slip39_english.txt");
+const RADIX_BITS: usize = 10;
+const CHECKSUM_WORDS: usize = 3;
+const METADATA_WORDS: usize = 4 + CHECKSUM_WORDS;
+const MIN_MNEMONIC_WORDS: usize = METADATA_WORDS + 13;
+const MAX_SHARE_COUNT: u8 = 16;
+const MIN_SECRET_BYTES: usize = 16;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const DIGEST_BYTES: usize = 4;
+const ROUND_COUNT: u8 = 4;
+const BASE_ITERATION_COUNT: u32 = 10000 / ROUND_COUNT as u32;
+const CUSTOMIZATION: &[u8] = b"shamir";
+const CUSTOMIZATION_EXTENDABLE: &[u8] = b"shamir_extendable";
+
+/// Member threshold and member count for one group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GroupSpec {
+    pub threshold: u8,
+    pub count: u8,
+}
+
+/// A single decoded SLIP-39 share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slip39Share {
+    pub identifier: u16,
+    pub extendable: bool,
+    pub iteration_exponent: u8,
+    pub group_index: u8,
+    pub group_threshold: u8,
+    pub group_count: u8,
+    pub member_index: u8,
+    pub member_threshold: u8,
+    pub value: Vec<u8>,
+}
+
+impl Slip39Share {
+    pub fn to_mnemonic(&self) -> String {
+        let words = wordlist();
+        let id_exp = (self.identifier as u32) << 5
+            | (self.extendable as u32) << 4
+            | self.iteration_exponent as u32;
+        let params = (self.group_index as u32) << 16
+            | (self.group_threshold as u32 - 1) << 12
+            | (self.group_count as u32 - 1) << 8
+            | (self.member_index as u32) << 4
+            | (self.member_threshold as u32 - 1);
+
+        let mut indices = vec![
+            (id_exp >> 10) as u16,
+            (id_exp & 0x3ff) as u16,
+            (params >> 10) as u16,
+            (params & 0x3ff) as u16,
+        ];
+        indices.extend(bytes_to_words(&self.value));
+        indices.extend(create_checksum(self.customization(), &indices));
+
+        indices
+            .iter()
+            .map(|&i| words[i as usize])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Result<Self> {
+        let indices = mnemonic
+            .split_whitespace()
+            .enumerate()
+            .map(|(position, word)| {
+                let word = word.to_lowercase();
+                wordlist()
+                    .binary_search(&word.as_str())
+                    .map(|i| i as u16)
+                    .map_err(|_| Error::UnknownWord { position, word })
+            })
+            .collect::<Result<Vec<u16>>>()?;
+
+        if indices.len() < MIN_MNEMONIC_WORDS {
+            return Err(Error::InvalidMnemonic("mnemonic too short"));
+        }
+        let padding = (RADIX_BITS * (indices.len() - METADATA_WORDS)) % 16;
+        if padding > 8 {
+            return Err(Error::InvalidMnemonic("invalid mnemonic length"));
+        }
+
+        let id_exp = (indices[0] as u32) << 10 | indices[1] as u32;
+        let extendable = (id_exp >> 4) & 1 == 1;
+        let customization = if extendable {
+            CUSTOMIZATION_EXTENDABLE
+        } else {
+            CUSTOMIZATION
+        };
+        if polymod(customization, &indices) != 1 {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        let params = (indices[2] as u32) << 10 | indices[3] as u32;
+        let field = |shift: u32| ((params >> shift) & 0xf) as u8;
+        let share = Slip39Share {
+            identifier: (id_exp >> 5) as u16,
+            extendable,
+            iteration_exponent: (id_exp & 0xf) as u8,
+            group_index: field(16),
+            group_threshold: field(12) + 1,
+            group_count: field(8) + 1,
+            member_index: field(4),
+            member_threshold: field(0) + 1,
+            value: words_to_bytes(&indices[4..indices.len() - CHECKSUM_WORDS], padding)?,
+        };
+        if share.group_threshold > share.group_count {
+            return Err(Error::InvalidMnemonic(
+                "group threshold exceeds group count",
+            ));
+        }
+        Ok(share)
+    }
+
+    fn customization(&self) -> &'static [u8] {
+        if self.extendable {
+            CUSTOMIZATION_EXTENDABLE
+        } else {
+            CUSTOMIZATION
+        }
+    }
+}
+
+/// Splits `master_secret` into mnemonic groups.
+///
+/// Any `group_threshold` groups can recover the secret, each group needing
+/// its own member threshold. Returns one list of mnemonics per group.
+pub fn generate_mnemonics(
+    group_threshold: u8,
+    groups: &[GroupSpec],
+    master_secret: &[u8],
+    passphrase: &[u8],
+    extendable: bool,
+    iteration_exponent: u8,
+) -> Result<Vec<Vec<String>>> {
+    if master_secret.len() < MIN_SECRET_BYTES || !master_secret.len().is_multiple_of(2) {
+        return Err(Error::InvalidParameters(
+            "master secret must be at least 16 bytes and of even length",
+        ));
+    }
+    if !passphrase.iter().all(|&c| (32..=126).contains(&c)) {
+        return Err(Error::InvalidParameters(
+            "passphrase must contain only printable ASCII",
+        ));
+    }
+    if groups.is_empty() || groups.len() > MAX_SHARE_COUNT as usize {
+        return Err(Error::InvalidParameters(
+            "group count must be between 1 and 16",
+        ));
+    }
+    if group_threshold == 0 || group_threshold as usize > groups.len() {
+        return Err(Error::InvalidParameters(
+            "group threshold must be between 1 and the group count",
+        ));
+    }
+    if iteration_exponent > 15 {
+        return Err(Error::InvalidParameters(
+            "iteration exponent must be at most 15",
+        ));
+    }
+    for group in groups {
+        if group.threshold == 1 && group.count > 1 {
+            return Err(Error::InvalidParameters(
+                "member threshold 1 requires a single member share",
+            ));
+        }
+    }
+
+    let mut rng = thread_rng();
+    let identifier = rng.gen::<u16>() & 0x7fff;
+    let encrypted = encrypt(
+        master_secret,
+        passphrase,
+        iteration_exponent,
+        identifier,
+        extendable,
+    );
+
+    let group_shares = split_secret(group_threshold, groups.len() as u8, &encrypted, &mut rng)?;
+    groups
+        .iter()
+        .zip(group_shares)
+        .map(|(group, (group_index, group_secret))| {
+            let members = split_secret(group.threshold, group.count, &group_secret, &mut rng)?;
+            Ok(members
+                .into_iter()
+                .map(|(member_index, value)| {
+                    Slip39Share {
+                        identifier,
+                        extendable,
+                        iteration_exponent,
+                        group_index,
+                        group_threshold,
+                        group_count: groups.len() as u8,
+                        member_index,
+                        member_threshold: group.threshold,
+                        value,
+                    }
+                    .to_mnemonic()
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// Recovers the master secret from a set of mnemonics.
+pub fn combine_mnemonics<S: AsRef<str>>(mnemonics: &[S], passphrase: &[u8]) -> Result<Vec<u8>> {
+    let shares = mnemonics
+        .iter()
+        .map(|m| Slip39Share::from_mnemonic(m.as_ref()))
+        .collect::<Result<Vec<_>>>()?;
+    let first = shares
+        .first()
+        .ok_or(Error::InvalidParameters("no mnemonics provided"))?;
+
+    let mut groups: BTreeMap<u8, Vec<&Slip39Share>> = BTreeMap::new();
+    for share in &shares {
+        if (share.identifier, share.extendable, share.iteration_exponent)
+            != (first.identifier, first.extendable, first.iteration_exponent)
+        {
+            return Err(Error::InconsistentShares(
+                "mnemonics belong to different secrets",
+            ));
+        }
+        if (share.group_threshold, share.group_count) != (first.group_threshold, first.group_count)
+        {
+            return Err(Error::InconsistentShares("mismatched group parameters"));
+        }
+        groups.entry(share.group_index).or_default().push(share);
+    }
+    if groups.len() < first.group_threshold as usize {
+        return Err(Error::InsufficientShares);
+    }
+
+    let mut group_shares = Vec::new();
+    for (&group_index, members) in &groups {
+        let threshold = members[0].member_threshold;
+        if members.iter().any(|m| m.member_threshold != threshold) {
+            return Err(Error::InconsistentShares("mismatched member thresholds"));
+        }
+        if members.len() < threshold as usize {
+            continue;
+        }
+        let points: Vec<(u8, &[u8])> = members
+            .iter()
+            .take(threshold as usize)
+            .map(|m| (m.member_index, m.value.as_slice()))
+            .collect();
+        group_shares.push((group_index, recover_secret(threshold, &points)?));
+    }
+    if group_shares.len() < first.group_threshold as usize {
+        return Err(Error::InsufficientShares);
+    }
+
+    let points: Vec<(u8, &[u8])> = group_shares
+        .iter()
+        .take(first.group_threshold as usize)
+        .map(|(index, value)| (*index, value.as_slice()))
+        .collect();
+    let encrypted = recover_secret(first.group_threshold, &points)?;
+    Ok(decrypt(
+        &encrypted,
+        passphrase,
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+    ))
+}
+
+/// Encrypts a master secret with the SLIP-39 Feistel cipher.
+pub fn encrypt(
+    master_secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Vec<u8> {
+    let salt = salt(identifier, extendable);
+    let (mut l, mut r) = halves(master_secret);
+    for i in 0..ROUND_COUNT {
+        let f = round_function(i, passphrase, iteration_exponent, &salt, &r);
+        (l, r) = (r, xor(&l, &f));
+    }
+    [r, l].concat()
+}
+
+/// Inverts [`encrypt`].
+pub fn decrypt(
+    encrypted: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Vec<u8> {
+    let salt = salt(identifier, extendable);
+    let (mut l, mut r) = halves(encrypted);
+    for i in (0..ROUND_COUNT).rev() {
+        let f = round_function(i, passphrase, iteration_exponent, &salt, &r);
+        (l, r) = (r, xor(&l, &f));
+    }
+    [r, l].concat()
+}
+
+fn split_secret(
+    threshold: u8,
+    count: u8,
+    secret: &[u8],
+    rng: &mut impl RngCore,
+) -> Result<Vec<(u8, Vec<u8>)>> {
+    if threshold == 0 || threshold > count || count > MAX_SHARE_COUNT {
+        return Err(Error::InvalidParameters(
+            "threshold must not exceed the share count of at most 16",
+        ));
+    }
+    if threshold == 1 {
+        return Ok((0..count).map(|i| (i, secret.to_vec())).collect());
+    }
+
+    // The first t-2 shares are random; the digest and secret fix the rest
+    let mut shares: Vec<(u8, Vec<u8>)> = (0..threshold - 2)
+        .map(|i| {
+            let mut value = vec![0u8; secret.len()];
+            rng.fill_bytes(&mut value);
+            (i, value)
+        })
+        .collect();
+
+    let mut random_part = vec![0u8; secret.len() - DIGEST_BYTES];
+    rng.fill_bytes(&mut random_part);
+    let digest_share = [digest(&random_part, secret).as_slice(), &random_part].concat();
+
+    let mut base: Vec<(u8, &[u8])> = shares.iter().map(|(i, v)| (*i, v.as_slice())).collect();
+    base.push((DIGEST_INDEX, &digest_share));
+    base.push((SECRET_INDEX, secret));
+
+    let derived = (threshold - 2..count)
+        .map(|i| Ok((i, gf256::interpolate(Gf256::RIJNDAEL, &base, i)?)))
+        .collect::<Result<Vec<_>>>()?;
+    shares.extend(derived);
+    Ok(shares)
+}
+
+fn recover_secret(threshold: u8, shares: &[(u8, &[u8])]) -> Result<Vec<u8>> {
+    if threshold == 1 {
+        return Ok(shares[0].1.to_vec());
+    }
+    let secret = gf256::interpolate(Gf256::RIJNDAEL, shares, SECRET_INDEX)?;
+    let digest_share = gf256::interpolate(Gf256::RIJNDAEL, shares, DIGEST_INDEX)?;
+    let (expected, random_part) = digest_share.split_at(DIGEST_BYTES);
+    if digest(random_part, &secret) != expected {
+        return Err(Error::DigestMismatch);
+    }
+    Ok(secret)
+}
+
+fn digest(random_part: &[u8], secret: &[u8]) -> [u8; DIGEST_BYTES] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(random_part).expect("HMAC accepts any key length");
+    mac.update(secret);
+    let tag = mac.finalize().into_bytes();
+    [tag[0], tag[1], tag[2], tag[3]]
+}
+
+fn salt(identifier: u16, extendable: bool) -> Vec<u8> {
+    if extendable {
+        Vec::new()
+    } else {
+        [CUSTOMIZATION, &identifier.to_be_bytes()].concat()
+    }
+}
+
+fn round_function(i: u8, passphrase: &[u8], exponent: u8, salt: &[u8], r: &[u8]) -> Vec<u8> {
+    let password = [&[i], passphrase].concat();
+    let salt = [salt, r].concat();
+    let mut out = vec![0u8; r.len()];
+    pbkdf2::pbkdf2_hmac::<Sha256>(&password, &salt, BASE_ITERATION_COUNT << exponent, &mut out);
+    out
+}
+
+fn halves(data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let (l, r) = data.split_at(data.len() / 2);
+    (l.to_vec(), r.to_vec())
+}
+
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+fn wordlist() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORDLIST.lines().collect())
+}
+
+/// Packs bytes into 10-bit words, zero-padding at the front.
+fn bytes_to_words(bytes: &[u8]) -> Vec<u16> {
+    let word_count = (bytes.len() * 8).div_ceil(RADIX_BITS);
+    let mut bits = word_count * RADIX_BITS - bytes.len() * 8;
+    let mut acc = 0u32;
+    let mut words = Vec::with_capacity(word_count);
+    for &byte in bytes {
+        acc = (acc << 8) | byte as u32;
+        bits += 8;
+        while bits >= RADIX_BITS {
+            bits -= RADIX_BITS;
+            words.push(((acc >> bits) & 0x3ff) as u16);
+        }
+    }
+    words
+}
+
+fn words_to_bytes(words: &[u16], padding: usize) -> Result<Vec<u8>> {
+    if words[0] >> (RADIX_BITS - padding) != 0 {
+        return Err(Error::InvalidMnemonic("invalid padding"));
+    }
+    let len = (words.len() * RADIX_BITS - padding) / 8;
+    let mut bits = 0usize;
+    let mut acc = 0u32;
+    let mut bytes = Vec::with_capacity(len);
+    let mut skip = padding;
+    for &word in words {
+        acc = (acc << RADIX_BITS) | word as u32;
+        bits += RADIX_BITS;
+        if skip > 0 {
+            bits -= skip;
+            acc &= (1 << bits) - 1;
+            skip = 0;
+        }
+        while bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+        }
+        acc &= (1 << bits) - 1;
+    }
+    Ok(bytes)
+}
+
+fn polymod(customization: &[u8], values: &[u16]) -> u32 {
+    const GEN: [u32; 10] = [
+        0x00e0e040, 0x01c1c080, 0x03838100, 0x07070200, 0x0e0e0009, 0x1c0c2412, 0x38086c24,
+        0x3090fc48, 0x21b1f890, 0x03f3f120,
+    ];
+    let mut chk = 1u32;
+    for v in customization
+        .iter()
+        .map(|&c| c as u32)
+        .chain(values.iter().map(|&v| v as u32))
+    {
+        let b = chk >> 20;
+        chk = ((chk & 0xfffff) << 10) ^ v;
+        for (i, g) in GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn create_checksum(customization: &[u8], data: &[u16]) -> [u16; CHECKSUM_WORDS] {
+    let mut values = data.to_vec();
+    values.extend([0; CHECKSUM_WORDS]);
+    let chk = polymod(customization, &values) ^ 1;
+    [
+        ((chk >> 20) & 0x3ff) as u16,
+        ((chk >> 10) & 0x3ff) as u16,
+        (chk & 0x3ff) as u16,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_slip39_reference_vectors() {
+        // Vectors from the SLIP-0039 specification, passphrase "TREZOR"
+        let single = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard";
+        assert_eq!(
+            hex(&combine_mnemonics(&[single], b"TREZOR").unwrap()),
+            "bb54aac4b89dc868ba37d9cc21b2cece"
+        );
+        assert_eq!(
+            Slip39Share::from_mnemonic(single).unwrap().to_mnemonic(),
+            single
+        );
+
+        let bad_checksum = single.replace("keyboard", "kidney");
+        assert!(combine_mnemonics(&[bad_checksum], b"TREZOR").is_err());
+
+        let two_of_three = [
+            "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+        ];
+        assert_eq!(
+            hex(&combine_mnemonics(&two_of_three, b"TREZOR").unwrap()),
+            "b43ceb7e57a0ea8766221624d01b0864"
+        );
+        assert!(combine_mnemonics(&two_of_three[..1], b"TREZOR").is_err());
+    }
+
+    #[test]
+    fn test_slip39_groups_roundtrip() {
+        let secret: Vec<u8> = (0..32).collect();
+        let groups = [
+            GroupSpec {
+                threshold: 1,
+                count: 1,
+            },
+            GroupSpec {
+                threshold: 2,
+                count: 3,
+            },
+            GroupSpec {
+                threshold: 3,
+                count: 5,
+            },
+        ];
+        for extendable in [false, true] {
+            let mnemonics =
+                generate_mnemonics(2, &groups, &secret, b"pass", extendable, 0).unwrap();
+            assert_eq!(mnemonics[2].len(), 5);
+
+            let chosen = [
+                mnemonics[0][0].as_str(),
+                mnemonics[2][4].as_str(),
+                mnemonics[2][0].as_str(),
+                mnemonics[2][2].as_str(),
+            ];
+            assert_eq!(combine_mnemonics(&chosen, b"pass").unwrap(), secret);
+            assert_ne!(combine_mnemonics(&chosen, b"other").unwrap(), secret);
+            // A group below its member threshold does not count toward the quorum
+            assert!(combine_mnemonics(&chosen[..3], b"pass").is_err());
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// File: src/sqlite.rs
+//! A [`ShareStore`] in a single SQLite database (`sqlite` feature).
+//!
+//! Shares are stored in their binary encoding, keyed by session and index;
+//! commitment documents as JSON. Every operation is one statement or one
+//! transaction, so several servers may share a database file.
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use num_bigint::BigUint;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::commitment::CommitmentDocument;
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+use crate::store::{check_session, ShareStore};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sessions (
+        id TEXT PRIMARY KEY
+    );
+    CREATE TABLE IF NOT EXISTS shares (
+        session TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+        x BLOB NOT NULL,
+        share BLOB NOT NULL,
+        PRIMARY KEY (session, x)
+    );
+    CREATE TABLE IF NOT EXISTS commitments (
+        session TEXT PRIMARY KEY REFERENCES sessions(id) ON DELETE CASCADE,
+        document TEXT NOT NULL
+    );
+";
+
+/// Keeps sessions in a SQLite database.
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Opens or creates the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(Connection::open(path).map_err(db_error)?)
+    }
+
+    /// A private database that lives as long as the store.
+    pub fn in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(db_error)?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self> {
+        connection
+            .execute_batch("PRAGMA foreign_keys = ON;")
+            .and_then(|()| connection.execute_batch(SCHEMA))
+            .map_err(db_error)?;
+        Ok(SqliteStore {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.connection.lock().expect("database lock")
+    }
+}
+
+/// Indices are keyed by their minimal big-endian bytes.
+fn index_key(x: &BigUint) -> Vec<u8> {
+    x.to_bytes_be()
+}
+
+fn db_error(err: rusqlite::Error) -> Error {
+    Error::Io(std::io::Error::other(err))
+}
+
+fn insert_session(connection: &Connection, session: &str) -> Result<()> {
+    check_session(session)?;
+    connection
+        .execute(
+            "INSERT OR IGNORE INTO sessions (id) VALUES (?1)",
+            params![session],
+        )
+        .map_err(db_error)?;
+    Ok(())
+}
+
+impl ShareStore for SqliteStore {
+    fn create_session(&self, session: &str) -> Result<()> {
+        insert_session(&self.lock(), session)
+    }
+
+    fn contains_session(&self, session: &str) -> Result<bool> {
+        self.lock()
+            .query_row(
+                "SELECT 1 FROM sessions WHERE id = ?1",
+                params![session],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|row| row.is_some())
+            .map_err(db_error)
+    }
+
+    fn sessions(&self) -> Result<Vec<String>> {
+        let connection = self.lock();
+        let mut statement = connection
+            .prepare("SELECT id FROM sessions ORDER BY id")
+            .map_err(db_error)?;
+        let ids = statement
+            .query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .map_err(db_error);
+        ids
+    }
+
+    fn delete_session(&self, session: &str) -> Result<bool> {
+        let deleted = self
+            .lock()
+            .execute("DELETE FROM sessions WHERE id = ?1", params![session])
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    fn put_share(&self, session: &str, share: &SerializedShare) -> Result<()> {
+        let mut connection = self.lock();
+        let transaction = connection.transaction().map_err(db_error)?;
+        insert_session(&transaction, session)?;
+        transaction
+            .execute(
+                "INSERT OR REPLACE INTO shares (session, x, share) VALUES (?1, ?2, ?3)",
+                params![session, index_key(&share.x), share.to_bytes()],
+            )
+            .map_err(db_error)?;
+        transaction.commit().map_err(db_error)
+    }
+
+    fn get_share(&self, session: &str, x: &BigUint) -> Result<Option<SerializedShare>> {
+        let bytes: Option<Vec<u8>> = self
+            .lock()
+            .query_row(
+                "SELECT share FROM shares WHERE session = ?1 AND x = ?2",
+                params![session, index_key(x)],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error)?;
+        bytes
+            .map(|bytes| SerializedShare::from_bytes(&bytes))
+            .transpose()
+    }
+
+    fn list_shares(&self, session: &str) -> Result<Vec<SerializedShare>> {
+        let connection = self.lock();
+        let mut statement = connection
+            .prepare("SELECT share FROM shares WHERE session = ?1")
+            .map_err(db_error)?;
+        let rows: Vec<Vec<u8>> = statement
+            .query_map(params![session], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .map_err(db_error)?;
+        let mut shares = rows
+            .iter()
+            .map(|bytes| SerializedShare::from_bytes(bytes))
+            .collect::<Result<Vec<_>>>()?;
+        shares.sort_by(|a, b| a.x.cmp(&b.x));
+        Ok(shares)
+    }
+
+    fn delete_share(&self, session: &str, x: &BigUint) -> Result<bool> {
+        let deleted = self
+            .lock()
+            .execute(
+                "DELETE FROM shares WHERE session = ?1 AND x = ?2",
+                params![session, index_key(x)],
+            )
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    fn put_commitments(&self, session: &str, document: &CommitmentDocument) -> Result<()> {
+        let mut connection = self.lock();
+        let transaction = connection.transaction().map_err(db_error)?;
+        insert_session(&transaction, session)?;
+        transaction
+            .execute(
+                "INSERT OR REPLACE INTO commitments (session, document) VALUES (?1, ?2)",
+                params![session, document.to_json()?],
+            )
+            .map_err(db_error)?;
+        transaction.commit().map_err(db_error)
+    }
+
+    fn get_commitments(&self, session: &str) -> Result<Option<CommitmentDocument>> {
+        let json: Option<String> = self
+            .lock()
+            .query_row(
+                "SELECT document FROM commitments WHERE session = ?1",
+                params![session],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error)?;
+        json.map(|json| CommitmentDocument::from_json(&json))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_store() {
+        crate::store::tests::exercise(&SqliteStore::in_memory().unwrap());
+    }
+}
@@ -1,6 +1,44 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::str::FromStr;
+
 use num_bigint::{BigUint, RandBigInt};
 use num_traits::{One, Zero};
-use rand::thread_rng;
+use rand::{thread_rng, RngCore};
+
+use crate::bech32;
+use crate::encoding::{
+    armor, dearmor, fingerprint, from_canonical, put_biguint, put_share_header, take_biguint,
+    take_share_header, to_canonical, SHARE_VERSION,
+};
+use crate::error::{Error, Result};
+use crate::identity;
+use crate::mnemonic;
+use crate::progress::Progress;
+#[cfg(feature = "qr")]
+use crate::qr::{self, QrFormat};
+use crate::serialized::{insecure_seeded_rng, Scheme, SerializedShare};
+
+const CANONICAL_PREFIX: &str = "sss";
+const ARMOR_LABEL: &str = "SHAMIR SHARE";
+const MAGIC: &[u8; 3] = b"SSS";
+
+/// The order of the secp256k1 group, the field of its private keys.
+const SECP256K1_ORDER: &str = "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141";
+
+/// The names accepted by [`named_prime`].
+pub const PRIMES: &[&str] = &["mersenne-521", "secp256k1"];
+
+/// Returns a well-known prime field by name: `mersenne-521`, the default
+/// field of [`SecretSharer::new`], or `secp256k1`, its scalar field.
+pub fn named_prime(name: &str) -> Result<BigUint> {
+    match name {
+        "mersenne-521" => Ok(BigUint::from(2u32).pow(521) - 1u32),
+        "secp256k1" => Ok(BigUint::parse_bytes(SECP256K1_ORDER.as_bytes(), 16)
+            .expect("named primes are valid hex")),
+        _ => Err(Error::InvalidParameters("unknown prime field")),
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Share {
@@ -8,6 +46,186 @@ pub struct Share {
     pub y: BigUint,
 }
 
+impl Share {
+    /// Serializes the share as the magic `SSS`, [`SHARE_VERSION`] and two
+    /// length-prefixed big-endian integers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = put_share_header(MAGIC);
+        put_biguint(&mut out, &self.x);
+        put_biguint(&mut out, &self.y);
+        out
+    }
+
+    /// Decodes a share in the current version; older encodings go through
+    /// [`Share::migrate`] first.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        match take_share_header(MAGIC, bytes)? {
+            (SHARE_VERSION, body) => Self::take_body(body),
+            (version, _) => Err(Error::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Upgrades a version 1 encoding, the bare two integers, to the current
+    /// version.
+    pub fn migrate(bytes: &[u8]) -> Result<Vec<u8>> {
+        match take_share_header(MAGIC, bytes)? {
+            (1, body) => Ok(Self::take_body(body)?.to_bytes()),
+            (version, _) => Err(Error::UnsupportedVersion(version)),
+        }
+    }
+
+    /// [`Share::migrate`] for an armored share.
+    pub fn migrate_armored(text: &str) -> Result<String> {
+        Ok(armor(
+            ARMOR_LABEL,
+            &Self::migrate(&dearmor(ARMOR_LABEL, text)?)?,
+        ))
+    }
+
+    fn take_body(mut input: &[u8]) -> Result<Self> {
+        let x = take_biguint(&mut input)?;
+        let y = take_biguint(&mut input)?;
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(Share { x, y })
+    }
+
+    /// Encodes the share as an ASCII-armored text block.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+
+    /// Encodes the share as a bech32m string whose checksum can locate typos.
+    pub fn to_bech32(&self) -> String {
+        bech32::encode(CANONICAL_PREFIX, &self.to_bytes())
+    }
+
+    pub fn from_bech32(text: &str) -> Result<Self> {
+        Self::from_bytes(&bech32::decode(CANONICAL_PREFIX, text)?)
+    }
+
+    /// Encodes the share as a space-separated BIP-39 word phrase.
+    pub fn to_mnemonic(&self) -> Result<String> {
+        Ok(mnemonic::encode(&self.to_bytes())?.join(" "))
+    }
+
+    /// Parses a phrase produced by [`Share::to_mnemonic`], tolerating common typos.
+    pub fn from_mnemonic(phrase: &str) -> Result<Self> {
+        let words: Vec<&str> = phrase.split_whitespace().collect();
+        Self::from_bytes(&mnemonic::decode(&words)?)
+    }
+
+    /// Adds a share of another secret dealt at the same index, giving a share
+    /// of the sum of the two secrets. The value is left unreduced;
+    /// reconstruction reduces it modulo the sharer's prime.
+    pub fn add(&self, other: &Share) -> Result<Share> {
+        if self.x != other.x {
+            return Err(Error::InconsistentShares("shares have different indices"));
+        }
+        Ok(Share {
+            x: self.x.clone(),
+            y: &self.y + &other.y,
+        })
+    }
+
+    /// A share of the secret multiplied by `c`, left unreduced.
+    pub fn scale(&self, c: &BigUint) -> Share {
+        Share {
+            x: self.x.clone(),
+            y: &self.y * c,
+        }
+    }
+
+    /// A share of the secret plus `c`: every share shifts by the same
+    /// constant, since only the polynomial's constant term changes.
+    pub fn add_constant(&self, c: &BigUint) -> Share {
+        Share {
+            x: self.x.clone(),
+            y: &self.y + c,
+        }
+    }
+
+    /// SHA-256 of the canonical form, for logging a share without revealing it.
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.to_string())
+    }
+
+    /// Renders the armored encoding of the share as a QR code image.
+    #[cfg(feature = "qr")]
+    pub fn to_qr(&self, format: QrFormat) -> Result<Vec<u8>> {
+        qr::render(&self.to_armored(), format)
+    }
+}
+
+/// The canonical single-line form, `sss:<index>:<hex value>:<checksum>`.
+impl fmt::Display for Share {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&to_canonical(CANONICAL_PREFIX, &self.x, &self.y))
+    }
+}
+
+impl FromStr for Share {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let (x, y) = from_canonical(CANONICAL_PREFIX, text)?;
+        Ok(Share { x, y })
+    }
+}
+
+/// A dealer's polynomial over the integers modulo a prime, kept to issue
+/// shares at further points later, e.g. to enroll a new shareholder. Its
+/// constant term is the secret.
+#[derive(Clone, Debug)]
+pub struct Polynomial {
+    prime: BigUint,
+    coefficients: Vec<BigUint>,
+}
+
+impl Polynomial {
+    /// Coefficients are reduced modulo `prime`, lowest degree first.
+    pub fn new(prime: BigUint, coefficients: Vec<BigUint>) -> Self {
+        let coefficients = coefficients.into_iter().map(|c| c % &prime).collect();
+        Polynomial {
+            prime,
+            coefficients,
+        }
+    }
+
+    pub fn coefficients(&self) -> &[BigUint] {
+        &self.coefficients
+    }
+
+    /// `f(x)` modulo the prime.
+    pub fn evaluate(&self, x: &BigUint) -> BigUint {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, coeff| {
+                (acc * x + coeff) % &self.prime
+            })
+    }
+
+    /// The share at `x`, which may lie beyond `1..=n`; its index is reduced
+    /// modulo the prime. Points equal to zero modulo the prime would reveal
+    /// the secret and are refused.
+    pub fn share_at(&self, x: &BigUint) -> Result<Share> {
+        let x = x % &self.prime;
+        if x.is_zero() {
+            return Err(Error::InvalidParameters(
+                "a share at zero would be the secret",
+            ));
+        }
+        let y = self.evaluate(&x);
+        Ok(Share { x, y })
+    }
+}
+
 pub struct SecretSharer {
     prime: BigUint,
     threshold: usize,
@@ -17,7 +235,12 @@ pub struct SecretSharer {
 impl SecretSharer {
     pub fn new(threshold: usize, total_shares: usize) -> Self {
         // Using a 521-bit prime for better security
-        let prime = BigUint::from(2u32).pow(521) - BigUint::from(1u32);
+        let prime = named_prime("mersenne-521").expect("built-in prime");
+        Self::with_prime(prime, threshold, total_shares)
+    }
+
+    /// Creates a sharer over the field of integers modulo `prime`.
+    pub fn with_prime(prime: BigUint, threshold: usize, total_shares: usize) -> Self {
         SecretSharer {
             prime,
             threshold,
@@ -25,49 +248,184 @@ impl SecretSharer {
         }
     }
 
+    pub fn prime(&self) -> &BigUint {
+        &self.prime
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn total_shares(&self) -> usize {
+        self.total_shares
+    }
+
+    /// Wraps a share with this sharer's parameters so it can be combined on its own.
+    pub fn serialize_share(&self, share: &Share) -> SerializedShare {
+        SerializedShare::new(
+            Scheme::Shamir {
+                prime: self.prime.clone(),
+            },
+            self.threshold,
+            self.total_shares,
+            share.x.clone(),
+            (&share.y % &self.prime).to_bytes_be(),
+        )
+        .expect("values reduced modulo the prime fit its width")
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(threshold = self.threshold, total = self.total_shares)))]
     pub fn split_secret(&self, secret: &BigUint) -> Vec<Share> {
-        let mut rng = thread_rng();
-        let mut coefficients = vec![secret.clone() % &self.prime];
+        self.shares_of(&self.polynomial(secret))
+    }
 
-        // Generate random coefficients
-        for _ in 1..self.threshold {
-            coefficients.push(rng.gen_biguint_range(&BigUint::zero(), &self.prime));
-        }
+    /// Like [`split_secret`](Self::split_secret), with the coefficients
+    /// drawn from [`insecure_seeded_rng`] keyed by `seed`, so the same seed,
+    /// secret and parameters always deal the same shares.
+    ///
+    /// INSECURE for anything but reproducible fixtures, idempotent re-dealing
+    /// and audits of a past dealing: whoever knows the seed and the secret,
+    /// or the seed and a share, can recompute every share. Guard the seed
+    /// like the secret itself.
+    pub fn split_secret_deterministic(&self, secret: &BigUint, seed: &[u8]) -> Vec<Share> {
+        self.shares_of(&self.polynomial_with_rng(secret, &mut insecure_seeded_rng(seed)))
+    }
 
-        // Generate shares
+    /// Like [`split_secret`](Self::split_secret), reporting each share dealt
+    /// to `progress` and stopping if it is cancelled.
+    pub fn split_secret_with_progress(
+        &self,
+        secret: &BigUint,
+        progress: &mut Progress,
+    ) -> Result<Vec<Share>> {
+        progress.check()?;
+        let polynomial = self.polynomial(secret);
+        let total = self.total_shares as u64;
         (1..=self.total_shares)
             .map(|x| {
-                let x = BigUint::from(x as u32);
-                let y = self.evaluate_polynomial(&coefficients, &x);
-                Share { x, y }
+                let share = polynomial.share_at(&BigUint::from(x as u32))?;
+                progress.step(x as u64, Some(total))?;
+                Ok(share)
             })
             .collect()
     }
 
-    pub fn reconstruct_secret(&self, shares: &[Share]) -> Option<BigUint> {
+    fn shares_of(&self, polynomial: &Polynomial) -> Vec<Share> {
+        (1..=self.total_shares)
+            .map(|x| {
+                polynomial
+                    .share_at(&BigUint::from(x as u32))
+                    .expect("indices 1..=n are nonzero")
+            })
+            .collect()
+    }
+
+    /// Deals one share per identity, at the identity's
+    /// [`ShareId`](crate::identity::ShareId) point in this sharer's field, in
+    /// roster order. The roster must name `total_shares` distinct identities.
+    pub fn split_for<S: AsRef<str>>(
+        &self,
+        secret: &BigUint,
+        identities: &[S],
+    ) -> Result<Vec<Share>> {
+        if identities.len() != self.total_shares {
+            return Err(Error::InvalidParameters(
+                "need exactly one identity per share",
+            ));
+        }
+        let ids = identity::assign(identities, &self.prime)?;
+        let polynomial = self.polynomial(secret);
+        ids.iter().map(|id| polynomial.share_at(&id.x)).collect()
+    }
+
+    /// A random polynomial of degree `threshold - 1` hiding `secret`, for
+    /// dealers that keep it to issue shares at other points.
+    pub fn polynomial(&self, secret: &BigUint) -> Polynomial {
+        self.polynomial_with_rng(secret, &mut thread_rng())
+    }
+
+    fn polynomial_with_rng(&self, secret: &BigUint, rng: &mut impl RngCore) -> Polynomial {
+        let mut coefficients = vec![secret.clone() % &self.prime];
+
+        // Generate random coefficients
+        for _ in 1..self.threshold {
+            coefficients.push(rng.gen_biguint_range(&BigUint::zero(), &self.prime));
+        }
+        Polynomial::new(self.prime.clone(), coefficients)
+    }
+
+    /// Recovers the secret from at least `threshold` shares, owned or
+    /// borrowed, in any order. Repeated shares are dropped and the first
+    /// `threshold` distinct ones interpolated; any surplus share must lie on
+    /// the same polynomial.
+    ///
+    /// `None` if too few distinct shares remain, two shares claim the same
+    /// index with different values, or a surplus share disagrees.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(threshold = self.threshold)))]
+    pub fn reconstruct_secret<S: Borrow<Share>>(
+        &self,
+        shares: impl IntoIterator<Item = S>,
+    ) -> Option<BigUint> {
+        let shares = distinct(shares)?;
         if shares.len() < self.threshold {
             return None;
         }
 
-        let shares = &shares[..self.threshold];
-        let mut secret = BigUint::zero();
+        let (quorum, surplus) = shares.split_at(self.threshold);
+        if !surplus.is_empty() {
+            let polynomial =
+                Polynomial::new(self.prime.clone(), self.interpolate_polynomial(quorum)?);
+            if surplus
+                .iter()
+                .any(|share| polynomial.evaluate(&share.x) != &share.y % &self.prime)
+            {
+                return None;
+            }
+        }
 
-        for (i, share_i) in shares.iter().enumerate() {
-            let lagrange_coeff = self.calculate_lagrange_coefficient(share_i, shares, i)?;
+        let mut secret = BigUint::zero();
+        for (i, share_i) in quorum.iter().enumerate() {
+            let lagrange_coeff = self.calculate_lagrange_coefficient(share_i, quorum, i)?;
             secret = (secret + (&share_i.y * &lagrange_coeff)) % &self.prime;
         }
 
         Some(secret)
     }
 
-    fn evaluate_polynomial(&self, coefficients: &[BigUint], x: &BigUint) -> BigUint {
-        coefficients
-            .iter()
-            .enumerate()
-            .fold(BigUint::zero(), |acc, (power, coeff)| {
-                let term = coeff * x.modpow(&BigUint::from(power as u32), &self.prime);
-                (acc + term) % &self.prime
-            })
+    /// Recovers every coefficient, lowest degree first, of the polynomial
+    /// through all of `shares`; `None` if two shares have the same index.
+    ///
+    /// With more than `threshold` shares of an honest dealing, the
+    /// coefficients beyond `threshold - 1` are zero, so a nonzero one exposes
+    /// a corrupted share or an inconsistent dealer.
+    pub fn interpolate_polynomial(&self, shares: &[Share]) -> Option<Vec<BigUint>> {
+        let p = &self.prime;
+        let mut coefficients = vec![BigUint::zero(); shares.len()];
+        for (i, share_i) in shares.iter().enumerate() {
+            // The basis polynomial Π_{j≠i} (X - x_j) / (x_i - x_j).
+            let mut basis = vec![BigUint::one()];
+            let mut denominator = BigUint::one();
+            for share_j in shares
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| i != j)
+                .map(|(_, s)| s)
+            {
+                let root = (p - &share_j.x % p) % p;
+                let mut next = vec![BigUint::zero(); basis.len() + 1];
+                for (k, c) in basis.iter().enumerate() {
+                    next[k] = (&next[k] + c * &root) % p;
+                    next[k + 1] = (&next[k + 1] + c) % p;
+                }
+                basis = next;
+                denominator = denominator * ((&share_i.x % p + &root) % p) % p;
+            }
+            let scale = &share_i.y * self.mod_inverse(&denominator)? % p;
+            for (coefficient, c) in coefficients.iter_mut().zip(basis) {
+                *coefficient = (&*coefficient + c * &scale) % p;
+            }
+        }
+        Some(coefficients)
     }
 
     fn calculate_lagrange_coefficient(
@@ -76,15 +434,13 @@ impl SecretSharer {
         shares: &[Share],
         i: usize,
     ) -> Option<BigUint> {
+        let x_i = &share_i.x % &self.prime;
         let (numerator, denominator) = shares.iter().enumerate().filter(|&(j, _)| i != j).fold(
             (BigUint::one(), BigUint::one()),
             |(num, den), (_, share_j)| {
-                let new_num = (num * &share_j.x) % &self.prime;
-                let diff = if &share_j.x > &share_i.x {
-                    (&share_j.x - &share_i.x) % &self.prime
-                } else {
-                    (&self.prime + &share_j.x - &share_i.x) % &self.prime
-                };
+                let x_j = &share_j.x % &self.prime;
+                let new_num = (num * &x_j) % &self.prime;
+                let diff = (&self.prime + x_j - &x_i) % &self.prime;
                 let new_den = (den * diff) % &self.prime;
                 (new_num, new_den)
             },
@@ -101,3 +457,226 @@ impl SecretSharer {
         Some(a.modpow(&(&self.prime - 2u32), &self.prime))
     }
 }
+
+/// The shares with exact repeats dropped, in first-seen order; `None` if two
+/// claim the same index with different values.
+fn distinct<S: Borrow<Share>>(shares: impl IntoIterator<Item = S>) -> Option<Vec<Share>> {
+    let mut seen: Vec<Share> = Vec::new();
+    for share in shares {
+        let share = share.borrow();
+        match seen.iter().find(|other| other.x == share.x) {
+            Some(other) if other.y != share.y => return None,
+            Some(_) => {}
+            None => seen.push(share.clone()),
+        }
+    }
+    Some(seen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::ToBigUint;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_share_armor_roundtrip() {
+        let sharer = SecretSharer::new(3, 5);
+        let secret = 22773311u64.to_biguint().unwrap();
+        let shares = sharer.split_secret(&secret);
+
+        let decoded: Vec<Share> = shares
+            .iter()
+            .map(|share| Share::from_armored(&share.to_armored()).unwrap())
+            .collect();
+        assert_eq!(sharer.reconstruct_secret(&decoded[1..4]), Some(secret));
+
+        let phrase = shares[4].to_mnemonic().unwrap();
+        assert_eq!(Share::from_mnemonic(&phrase).unwrap().y, shares[4].y);
+
+        // Trailing garbage after the two integers is rejected
+        let mut bytes = shares[0].to_bytes();
+        bytes.push(0);
+        assert!(Share::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_share_version_migration() {
+        let share = Share {
+            x: 2u32.into(),
+            y: 0xbeefu32.into(),
+        };
+        let current = share.to_bytes();
+        let v1 = [0, 1, 2, 0, 2, 0xbe, 0xef];
+        assert_eq!(current[..4], [b'S', b'S', b'S', SHARE_VERSION]);
+        assert_eq!(current[4..], v1);
+
+        assert!(matches!(
+            Share::from_bytes(&v1),
+            Err(Error::UnsupportedVersion(1))
+        ));
+        assert_eq!(Share::migrate(&v1).unwrap(), current);
+        let armored = Share::migrate_armored(&armor(ARMOR_LABEL, &v1)).unwrap();
+        assert_eq!(Share::from_armored(&armored).unwrap().y, share.y);
+
+        let mut future = current.clone();
+        future[3] = SHARE_VERSION + 1;
+        assert!(matches!(
+            Share::from_bytes(&future),
+            Err(Error::UnsupportedVersion(3))
+        ));
+    }
+
+    #[test]
+    fn test_canonical_text_form() {
+        let share = Share {
+            x: 3u32.into(),
+            y: 0xbeefu32.into(),
+        };
+        let line = share.to_string();
+        assert!(line.starts_with("sss:3:beef:"));
+        let parsed: Share = line.parse().unwrap();
+        assert_eq!((parsed.x, parsed.y), (share.x.clone(), share.y.clone()));
+        assert_eq!(share.fingerprint().len(), 64);
+
+        // A single flipped digit is caught by the checksum
+        let corrupted = line.replacen("beef", "beee", 1);
+        assert!(matches!(
+            corrupted.parse::<Share>(),
+            Err(Error::ChecksumMismatch)
+        ));
+        assert!(line.replacen("sss", "vss", 1).parse::<Share>().is_err());
+
+        let text = share.to_bech32();
+        assert_eq!(Share::from_bech32(&text).unwrap().y, share.y);
+    }
+
+    #[test]
+    fn test_retained_polynomial() {
+        let sharer = SecretSharer::with_prime(BigUint::from(7919u32), 3, 5);
+        let polynomial = sharer.polynomial(&BigUint::from(1234u32));
+        let late = polynomial.share_at(&BigUint::from(100u32)).unwrap();
+        let early = polynomial.share_at(&BigUint::from(2u32)).unwrap();
+        let other = polynomial.share_at(&BigUint::from(5u32)).unwrap();
+        assert_eq!(
+            sharer.reconstruct_secret(&[late, early, other]),
+            Some(BigUint::from(1234u32))
+        );
+        assert!(polynomial.share_at(&BigUint::from(7919u32)).is_err());
+
+        let wrapped = polynomial.share_at(&BigUint::from(10000u32)).unwrap();
+        assert_eq!(wrapped.x, BigUint::from(10000u32 - 7919));
+        let shares = [
+            wrapped,
+            polynomial.share_at(&BigUint::from(2u32)).unwrap(),
+            polynomial.share_at(&BigUint::from(5u32)).unwrap(),
+        ];
+        assert_eq!(
+            sharer.reconstruct_secret(&shares),
+            Some(BigUint::from(1234u32))
+        );
+        assert!(polynomial.share_at(&BigUint::from(2 * 7919u32)).is_err());
+    }
+
+    #[test]
+    fn test_interpolate_polynomial() {
+        let sharer = SecretSharer::with_prime(BigUint::from(7919u32), 3, 5);
+        let polynomial = sharer.polynomial(&BigUint::from(1234u32));
+        let mut shares = sharer.split_secret(&BigUint::zero());
+        for share in &mut shares {
+            share.y = polynomial.evaluate(&share.x);
+        }
+
+        let coefficients = sharer.interpolate_polynomial(&shares).unwrap();
+        assert_eq!(&coefficients[..3], polynomial.coefficients());
+        assert!(coefficients[3..].iter().all(Zero::is_zero));
+
+        shares[4].y += 1u32;
+        let coefficients = sharer.interpolate_polynomial(&shares).unwrap();
+        assert!(!coefficients[3..].iter().all(Zero::is_zero));
+        shares[4].x = shares[0].x.clone();
+        assert!(sharer.interpolate_polynomial(&shares).is_none());
+    }
+
+    #[test]
+    fn test_deterministic_split() {
+        let sharer = SecretSharer::new(3, 5);
+        let secret = BigUint::from(424242u32);
+        let deal = |seed: &[u8]| -> Vec<Vec<u8>> {
+            sharer
+                .split_secret_deterministic(&secret, seed)
+                .iter()
+                .map(Share::to_bytes)
+                .collect()
+        };
+        assert_eq!(deal(b"backup-2024"), deal(b"backup-2024"));
+        assert_ne!(deal(b"backup-2024"), deal(b"backup-2025"));
+        let shares = sharer.split_secret_deterministic(&secret, b"backup-2024");
+        assert_eq!(sharer.reconstruct_secret(&shares[2..]), Some(secret));
+    }
+
+    #[test]
+    fn test_reconstruct_unordered_surplus() {
+        let sharer = SecretSharer::new(3, 6);
+        let secret = BigUint::from(1234567u32);
+        let shares = sharer.split_secret(&secret);
+
+        let shuffled = [&shares[4], &shares[1], &shares[4], &shares[5], &shares[0]];
+        assert_eq!(sharer.reconstruct_secret(shuffled), Some(secret.clone()));
+        assert_eq!(
+            sharer.reconstruct_secret(shares.iter().rev().cloned()),
+            Some(secret.clone())
+        );
+        assert_eq!(
+            sharer.reconstruct_secret([&shares[2], &shares[2], &shares[3]]),
+            None
+        );
+
+        let mut corrupted = shares.clone();
+        corrupted[5].y += 1u32;
+        assert_eq!(sharer.reconstruct_secret(&corrupted[..3]), Some(secret));
+        assert_eq!(sharer.reconstruct_secret(corrupted), None);
+        let mut conflicting = shares[..3].to_vec();
+        conflicting.push(Share {
+            x: shares[0].x.clone(),
+            y: &shares[0].y + 1u32,
+        });
+        assert_eq!(sharer.reconstruct_secret(conflicting), None);
+    }
+
+    /// A threshold, a total and a threshold-sized subset of `0..total`.
+    fn quorum() -> impl Strategy<Value = (usize, usize, Vec<usize>)> {
+        (1usize..6)
+            .prop_flat_map(|t| (Just(t), t..8))
+            .prop_flat_map(|(t, n)| {
+                let subset = prop::sample::subsequence((0..n).collect::<Vec<_>>(), t);
+                (Just(t), Just(n), subset.prop_shuffle())
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn prop_threshold_subsets_reconstruct(
+            secret in any::<u128>(),
+            (threshold, total, subset) in quorum(),
+            field in prop::sample::select(PRIMES),
+        ) {
+            let prime = named_prime(field).unwrap();
+            let sharer = SecretSharer::with_prime(prime.clone(), threshold, total);
+            let secret = BigUint::from(secret);
+            let shares = sharer.split_secret(&secret);
+            let chosen: Vec<_> = subset.iter().map(|&i| shares[i].clone()).collect();
+            prop_assert_eq!(sharer.reconstruct_secret(&chosen), Some(secret.clone()));
+
+            let short = &chosen[..threshold - 1];
+            prop_assert_eq!(sharer.reconstruct_secret(short), None);
+            if threshold > 1 {
+                // Interpolating too few shares lands on an unrelated value.
+                let naive = SecretSharer::with_prime(prime, threshold - 1, total);
+                prop_assert_ne!(naive.reconstruct_secret(short), Some(secret));
+            }
+        }
+    }
+}
@@ -0,0 +1,365 @@
+// File: src/ssss.rs
+//! Interoperability with the classic `ssss-split`/`ssss-combine` tools.
+//!
+//! ssss works in GF(2^m) with m = 8 × the secret length, using the fixed
+//! irreducible pentanomials below. Shares are printed as
+//! `[token-]index-hexvalue` lines, the index zero-padded to the width of the
+//! share count. Secrets of 64 bits or more first pass through an XTEA-based
+//! diffusion layer, and the dealing polynomial is monic of degree `threshold`.
+
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, Zero};
+use rand::thread_rng;
+
+use crate::error::{Error, Result};
+
+const MAX_DEGREE: usize = 1024;
+const MIN_DIFFUSION_DEGREE: usize = 64;
+
+/// Middle exponents of x^m + x^a + x^b + x^c + 1 for m = 8, 16, ..., 1024.
+#[rustfmt::skip]
+const IRRED_COEFF: [u8; 384] = [
+    4,3,1, 5,3,1, 4,3,1, 7,3,2, 5,4,3, 5,3,2, 7,4,2, 4,3,1, 10,9,3, 9,4,2,
+    7,6,2, 10,9,6, 4,3,1, 5,4,3, 4,3,1, 7,2,1, 5,3,2, 7,4,2, 6,3,2, 5,3,2,
+    15,3,2, 11,3,2, 9,8,7, 7,2,1, 5,3,2, 9,3,1, 7,3,1, 9,8,3, 9,4,2, 8,5,3,
+    15,14,10, 10,5,2, 9,6,2, 9,3,2, 9,5,2, 11,10,1, 7,3,2, 11,2,1, 9,7,4, 4,3,1,
+    8,3,1, 7,4,1, 7,2,1, 13,11,6, 5,3,2, 7,3,2, 8,7,5, 12,3,2, 13,10,6, 5,3,2,
+    5,3,2, 9,5,2, 9,7,2, 13,4,3, 4,3,1, 11,6,4, 18,9,6, 19,18,13, 11,3,2, 15,9,6,
+    4,3,1, 16,5,2, 15,14,6, 8,5,2, 15,11,2, 11,6,2, 7,5,3, 8,3,1, 19,16,9, 11,9,6,
+    15,7,6, 13,4,3, 14,13,3, 13,6,3, 9,5,2, 19,13,6, 19,10,3, 11,6,5, 9,2,1, 14,3,2,
+    13,3,1, 7,5,4, 11,9,8, 11,6,5, 23,16,9, 19,14,6, 23,10,2, 8,3,2, 5,4,3, 9,6,4,
+    4,3,2, 13,8,6, 13,11,1, 13,10,3, 11,6,5, 19,17,4, 15,14,7, 13,9,6, 9,7,3, 9,7,1,
+    14,3,2, 11,8,2, 11,6,4, 13,5,2, 11,5,1, 11,4,1, 19,10,3, 21,10,6, 13,3,1, 15,7,5,
+    19,18,10, 7,5,3, 12,7,2, 7,5,1, 14,9,6, 10,3,2, 15,13,12, 12,11,9, 16,9,7, 12,9,3,
+    9,5,2, 17,10,6, 24,9,3, 17,15,13, 5,4,3, 19,17,8, 15,6,3, 19,6,1,
+];
+
+/// Options mirroring the relevant `ssss-split` flags.
+#[derive(Clone, Debug)]
+pub struct SsssOptions {
+    /// Prefix printed before each share (`-w token`).
+    pub token: Option<String>,
+    /// Field size in bits (`-s`); defaults to 8 × the secret length.
+    pub security_bits: Option<usize>,
+    /// Apply the diffusion layer (disabled by `-D`).
+    pub diffusion: bool,
+}
+
+impl Default for SsssOptions {
+    fn default() -> Self {
+        SsssOptions {
+            token: None,
+            security_bits: None,
+            diffusion: true,
+        }
+    }
+}
+
+/// A parsed `ssss` share line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SsssShare {
+    pub token: Option<String>,
+    pub index: u32,
+    pub value: BigUint,
+    pub degree: usize,
+}
+
+impl SsssShare {
+    pub fn parse(line: &str) -> Result<Self> {
+        let mut parts: Vec<&str> = line.trim().rsplitn(3, '-').collect();
+        parts.reverse();
+        let (token, index, hex) = match parts.as_slice() {
+            [index, hex] => (None, *index, *hex),
+            [token, index, hex] => (Some(token.to_string()), *index, *hex),
+            _ => return Err(Error::InvalidEncoding("expected index-hexshare")),
+        };
+
+        let index: u32 = index
+            .parse()
+            .map_err(|_| Error::InvalidEncoding("invalid share index"))?;
+        let degree = hex.len() * 4;
+        if index == 0 || degree == 0 || !degree.is_multiple_of(8) || degree > MAX_DEGREE {
+            return Err(Error::InvalidEncoding("invalid ssss share"));
+        }
+        // Indices past the field's width would alias other elements
+        if index.checked_shr(degree as u32).unwrap_or(0) != 0 {
+            return Err(Error::InvalidEncoding("share index exceeds the field"));
+        }
+        let value = BigUint::parse_bytes(hex.as_bytes(), 16)
+            .ok_or(Error::InvalidEncoding("share is not hexadecimal"))?;
+        Ok(SsssShare {
+            token,
+            index,
+            value,
+            degree,
+        })
+    }
+
+    /// Formats the share as `ssss-split` does, padding the index to `width` digits.
+    pub fn to_line(&self, width: usize) -> String {
+        let token = self
+            .token
+            .as_ref()
+            .map(|t| format!("{}-", t))
+            .unwrap_or_default();
+        format!(
+            "{}{:0width$}-{:0digits$x}",
+            token,
+            self.index,
+            self.value,
+            width = width,
+            digits = self.degree / 4
+        )
+    }
+}
+
+/// Splits `secret` into `count` share lines, any `threshold` of which recover it.
+pub fn split(
+    secret: &[u8],
+    threshold: usize,
+    count: usize,
+    options: &SsssOptions,
+) -> Result<Vec<String>> {
+    let degree = options.security_bits.unwrap_or(secret.len() * 8);
+    if degree == 0 || !degree.is_multiple_of(8) || degree > MAX_DEGREE || secret.len() * 8 > degree
+    {
+        return Err(Error::InvalidParameters(
+            "secret must fit a security level of 8 to 1024 bits",
+        ));
+    }
+    if threshold < 2
+        || threshold > count
+        || count > u16::MAX as usize
+        || (degree < 16 && count >> degree != 0)
+    {
+        return Err(Error::InvalidParameters(
+            "threshold must be between 2 and the share count",
+        ));
+    }
+
+    let field = Field::new(degree);
+    let mut coefficients = vec![BigUint::from_bytes_be(secret)];
+    if options.diffusion && degree >= MIN_DIFFUSION_DEGREE {
+        coefficients[0] = diffuse(&coefficients[0], degree, true);
+    }
+    let mut rng = thread_rng();
+    for _ in 1..threshold {
+        coefficients.push(rng.gen_biguint(degree as u64));
+    }
+
+    let width = count.to_string().len();
+    Ok((1..=count as u32)
+        .map(|index| {
+            let share = SsssShare {
+                token: options.token.clone(),
+                index,
+                value: field.horner(&coefficients, &BigUint::from(index)),
+                degree,
+            };
+            share.to_line(width)
+        })
+        .collect())
+}
+
+/// Recovers the secret from `threshold` share lines, as `ssss-combine -t`.
+///
+/// Like `ssss-combine`, the secret is returned without leading zero bytes.
+pub fn combine<S: AsRef<str>>(lines: &[S], threshold: usize, diffusion: bool) -> Result<Vec<u8>> {
+    if threshold == 0 || lines.len() < threshold {
+        return Err(Error::InsufficientShares);
+    }
+    let shares = lines[..threshold]
+        .iter()
+        .map(|line| SsssShare::parse(line.as_ref()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let degree = shares[0].degree;
+    for (i, share) in shares.iter().enumerate() {
+        if share.degree != degree || share.token != shares[0].token {
+            return Err(Error::InconsistentShares(
+                "shares come from different dealings",
+            ));
+        }
+        if shares[..i].iter().any(|s| s.index == share.index) {
+            return Err(Error::InconsistentShares("duplicate share index"));
+        }
+    }
+
+    // Remove the monic x^t term, then interpolate the remaining polynomial at 0
+    let field = Field::new(degree);
+    let mut secret = BigUint::zero();
+    for (i, share) in shares.iter().enumerate() {
+        let x = BigUint::from(share.index);
+        let y = &share.value ^ field.pow(&x, threshold);
+        let (num, den) = shares.iter().enumerate().filter(|&(j, _)| i != j).fold(
+            (BigUint::one(), BigUint::one()),
+            |(num, den), (_, s)| {
+                let xj = BigUint::from(s.index);
+                (field.mul(&num, &xj), field.mul(&den, &(&x ^ &xj)))
+            },
+        );
+        secret ^= field.mul(&y, &field.mul(&num, &field.inv(&den)));
+    }
+
+    if diffusion && degree >= MIN_DIFFUSION_DEGREE {
+        secret = diffuse(&secret, degree, false);
+    }
+    Ok(if secret.is_zero() {
+        Vec::new()
+    } else {
+        secret.to_bytes_be()
+    })
+}
+
+/// GF(2^m) with elements stored as bit-polynomials in a `BigUint`.
+struct Field {
+    degree: usize,
+    poly: BigUint,
+}
+
+impl Field {
+    fn new(degree: usize) -> Self {
+        let mut poly = BigUint::zero();
+        poly.set_bit(degree as u64, true);
+        for &c in &IRRED_COEFF[3 * (degree / 8 - 1)..3 * (degree / 8)] {
+            poly.set_bit(c as u64, true);
+        }
+        poly.set_bit(0, true);
+        Field { degree, poly }
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let mut r = BigUint::zero();
+        for i in (0..b.bits()).rev() {
+            r <<= 1;
+            if r.bit(self.degree as u64) {
+                r ^= &self.poly;
+            }
+            if b.bit(i) {
+                r ^= a;
+            }
+        }
+        r
+    }
+
+    fn pow(&self, x: &BigUint, exp: usize) -> BigUint {
+        (0..exp).fold(BigUint::one(), |acc, _| self.mul(&acc, x))
+    }
+
+    /// Inverts via the extended Euclidean algorithm over GF(2)[x].
+    fn inv(&self, a: &BigUint) -> BigUint {
+        let (mut r0, mut r1) = (self.poly.clone(), a.clone());
+        let (mut s0, mut s1) = (BigUint::zero(), BigUint::one());
+        while !r1.is_zero() {
+            while r0.bits() >= r1.bits() {
+                let shift = r0.bits() - r1.bits();
+                r0 ^= &r1 << shift;
+                s0 ^= &s1 << shift;
+            }
+            std::mem::swap(&mut r0, &mut r1);
+            std::mem::swap(&mut s0, &mut s1);
+        }
+        s0
+    }
+
+    /// Evaluates x^t + c_{t-1} x^{t-1} + ... + c_0, matching ssss's `horner`.
+    fn horner(&self, coefficients: &[BigUint], x: &BigUint) -> BigUint {
+        let y = coefficients[1..]
+            .iter()
+            .rev()
+            .fold(x.clone(), |y, c| self.mul(&(y ^ c), x));
+        y ^ &coefficients[0]
+    }
+}
+
+/// Applies (or removes) ssss's diffusion layer: 40 passes of zero-key XTEA
+/// over overlapping 8-byte windows of the secret.
+fn diffuse(x: &BigUint, degree: usize, encode: bool) -> BigUint {
+    let len = degree / 8;
+    // GMP exports 16-bit words least significant first, each word big-endian
+    let mut le = x.to_bytes_le();
+    le.resize(len.div_ceil(2) * 2, 0);
+    let mut v: Vec<u8> = le.chunks(2).flat_map(|w| [w[1], w[0]]).collect();
+    if len % 2 == 1 {
+        v[len - 1] = v[len];
+    }
+
+    if encode {
+        for i in (0..40 * len).step_by(2) {
+            xtea_slice(&mut v, i, len, true);
+        }
+    } else {
+        for i in (0..40 * len).step_by(2).rev() {
+            xtea_slice(&mut v, i, len, false);
+        }
+    }
+
+    if len % 2 == 1 {
+        v[len] = v[len - 1];
+        v[len - 1] = 0;
+    }
+    let le: Vec<u8> = v.chunks(2).flat_map(|w| [w[1], w[0]]).collect();
+    BigUint::from_bytes_le(&le)
+}
+
+fn xtea_slice(data: &mut [u8], idx: usize, len: usize, encode: bool) {
+    let at = |i: usize| (idx + i) % len;
+    let mut v = [0u32; 2];
+    for (k, word) in v.iter_mut().enumerate() {
+        *word = (0..4).fold(0u32, |acc, b| (acc << 8) | data[at(4 * k + b)] as u32);
+    }
+
+    const DELTA: u32 = 0x9E3779B9;
+    let f = |w: u32| ((w << 4) ^ (w >> 5)).wrapping_add(w);
+    if encode {
+        let mut sum = 0u32;
+        for _ in 0..32 {
+            v[0] = v[0].wrapping_add(f(v[1]) ^ sum);
+            sum = sum.wrapping_add(DELTA);
+            v[1] = v[1].wrapping_add(f(v[0]) ^ sum);
+        }
+    } else {
+        let mut sum = 0xC6EF3720u32;
+        for _ in 0..32 {
+            v[1] = v[1].wrapping_sub(f(v[0]) ^ sum);
+            sum = sum.wrapping_sub(DELTA);
+            v[0] = v[0].wrapping_sub(f(v[1]) ^ sum);
+        }
+    }
+
+    for (k, word) in v.iter().enumerate() {
+        for b in 0..4 {
+            data[at(4 * k + b)] = (word >> (24 - 8 * b)) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ssss_split_combine() {
+        let options = SsssOptions {
+            token: Some("backup".to_string()),
+            ..SsssOptions::default()
+        };
+        for secret in [&b"hi"[..], b"odd length secret", b"0123456789abcdef"] {
+            let lines = split(secret, 3, 12, &options).unwrap();
+            assert!(lines[0].starts_with("backup-01-"));
+            assert_eq!(lines[0].len(), "backup-01-".len() + secret.len() * 2);
+
+            let chosen = [&lines[11], &lines[4], &lines[7]];
+            assert_eq!(combine(&chosen, 3, true).unwrap(), secret);
+            if secret.len() > 2 {
+                assert_ne!(combine(&chosen[..2], 2, true).unwrap(), secret);
+            }
+        }
+
+        let x = BigUint::parse_bytes(b"00ff00ff00ff00ff00ff", 16).unwrap();
+        assert_eq!(diffuse(&diffuse(&x, 80, true), 80, false), x);
+        assert!(SsssShare::parse("1-zz").is_err());
+        assert!(SsssShare::parse("255-ab").is_ok());
+        assert!(SsssShare::parse("256-ab").is_err());
+    }
+}
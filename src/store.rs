@@ -0,0 +1,357 @@
+// File: src/store.rs
+//! Persistent ceremony state: shares and commitments grouped by session.
+//!
+//! A [`ShareStore`] keeps, per session id, the shares collected so far
+//! (keyed by their index) and at most one commitment document. The
+//! reconstruction sessions of [`crate::service`] and the servers built on it
+//! run against any backend: [`MemoryStore`] for throwaway state,
+//! [`FileStore`] for a directory per session, and
+//! [`crate::sqlite::SqliteStore`] with the `sqlite` feature. [`open`] picks one
+//! from a command-line style specification.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use num_bigint::BigUint;
+
+use crate::commitment::CommitmentDocument;
+use crate::encoding::hex;
+use crate::error::{Error, Result};
+use crate::serialized::SerializedShare;
+
+/// Storage for ceremony sessions.
+///
+/// Putting a share or commitments creates the session if needed; a share
+/// replaces any stored share with the same index.
+pub trait ShareStore: Send + Sync {
+    fn create_session(&self, session: &str) -> Result<()>;
+
+    fn contains_session(&self, session: &str) -> Result<bool>;
+
+    /// All session ids, sorted.
+    fn sessions(&self) -> Result<Vec<String>>;
+
+    /// Removes a session with its shares and commitments; false if absent.
+    fn delete_session(&self, session: &str) -> Result<bool>;
+
+    fn put_share(&self, session: &str, share: &SerializedShare) -> Result<()>;
+
+    fn get_share(&self, session: &str, x: &BigUint) -> Result<Option<SerializedShare>>;
+
+    /// The shares of a session in index order; empty if it is absent.
+    fn list_shares(&self, session: &str) -> Result<Vec<SerializedShare>>;
+
+    fn delete_share(&self, session: &str, x: &BigUint) -> Result<bool>;
+
+    fn put_commitments(&self, session: &str, document: &CommitmentDocument) -> Result<()>;
+
+    fn get_commitments(&self, session: &str) -> Result<Option<CommitmentDocument>>;
+}
+
+/// Opens a store from a specification: `memory`, `sqlite:PATH` (with the
+/// `sqlite` feature) or a directory path for a [`FileStore`].
+pub fn open(spec: &str) -> Result<Box<dyn ShareStore>> {
+    if spec == "memory" {
+        return Ok(Box::new(MemoryStore::default()));
+    }
+    if let Some(path) = spec.strip_prefix("sqlite:") {
+        #[cfg(feature = "sqlite")]
+        return Ok(Box::new(crate::sqlite::SqliteStore::open(path)?));
+        #[cfg(not(feature = "sqlite"))]
+        {
+            let _ = path;
+            return Err(Error::InvalidParameters(
+                "sqlite stores need the sqlite feature",
+            ));
+        }
+    }
+    Ok(Box::new(FileStore::open(spec)?))
+}
+
+/// Rejects session ids that could escape a directory or a key namespace.
+pub(crate) fn check_session(session: &str) -> Result<()> {
+    let valid = !session.is_empty()
+        && session.len() <= 128
+        && session
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameters(
+            "session ids are 1 to 128 letters, digits, '-' or '_'",
+        ))
+    }
+}
+
+#[derive(Default)]
+struct SessionData {
+    shares: BTreeMap<BigUint, SerializedShare>,
+    commitments: Option<CommitmentDocument>,
+}
+
+/// Keeps sessions in process memory.
+#[derive(Default)]
+pub struct MemoryStore {
+    sessions: Mutex<BTreeMap<String, SessionData>>,
+}
+
+impl MemoryStore {
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, SessionData>> {
+        self.sessions.lock().expect("store lock")
+    }
+}
+
+impl ShareStore for MemoryStore {
+    fn create_session(&self, session: &str) -> Result<()> {
+        check_session(session)?;
+        self.lock().entry(session.into()).or_default();
+        Ok(())
+    }
+
+    fn contains_session(&self, session: &str) -> Result<bool> {
+        Ok(self.lock().contains_key(session))
+    }
+
+    fn sessions(&self) -> Result<Vec<String>> {
+        Ok(self.lock().keys().cloned().collect())
+    }
+
+    fn delete_session(&self, session: &str) -> Result<bool> {
+        Ok(self.lock().remove(session).is_some())
+    }
+
+    fn put_share(&self, session: &str, share: &SerializedShare) -> Result<()> {
+        check_session(session)?;
+        self.lock()
+            .entry(session.into())
+            .or_default()
+            .shares
+            .insert(share.x.clone(), share.clone());
+        Ok(())
+    }
+
+    fn get_share(&self, session: &str, x: &BigUint) -> Result<Option<SerializedShare>> {
+        Ok(self
+            .lock()
+            .get(session)
+            .and_then(|data| data.shares.get(x).cloned()))
+    }
+
+    fn list_shares(&self, session: &str) -> Result<Vec<SerializedShare>> {
+        Ok(self
+            .lock()
+            .get(session)
+            .map(|data| data.shares.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn delete_share(&self, session: &str, x: &BigUint) -> Result<bool> {
+        Ok(self
+            .lock()
+            .get_mut(session)
+            .is_some_and(|data| data.shares.remove(x).is_some()))
+    }
+
+    fn put_commitments(&self, session: &str, document: &CommitmentDocument) -> Result<()> {
+        check_session(session)?;
+        self.lock().entry(session.into()).or_default().commitments = Some(document.clone());
+        Ok(())
+    }
+
+    fn get_commitments(&self, session: &str) -> Result<Option<CommitmentDocument>> {
+        Ok(self
+            .lock()
+            .get(session)
+            .and_then(|data| data.commitments.clone()))
+    }
+}
+
+/// Keeps each session in a directory under a root: one armored file per
+/// share, named by its index in hex, and `commitments.json`.
+pub struct FileStore {
+    root: PathBuf,
+}
+
+const COMMITMENTS_FILE: &str = "commitments.json";
+
+impl FileStore {
+    /// Uses `root`, creating it if needed.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+        fs::create_dir_all(root.as_ref())?;
+        Ok(FileStore {
+            root: root.as_ref().into(),
+        })
+    }
+
+    fn session_dir(&self, session: &str) -> Result<PathBuf> {
+        check_session(session)?;
+        Ok(self.root.join(session))
+    }
+
+    fn share_path(&self, session: &str, x: &BigUint) -> Result<PathBuf> {
+        Ok(self
+            .session_dir(session)?
+            .join(format!("share-{}.asc", hex(&x.to_bytes_be()))))
+    }
+
+    /// Writes through a temporary file so readers never see half a share.
+    fn write(path: &Path, contents: &str) -> Result<()> {
+        let partial = path.with_extension("partial");
+        fs::write(&partial, contents)?;
+        fs::rename(partial, path)?;
+        Ok(())
+    }
+}
+
+/// Maps a missing file to `None`.
+fn read_optional(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Maps a missing file to `false`.
+fn remove_optional(result: std::io::Result<()>) -> Result<bool> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+impl ShareStore for FileStore {
+    fn create_session(&self, session: &str) -> Result<()> {
+        fs::create_dir_all(self.session_dir(session)?)?;
+        Ok(())
+    }
+
+    fn contains_session(&self, session: &str) -> Result<bool> {
+        Ok(check_session(session).is_ok() && self.root.join(session).is_dir())
+    }
+
+    fn sessions(&self) -> Result<Vec<String>> {
+        let mut sessions = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str() {
+                if check_session(name).is_ok() {
+                    sessions.push(name.to_string());
+                }
+            }
+        }
+        sessions.sort();
+        Ok(sessions)
+    }
+
+    fn delete_session(&self, session: &str) -> Result<bool> {
+        if !self.contains_session(session)? {
+            return Ok(false);
+        }
+        remove_optional(fs::remove_dir_all(self.root.join(session)))
+    }
+
+    fn put_share(&self, session: &str, share: &SerializedShare) -> Result<()> {
+        self.create_session(session)?;
+        Self::write(&self.share_path(session, &share.x)?, &share.to_armored())
+    }
+
+    fn get_share(&self, session: &str, x: &BigUint) -> Result<Option<SerializedShare>> {
+        read_optional(&self.share_path(session, x)?)?
+            .map(|text| SerializedShare::from_armored(&text))
+            .transpose()
+    }
+
+    fn list_shares(&self, session: &str) -> Result<Vec<SerializedShare>> {
+        if !self.contains_session(session)? {
+            return Ok(Vec::new());
+        }
+        let mut shares = Vec::new();
+        for entry in fs::read_dir(self.root.join(session))? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|name| name.to_str());
+            if name.is_some_and(|name| name.starts_with("share-") && name.ends_with(".asc")) {
+                shares.push(SerializedShare::from_armored(&fs::read_to_string(&path)?)?);
+            }
+        }
+        shares.sort_by(|a, b| a.x.cmp(&b.x));
+        Ok(shares)
+    }
+
+    fn delete_share(&self, session: &str, x: &BigUint) -> Result<bool> {
+        remove_optional(fs::remove_file(self.share_path(session, x)?))
+    }
+
+    fn put_commitments(&self, session: &str, document: &CommitmentDocument) -> Result<()> {
+        self.create_session(session)?;
+        Self::write(
+            &self.session_dir(session)?.join(COMMITMENTS_FILE),
+            &document.to_json()?,
+        )
+    }
+
+    fn get_commitments(&self, session: &str) -> Result<Option<CommitmentDocument>> {
+        read_optional(&self.session_dir(session)?.join(COMMITMENTS_FILE))?
+            .map(|text| CommitmentDocument::from_json(&text))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::service;
+
+    /// Exercises a backend; shared with the sqlite tests.
+    pub(crate) fn exercise(store: &dyn ShareStore) {
+        let group = crate::params::GroupParameters::generate(256, 96).unwrap();
+        let dealing = service::deal(&[42], 2, 3, Some(&group)).unwrap();
+        let commitments = dealing.commitments.unwrap();
+
+        assert!(!store.contains_session("s1").unwrap());
+        assert!(store.list_shares("s1").unwrap().is_empty());
+        store.put_share("s1", &dealing.shares[2]).unwrap();
+        store.put_share("s1", &dealing.shares[0]).unwrap();
+        store.put_commitments("s1", &commitments).unwrap();
+        store.create_session("s2").unwrap();
+
+        assert_eq!(store.sessions().unwrap(), ["s1", "s2"]);
+        assert_eq!(
+            store.list_shares("s1").unwrap(),
+            [dealing.shares[0].clone(), dealing.shares[2].clone()]
+        );
+        let x = &dealing.shares[2].x;
+        assert_eq!(
+            store.get_share("s1", x).unwrap().as_ref(),
+            Some(&dealing.shares[2])
+        );
+        assert!(store.get_share("s2", x).unwrap().is_none());
+        assert_eq!(store.get_commitments("s1").unwrap(), Some(commitments));
+        assert!(store.get_commitments("s2").unwrap().is_none());
+
+        assert!(store.delete_share("s1", x).unwrap());
+        assert!(!store.delete_share("s1", x).unwrap());
+        assert_eq!(store.list_shares("s1").unwrap().len(), 1);
+        assert!(store.delete_session("s1").unwrap());
+        assert!(!store.delete_session("s1").unwrap());
+        assert_eq!(store.sessions().unwrap(), ["s2"]);
+        assert!(store.create_session("../escape").is_err());
+    }
+
+    #[test]
+    fn test_memory_and_file_stores() {
+        exercise(&MemoryStore::default());
+
+        let root = std::env::temp_dir().join(format!("sss-store-{}", std::process::id()));
+        exercise(&FileStore::open(&root).unwrap());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
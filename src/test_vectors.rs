@@ -0,0 +1,357 @@
+// File: src/test_vectors.rs
+//! Known-answer test vectors for checking other implementations against
+//! this crate byte for byte.
+//!
+//! Every vector is a dealing reproduced from a fixed seed:
+//!
+//! - `gf256` vectors come from [`split_bytes_with_rng`] over the Rijndael
+//!   field, drawing coefficients from [`insecure_seeded_rng`], a ChaCha20
+//!   DRBG keyed by the SHA-256 of the seed. For each secret byte in turn the
+//!   `threshold - 1` higher coefficients are read from the stream, lowest
+//!   degree first, and shares sit at `x = 1..=total`.
+//! - `shamir` and `feldman` vectors take their coefficients from a
+//!   [`SeededDealer`] at the vector's derivation path. Feldman dealings work
+//!   modulo the group order `q` and publish `g^a_i mod p` for every
+//!   coefficient `a_i`.
+//!
+//! Each share is listed by index, value and its binary encoding as written
+//! by [`SerializedShare::to_bytes`]; all numbers are big-endian hex. The
+//! published set lives in `test-vectors/kat.json` and is regenerated with
+//! `sss test-vectors`.
+
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::commitment::CommitmentDocument;
+use crate::derive::{DerivationPath, SeededDealer};
+use crate::encoding::{hex, to_fixed, unhex};
+use crate::error::{Error, Result};
+use crate::params::GroupParameters;
+use crate::serialized::{
+    insecure_seeded_rng, reconstruct_auto, split_bytes_with_rng, Scheme, SerializedShare,
+};
+use crate::sss::{named_prime, SecretSharer};
+use crate::vss;
+
+const VECTORS_VERSION: u8 = 1;
+
+/// How a vector is dealt, and over which field or group.
+enum Kind {
+    Gf256,
+    Shamir(&'static str),
+    Feldman(&'static str),
+}
+
+struct Spec {
+    name: &'static str,
+    description: &'static str,
+    kind: Kind,
+    seed: &'static [u8],
+    path: Option<&'static str>,
+    threshold: usize,
+    total: usize,
+    secret: &'static [u8],
+}
+
+const SPECS: &[Spec] = &[
+    Spec {
+        name: "gf256-2-of-3",
+        description: "Byte-wise 2-of-3 split of an ASCII passphrase",
+        kind: Kind::Gf256,
+        seed: b"sss-kat gf256 2-of-3",
+        path: None,
+        threshold: 2,
+        total: 3,
+        secret: b"correct horse battery staple",
+    },
+    Spec {
+        name: "gf256-3-of-5",
+        description: "Byte-wise 3-of-5 split of a 32-byte key with a leading zero byte",
+        kind: Kind::Gf256,
+        seed: b"sss-kat gf256 3-of-5",
+        path: None,
+        threshold: 3,
+        total: 5,
+        secret: &[
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+            0x1c, 0x1d, 0x1e, 0x1f,
+        ],
+    },
+    Spec {
+        name: "shamir-mersenne-521-3-of-5",
+        description: "3-of-5 Shamir dealing over the 2^521 - 1 field",
+        kind: Kind::Shamir("mersenne-521"),
+        seed: b"sss-kat shamir seed 0001",
+        path: Some("m/0"),
+        threshold: 3,
+        total: 5,
+        secret: b"sss known answer",
+    },
+    Spec {
+        name: "shamir-secp256k1-2-of-4",
+        description: "2-of-4 Shamir dealing of a 32-byte scalar over the secp256k1 order",
+        kind: Kind::Shamir("secp256k1"),
+        seed: b"sss-kat shamir seed 0001",
+        path: Some("m/1"),
+        threshold: 2,
+        total: 4,
+        secret: &[
+            0x7f, 0xa1, 0x3c, 0x52, 0x09, 0xe4, 0x6b, 0xd8, 0x31, 0x90, 0x2e, 0xc7, 0x5a, 0x14,
+            0xf3, 0x86, 0x4d, 0x27, 0xb0, 0x6e, 0x98, 0x03, 0xca, 0x5f, 0x11, 0xe2, 0x7d, 0x44,
+            0xab, 0x36, 0xc9, 0x58,
+        ],
+    },
+    Spec {
+        name: "feldman-modp-2048-2-of-3",
+        description: "2-of-3 Feldman VSS dealing over the RFC 3526 2048-bit MODP group",
+        kind: Kind::Feldman("modp-2048"),
+        seed: b"sss-kat feldman seed 01",
+        path: Some("m/2"),
+        threshold: 2,
+        total: 3,
+        secret: b"feldman known answer",
+    },
+];
+
+/// One share of a vector.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorShare {
+    pub x: String,
+    /// The share value, or the byte-wise payload for `gf256`.
+    pub y: String,
+    /// The share's binary encoding.
+    pub encoded: String,
+}
+
+/// A dealing with everything needed to reproduce it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    pub name: String,
+    pub description: String,
+    /// `gf256`, `shamir` or `feldman`.
+    pub scheme: String,
+    /// The field or group: `poly`, `prime`, or `p`, `q` and `g`.
+    pub parameters: BTreeMap<String, String>,
+    pub seed: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub threshold: usize,
+    pub total: usize,
+    pub secret: String,
+    pub shares: Vec<VectorShare>,
+    /// Feldman commitments `g^a_i mod p`, lowest degree first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commitments: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonVectors {
+    version: u8,
+    vectors: Vec<TestVector>,
+}
+
+/// Deals every published vector afresh.
+pub fn generate() -> Result<Vec<TestVector>> {
+    SPECS.iter().map(deal).collect()
+}
+
+pub fn to_json(vectors: &[TestVector]) -> String {
+    let document = JsonVectors {
+        version: VECTORS_VERSION,
+        vectors: vectors.to_vec(),
+    };
+    let mut json = serde_json::to_string_pretty(&document).expect("test vectors serialize");
+    json.push('\n');
+    json
+}
+
+pub fn from_json(text: &str) -> Result<Vec<TestVector>> {
+    let document: JsonVectors = serde_json::from_str(text)
+        .map_err(|_| Error::InvalidEncoding("invalid test vector JSON"))?;
+    if document.version != VECTORS_VERSION {
+        return Err(Error::UnsupportedVersion(document.version));
+    }
+    Ok(document.vectors)
+}
+
+/// Checks that a vector is self-consistent: every encoding decodes to the
+/// listed index and value over the listed parameters, a quorum recovers the
+/// secret and, for Feldman vectors, every share matches the commitments.
+pub fn check(vector: &TestVector) -> Result<()> {
+    let mut shares = Vec::with_capacity(vector.shares.len());
+    for listed in &vector.shares {
+        let share = SerializedShare::from_bytes(&unhex(&listed.encoded)?)?;
+        if hex(&share.x.to_bytes_be()) != listed.x
+            || hex(&share.value) != listed.y
+            || parameters(&share.scheme)? != vector.parameters
+            || (share.threshold, share.total) != (vector.threshold, vector.total)
+        {
+            return Err(Error::InconsistentShares(
+                "share encoding disagrees with the vector",
+            ));
+        }
+        shares.push(share);
+    }
+    if vector.shares.len() < vector.threshold
+        || reconstruct_auto(&shares[..vector.threshold])? != unhex(&vector.secret)?
+    {
+        return Err(Error::DigestMismatch);
+    }
+    if let Scheme::Feldman { p, q, g } = &shares[0].scheme {
+        let document = CommitmentDocument {
+            p: p.clone(),
+            q: q.clone(),
+            g: g.clone(),
+            threshold: vector.threshold,
+            total: vector.total,
+            commitments: vector
+                .commitments
+                .iter()
+                .map(|c| unhex(c).map(|bytes| BigUint::from_bytes_be(&bytes)))
+                .collect::<Result<_>>()?,
+            signature: None,
+        };
+        for share in &shares {
+            let share = vss::Share {
+                id: share.x.clone(),
+                value: BigUint::from_bytes_be(&share.value),
+            };
+            if !document.verify_share(&share) {
+                return Err(Error::InconsistentShares(
+                    "share does not match the commitments",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn deal(spec: &Spec) -> Result<TestVector> {
+    let (shares, commitments) = match spec.kind {
+        Kind::Gf256 => {
+            let mut rng = insecure_seeded_rng(spec.seed);
+            let shares = split_bytes_with_rng(spec.secret, spec.threshold, spec.total, &mut rng)?;
+            (shares, Vec::new())
+        }
+        Kind::Shamir(prime) => {
+            let sharer = SecretSharer::with_prime(named_prime(prime)?, spec.threshold, spec.total);
+            let shares = seeded_dealing(spec, &sharer)?
+                .1
+                .iter()
+                .map(|share| sharer.serialize_share(share))
+                .collect();
+            (shares, Vec::new())
+        }
+        Kind::Feldman(preset) => {
+            let group = GroupParameters::preset(preset)?;
+            let sharer = SecretSharer::with_prime(group.q.clone(), spec.threshold, spec.total);
+            let (coefficients, shares) = seeded_dealing(spec, &sharer)?;
+            let scheme = Scheme::Feldman {
+                p: group.p.clone(),
+                q: group.q.clone(),
+                g: group.g.clone(),
+            };
+            let shares = shares
+                .into_iter()
+                .map(|share| {
+                    SerializedShare::new(
+                        scheme.clone(),
+                        spec.threshold,
+                        spec.total,
+                        share.x,
+                        share.y.to_bytes_be(),
+                    )
+                })
+                .collect::<Result<_>>()?;
+            let commitments = coefficients
+                .iter()
+                .map(|a| Ok(hex(&to_fixed(&group.g.modpow(a, &group.p), &group.p)?)))
+                .collect::<Result<_>>()?;
+            (shares, commitments)
+        }
+    };
+    Ok(TestVector {
+        name: spec.name.to_string(),
+        description: spec.description.to_string(),
+        scheme: match spec.kind {
+            Kind::Gf256 => "gf256",
+            Kind::Shamir(_) => "shamir",
+            Kind::Feldman(_) => "feldman",
+        }
+        .to_string(),
+        parameters: parameters(&shares[0].scheme)?,
+        seed: hex(spec.seed),
+        path: spec.path.map(str::to_string),
+        threshold: spec.threshold,
+        total: spec.total,
+        secret: hex(spec.secret),
+        shares: shares
+            .iter()
+            .map(|share| VectorShare {
+                x: hex(&share.x.to_bytes_be()),
+                y: hex(&share.value),
+                encoded: hex(&share.to_bytes()),
+            })
+            .collect(),
+        commitments,
+    })
+}
+
+/// The coefficients and shares of a [`SeededDealer`] dealing at the spec's path.
+fn seeded_dealing(
+    spec: &Spec,
+    sharer: &SecretSharer,
+) -> Result<(Vec<BigUint>, Vec<crate::sss::Share>)> {
+    let dealer = SeededDealer::new(spec.seed)?;
+    let path: DerivationPath = spec.path.unwrap_or("m").parse()?;
+    let secret = BigUint::from_bytes_be(spec.secret);
+    let polynomial = dealer.polynomial(sharer, &path, &secret);
+    Ok((
+        polynomial.coefficients().to_vec(),
+        dealer.split_secret(sharer, &path, &secret),
+    ))
+}
+
+fn parameters(scheme: &Scheme) -> Result<BTreeMap<String, String>> {
+    let entries: Vec<(&str, String)> = match scheme {
+        Scheme::Gf256 { poly } => vec![("poly", hex(&poly.to_be_bytes()))],
+        Scheme::Shamir { prime } => vec![("prime", hex(&prime.to_bytes_be()))],
+        Scheme::Feldman { p, q, g } => vec![
+            ("p", hex(&p.to_bytes_be())),
+            ("q", hex(&q.to_bytes_be())),
+            ("g", hex(&to_fixed(g, p)?)),
+        ],
+    };
+    Ok(entries
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_answers() {
+        let published = from_json(include_str!("../test-vectors/kat.json")).unwrap();
+        assert_eq!(
+            generate().unwrap(),
+            published,
+            "dealings changed; regenerate test-vectors/kat.json with `sss test-vectors`"
+        );
+        for vector in &published {
+            check(vector).unwrap();
+        }
+
+        let mut tampered = published[0].clone();
+        tampered.secret = hex(b"correct horse battery stable");
+        assert!(check(&tampered).is_err());
+        let mut tampered = published[4].clone();
+        tampered.commitments.swap(0, 1);
+        assert!(check(&tampered).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+// File: src/vault.rs
+//! HashiCorp Vault unseal-key compatible Shamir sharing.
+//!
+//! Vault shares every byte of the secret over GF(2^8) (Rijndael polynomial)
+//! and appends the share's x-coordinate as the final byte. Coordinates are a
+//! random selection of distinct values in 1..=255. Unseal keys are exchanged
+//! as base64 or hex strings.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::encoding::unhex;
+use crate::error::{Error, Result};
+use crate::gf256::{self, Gf256};
+
+/// Splits `secret` into `parts` Vault-format shares, any `threshold` of which recover it.
+pub fn split(secret: &[u8], parts: usize, threshold: usize) -> Result<Vec<Vec<u8>>> {
+    if secret.is_empty() {
+        return Err(Error::InvalidParameters("cannot split an empty secret"));
+    }
+    if !(2..=255).contains(&parts) || !(2..=parts).contains(&threshold) {
+        return Err(Error::InvalidParameters(
+            "parts must be 2..=255 and threshold between 2 and parts",
+        ));
+    }
+
+    let mut rng = thread_rng();
+    let mut xs: Vec<u8> = (1..=255).collect();
+    xs.shuffle(&mut rng);
+    xs.truncate(parts);
+
+    let payloads = gf256::split(Gf256::RIJNDAEL, secret, threshold, &xs, &mut rng)?;
+    Ok(payloads
+        .into_iter()
+        .zip(xs)
+        .map(|(mut payload, x)| {
+            payload.push(x);
+            payload
+        })
+        .collect())
+}
+
+/// Recombines Vault-format shares. All provided shares are used, as in Vault.
+pub fn combine<P: AsRef<[u8]>>(parts: &[P]) -> Result<Vec<u8>> {
+    if parts.len() < 2 {
+        return Err(Error::InsufficientShares);
+    }
+    let len = parts[0].as_ref().len();
+    if len < 2 {
+        return Err(Error::MalformedShare);
+    }
+
+    let shares = parts
+        .iter()
+        .map(|part| {
+            let part = part.as_ref();
+            if part.len() != len {
+                return Err(Error::InconsistentShares("share lengths differ"));
+            }
+            let (payload, x) = part.split_at(len - 1);
+            Ok((x[0], payload))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    gf256::interpolate(Gf256::RIJNDAEL, &shares, 0)
+}
+
+/// Encodes a share the way `vault operator init` prints unseal keys (base64).
+pub fn encode_key(share: &[u8]) -> String {
+    STANDARD.encode(share)
+}
+
+/// Parses an unseal key given in hex or base64, trying hex first like Vault.
+pub fn decode_key(key: &str) -> Result<Vec<u8>> {
+    let key = key.trim();
+    let is_hex = key.len().is_multiple_of(2) && key.bytes().all(|c| c.is_ascii_hexdigit());
+    if is_hex {
+        return unhex(key);
+    }
+    STANDARD
+        .decode(key)
+        .map_err(|_| Error::InvalidEncoding("unseal key is neither hex nor base64"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_split_combine() {
+        // Field behaviour pinned by Vault's own tests
+        assert_eq!(Gf256::RIJNDAEL.mul(3, 7), 9);
+        assert_eq!(Gf256::RIJNDAEL.mul(7, 7), 21);
+        assert_eq!(Gf256::RIJNDAEL.div(6, 3), 2);
+
+        let secret = b"vault root key material";
+        let shares = split(secret, 5, 3).unwrap();
+        assert!(shares.iter().all(|s| s.len() == secret.len() + 1));
+
+        let keys: Vec<String> = shares.iter().map(|s| encode_key(s)).collect();
+        let decoded: Vec<Vec<u8>> = keys[1..4].iter().map(|k| decode_key(k).unwrap()).collect();
+        assert_eq!(combine(&decoded).unwrap(), secret);
+
+        let hex: String = shares[0].iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(decode_key(&hex).unwrap(), shares[0]);
+
+        assert!(combine(&[&shares[0], &shares[0]]).is_err());
+        assert!(split(secret, 1, 1).is_err());
+    }
+}
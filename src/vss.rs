@@ -1,7 +1,29 @@
 // File: src/vss.rs
+use std::borrow::Borrow;
+use std::fmt;
+use std::str::FromStr;
+
 use num_bigint::{BigUint, RandBigInt};
 use num_traits::{One, Zero};
-use rand::thread_rng;
+use rand::{thread_rng, RngCore};
+
+use crate::bech32;
+use crate::commitment::CommitmentDocument;
+use crate::encoding::{
+    armor, dearmor, fingerprint, from_canonical, from_fixed, put_biguint, put_share_header,
+    take_biguint, take_share_header, to_canonical, to_fixed, SHARE_VERSION,
+};
+use crate::error::{self, Error};
+use crate::mnemonic;
+use crate::progress::Progress;
+#[cfg(feature = "qr")]
+use crate::qr::{self, QrFormat};
+use crate::serialized::{insecure_seeded_rng, Scheme, SerializedShare};
+
+const CANONICAL_PREFIX: &str = "vss";
+const ARMOR_LABEL: &str = "FELDMAN SHARE";
+const MAGIC: &[u8; 3] = b"VSS";
+const PUBLIC_KEY_LABEL: &str = "FELDMAN PUBLIC KEY";
 
 #[derive(Clone, Debug)]
 pub struct Share {
@@ -9,18 +31,401 @@ pub struct Share {
     pub value: BigUint,
 }
 
+impl Share {
+    /// Serializes the share as the magic `VSS`, [`SHARE_VERSION`] and two
+    /// length-prefixed big-endian integers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = put_share_header(MAGIC);
+        put_biguint(&mut out, &self.id);
+        put_biguint(&mut out, &self.value);
+        out
+    }
+
+    /// Decodes a share in the current version; older encodings go through
+    /// [`Share::migrate`] first.
+    pub fn from_bytes(bytes: &[u8]) -> error::Result<Self> {
+        match take_share_header(MAGIC, bytes)? {
+            (SHARE_VERSION, body) => Self::take_body(body),
+            (version, _) => Err(Error::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Upgrades a version 1 encoding, the bare two integers, to the current
+    /// version.
+    pub fn migrate(bytes: &[u8]) -> error::Result<Vec<u8>> {
+        match take_share_header(MAGIC, bytes)? {
+            (1, body) => Ok(Self::take_body(body)?.to_bytes()),
+            (version, _) => Err(Error::UnsupportedVersion(version)),
+        }
+    }
+
+    /// [`Share::migrate`] for an armored share.
+    pub fn migrate_armored(text: &str) -> error::Result<String> {
+        Ok(armor(
+            ARMOR_LABEL,
+            &Self::migrate(&dearmor(ARMOR_LABEL, text)?)?,
+        ))
+    }
+
+    fn take_body(mut input: &[u8]) -> error::Result<Self> {
+        let id = take_biguint(&mut input)?;
+        let value = take_biguint(&mut input)?;
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(Share { id, value })
+    }
+
+    /// Encodes the share as an ASCII-armored text block.
+    pub fn to_armored(&self) -> String {
+        armor(ARMOR_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> error::Result<Self> {
+        Self::from_bytes(&dearmor(ARMOR_LABEL, text)?)
+    }
+
+    /// Encodes the share as a bech32m string whose checksum can locate typos.
+    pub fn to_bech32(&self) -> String {
+        bech32::encode(CANONICAL_PREFIX, &self.to_bytes())
+    }
+
+    pub fn from_bech32(text: &str) -> error::Result<Self> {
+        Self::from_bytes(&bech32::decode(CANONICAL_PREFIX, text)?)
+    }
+
+    /// Encodes the share as a space-separated BIP-39 word phrase.
+    pub fn to_mnemonic(&self) -> error::Result<String> {
+        Ok(mnemonic::encode(&self.to_bytes())?.join(" "))
+    }
+
+    /// Parses a phrase produced by [`Share::to_mnemonic`], tolerating common typos.
+    pub fn from_mnemonic(phrase: &str) -> error::Result<Self> {
+        let words: Vec<&str> = phrase.split_whitespace().collect();
+        Self::from_bytes(&mnemonic::decode(&words)?)
+    }
+
+    /// Adds a share of another secret dealt at the same index, giving a share
+    /// of the sum of the two secrets; see [`Commitment::add`] for the matching
+    /// commitments. The value is left unreduced; verification and
+    /// reconstruction reduce it modulo q.
+    pub fn add(&self, other: &Share) -> error::Result<Share> {
+        if self.id != other.id {
+            return Err(Error::InconsistentShares("shares have different indices"));
+        }
+        Ok(Share {
+            id: self.id.clone(),
+            value: &self.value + &other.value,
+        })
+    }
+
+    /// A share of the secret multiplied by `c`, left unreduced; see
+    /// [`Commitment::scale`].
+    pub fn scale(&self, c: &BigUint) -> Share {
+        Share {
+            id: self.id.clone(),
+            value: &self.value * c,
+        }
+    }
+
+    /// A share of the secret plus `c`, left unreduced; see
+    /// [`Commitment::add_constant`].
+    pub fn add_constant(&self, c: &BigUint) -> Share {
+        Share {
+            id: self.id.clone(),
+            value: &self.value + c,
+        }
+    }
+
+    /// SHA-256 of the canonical form, for logging a share without revealing it.
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.to_string())
+    }
+
+    /// Renders the armored encoding of the share as a QR code image.
+    #[cfg(feature = "qr")]
+    pub fn to_qr(&self, format: QrFormat) -> error::Result<Vec<u8>> {
+        qr::render(&self.to_armored(), format)
+    }
+}
+
+/// The canonical single-line form, `vss:<index>:<hex value>:<checksum>`.
+impl fmt::Display for Share {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&to_canonical(CANONICAL_PREFIX, &self.id, &self.value))
+    }
+}
+
+impl FromStr for Share {
+    type Err = Error;
+
+    fn from_str(text: &str) -> error::Result<Self> {
+        let (id, value) = from_canonical(CANONICAL_PREFIX, text)?;
+        Ok(Share { id, value })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Commitment(pub Vec<BigUint>);
 
+impl Commitment {
+    /// The group public key `C_0 = g^secret mod p`; `None` for an empty
+    /// commitment vector.
+    pub fn public_key(&self) -> Option<PublicKey> {
+        self.0.first().cloned().map(PublicKey)
+    }
+
+    /// Encodes the commitments as a u16 count followed by each one
+    /// big-endian and zero-padded to the byte width of `p`, so equal
+    /// commitments always encode to equal bytes.
+    pub fn to_fixed_bytes(&self, p: &BigUint) -> error::Result<Vec<u8>> {
+        let mut out = (self.0.len() as u16).to_be_bytes().to_vec();
+        for commitment in &self.0 {
+            out.extend_from_slice(&to_fixed(commitment, p)?);
+        }
+        Ok(out)
+    }
+
+    /// Decodes [`Commitment::to_fixed_bytes`], rejecting elements not below `p`.
+    pub fn from_fixed_bytes(bytes: &[u8], p: &BigUint) -> error::Result<Self> {
+        let width = (p.bits() as usize).div_ceil(8);
+        let (count, body) = bytes.split_at_checked(2).ok_or(Error::MalformedShare)?;
+        let count = u16::from_be_bytes([count[0], count[1]]) as usize;
+        if width == 0 || body.len() != count * width {
+            return Err(Error::MalformedShare);
+        }
+        body.chunks(width)
+            .map(|element| from_fixed(element, p))
+            .collect::<error::Result<_>>()
+            .map(Commitment)
+    }
+
+    /// Whether these commitments prove a sharing of zero: `C_0 = g^0 = 1`.
+    pub fn shares_zero(&self) -> bool {
+        self.0.first().is_some_and(One::is_one)
+    }
+
+    /// The public share `g^f(id) = prod C_k^(id^k) mod p` of the holder at
+    /// `id`, computed from the commitments alone; exponents reduce mod `q`.
+    pub fn public_share_for(&self, id: &BigUint, p: &BigUint, q: &BigUint) -> PublicKey {
+        PublicKey(
+            self.0
+                .iter()
+                .enumerate()
+                .fold(BigUint::one(), |acc, (power, commitment)| {
+                    let x_power = id.modpow(&BigUint::from(power as u32), q);
+                    (acc * commitment.modpow(&x_power, p)) % p
+                }),
+        )
+    }
+
+    /// The commitments to the sum of two polynomials over the same group:
+    /// `g^(a_k + b_k) = g^a_k * g^b_k mod p`. A shorter polynomial is padded
+    /// with commitments to zero.
+    pub fn add(&self, other: &Commitment, p: &BigUint) -> Commitment {
+        let len = self.0.len().max(other.0.len());
+        let one = BigUint::one();
+        Commitment(
+            (0..len)
+                .map(|k| {
+                    let a = self.0.get(k).unwrap_or(&one);
+                    let b = other.0.get(k).unwrap_or(&one);
+                    (a * b) % p
+                })
+                .collect(),
+        )
+    }
+
+    /// The commitments to the polynomial multiplied by `c`: `C_k^c mod p`.
+    pub fn scale(&self, c: &BigUint, p: &BigUint) -> Commitment {
+        Commitment(
+            self.0
+                .iter()
+                .map(|commitment| commitment.modpow(c, p))
+                .collect(),
+        )
+    }
+
+    /// The commitments after adding `c` to the secret: only the constant
+    /// term changes, to `C_0 * g^c mod p`.
+    pub fn add_constant(&self, c: &BigUint, g: &BigUint, p: &BigUint) -> Commitment {
+        let mut commitments = self.0.clone();
+        if let Some(first) = commitments.first_mut() {
+            *first = (&*first * g.modpow(c, p)) % p;
+        }
+        Commitment(commitments)
+    }
+}
+
+/// The public key `g^secret mod p` of a shared secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey(pub BigUint);
+
+impl PublicKey {
+    /// Serializes the key as one length-prefixed big-endian integer, minimal
+    /// since the key carries no `p` to pad to.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        put_biguint(&mut out, &self.0);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> error::Result<Self> {
+        let mut input = bytes;
+        let key = take_biguint(&mut input)?;
+        if !input.is_empty() {
+            return Err(Error::MalformedShare);
+        }
+        Ok(PublicKey(key))
+    }
+
+    /// Encodes the key as an ASCII-armored text block for publication.
+    pub fn to_armored(&self) -> String {
+        armor(PUBLIC_KEY_LABEL, &self.to_bytes())
+    }
+
+    pub fn from_armored(text: &str) -> error::Result<Self> {
+        Self::from_bytes(&dearmor(PUBLIC_KEY_LABEL, text)?)
+    }
+}
+
+/// The key as lowercase hex.
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_str_radix(16))
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = Error;
+
+    fn from_str(text: &str) -> error::Result<Self> {
+        BigUint::parse_bytes(text.trim().as_bytes(), 16)
+            .map(PublicKey)
+            .ok_or(Error::InvalidEncoding("invalid hex integer"))
+    }
+}
+
+/// A holder's share in the exponent, `g^share mod p`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicShare {
+    pub id: BigUint,
+    pub value: BigUint,
+}
+
 #[derive(Debug)]
 struct VSSParams {
-    p: BigUint, // Prime field modulus
-    q: BigUint, // Prime order of generator
-    g: BigUint, // Generator
+    group: Feldman,
     threshold: usize,
     total_shares: usize,
 }
 
+/// How a dealing commits to its polynomial, so holders can check their
+/// shares. [`FeldmanVSS`] draws the polynomial, evaluates the shares and
+/// reconstructs the same way whatever the scheme; the scheme commits to the
+/// coefficients and may add its own per-holder data, such as Pedersen's
+/// shares of a blinding polynomial, to each share.
+pub trait CommitmentScheme {
+    /// What each holder receives and checks against the commitment.
+    type Share;
+    type Commitment;
+    /// Dealer-private data the shares are completed from, e.g. a blinding
+    /// polynomial; `()` for schemes without any.
+    type Opening;
+
+    /// Commits to the coefficients `a_0..a_{t-1}`, constant term first,
+    /// drawing any blinding randomness from `rng`.
+    fn commit(
+        &self,
+        coefficients: &[BigUint],
+        rng: &mut dyn RngCore,
+    ) -> (Self::Commitment, Self::Opening);
+
+    /// Completes a holder's share of the secret polynomial from the opening.
+    fn share(&self, share: Share, opening: &Self::Opening) -> Self::Share;
+
+    /// The share of the secret polynomial inside, which reconstruction
+    /// interpolates.
+    fn secret_share<'a>(&self, share: &'a Self::Share) -> &'a Share;
+
+    /// Whether `share` lies on the committed polynomial.
+    fn verify(&self, share: &Self::Share, commitment: &Self::Commitment) -> bool;
+
+    /// A canonical encoding of the commitment for publication; fails for a
+    /// commitment outside the scheme's group.
+    fn serialize(&self, commitment: &Self::Commitment) -> error::Result<Vec<u8>>;
+
+    /// Decodes [`serialize`](Self::serialize), rejecting malformed input.
+    fn deserialize(&self, bytes: &[u8]) -> error::Result<Self::Commitment>;
+}
+
+/// Feldman commitments `C_k = g^a_k mod p` in the order-`q` subgroup
+/// generated by `g`: the scheme [`FeldmanVSS::split_secret`] uses.
+#[derive(Clone, Debug)]
+pub struct Feldman {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl Feldman {
+    fn commitments(&self, coefficients: &[BigUint]) -> Commitment {
+        Commitment(self.commitment_terms(coefficients).collect())
+    }
+
+    /// `C_k = g^a_k mod p` for each coefficient, computed as the iterator
+    /// advances so callers can report progress between them.
+    fn commitment_terms<'a>(
+        &'a self,
+        coefficients: &'a [BigUint],
+    ) -> impl Iterator<Item = BigUint> + 'a {
+        coefficients
+            .iter()
+            .map(|coeff| self.g.modpow(coeff, &self.p))
+    }
+}
+
+impl CommitmentScheme for Feldman {
+    type Share = Share;
+    type Commitment = Commitment;
+    type Opening = ();
+
+    fn commit(&self, coefficients: &[BigUint], _rng: &mut dyn RngCore) -> (Commitment, ()) {
+        (self.commitments(coefficients), ())
+    }
+
+    fn share(&self, share: Share, _opening: &()) -> Share {
+        share
+    }
+
+    fn secret_share<'a>(&self, share: &'a Share) -> &'a Share {
+        share
+    }
+
+    fn verify(&self, share: &Share, commitment: &Commitment) -> bool {
+        commitment.public_share_for(&share.id, &self.p, &self.q).0
+            == self.g.modpow(&share.value, &self.p)
+    }
+
+    fn serialize(&self, commitment: &Commitment) -> error::Result<Vec<u8>> {
+        commitment.to_fixed_bytes(&self.p)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> error::Result<Commitment> {
+        Commitment::from_fixed_bytes(bytes, &self.p)
+    }
+}
+
+/// A dealing computed before its secret is known: the shares and commitments
+/// of a random polynomial whose constant term, the mask, stays private.
+/// [`FeldmanVSS::bind`] shifts it onto the secret. It is consumed by binding,
+/// since binding two secrets to one mask would reveal their difference.
+pub struct PreparedDealing {
+    mask: BigUint,
+    shares: Vec<Share>,
+    commitments: Commitment,
+}
+
 pub struct FeldmanVSS {
     params: VSSParams,
     rng: rand::rngs::ThreadRng,
@@ -34,9 +439,7 @@ impl FeldmanVSS {
 
         FeldmanVSS {
             params: VSSParams {
-                p,
-                q,
-                g,
+                group: Feldman { p, q, g },
                 threshold,
                 total_shares,
             },
@@ -44,67 +447,355 @@ impl FeldmanVSS {
         }
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(threshold = self.params.threshold, total = self.params.total_shares)))]
     pub fn split_secret(
         &mut self,
         secret: &BigUint,
     ) -> Result<(Vec<Share>, Commitment), &'static str> {
-        if secret >= &self.params.q {
-            return Err("Secret must be less than q");
+        let group = self.params.group.clone();
+        self.split_secret_with(&group, secret)
+            .map_err(|_| "Secret must be less than q")
+    }
+
+    /// Like [`split_secret`](Self::split_secret), committing to the
+    /// polynomial with `scheme` instead of Feldman's; the shares are drawn
+    /// and evaluated exactly as there, completed by the scheme, and
+    /// reconstruct from their [`CommitmentScheme::secret_share`]s.
+    pub fn split_secret_with<C: CommitmentScheme>(
+        &mut self,
+        scheme: &C,
+        secret: &BigUint,
+    ) -> error::Result<(Vec<C::Share>, C::Commitment)> {
+        if secret >= &self.params.group.q {
+            return Err(Error::InvalidParameters("secret must be less than q"));
         }
 
         let coefficients = self.generate_polynomial(secret);
+        let (commitments, opening) = scheme.commit(&coefficients, &mut self.rng);
+        let shares = self
+            .generate_shares(&coefficients)
+            .into_iter()
+            .map(|share| scheme.share(share, &opening))
+            .collect();
+
+        Ok((shares, commitments))
+    }
+
+    /// The Feldman commitment scheme over this sharing's group.
+    pub fn feldman(&self) -> &Feldman {
+        &self.params.group
+    }
+
+    /// Like [`split_secret`](Self::split_secret), reporting each commitment
+    /// and share computed to `progress`, out of `threshold + total_shares`
+    /// steps, and stopping if it is cancelled.
+    pub fn split_secret_with_progress(
+        &mut self,
+        secret: &BigUint,
+        progress: &mut Progress,
+    ) -> error::Result<(Vec<Share>, Commitment)> {
+        if secret >= &self.params.group.q {
+            return Err(Error::InvalidParameters("secret must be less than q"));
+        }
+        progress.check()?;
+
+        let coefficients = self.generate_polynomial(secret);
+        let total = (self.params.threshold + self.params.total_shares) as u64;
+        let mut done = 0;
+        let mut commitments = Vec::with_capacity(coefficients.len());
+        for commitment in self.params.group.commitment_terms(&coefficients) {
+            commitments.push(commitment);
+            done += 1;
+            progress.step(done, Some(total))?;
+        }
+        let mut shares = Vec::with_capacity(self.params.total_shares);
+        for share in self.share_terms(&coefficients) {
+            shares.push(share);
+            done += 1;
+            progress.step(done, Some(total))?;
+        }
+
+        Ok((shares, Commitment(commitments)))
+    }
+
+    /// Like [`split_secret`](Self::split_secret), with the coefficients
+    /// drawn from [`insecure_seeded_rng`] keyed by `seed`, so the shares and
+    /// commitments of a past dealing can be re-derived and audited.
+    ///
+    /// INSECURE for live secrets: whoever knows the seed and a share can
+    /// recompute every share.
+    pub fn split_secret_deterministic(
+        &self,
+        secret: &BigUint,
+        seed: &[u8],
+    ) -> Result<(Vec<Share>, Commitment), &'static str> {
+        if secret >= &self.params.group.q {
+            return Err("Secret must be less than q");
+        }
+
+        let coefficients = random_polynomial(&self.params, secret, &mut insecure_seeded_rng(seed));
         let commitments = self.generate_commitments(&coefficients);
         let shares = self.generate_shares(&coefficients);
 
         Ok((shares, commitments))
     }
 
+    /// A verifiable sharing of zero: shares of a random polynomial with
+    /// `f(0) = 0` and its commitments, whose `C_0 = 1` proves the constant
+    /// term. Adding it to a dealing re-randomizes the shares without changing
+    /// the secret, the core of proactive refresh.
+    pub fn split_zero(&mut self) -> (Vec<Share>, Commitment) {
+        self.split_secret(&BigUint::zero())
+            .expect("zero is below q")
+    }
+
+    /// The offline half of a two-phase dealing: draws the polynomial and
+    /// computes its shares and commitments (the `threshold` exponentiations
+    /// that dominate dealing) ahead of time, for [`bind`](Self::bind) to
+    /// finish once the secret arrives.
+    pub fn prepare(&mut self) -> PreparedDealing {
+        let mask = self
+            .rng
+            .gen_biguint_range(&BigUint::zero(), &self.params.group.q);
+        let coefficients = self.generate_polynomial(&mask);
+        PreparedDealing {
+            commitments: self.generate_commitments(&coefficients),
+            shares: self.generate_shares(&coefficients),
+            mask,
+        }
+    }
+
+    /// The online half: turns a prepared dealing into a dealing of `secret`
+    /// by adding `secret - mask` to every share and to the committed
+    /// constant term, at the cost of one exponentiation. The result is
+    /// distributed exactly like [`split_secret`](Self::split_secret)'s.
+    pub fn bind(
+        &self,
+        dealing: PreparedDealing,
+        secret: &BigUint,
+    ) -> error::Result<(Vec<Share>, Commitment)> {
+        let q = &self.params.group.q;
+        if secret >= q {
+            return Err(Error::InvalidParameters("secret must be less than q"));
+        }
+        if dealing.commitments.0.len() != self.params.threshold
+            || dealing.shares.len() != self.params.total_shares
+        {
+            return Err(Error::InvalidParameters(
+                "dealing was prepared for other parameters",
+            ));
+        }
+        let offset = (q + secret - &dealing.mask) % q;
+        let shares = dealing
+            .shares
+            .iter()
+            .map(|share| Share {
+                id: share.id.clone(),
+                value: share.add_constant(&offset).value % q,
+            })
+            .collect();
+        let commitments =
+            dealing
+                .commitments
+                .add_constant(&offset, &self.params.group.g, &self.params.group.p);
+        Ok((shares, commitments))
+    }
+
+    /// Sums several dealers' dealings over the same holders into a sharing
+    /// of the sum of their secrets, committed to by the product of their
+    /// commitments: the reusable core of dealerless and contributory key
+    /// generation. No single dealer knows the summed secret.
+    ///
+    /// Every dealing must give exactly one share to each holder of the
+    /// first, commit to a polynomial of this sharing's degree, and have every
+    /// share verify against its own commitments; otherwise the dealings are
+    /// refused with [`Error::InconsistentShares`]. Shares come back ordered
+    /// by id, reduced modulo q.
+    pub fn aggregate(
+        &self,
+        dealings: &[(Vec<Share>, Commitment)],
+    ) -> error::Result<(Vec<Share>, Commitment)> {
+        let (first, _) = dealings.first().ok_or(Error::InsufficientShares)?;
+        let holders = |shares: &[Share]| {
+            let mut ids: Vec<BigUint> = shares.iter().map(|share| share.id.clone()).collect();
+            ids.sort();
+            ids
+        };
+        let ids = holders(first);
+        if ids.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(Error::InconsistentShares("duplicate share indices"));
+        }
+
+        let mut values = vec![BigUint::zero(); ids.len()];
+        let mut commitments = Commitment(Vec::new());
+        for (shares, dealt) in dealings {
+            if dealt.0.len() != self.params.threshold {
+                return Err(Error::InconsistentShares(
+                    "a dealing commits to a polynomial of the wrong degree",
+                ));
+            }
+            if holders(shares) != ids {
+                return Err(Error::InconsistentShares(
+                    "dealings cover different holders",
+                ));
+            }
+            for share in shares {
+                if !self.verify_share(share, dealt) {
+                    return Err(Error::InconsistentShares(
+                        "a share does not match its dealer's commitments",
+                    ));
+                }
+                let slot = ids.binary_search(&share.id).expect("holder sets match");
+                values[slot] = (&values[slot] + &share.value) % &self.params.group.q;
+            }
+            commitments = commitments.add(dealt, &self.params.group.p);
+        }
+        let shares = ids
+            .into_iter()
+            .zip(values)
+            .map(|(id, value)| Share { id, value })
+            .collect();
+        Ok((shares, commitments))
+    }
+
+    /// Wraps a share with the group parameters so it can be combined on its own.
+    pub fn serialize_share(&self, share: &Share) -> SerializedShare {
+        SerializedShare::new(
+            Scheme::Feldman {
+                p: self.params.group.p.clone(),
+                q: self.params.group.q.clone(),
+                g: self.params.group.g.clone(),
+            },
+            self.params.threshold,
+            self.params.total_shares,
+            share.id.clone(),
+            (&share.value % &self.params.group.q).to_bytes_be(),
+        )
+        .expect("values reduced modulo q fit its width")
+    }
+
+    /// Packages the commitments of a dealing for publication.
+    pub fn commitment_document(&self, commitments: &Commitment) -> CommitmentDocument {
+        CommitmentDocument {
+            p: self.params.group.p.clone(),
+            q: self.params.group.q.clone(),
+            g: self.params.group.g.clone(),
+            threshold: self.params.threshold,
+            total: self.params.total_shares,
+            commitments: commitments.0.clone(),
+            signature: None,
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(id = %share.id)))]
     pub fn verify_share(&self, share: &Share, commitments: &Commitment) -> bool {
-        let lhs = self.compute_commitment_product(share, commitments);
-        let rhs = self.params.g.modpow(&share.value, &self.params.p);
-        lhs == rhs
+        let valid = self.params.group.verify(share, commitments);
+        #[cfg(feature = "tracing")]
+        if !valid {
+            tracing::warn!(id = %share.id, "share does not match the commitments");
+        }
+        valid
     }
 
-    pub fn reconstruct_secret(&self, shares: &[Share]) -> Option<BigUint> {
+    /// Recovers the secret from at least `threshold` shares, owned or
+    /// borrowed, in any order. Repeated shares are dropped and the first
+    /// `threshold` distinct ones interpolated; any surplus share must lie on
+    /// the same polynomial.
+    ///
+    /// `None` if too few distinct shares remain, two shares claim the same
+    /// index with different values, or a surplus share disagrees.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(threshold = self.params.threshold)))]
+    pub fn reconstruct_secret<S: Borrow<Share>>(
+        &self,
+        shares: impl IntoIterator<Item = S>,
+    ) -> Option<BigUint> {
+        let shares = distinct(shares)?;
         if shares.len() < self.params.threshold {
             return None;
         }
 
-        let shares = &shares[0..self.params.threshold];
-        shares
-            .iter()
-            .enumerate()
-            .try_fold(BigUint::zero(), |acc, (i, share)| {
-                self.calculate_lagrange_coefficient(share, shares, i)
-                    .map(|coeff| (acc + &share.value * coeff) % &self.params.q)
-            })
+        let (quorum, surplus) = shares.split_at(self.params.threshold);
+        for share in surplus {
+            if self.interpolate_at(quorum, &share.id)? != &share.value % &self.params.group.q {
+                return None;
+            }
+        }
+        self.interpolate_at(quorum, &BigUint::zero())
     }
 
-    fn generate_polynomial(&mut self, secret: &BigUint) -> Vec<BigUint> {
-        let mut coefficients = vec![secret.clone()];
-        for _ in 1..self.params.threshold {
-            coefficients.push(self.rng.gen_biguint_range(&BigUint::zero(), &self.params.q));
+    /// A holder's public share `g^share`, to submit in place of the share.
+    pub fn public_share(&self, share: &Share) -> PublicShare {
+        PublicShare {
+            id: share.id.clone(),
+            value: self
+                .params
+                .group
+                .g
+                .modpow(&share.value, &self.params.group.p),
         }
-        coefficients
     }
 
-    fn generate_commitments(&self, coefficients: &[BigUint]) -> Commitment {
-        Commitment(
-            coefficients
+    /// Recovers `g^secret` from a threshold of public shares by Lagrange
+    /// interpolation in the exponent, without assembling the secret. Check
+    /// each public share against [`Commitment::public_share_for`] first when
+    /// the commitments are at hand. The first `threshold` shares are
+    /// interpolated and any surplus share must lie on the same polynomial.
+    ///
+    /// `None` with too few shares, an id that is zero or repeated modulo q,
+    /// or a surplus share that disagrees.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(shares = shares.len())))]
+    pub fn reconstruct_in_exponent(&self, shares: &[PublicShare]) -> Option<PublicKey> {
+        if shares.len() < self.params.threshold {
+            return None;
+        }
+        let (p, q) = (&self.params.group.p, &self.params.group.q);
+        let ids: Vec<BigUint> = shares.iter().map(|share| &share.id % q).collect();
+        for (i, id) in ids.iter().enumerate() {
+            if id.is_zero() || ids[..i].contains(id) {
+                return None;
+            }
+        }
+
+        let (quorum, surplus) = shares.split_at(self.params.threshold);
+        let quorum_ids = &ids[..self.params.threshold];
+        let exponent_at = |x: &BigUint| {
+            quorum
                 .iter()
-                .map(|coeff| self.params.g.modpow(coeff, &self.params.p))
-                .collect(),
-        )
+                .enumerate()
+                .try_fold(BigUint::one(), |acc, (i, share)| {
+                    self.lagrange_at(quorum_ids, i, x)
+                        .map(|coeff| (acc * share.value.modpow(&coeff, p)) % p)
+                })
+        };
+        for (share, id) in surplus.iter().zip(&ids[self.params.threshold..]) {
+            if exponent_at(id)? != &share.value % p {
+                return None;
+            }
+        }
+        exponent_at(&BigUint::zero()).map(PublicKey)
+    }
+
+    fn generate_polynomial(&mut self, secret: &BigUint) -> Vec<BigUint> {
+        random_polynomial(&self.params, secret, &mut self.rng)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(count = coefficients.len())))]
+    fn generate_commitments(&self, coefficients: &[BigUint]) -> Commitment {
+        self.params.group.commitments(coefficients)
     }
 
     fn generate_shares(&self, coefficients: &[BigUint]) -> Vec<Share> {
-        (1..=self.params.total_shares)
-            .map(|i| {
-                let id = BigUint::from(i as u32);
-                let value = self.evaluate_polynomial(coefficients, &id);
-                Share { id, value }
-            })
-            .collect()
+        self.share_terms(coefficients).collect()
+    }
+
+    /// The shares at `1..=total_shares`, computed as the iterator advances
+    /// so callers can report progress between them.
+    fn share_terms<'a>(&'a self, coefficients: &'a [BigUint]) -> impl Iterator<Item = Share> + 'a {
+        (1..=self.params.total_shares).map(|i| {
+            let id = BigUint::from(i as u32);
+            let value = self.evaluate_polynomial(coefficients, &id);
+            Share { id, value }
+        })
     }
 
     fn evaluate_polynomial(&self, coefficients: &[BigUint], x: &BigUint) -> BigUint {
@@ -112,61 +803,81 @@ impl FeldmanVSS {
             .iter()
             .enumerate()
             .fold(BigUint::zero(), |acc, (power, coeff)| {
-                let term = coeff * x.modpow(&BigUint::from(power as u32), &self.params.q);
-                (acc + term) % &self.params.q
+                let term = coeff * x.modpow(&BigUint::from(power as u32), &self.params.group.q);
+                (acc + term) % &self.params.group.q
             })
     }
 
-    fn compute_commitment_product(&self, share: &Share, commitments: &Commitment) -> BigUint {
-        commitments
-            .0
+    /// The value at `x` of the polynomial through `shares`, modulo q.
+    fn interpolate_at(&self, shares: &[Share], x: &BigUint) -> Option<BigUint> {
+        let q = &self.params.group.q;
+        let ids: Vec<BigUint> = shares.iter().map(|share| &share.id % q).collect();
+        shares
             .iter()
             .enumerate()
-            .fold(BigUint::one(), |acc, (power, commitment)| {
-                let x_power = share
-                    .id
-                    .modpow(&BigUint::from(power as u32), &self.params.q);
-                let term = commitment.modpow(&x_power, &self.params.p);
-                (acc * term) % &self.params.p
+            .try_fold(BigUint::zero(), |acc, (i, share)| {
+                self.lagrange_at(&ids, i, x)
+                    .map(|coeff| (acc + &share.value * coeff) % q)
             })
     }
 
-    fn calculate_lagrange_coefficient(
-        &self,
-        share_i: &Share,
-        shares: &[Share],
-        i: usize,
-    ) -> Option<BigUint> {
-        let (numerator, denominator) = shares.iter().enumerate().filter(|&(j, _)| i != j).fold(
+    /// The Lagrange basis polynomial of `ids[i]` evaluated at `x`, modulo q:
+    /// `prod_{j != i} (x - id_j) / (id_i - id_j)`. `ids` must be reduced
+    /// modulo q; `None` if two of them coincide.
+    fn lagrange_at(&self, ids: &[BigUint], i: usize, x: &BigUint) -> Option<BigUint> {
+        let q = &self.params.group.q;
+        let x = x % q;
+        let (numerator, denominator) = ids.iter().enumerate().filter(|&(j, _)| i != j).fold(
             (BigUint::one(), BigUint::one()),
-            |(num, den), (_, share_j)| {
-                let new_num = (num * &share_j.id) % &self.params.q;
-                let diff = if share_j.id > share_i.id {
-                    (&share_j.id - &share_i.id) % &self.params.q
-                } else {
-                    (&self.params.q + &share_j.id - &share_i.id) % &self.params.q
-                };
-                let new_den = (den * diff) % &self.params.q;
-                (new_num, new_den)
+            |(num, den), (_, id_j)| {
+                (
+                    num * ((q + &x - id_j) % q) % q,
+                    den * ((q + &ids[i] - id_j) % q) % q,
+                )
             },
         );
-
         self.mod_inverse(&denominator)
-            .map(|den_inv| (numerator * den_inv) % &self.params.q)
+            .map(|inverse| numerator * inverse % q)
     }
 
     fn mod_inverse(&self, a: &BigUint) -> Option<BigUint> {
         if a.is_zero() {
             return None;
         }
-        Some(a.modpow(&(&self.params.q - 2u32), &self.params.q))
+        Some(a.modpow(&(&self.params.group.q - 2u32), &self.params.group.q))
     }
 }
 
+/// The shares with exact repeats dropped, in first-seen order; `None` if two
+/// claim the same id with different values.
+fn distinct<S: Borrow<Share>>(shares: impl IntoIterator<Item = S>) -> Option<Vec<Share>> {
+    let mut seen: Vec<Share> = Vec::new();
+    for share in shares {
+        let share = share.borrow();
+        match seen.iter().find(|other| other.id == share.id) {
+            Some(other) if other.value != share.value => return None,
+            Some(_) => {}
+            None => seen.push(share.clone()),
+        }
+    }
+    Some(seen)
+}
+
+fn random_polynomial(params: &VSSParams, secret: &BigUint, rng: &mut impl RngCore) -> Vec<BigUint> {
+    let mut coefficients = vec![secret.clone()];
+    for _ in 1..params.threshold {
+        coefficients.push(rng.gen_biguint_range(&BigUint::zero(), &params.group.q));
+    }
+    coefficients
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::params::GroupParameters;
     use num_bigint::ToBigUint;
+    use proptest::prelude::*;
+    use std::sync::OnceLock;
 
     #[test]
     fn test_vss_workflow() {
@@ -186,6 +897,13 @@ mod tests {
             .iter()
             .all(|share| vss.verify_share(share, &commitments)));
 
+        // Shares survive the canonical text form
+        let parsed: Vec<Share> = shares
+            .iter()
+            .map(|share| share.to_string().parse().unwrap())
+            .collect();
+        assert!(vss.verify_share(&parsed[0], &commitments));
+
         // Test reconstruction
         let reconstructed = vss.reconstruct_secret(&shares[0..threshold]);
         assert_eq!(reconstructed, Some(secret));
@@ -194,4 +912,342 @@ mod tests {
         let insufficient = vss.reconstruct_secret(&shares[0..threshold - 1]);
         assert_eq!(insufficient, None);
     }
+
+    #[test]
+    fn test_public_key() {
+        let (p, q, g) = (23u32.into(), 11u32.into(), 2u32.to_biguint().unwrap());
+        let mut vss = FeldmanVSS::new(p, q, g, 2, 3);
+        let (_, commitments) = vss.split_secret(&4u32.into()).unwrap();
+
+        // 2^4 = 16 mod 23
+        let key = commitments.public_key().unwrap();
+        assert_eq!(key, PublicKey(16u32.into()));
+        assert_eq!(PublicKey::from_armored(&key.to_armored()).unwrap(), key);
+        assert_eq!(key.to_string().parse::<PublicKey>().unwrap(), key);
+        assert_eq!(
+            vss.commitment_document(&commitments).public_key(),
+            Some(key)
+        );
+        assert!(Commitment(Vec::new()).public_key().is_none());
+    }
+
+    #[test]
+    fn test_reconstruct_in_exponent() {
+        let (p, q, g) = (23u32.into(), 11u32.into(), 2u32.to_biguint().unwrap());
+        let mut vss = FeldmanVSS::new(p, q, g, 3, 5);
+        let (shares, commitments) = vss.split_secret(&6u32.into()).unwrap();
+        let public: Vec<_> = shares.iter().map(|share| vss.public_share(share)).collect();
+        assert!(public.iter().all(|share| {
+            commitments.public_share_for(&share.id, &23u32.into(), &11u32.into())
+                == PublicKey(share.value.clone())
+        }));
+
+        // 2^6 = 64 = 18 mod 23
+        assert_eq!(
+            vss.reconstruct_in_exponent(&public[2..]),
+            Some(PublicKey(18u32.into()))
+        );
+        assert_eq!(
+            vss.reconstruct_in_exponent(&public[2..]),
+            commitments.public_key()
+        );
+        assert!(vss.reconstruct_in_exponent(&public[3..]).is_none());
+
+        // Ids are reduced mod q: 13 names the holder at 2, so it collides
+        let mut aliased = public[1..4].to_vec();
+        aliased[2].id = 13u32.into();
+        assert!(vss.reconstruct_in_exponent(&aliased).is_none());
+        // ... and 4 + 90q still names the holder at 4, without underflowing
+        aliased[2].id = (4 + 90 * 11u32).into();
+        assert_eq!(
+            vss.reconstruct_in_exponent(&aliased),
+            commitments.public_key()
+        );
+        let mut zero = public[2..].to_vec();
+        zero[0].id = 11u32.into();
+        assert!(vss.reconstruct_in_exponent(&zero).is_none());
+        // Surplus shares are checked against the quorum
+        assert_eq!(
+            vss.reconstruct_in_exponent(&public),
+            commitments.public_key()
+        );
+        let mut forged = public.clone();
+        forged[4].value = (&forged[4].value * 2u32) % 23u32;
+        assert!(vss.reconstruct_in_exponent(&forged).is_none());
+    }
+
+    #[test]
+    fn test_zero_sharing() {
+        let (p, q, g) = (23u32.into(), 11u32.into(), 2u32.to_biguint().unwrap());
+        let mut vss = FeldmanVSS::new(p, q, g, 3, 4);
+        let (shares, commitments) = vss.split_secret(&9u32.into()).unwrap();
+        let (zeros, zero_commitments) = vss.split_zero();
+        assert!(zero_commitments.shares_zero());
+        assert!(!commitments.shares_zero());
+
+        let refreshed: Vec<_> = shares
+            .iter()
+            .zip(&zeros)
+            .map(|(share, zero)| share.add(zero).unwrap())
+            .collect();
+        let commitments = commitments.add(&zero_commitments, &23u32.into());
+        assert!(refreshed
+            .iter()
+            .all(|share| vss.verify_share(share, &commitments)));
+        assert_eq!(vss.reconstruct_secret(&refreshed[1..]), Some(9u32.into()));
+    }
+
+    #[test]
+    fn test_affine_operations() {
+        let (p, q, g) = (23u32.into(), 11u32.into(), 2u32.to_biguint().unwrap());
+        let mut vss = FeldmanVSS::new(p, q, g.clone(), 2, 3);
+        let (shares, commitments) = vss.split_secret(&4u32.into()).unwrap();
+        let document = vss.commitment_document(&commitments);
+
+        // 3 * 4 + 5 = 17 = 6 mod 11
+        let (c, d) = (3u32.into(), 5u32.into());
+        let tweaked = document.scale(&c).add_constant(&d);
+        let shares: Vec<_> = shares
+            .iter()
+            .map(|share| share.scale(&c).add_constant(&d))
+            .collect();
+        assert!(shares.iter().all(|share| tweaked.verify_share(share)));
+        assert_eq!(vss.reconstruct_secret(&shares[1..]), Some(6u32.into()));
+
+        let serialized = vss.serialize_share(&shares[0]);
+        let scaled = serialized.scale(&2u32.into()).unwrap();
+        assert!(tweaked.scale(&2u32.into()).verify_share(&Share {
+            id: scaled.x.clone(),
+            value: BigUint::from_bytes_be(&scaled.value),
+        }));
+        let bytewise = crate::serialized::split_bytes(b"k", 2, 3).unwrap();
+        assert!(bytewise[0].add_constant(&g).is_err());
+    }
+
+    #[test]
+    fn test_deterministic_split() {
+        let (p, q, g) = (23u32.into(), 11u32.into(), 2u32.to_biguint().unwrap());
+        let vss = FeldmanVSS::new(p, q, g, 3, 5);
+        let (shares, commitments) = vss
+            .split_secret_deterministic(&7u32.into(), b"audit")
+            .unwrap();
+        let (again, recommitted) = vss
+            .split_secret_deterministic(&7u32.into(), b"audit")
+            .unwrap();
+        assert_eq!(commitments.0, recommitted.0);
+        assert!(shares.iter().zip(&again).all(|(a, b)| a.value == b.value));
+        assert!(shares
+            .iter()
+            .all(|share| vss.verify_share(share, &commitments)));
+        assert!(vss
+            .split_secret_deterministic(&11u32.into(), b"audit")
+            .is_err());
+    }
+
+    #[test]
+    fn test_progress_dealing() {
+        let (p, q, g) = (23u32.into(), 11u32.into(), 2u32.to_biguint().unwrap());
+        let mut vss = FeldmanVSS::new(p, q, g, 3, 5);
+        let mut steps = Vec::new();
+        let (shares, commitments) = vss
+            .split_secret_with_progress(
+                &7u32.into(),
+                &mut Progress::new().on_progress(|done, total| steps.push((done, total))),
+            )
+            .unwrap();
+        assert_eq!(steps.len(), 8);
+        assert_eq!(steps[7], (8, Some(8)));
+        assert_eq!(commitments.public_key(), Some(PublicKey(13u32.into())));
+        assert!(shares
+            .iter()
+            .all(|share| vss.verify_share(share, &commitments)));
+        assert_eq!(vss.reconstruct_secret(&shares[2..]), Some(7u32.into()));
+    }
+
+    #[test]
+    fn test_reconstruct_unordered_surplus() {
+        let (p, q, g) = (23u32.into(), 11u32.into(), 2u32.to_biguint().unwrap());
+        let mut vss = FeldmanVSS::new(p, q, g, 2, 4);
+        let (mut shares, _) = vss.split_secret(&9u32.into()).unwrap();
+        shares.reverse();
+        assert_eq!(vss.reconstruct_secret(&shares), Some(9u32.into()));
+        assert_eq!(vss.reconstruct_secret([&shares[1], &shares[1]]), None);
+
+        shares[3].value = (&shares[3].value + 1u32) % 11u32;
+        assert_eq!(vss.reconstruct_secret(&shares[..3]), Some(9u32.into()));
+        assert_eq!(vss.reconstruct_secret(shares), None);
+    }
+
+    #[test]
+    fn test_aggregate_dealings() {
+        let mut vss = group().vss(2, 3).unwrap();
+        let secrets = [5u32, 17, 40];
+        let dealings: Vec<_> = secrets
+            .iter()
+            .map(|secret| vss.split_secret(&BigUint::from(*secret)).unwrap())
+            .collect();
+        let (shares, commitments) = vss.aggregate(&dealings).unwrap();
+        assert!(shares
+            .iter()
+            .all(|share| vss.verify_share(share, &commitments)));
+        assert_eq!(vss.reconstruct_secret(&shares[1..]), Some(62u32.into()));
+        assert_eq!(
+            commitments.public_key().unwrap().0,
+            group().g.modpow(&62u32.into(), &group().p)
+        );
+
+        let mut tampered = dealings.clone();
+        tampered[1].0[2].value += 1u32;
+        assert!(vss.aggregate(&tampered).is_err());
+        let mut partial = dealings.clone();
+        partial[2].0.pop();
+        assert!(vss.aggregate(&partial).is_err());
+        let mut steep = dealings;
+        steep[0] = group()
+            .vss(3, 3)
+            .unwrap()
+            .split_secret(&1u32.into())
+            .unwrap();
+        assert!(vss.aggregate(&steep).is_err());
+    }
+
+    #[test]
+    fn test_prepared_dealing() {
+        let mut vss = group().vss(3, 5).unwrap();
+        let secret = 987654321u64.to_biguint().unwrap();
+        let prepared = vss.prepare();
+        let (shares, commitments) = vss.bind(prepared, &secret).unwrap();
+        assert!(shares
+            .iter()
+            .all(|share| vss.verify_share(share, &commitments)));
+        assert_eq!(vss.reconstruct_secret(&shares[2..]), Some(secret.clone()));
+        assert_eq!(
+            commitments.public_key().unwrap().0,
+            group().g.modpow(&secret, &group().p)
+        );
+
+        let prepared = vss.prepare();
+        assert!(vss.bind(prepared, &group().q).is_err());
+        let other = group().vss(2, 5).unwrap().prepare();
+        assert!(vss.bind(other, &secret).is_err());
+    }
+
+    #[test]
+    fn test_pluggable_commitment_scheme() {
+        // Pedersen: C_k = g^a_k * h^b_k with a blinding polynomial b, so each
+        // holder also needs its blinding share b(id)
+        struct Pedersen {
+            group: Feldman,
+            h: BigUint,
+        }
+        impl Pedersen {
+            fn evaluate(&self, coefficients: &[BigUint], x: &BigUint) -> BigUint {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(BigUint::zero(), |acc, a| (acc * x + a) % &self.group.q)
+            }
+        }
+        impl CommitmentScheme for Pedersen {
+            type Share = (Share, BigUint);
+            type Commitment = Commitment;
+            type Opening = Vec<BigUint>;
+            fn commit(
+                &self,
+                coefficients: &[BigUint],
+                rng: &mut dyn RngCore,
+            ) -> (Commitment, Vec<BigUint>) {
+                let Feldman { p, q, g } = &self.group;
+                let blinding: Vec<BigUint> = coefficients
+                    .iter()
+                    .map(|_| rng.gen_biguint_below(q))
+                    .collect();
+                let commitments = coefficients
+                    .iter()
+                    .zip(&blinding)
+                    .map(|(a, b)| g.modpow(a, p) * self.h.modpow(b, p) % p)
+                    .collect();
+                (Commitment(commitments), blinding)
+            }
+            fn share(&self, share: Share, blinding: &Vec<BigUint>) -> (Share, BigUint) {
+                let t = self.evaluate(blinding, &share.id);
+                (share, t)
+            }
+            fn secret_share<'a>(&self, share: &'a (Share, BigUint)) -> &'a Share {
+                &share.0
+            }
+            fn verify(&self, (share, t): &(Share, BigUint), commitment: &Commitment) -> bool {
+                let Feldman { p, q, g } = &self.group;
+                let expected = commitment.public_share_for(&share.id, p, q).0;
+                g.modpow(&share.value, p) * self.h.modpow(t, p) % p == expected
+            }
+            fn serialize(&self, commitment: &Commitment) -> error::Result<Vec<u8>> {
+                commitment.to_fixed_bytes(&self.group.p)
+            }
+            fn deserialize(&self, bytes: &[u8]) -> error::Result<Commitment> {
+                Commitment::from_fixed_bytes(bytes, &self.group.p)
+            }
+        }
+
+        let mut vss = group().vss(3, 5).unwrap();
+        let secret = 424242u64.to_biguint().unwrap();
+        let feldman = vss.feldman().clone();
+        let h = feldman.g.modpow(&7u32.to_biguint().unwrap(), &feldman.p);
+        let scheme = Pedersen { group: feldman, h };
+        let (shares, commitments) = vss.split_secret_with(&scheme, &secret).unwrap();
+        let commitments = scheme
+            .deserialize(&scheme.serialize(&commitments).unwrap())
+            .unwrap();
+        assert!(shares
+            .iter()
+            .all(|share| scheme.verify(share, &commitments)));
+        assert_eq!(
+            vss.reconstruct_secret(shares[1..4].iter().map(|s| scheme.secret_share(s))),
+            Some(secret.clone())
+        );
+        let (share, t) = &shares[0];
+        let forged = (share.clone(), (t + 1u32) % &scheme.group.q);
+        assert!(!scheme.verify(&forged, &commitments));
+
+        let feldman = vss.feldman().clone();
+        let (shares, commitments) = vss.split_secret_with(&feldman, &secret).unwrap();
+        assert!(shares
+            .iter()
+            .all(|share| vss.verify_share(share, &commitments)));
+        let forged = shares[0].add_constant(&BigUint::one());
+        assert!(!feldman.verify(&forged, &commitments));
+    }
+
+    fn group() -> &'static GroupParameters {
+        static GROUP: OnceLock<GroupParameters> = OnceLock::new();
+        GROUP.get_or_init(|| GroupParameters::generate(256, 96).unwrap())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_verification_catches_bit_flips(
+            secret in any::<u64>(),
+            (threshold, total) in (1usize..5).prop_flat_map(|t| (Just(t), t..7)),
+            holder in any::<prop::sample::Index>(),
+            bit in 0u64..96,
+        ) {
+            let mut vss = group().vss(threshold, total).unwrap();
+            let (shares, commitments) = vss.split_secret(&BigUint::from(secret)).unwrap();
+            prop_assert!(shares.iter().all(|share| vss.verify_share(share, &commitments)));
+
+            let share = &shares[holder.index(total)];
+            let flip = BigUint::one() << bit;
+            let mut value = share.clone();
+            value.value ^= &flip;
+            prop_assert!(!vss.verify_share(&value, &commitments));
+            // A degree-zero polynomial gives every holder the same share.
+            let mut id = share.clone();
+            id.id ^= &flip;
+            if threshold > 1 && !id.id.is_zero() {
+                prop_assert!(!vss.verify_share(&id, &commitments));
+            }
+        }
+    }
 }
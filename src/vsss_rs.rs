@@ -0,0 +1,148 @@
+// File: src/vsss_rs.rs
+//! Shares in the byte layout of the `vsss-rs` crate.
+//!
+//! `vsss-rs` (2.x through 4.x) stores a Shamir share of a prime-field secret
+//! as a one-byte identifier followed by the field element's canonical
+//! encoding (`PrimeField::to_repr`). That encoding is fixed-width, but its
+//! byte order depends on the curve: big-endian for secp256k1 and the NIST
+//! curves, little-endian for Curve25519 and BLS12-381. The bytes carry
+//! neither the field nor the threshold, so importers must supply both.
+
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+use crate::encoding::{byte_width, from_fixed, to_fixed_bytes};
+use crate::error::{Error, Result};
+use crate::serialized::{Scheme, SerializedShare};
+
+/// The order of the Ed25519 base point, the scalar field of `curve25519-dalek`.
+const ED25519_ORDER: &str =
+    "7237005577332262213973186563042994240857116359379907606001950938285454250989";
+
+/// How a curve's scalars are laid out by `PrimeField::to_repr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+/// A scalar field as `vsss-rs` encodes it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    pub modulus: BigUint,
+    pub order: ByteOrder,
+}
+
+impl Field {
+    /// The scalars of `k256`.
+    pub fn secp256k1() -> Self {
+        Field {
+            modulus: crate::sss::named_prime("secp256k1").expect("built-in prime"),
+            order: ByteOrder::BigEndian,
+        }
+    }
+
+    /// The scalars of `curve25519-dalek`.
+    pub fn ed25519() -> Self {
+        Field {
+            modulus: BigUint::parse_bytes(ED25519_ORDER.as_bytes(), 10)
+                .expect("the Ed25519 order is valid decimal"),
+            order: ByteOrder::LittleEndian,
+        }
+    }
+
+    fn width(&self) -> usize {
+        byte_width(&self.modulus)
+    }
+}
+
+/// Encodes a Shamir share over `field` in the `vsss-rs` layout.
+pub fn to_vsss(share: &SerializedShare, field: &Field) -> Result<Vec<u8>> {
+    match &share.scheme {
+        Scheme::Shamir { prime } if *prime == field.modulus => {}
+        _ => {
+            return Err(Error::InvalidParameters(
+                "only Shamir shares over the given field can be exported",
+            ))
+        }
+    }
+    let identifier = share.x.to_u8().ok_or(Error::InvalidParameters(
+        "share index does not fit in a byte",
+    ))?;
+    let mut value = to_fixed_bytes(&BigUint::from_bytes_be(&share.value), field.width())?;
+    if field.order == ByteOrder::LittleEndian {
+        value.reverse();
+    }
+    let mut bytes = Vec::with_capacity(value.len() + 1);
+    bytes.push(identifier);
+    bytes.extend_from_slice(&value);
+    Ok(bytes)
+}
+
+/// Decodes a `vsss-rs` share over `field`, tagging it with the `threshold`
+/// and `total` it was dealt with.
+pub fn from_vsss(
+    bytes: &[u8],
+    field: &Field,
+    threshold: usize,
+    total: usize,
+) -> Result<SerializedShare> {
+    let (&identifier, value) = bytes.split_first().ok_or(Error::MalformedShare)?;
+    if identifier == 0 {
+        return Err(Error::MalformedShare);
+    }
+    let mut value = value.to_vec();
+    if field.order == ByteOrder::LittleEndian {
+        value.reverse();
+    }
+    let value = from_fixed(&value, &field.modulus)?;
+    SerializedShare::new(
+        Scheme::Shamir {
+            prime: field.modulus.clone(),
+        },
+        threshold,
+        total,
+        BigUint::from(identifier),
+        value.to_bytes_be(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialized::reconstruct_auto;
+    use crate::sss::SecretSharer;
+
+    #[test]
+    fn test_vsss_layout_roundtrip() {
+        for field in [Field::secp256k1(), Field::ed25519()] {
+            let sharer = SecretSharer::with_prime(field.modulus.clone(), 2, 3);
+            let secret = BigUint::from(0x0102u32);
+            let exported: Vec<Vec<u8>> = sharer
+                .split_secret(&secret)
+                .iter()
+                .map(|share| to_vsss(&sharer.serialize_share(share), &field).unwrap())
+                .collect();
+            assert!(exported.iter().all(|bytes| bytes.len() == 33));
+            assert_eq!(exported[2][0], 3);
+
+            let imported: Vec<SerializedShare> = exported[1..]
+                .iter()
+                .map(|bytes| from_vsss(bytes, &field, 2, 3).unwrap())
+                .collect();
+            assert_eq!(reconstruct_auto(&imported).unwrap(), [1, 2]);
+        }
+
+        // A little-endian share of 1 at identifier 1
+        let mut one = vec![0; 33];
+        one[..2].copy_from_slice(&[1, 1]);
+        let share = from_vsss(&one, &Field::ed25519(), 2, 3).unwrap();
+        assert_eq!(BigUint::from_bytes_be(&share.value), BigUint::from(1u32));
+        assert!(to_vsss(&share, &Field::secp256k1()).is_err());
+
+        let mut too_big = vec![0xff; 33];
+        too_big[0] = 1;
+        assert!(from_vsss(&too_big, &Field::secp256k1(), 2, 3).is_err());
+        assert!(from_vsss(&one[..32], &Field::ed25519(), 2, 3).is_err());
+    }
+}
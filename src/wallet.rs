@@ -0,0 +1,128 @@
+// File: src/wallet.rs
+//! Splitting of wallet seeds.
+//!
+//! A BIP-39 mnemonic is shared as the entropy it encodes, not its words, so
+//! the combiner regenerates the checksum word and always yields a phrase
+//! wallets accept. A BIP-32 master seed (16 to 64 bytes, e.g. derived from a
+//! mnemonic and passphrase) is shared as-is. Both are shared byte-wise over
+//! GF(2^8), tagged with their representation so reconstruction returns the
+//! same kind of secret that was split.
+
+use std::fmt;
+
+use crate::encoding::{hex, unhex};
+use crate::error::{Error, Result};
+use crate::keys::{check_format, META_FORMAT};
+use crate::mnemonic;
+use crate::serialized::{reconstruct_auto, split_bytes, SerializedShare};
+
+const MNEMONIC_FORMAT: &str = "bip39";
+const SEED_FORMAT: &str = "bip32";
+
+/// A recovered wallet secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WalletSecret {
+    /// A BIP-39 phrase, words separated by single spaces.
+    Mnemonic(String),
+    /// A BIP-32 master seed.
+    Seed(Vec<u8>),
+}
+
+impl fmt::Display for WalletSecret {
+    /// The phrase, or the seed as hex.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalletSecret::Mnemonic(phrase) => f.write_str(phrase),
+            WalletSecret::Seed(seed) => f.write_str(&hex(seed)),
+        }
+    }
+}
+
+/// Splits a BIP-39 phrase after checking its checksum.
+pub fn split_mnemonic(
+    phrase: &str,
+    threshold: usize,
+    total: usize,
+) -> Result<Vec<SerializedShare>> {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let entropy = mnemonic::phrase_to_entropy(&words)?;
+    Ok(tag(
+        split_bytes(&entropy, threshold, total)?,
+        MNEMONIC_FORMAT,
+    ))
+}
+
+/// Splits a BIP-32 master seed of 16 to 64 bytes.
+pub fn split_seed(seed: &[u8], threshold: usize, total: usize) -> Result<Vec<SerializedShare>> {
+    if !(16..=64).contains(&seed.len()) {
+        return Err(Error::InvalidParameters("BIP-32 seeds are 16 to 64 bytes"));
+    }
+    Ok(tag(split_bytes(seed, threshold, total)?, SEED_FORMAT))
+}
+
+/// Splits `input` as a hex BIP-32 seed if it is one, and as a BIP-39 phrase
+/// otherwise.
+pub fn split_wallet(input: &str, threshold: usize, total: usize) -> Result<Vec<SerializedShare>> {
+    let input = input.trim();
+    if !input.contains(char::is_whitespace) {
+        if let Ok(seed) = unhex(input) {
+            return split_seed(&seed, threshold, total);
+        }
+    }
+    split_mnemonic(input, threshold, total)
+}
+
+/// Rebuilds the phrase or seed from shares made by the splitters above.
+pub fn reconstruct_wallet(shares: &[SerializedShare]) -> Result<WalletSecret> {
+    let first = shares.first().ok_or(Error::InsufficientShares)?;
+    match first.metadata.get(META_FORMAT).map(String::as_str) {
+        Some(MNEMONIC_FORMAT) => {
+            check_format(shares, MNEMONIC_FORMAT)?;
+            let entropy = reconstruct_auto(shares)?;
+            Ok(WalletSecret::Mnemonic(
+                mnemonic::entropy_to_phrase(&entropy)?.join(" "),
+            ))
+        }
+        Some(SEED_FORMAT) => {
+            check_format(shares, SEED_FORMAT)?;
+            Ok(WalletSecret::Seed(reconstruct_auto(shares)?))
+        }
+        _ => Err(Error::InconsistentShares(
+            "shares do not hold a wallet seed",
+        )),
+    }
+}
+
+fn tag(shares: Vec<SerializedShare>, format: &str) -> Vec<SerializedShare> {
+    shares
+        .into_iter()
+        .map(|share| share.with_metadata(META_FORMAT, format))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mnemonic_and_seed_roundtrip() {
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let shares = split_wallet(&format!("  {}\n", phrase.to_uppercase()), 2, 3).unwrap();
+        // Only the 16 bytes of entropy are shared, not the words
+        assert_eq!(shares[0].value.len(), 16);
+        assert_eq!(
+            reconstruct_wallet(&shares[1..]).unwrap(),
+            WalletSecret::Mnemonic(phrase.into())
+        );
+        assert!(split_mnemonic("legal winner thank year", 2, 3).is_err());
+
+        let seed = hex(&[0x5a; 64]);
+        let shares = split_wallet(&seed, 3, 5).unwrap();
+        let recovered = reconstruct_wallet(&shares[2..]).unwrap();
+        assert_eq!(recovered.to_string(), seed);
+        assert!(split_seed(&[1; 8], 2, 3).is_err());
+
+        let plain = split_bytes(&[1; 16], 2, 3).unwrap();
+        assert!(reconstruct_wallet(&plain).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+// File: src/wasm.rs
+//! JavaScript bindings (`wasm` feature).
+//!
+//! Built with `wasm-pack build --features wasm`, this exposes splitting,
+//! verification and reconstruction to browsers with generated TypeScript
+//! types, so recovery tools run exactly this implementation. Shares cross the
+//! boundary as armored strings; randomness comes from `crypto.getRandomValues`
+//! through getrandom's `js` backend.
+//!
+//! [`CeremonyParticipant`] lets a browser join a ceremony hosted by the
+//! `http` feature's server: each binary WebSocket message carries one
+//! ceremony message, and the participant turns the dealer's messages into
+//! its replies.
+
+use ed25519_dalek::VerifyingKey;
+use rand::rngs::OsRng;
+use wasm_bindgen::prelude::*;
+use x25519_dalek::StaticSecret;
+
+use crate::ceremony::{reconstructor, Message, Participant};
+use crate::params::GroupParameters;
+use crate::serialized::{reconstruct_auto, split_bytes, SerializedShare};
+use crate::service;
+
+/// Feldman VSS shares and the commitment document to verify them against.
+#[wasm_bindgen]
+pub struct VssDealing {
+    shares: Vec<String>,
+    commitments: String,
+}
+
+#[wasm_bindgen]
+impl VssDealing {
+    /// The armored shares, one per participant.
+    #[wasm_bindgen(getter)]
+    pub fn shares(&self) -> Vec<String> {
+        self.shares.clone()
+    }
+
+    /// The commitment document as JSON.
+    #[wasm_bindgen(getter)]
+    pub fn commitments(&self) -> String {
+        self.commitments.clone()
+    }
+}
+
+/// Splits a secret into `shares` armored byte-wise shares, any `threshold`
+/// of which recover it.
+#[wasm_bindgen]
+pub fn split(secret: &[u8], threshold: usize, shares: usize) -> Result<Vec<String>, JsError> {
+    Ok(split_bytes(secret, threshold, shares)?
+        .iter()
+        .map(SerializedShare::to_armored)
+        .collect())
+}
+
+/// Deals Feldman VSS shares of a secret, read as a big-endian integer below
+/// the group order, over a parameter document from `sss gen-params`.
+#[wasm_bindgen(js_name = splitVss)]
+pub fn split_vss(
+    secret: &[u8],
+    threshold: usize,
+    shares: usize,
+    group_parameters: &str,
+) -> Result<VssDealing, JsError> {
+    let group = GroupParameters::from_json(group_parameters)?;
+    let dealing = service::deal(secret, threshold, shares, Some(&group))?;
+    Ok(VssDealing {
+        shares: dealing.shares.iter().map(|s| s.to_armored()).collect(),
+        commitments: dealing
+            .commitments
+            .expect("VSS dealings have commitments")
+            .to_json()?,
+    })
+}
+
+/// Checks a Feldman share against a commitment document (JSON or armor).
+#[wasm_bindgen]
+pub fn verify(share: &str, commitments: &str) -> Result<bool, JsError> {
+    Ok(service::verify_share(share, commitments)?)
+}
+
+/// Recovers the secret from armored or bech32 shares of any scheme.
+#[wasm_bindgen]
+pub fn reconstruct(shares: Vec<String>) -> Result<Vec<u8>, JsError> {
+    let shares = shares
+        .iter()
+        .map(|text| {
+            SerializedShare::from_armored(text).or_else(|_| SerializedShare::from_bech32(text))
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+    Ok(reconstruct_auto(&shares)?)
+}
+
+/// A participant's side of a dealer ceremony, moving messages as bytes.
+#[wasm_bindgen]
+pub struct CeremonyParticipant {
+    participant: Option<Participant>,
+    hello: Vec<u8>,
+    share: Option<String>,
+    commitments: Option<String>,
+    rejection: Option<String>,
+}
+
+#[wasm_bindgen]
+impl CeremonyParticipant {
+    /// Joins as participant `index` with a fresh encryption key. With
+    /// `dealer_key`, a 32-byte Ed25519 key, the commitments must be signed
+    /// by that dealer.
+    #[wasm_bindgen(constructor)]
+    pub fn new(index: u32, dealer_key: Option<Vec<u8>>) -> Result<CeremonyParticipant, JsError> {
+        let dealer = dealer_key
+            .map(|key| {
+                <[u8; 32]>::try_from(key)
+                    .ok()
+                    .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+                    .ok_or_else(|| JsError::new("dealer key is not an Ed25519 public key"))
+            })
+            .transpose()?;
+        let participant = Participant::new(index, StaticSecret::random_from_rng(OsRng), dealer);
+        Ok(CeremonyParticipant {
+            hello: participant.hello().to_bytes(),
+            participant: Some(participant),
+            share: None,
+            commitments: None,
+            rejection: None,
+        })
+    }
+
+    /// The first message to send.
+    pub fn hello(&self) -> Vec<u8> {
+        self.hello.clone()
+    }
+
+    /// Takes a message from the dealer, returning the ack or complaint to
+    /// send once the share has arrived.
+    pub fn receive(&mut self, message: &[u8]) -> Result<Option<Vec<u8>>, JsError> {
+        let participant = self
+            .participant
+            .as_mut()
+            .ok_or_else(|| JsError::new("the ceremony is over"))?;
+        let Some(reply) = participant.receive(Message::from_bytes(message)?)? else {
+            return Ok(None);
+        };
+        match self.participant.take().expect("present").finish() {
+            Ok((share, document)) => {
+                self.share = Some(
+                    reconstructor(&document)
+                        .serialize_share(&share)
+                        .to_armored(),
+                );
+                self.commitments = Some(document.to_json()?);
+            }
+            Err(err) => self.rejection = Some(err.to_string()),
+        }
+        Ok(Some(reply.to_bytes()))
+    }
+
+    /// The armored share, once accepted.
+    #[wasm_bindgen(getter)]
+    pub fn share(&self) -> Option<String> {
+        self.share.clone()
+    }
+
+    /// The commitment document as JSON, once the share is accepted.
+    #[wasm_bindgen(getter)]
+    pub fn commitments(&self) -> Option<String> {
+        self.commitments.clone()
+    }
+
+    /// Why the share was rejected, if it was.
+    #[wasm_bindgen(getter)]
+    pub fn rejection(&self) -> Option<String> {
+        self.rejection.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_round_trip() {
+        let shares = split(b"browser recovery", 2, 3).unwrap();
+        let picked = vec![shares[2].clone(), shares[0].clone()];
+        assert_eq!(reconstruct(picked).unwrap(), b"browser recovery");
+
+        let group = GroupParameters::generate(256, 96).unwrap();
+        let dealing = split_vss(&[7, 7], 2, 3, &group.to_json().unwrap()).unwrap();
+        assert!(verify(&dealing.shares()[1], &dealing.commitments()).unwrap());
+        assert_eq!(reconstruct(dealing.shares()).unwrap(), [7, 7]);
+    }
+}
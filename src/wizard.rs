@@ -0,0 +1,400 @@
+// File: src/wizard.rs
+//! Interactive terminal wizard for split and combine ceremonies (`tui` feature).
+//!
+//! The wizard prompts for everything it needs, shows each new share on its
+//! own screen and asks the custodian to type it back before moving on, so a
+//! miscopied share is caught while the dealer is still in the room. Secrets
+//! stay in memory and only reach disk if the operator asks to save one.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use cryptographic_algorithms::encoding::hex;
+use cryptographic_algorithms::serialized::{reconstruct_auto, split_bytes, SerializedShare};
+use cryptographic_algorithms::{Error, Result};
+use rand::RngCore;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// Runs the wizard until the ceremony finishes or the operator presses Esc.
+pub fn run() -> Result<()> {
+    let mut terminal = ratatui::init();
+    let mut wizard = Wizard::new();
+    let result = (|| {
+        while !wizard.finished() {
+            terminal.draw(|frame| wizard.render(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    wizard.handle_key(key.code);
+                }
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    result
+}
+
+enum Step {
+    Menu,
+    SplitSecret,
+    SplitThreshold {
+        secret: Vec<u8>,
+    },
+    SplitShares {
+        secret: Vec<u8>,
+        threshold: usize,
+    },
+    ShowShare {
+        shares: Vec<SerializedShare>,
+        index: usize,
+    },
+    ConfirmShare {
+        shares: Vec<SerializedShare>,
+        index: usize,
+    },
+    CombineShares {
+        shares: Vec<SerializedShare>,
+    },
+    ShowSecret {
+        secret: Vec<u8>,
+    },
+    SaveSecret {
+        secret: Vec<u8>,
+    },
+    Done(String),
+    Quit,
+}
+
+struct Wizard {
+    step: Step,
+    input: String,
+    notice: Option<String>,
+}
+
+impl Wizard {
+    fn new() -> Self {
+        Wizard {
+            step: Step::Menu,
+            input: String::new(),
+            notice: None,
+        }
+    }
+
+    fn finished(&self) -> bool {
+        matches!(self.step, Step::Quit)
+    }
+
+    /// Whether the current step reads a line of text rather than single keys.
+    fn reads_line(&self) -> bool {
+        !matches!(
+            self.step,
+            Step::Menu | Step::ShowShare { .. } | Step::ShowSecret { .. } | Step::Done(_)
+        )
+    }
+
+    fn handle_key(&mut self, key: KeyCode) {
+        if key == KeyCode::Esc {
+            self.step = Step::Quit;
+            return;
+        }
+        if self.reads_line() {
+            match key {
+                KeyCode::Char(c) => self.input.push(c),
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Enter => {
+                    let line = std::mem::take(&mut self.input);
+                    self.notice = None;
+                    let step = std::mem::replace(&mut self.step, Step::Quit);
+                    self.step = self.submit(step, line.trim());
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let step = std::mem::replace(&mut self.step, Step::Quit);
+        self.step = match (step, key) {
+            (Step::Menu, KeyCode::Char('s')) => Step::SplitSecret,
+            (Step::Menu, KeyCode::Char('c')) => Step::CombineShares { shares: Vec::new() },
+            (Step::ShowShare { shares, index }, KeyCode::Enter) => {
+                Step::ConfirmShare { shares, index }
+            }
+            (Step::ShowSecret { secret }, KeyCode::Char('s')) => Step::SaveSecret { secret },
+            (Step::ShowSecret { .. }, KeyCode::Enter) => {
+                Step::Done("The secret was not written to disk.".into())
+            }
+            (Step::Done(_), _) => Step::Quit,
+            (step, _) => step,
+        };
+    }
+
+    fn submit(&mut self, step: Step, line: &str) -> Step {
+        match step {
+            Step::SplitSecret if line.is_empty() => {
+                self.notice = Some("The secret cannot be empty.".into());
+                Step::SplitSecret
+            }
+            Step::SplitSecret => Step::SplitThreshold {
+                secret: line.as_bytes().to_vec(),
+            },
+            Step::SplitThreshold { secret } => match line.parse() {
+                Ok(threshold) if threshold > 0 => Step::SplitShares { secret, threshold },
+                _ => {
+                    self.notice = Some("Enter a whole number of at least 1.".into());
+                    Step::SplitThreshold { secret }
+                }
+            },
+            Step::SplitShares { secret, threshold } => {
+                let total = line.parse().unwrap_or(0);
+                let mut session = [0u8; 8];
+                rand::thread_rng().fill_bytes(&mut session);
+                match split_bytes(&secret, threshold, total) {
+                    Ok(shares) => Step::ShowShare {
+                        shares: shares
+                            .into_iter()
+                            .map(|share| share.with_session(hex(&session)))
+                            .collect(),
+                        index: 0,
+                    },
+                    Err(err) => {
+                        self.notice = Some(format!("{}; choose another share count.", err));
+                        Step::SplitShares { secret, threshold }
+                    }
+                }
+            }
+            Step::ConfirmShare { shares, index } => {
+                match SerializedShare::from_bech32(line) {
+                    Ok(typed) if typed == shares[index] => {}
+                    Ok(_) => {
+                        self.notice = Some("That is a different share; here it is again.".into());
+                        return Step::ShowShare { shares, index };
+                    }
+                    Err(err) => {
+                        self.notice = Some(format!("{}; here is the share again.", err));
+                        return Step::ShowShare { shares, index };
+                    }
+                }
+                if index + 1 < shares.len() {
+                    Step::ShowShare {
+                        shares,
+                        index: index + 1,
+                    }
+                } else {
+                    Step::Done(format!(
+                        "All {} shares were recorded and verified.",
+                        shares.len()
+                    ))
+                }
+            }
+            Step::CombineShares { mut shares } => {
+                match SerializedShare::from_bech32(line) {
+                    Ok(share) => shares.push(share),
+                    Err(err) => {
+                        self.notice = Some(format!("{}; please retype that share.", err));
+                        return Step::CombineShares { shares };
+                    }
+                }
+                if shares.len() < shares[0].threshold {
+                    return Step::CombineShares { shares };
+                }
+                match reconstruct_auto(&shares) {
+                    Ok(secret) => Step::ShowSecret { secret },
+                    Err(err) => {
+                        self.notice = Some(format!("{}; start again with other shares.", err));
+                        Step::CombineShares { shares: Vec::new() }
+                    }
+                }
+            }
+            Step::SaveSecret { secret } => match save(line, &secret) {
+                Ok(()) => Step::Done(format!("The secret was saved to {}.", line)),
+                Err(err) => {
+                    self.notice = Some(format!("{}; try another path.", err));
+                    Step::SaveSecret { secret }
+                }
+            },
+            step => step,
+        }
+    }
+
+    /// The title, instructions and whether typed input is masked for a step.
+    fn screen(&self) -> (&'static str, Vec<String>, bool) {
+        match &self.step {
+            Step::Menu => (
+                "Secret sharing ceremony",
+                vec![
+                    "Press s to split a secret into shares.".into(),
+                    "Press c to combine shares into the secret.".into(),
+                    "Press Esc at any time to leave.".into(),
+                ],
+                false,
+            ),
+            Step::SplitSecret => (
+                "Split: secret",
+                vec!["Type the secret and press Enter. It is not shown.".into()],
+                true,
+            ),
+            Step::SplitThreshold { .. } => (
+                "Split: threshold",
+                vec!["How many shares should be needed to recover the secret?".into()],
+                false,
+            ),
+            Step::SplitShares { threshold, .. } => (
+                "Split: shares",
+                vec![format!(
+                    "How many shares should be created? At least {}.",
+                    threshold
+                )],
+                false,
+            ),
+            Step::ShowShare { shares, index } => (
+                "Split: record share",
+                vec![
+                    format!(
+                        "Share {} of {}. Hand it to its custodian to write down.",
+                        index + 1,
+                        shares.len()
+                    ),
+                    String::new(),
+                    shares[*index].to_bech32(),
+                    String::new(),
+                    "Press Enter once it has been recorded.".into(),
+                ],
+                false,
+            ),
+            Step::ConfirmShare { shares, index } => (
+                "Split: confirm share",
+                vec![format!(
+                    "Custodian of share {} of {}: type your share back from your copy.",
+                    index + 1,
+                    shares.len()
+                )],
+                false,
+            ),
+            Step::CombineShares { shares } => (
+                "Combine: enter shares",
+                vec![match shares.first() {
+                    Some(first) => format!(
+                        "{} of {} shares entered. Type the next share.",
+                        shares.len(),
+                        first.threshold
+                    ),
+                    None => "Type the first share.".into(),
+                }],
+                false,
+            ),
+            Step::ShowSecret { secret } => (
+                "Combine: secret recovered",
+                vec![
+                    match std::str::from_utf8(secret) {
+                        Ok(text) => text.to_string(),
+                        Err(_) => format!("(hex) {}", hex(secret)),
+                    },
+                    String::new(),
+                    "Press s to save it to a file, or Enter to finish without saving.".into(),
+                ],
+                false,
+            ),
+            Step::SaveSecret { .. } => (
+                "Combine: save secret",
+                vec!["Type a path for a new file. Existing files are never replaced.".into()],
+                false,
+            ),
+            Step::Done(summary) => (
+                "Done",
+                vec![summary.clone(), "Press any key to leave.".into()],
+                false,
+            ),
+            Step::Quit => ("", Vec::new(), false),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let (title, body, masked) = self.screen();
+        let mut lines: Vec<Line> = body.into_iter().map(Line::from).collect();
+        if self.reads_line() {
+            let shown = if masked {
+                "*".repeat(self.input.chars().count())
+            } else {
+                self.input.clone()
+            };
+            lines.push(Line::default());
+            lines.push(Line::from(format!("> {}", shown)));
+        }
+        if let Some(notice) = &self.notice {
+            lines.push(Line::default());
+            lines.push(Line::from(Span::styled(
+                notice.clone(),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        let block = Block::default().borders(Borders::ALL).title(title);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            frame.area(),
+        );
+    }
+}
+
+fn save(path: &str, secret: &[u8]) -> Result<()> {
+    if path.is_empty() {
+        return Err(Error::InvalidParameters("no path given"));
+    }
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    Ok(file.write_all(secret)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_line(wizard: &mut Wizard, line: &str) {
+        for c in line.chars() {
+            wizard.handle_key(KeyCode::Char(c));
+        }
+        wizard.handle_key(KeyCode::Enter);
+    }
+
+    #[test]
+    fn test_split_then_combine_ceremony() {
+        let mut wizard = Wizard::new();
+        wizard.handle_key(KeyCode::Char('s'));
+        type_line(&mut wizard, "hunter2");
+        type_line(&mut wizard, "2");
+        type_line(&mut wizard, "3");
+
+        let mut recorded = Vec::new();
+        while let Step::ShowShare { shares, index } = &wizard.step {
+            let share = shares[*index].to_bech32();
+            wizard.handle_key(KeyCode::Enter);
+            if recorded.is_empty() {
+                // A mistyped copy is caught and the share shown again
+                let mut typo = share.clone();
+                let last = typo.pop().unwrap();
+                typo.push(if last == 'q' { 'p' } else { 'q' });
+                type_line(&mut wizard, &typo);
+                assert!(wizard.notice.is_some());
+                wizard.handle_key(KeyCode::Enter);
+            }
+            type_line(&mut wizard, &share);
+            recorded.push(share);
+        }
+        assert!(matches!(wizard.step, Step::Done(_)));
+        assert_eq!(recorded.len(), 3);
+
+        let mut wizard = Wizard::new();
+        wizard.handle_key(KeyCode::Char('c'));
+        type_line(&mut wizard, &recorded[2]);
+        type_line(&mut wizard, &recorded[0]);
+        assert!(matches!(&wizard.step, Step::ShowSecret { secret } if secret == b"hunter2"));
+        wizard.handle_key(KeyCode::Enter);
+        assert!(matches!(wizard.step, Step::Done(_)));
+    }
+}